@@ -0,0 +1,8 @@
+BEGIN;
+
+DROP FUNCTION IF EXISTS station_open_window(UUID, TIMESTAMPTZ);
+DROP TABLE IF EXISTS station_opening_hour_overrides;
+DROP TABLE IF EXISTS station_opening_hours;
+ALTER TABLE stations DROP COLUMN IF EXISTS temporarily_closed;
+
+COMMIT;
@@ -0,0 +1,108 @@
+BEGIN;
+
+ALTER TABLE stations
+    ADD COLUMN IF NOT EXISTS temporarily_closed BOOLEAN NOT NULL DEFAULT false;
+
+-- Weekly schedule. day_of_week follows ISO numbering (1 = Monday, 7 = Sunday).
+-- A closes_at at or before opens_at means the window runs past midnight, so
+-- 00:00-00:00 is open around the clock.
+CREATE TABLE IF NOT EXISTS station_opening_hours (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    station_id UUID NOT NULL REFERENCES stations (id) ON DELETE CASCADE,
+    day_of_week SMALLINT NOT NULL CHECK (day_of_week BETWEEN 1 AND 7),
+    opens_at TIME NOT NULL,
+    closes_at TIME NOT NULL,
+    UNIQUE (station_id, day_of_week)
+);
+
+-- Per-date overrides (public holidays, maintenance days). A closed override
+-- wins over the weekly schedule for that date.
+CREATE TABLE IF NOT EXISTS station_opening_hour_overrides (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    station_id UUID NOT NULL REFERENCES stations (id) ON DELETE CASCADE,
+    override_date DATE NOT NULL,
+    is_closed BOOLEAN NOT NULL DEFAULT false,
+    opens_at TIME,
+    closes_at TIME,
+    note VARCHAR(255),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    UNIQUE (station_id, override_date),
+    CONSTRAINT station_opening_hour_overrides_window CHECK (
+        is_closed OR (opens_at IS NOT NULL AND closes_at IS NOT NULL)
+    )
+);
+
+-- Evaluates a station's opening status at p_at in the Africa/Lagos timezone.
+-- Stations without a weekly schedule are treated as always open so that
+-- existing listings are not hidden until they configure their hours.
+CREATE OR REPLACE FUNCTION station_open_window(
+        p_station_id UUID,
+        p_at TIMESTAMPTZ
+    ) RETURNS TABLE (is_open BOOLEAN, closes_at TIMESTAMPTZ) AS $$
+DECLARE
+    local_at TIMESTAMP := p_at AT TIME ZONE 'Africa/Lagos';
+    candidate_day DATE;
+    window_opens TIME;
+    window_closes TIME;
+    window_start TIMESTAMP;
+    window_end TIMESTAMP;
+    override_row station_opening_hour_overrides%ROWTYPE;
+    has_schedule BOOLEAN;
+BEGIN
+    IF (SELECT s.temporarily_closed FROM stations s WHERE s.id = p_station_id) THEN
+        RETURN QUERY SELECT FALSE, NULL::TIMESTAMPTZ;
+        RETURN;
+    END IF;
+
+    -- Yesterday is checked too, for windows that run past midnight.
+    FOREACH candidate_day IN ARRAY ARRAY[local_at::date - 1, local_at::date] LOOP
+        window_opens := NULL;
+        window_closes := NULL;
+
+        SELECT * INTO override_row
+        FROM station_opening_hour_overrides o
+        WHERE o.station_id = p_station_id AND o.override_date = candidate_day;
+
+        IF FOUND THEN
+            IF NOT override_row.is_closed THEN
+                window_opens := override_row.opens_at;
+                window_closes := override_row.closes_at;
+            END IF;
+        ELSE
+            SELECT h.opens_at, h.closes_at INTO window_opens, window_closes
+            FROM station_opening_hours h
+            WHERE h.station_id = p_station_id
+              AND h.day_of_week = EXTRACT(ISODOW FROM candidate_day);
+        END IF;
+
+        IF window_opens IS NOT NULL THEN
+            window_start := candidate_day + window_opens;
+            window_end := CASE
+                WHEN window_closes > window_opens THEN candidate_day + window_closes
+                ELSE candidate_day + 1 + window_closes
+            END;
+
+            IF local_at >= window_start AND local_at < window_end THEN
+                RETURN QUERY SELECT TRUE, window_end AT TIME ZONE 'Africa/Lagos';
+                RETURN;
+            END IF;
+        END IF;
+    END LOOP;
+
+    SELECT EXISTS (
+        SELECT 1 FROM station_opening_hours h WHERE h.station_id = p_station_id
+    ) INTO has_schedule;
+
+    IF NOT has_schedule AND NOT EXISTS (
+        SELECT 1 FROM station_opening_hour_overrides o
+        WHERE o.station_id = p_station_id AND o.override_date = local_at::date
+    ) THEN
+        RETURN QUERY SELECT TRUE, NULL::TIMESTAMPTZ;
+        RETURN;
+    END IF;
+
+    RETURN QUERY SELECT FALSE, NULL::TIMESTAMPTZ;
+END;
+$$ LANGUAGE plpgsql STABLE;
+
+COMMIT;
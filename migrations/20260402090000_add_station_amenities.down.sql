@@ -0,0 +1,11 @@
+BEGIN;
+
+DROP INDEX IF EXISTS idx_stations_payment_methods;
+DROP INDEX IF EXISTS idx_stations_amenities;
+ALTER TABLE stations
+    DROP CONSTRAINT IF EXISTS valid_station_payment_methods,
+    DROP CONSTRAINT IF EXISTS valid_station_amenities,
+    DROP COLUMN IF EXISTS payment_methods,
+    DROP COLUMN IF EXISTS amenities;
+
+COMMIT;
@@ -0,0 +1,16 @@
+BEGIN;
+
+ALTER TABLE stations
+    ADD COLUMN IF NOT EXISTS amenities TEXT[] NOT NULL DEFAULT '{}',
+    ADD COLUMN IF NOT EXISTS payment_methods TEXT[] NOT NULL DEFAULT '{}',
+    ADD CONSTRAINT valid_station_amenities CHECK (
+        amenities <@ ARRAY['atm', 'shop', 'air_pump', 'open_24_hours', 'car_wash', 'restroom']::TEXT[]
+    ),
+    ADD CONSTRAINT valid_station_payment_methods CHECK (
+        payment_methods <@ ARRAY['cash', 'transfer', 'pos']::TEXT[]
+    );
+
+CREATE INDEX IF NOT EXISTS idx_stations_amenities ON stations USING GIN (amenities);
+CREATE INDEX IF NOT EXISTS idx_stations_payment_methods ON stations USING GIN (payment_methods);
+
+COMMIT;
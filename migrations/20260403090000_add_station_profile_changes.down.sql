@@ -0,0 +1,6 @@
+BEGIN;
+
+DROP TABLE IF EXISTS station_relocation_requests;
+DROP TABLE IF EXISTS station_profile_changes;
+
+COMMIT;
@@ -0,0 +1,10 @@
+BEGIN;
+
+DROP TABLE IF EXISTS station_verification_documents;
+DROP TABLE IF EXISTS station_verification_requests;
+ALTER TABLE stations
+    DROP CONSTRAINT IF EXISTS valid_station_verification_status,
+    DROP COLUMN IF EXISTS verified_at,
+    DROP COLUMN IF EXISTS verification_status;
+
+COMMIT;
@@ -0,0 +1,40 @@
+BEGIN;
+
+ALTER TABLE stations
+    ADD COLUMN IF NOT EXISTS verification_status VARCHAR(32) NOT NULL DEFAULT 'unverified',
+    ADD COLUMN IF NOT EXISTS verified_at TIMESTAMPTZ,
+    ADD CONSTRAINT valid_station_verification_status CHECK (
+        verification_status IN ('unverified', 'pending', 'verified', 'rejected')
+    );
+
+CREATE TABLE IF NOT EXISTS station_verification_requests (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    station_id UUID NOT NULL REFERENCES stations (id) ON DELETE CASCADE,
+    document_type VARCHAR(32) NOT NULL
+        CHECK (document_type IN ('business_registration', 'dpr_licence')),
+    document_number VARCHAR(64) NOT NULL,
+    status VARCHAR(32) NOT NULL DEFAULT 'pending'
+        CHECK (status IN ('pending', 'approved', 'rejected')),
+    review_reason TEXT,
+    reviewed_by_admin UUID REFERENCES admins (id),
+    reviewed_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS uniq_pending_verification_per_station
+    ON station_verification_requests (station_id)
+    WHERE status = 'pending';
+
+CREATE TABLE IF NOT EXISTS station_verification_documents (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    request_id UUID NOT NULL REFERENCES station_verification_requests (id) ON DELETE CASCADE,
+    storage_key VARCHAR(255) NOT NULL,
+    content_type VARCHAR(64) NOT NULL,
+    size_bytes INTEGER NOT NULL CHECK (size_bytes > 0),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS idx_station_verification_documents_request
+    ON station_verification_documents (request_id);
+
+COMMIT;
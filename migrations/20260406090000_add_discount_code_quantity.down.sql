@@ -0,0 +1,13 @@
+BEGIN;
+
+ALTER TABLE discount_codes
+    DROP CONSTRAINT IF EXISTS discount_codes_totals_non_negative,
+    DROP CONSTRAINT IF EXISTS discount_codes_quantity_positive,
+    DROP CONSTRAINT IF EXISTS discount_codes_quantity_unit,
+    DROP COLUMN IF EXISTS total_saving,
+    DROP COLUMN IF EXISTS total_discounted_price,
+    DROP COLUMN IF EXISTS total_price,
+    DROP COLUMN IF EXISTS quantity_litres,
+    DROP COLUMN IF EXISTS quantity_unit;
+
+COMMIT;
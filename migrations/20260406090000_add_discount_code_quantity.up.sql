@@ -0,0 +1,28 @@
+BEGIN;
+
+-- Codes are issued for a specific commodity and quantity. Existing codes were
+-- single-litre codes, which the defaults below reproduce.
+ALTER TABLE discount_codes
+    ADD COLUMN IF NOT EXISTS quantity_unit VARCHAR(16) NOT NULL DEFAULT 'litres',
+    ADD COLUMN IF NOT EXISTS quantity_litres DOUBLE PRECISION NOT NULL DEFAULT 1,
+    ADD COLUMN IF NOT EXISTS total_price INTEGER,
+    ADD COLUMN IF NOT EXISTS total_discounted_price INTEGER,
+    ADD COLUMN IF NOT EXISTS total_saving INTEGER;
+
+UPDATE discount_codes
+SET total_price = created_price,
+    total_discounted_price = discounted_price,
+    total_saving = created_price - discounted_price
+WHERE total_price IS NULL;
+
+ALTER TABLE discount_codes
+    ALTER COLUMN total_price SET NOT NULL,
+    ALTER COLUMN total_discounted_price SET NOT NULL,
+    ALTER COLUMN total_saving SET NOT NULL,
+    ADD CONSTRAINT discount_codes_quantity_unit CHECK (quantity_unit IN ('litres', 'amount')),
+    ADD CONSTRAINT discount_codes_quantity_positive CHECK (quantity_litres > 0),
+    ADD CONSTRAINT discount_codes_totals_non_negative CHECK (
+        total_price >= 0 AND total_discounted_price >= 0 AND total_saving >= 0
+    );
+
+COMMIT;
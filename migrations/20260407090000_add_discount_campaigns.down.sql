@@ -0,0 +1,17 @@
+BEGIN;
+
+DROP FUNCTION IF EXISTS discount_campaign_is_live(UUID, TIMESTAMPTZ);
+
+-- Fixed-amount codes issued while campaigns existed keep their zero
+-- percentage; NOT VALID restores the old rule for new rows only.
+ALTER TABLE discount_codes
+    DROP CONSTRAINT IF EXISTS discount_codes_discount_percentage_check,
+    ADD CONSTRAINT discount_codes_discount_percentage_check CHECK (
+        discount_percentage BETWEEN 1 AND 10
+    ) NOT VALID,
+    DROP COLUMN IF EXISTS discount_type,
+    DROP COLUMN IF EXISTS campaign_id;
+
+DROP TABLE IF EXISTS discount_campaigns;
+
+COMMIT;
@@ -0,0 +1,71 @@
+BEGIN;
+
+CREATE TABLE IF NOT EXISTS discount_campaigns (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    station_id UUID NOT NULL REFERENCES stations (id) ON DELETE CASCADE,
+    commodity_id UUID NOT NULL REFERENCES commodities (id) ON DELETE CASCADE,
+    name VARCHAR(120) NOT NULL,
+    discount_type VARCHAR(16) NOT NULL CHECK (discount_type IN ('percentage', 'fixed')),
+    -- Percent off for 'percentage' campaigns, naira off per litre for 'fixed'.
+    discount_value INTEGER NOT NULL CHECK (discount_value > 0),
+    starts_at TIMESTAMPTZ NOT NULL,
+    ends_at TIMESTAMPTZ,
+    -- ISO weekdays (1 = Monday) and a daily window, in Africa/Lagos time.
+    recurrence_days SMALLINT[],
+    daily_starts_at TIME,
+    daily_ends_at TIME,
+    max_codes INTEGER CHECK (max_codes IS NULL OR max_codes > 0),
+    budget INTEGER CHECK (budget IS NULL OR budget > 0),
+    is_active BOOLEAN NOT NULL DEFAULT true,
+    created_by_admin UUID REFERENCES admins (id),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    CONSTRAINT discount_campaigns_window CHECK (ends_at IS NULL OR ends_at > starts_at),
+    CONSTRAINT discount_campaigns_daily_window CHECK (
+        (daily_starts_at IS NULL AND daily_ends_at IS NULL)
+        OR (daily_starts_at IS NOT NULL AND daily_ends_at IS NOT NULL
+            AND daily_ends_at > daily_starts_at)
+    ),
+    CONSTRAINT discount_campaigns_percentage_range CHECK (
+        discount_type <> 'percentage' OR discount_value BETWEEN 1 AND 10
+    )
+);
+
+CREATE INDEX IF NOT EXISTS idx_discount_campaigns_commodity
+    ON discount_campaigns (commodity_id) WHERE is_active;
+CREATE INDEX IF NOT EXISTS idx_discount_campaigns_station ON discount_campaigns (station_id);
+
+ALTER TABLE discount_codes
+    ADD COLUMN IF NOT EXISTS campaign_id UUID REFERENCES discount_campaigns (id) ON DELETE SET NULL,
+    ADD COLUMN IF NOT EXISTS discount_type VARCHAR(16) NOT NULL DEFAULT 'percentage',
+    DROP CONSTRAINT IF EXISTS discount_codes_discount_percentage_check,
+    -- Fixed-amount codes carry a zero percentage.
+    ADD CONSTRAINT discount_codes_discount_percentage_check CHECK (
+        discount_percentage BETWEEN 0 AND 10
+    );
+
+CREATE INDEX IF NOT EXISTS idx_discount_codes_campaign_id ON discount_codes (campaign_id);
+
+-- Whether a campaign hands out codes at p_at: active, inside its start/end
+-- window and, when it recurs, on a matching Africa/Lagos weekday and time.
+CREATE OR REPLACE FUNCTION discount_campaign_is_live(
+        p_campaign_id UUID,
+        p_at TIMESTAMPTZ
+    ) RETURNS BOOLEAN AS $$
+    SELECT EXISTS (
+        SELECT 1
+        FROM discount_campaigns c
+        WHERE c.id = p_campaign_id
+          AND c.is_active
+          AND p_at >= c.starts_at
+          AND (c.ends_at IS NULL OR p_at < c.ends_at)
+          AND (c.recurrence_days IS NULL
+               OR EXTRACT(ISODOW FROM p_at AT TIME ZONE 'Africa/Lagos')::SMALLINT
+                  = ANY (c.recurrence_days))
+          AND (c.daily_starts_at IS NULL
+               OR ((p_at AT TIME ZONE 'Africa/Lagos')::TIME >= c.daily_starts_at
+                   AND (p_at AT TIME ZONE 'Africa/Lagos')::TIME < c.daily_ends_at))
+    );
+$$ LANGUAGE sql STABLE;
+
+COMMIT;
@@ -0,0 +1,20 @@
+BEGIN;
+
+DROP TABLE IF EXISTS commodity_discount_changes;
+
+ALTER TABLE commodity_discounts
+    ADD COLUMN IF NOT EXISTS updated_by_admin UUID REFERENCES admins (id);
+
+UPDATE commodity_discounts
+SET updated_by_admin = updated_by_id
+WHERE updated_by_type = 'admin';
+
+ALTER TABLE commodity_discounts
+    DROP CONSTRAINT IF EXISTS commodity_discounts_updated_by_type,
+    DROP COLUMN IF EXISTS admin_locked,
+    DROP COLUMN IF EXISTS updated_by_id,
+    DROP COLUMN IF EXISTS updated_by_type;
+
+DROP TABLE IF EXISTS station_discount_limits;
+
+COMMIT;
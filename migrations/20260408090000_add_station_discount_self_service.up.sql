@@ -0,0 +1,48 @@
+BEGIN;
+
+-- Floor and ceiling an admin allows a station to pick its own discount within.
+CREATE TABLE IF NOT EXISTS station_discount_limits (
+    station_id UUID PRIMARY KEY REFERENCES stations (id) ON DELETE CASCADE,
+    min_percentage INTEGER NOT NULL,
+    max_percentage INTEGER NOT NULL,
+    updated_by_admin UUID REFERENCES admins (id),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    CONSTRAINT station_discount_limits_range CHECK (
+        min_percentage BETWEEN 1 AND 10
+        AND max_percentage BETWEEN 1 AND 10
+        AND min_percentage <= max_percentage
+    )
+);
+
+-- "Updated by" can now be an admin or the station itself.
+ALTER TABLE commodity_discounts
+    ADD COLUMN IF NOT EXISTS updated_by_type VARCHAR(16) NOT NULL DEFAULT 'admin',
+    ADD COLUMN IF NOT EXISTS updated_by_id UUID,
+    -- Set when an admin's configuration must not be changed by the station.
+    ADD COLUMN IF NOT EXISTS admin_locked BOOLEAN NOT NULL DEFAULT false,
+    ADD CONSTRAINT commodity_discounts_updated_by_type CHECK (
+        updated_by_type IN ('admin', 'station')
+    );
+
+UPDATE commodity_discounts SET updated_by_id = updated_by_admin;
+
+ALTER TABLE commodity_discounts DROP COLUMN IF EXISTS updated_by_admin;
+
+CREATE TABLE IF NOT EXISTS commodity_discount_changes (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    commodity_id UUID NOT NULL REFERENCES commodities (id) ON DELETE CASCADE,
+    station_id UUID NOT NULL REFERENCES stations (id) ON DELETE CASCADE,
+    actor_type VARCHAR(16) NOT NULL CHECK (actor_type IN ('admin', 'station')),
+    actor_id UUID NOT NULL,
+    previous_enabled BOOLEAN,
+    previous_percentage INTEGER,
+    new_enabled BOOLEAN NOT NULL,
+    new_percentage INTEGER,
+    admin_locked BOOLEAN NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS idx_commodity_discount_changes_commodity
+    ON commodity_discount_changes (commodity_id, created_at DESC);
+
+COMMIT;
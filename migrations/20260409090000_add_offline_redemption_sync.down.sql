@@ -0,0 +1,8 @@
+BEGIN;
+
+ALTER TABLE discount_codes
+    DROP CONSTRAINT IF EXISTS discount_codes_redemption_source,
+    DROP COLUMN IF EXISTS redemption_synced_at,
+    DROP COLUMN IF EXISTS redemption_source;
+
+COMMIT;
@@ -0,0 +1,14 @@
+BEGIN;
+
+ALTER TABLE discount_codes
+    ADD COLUMN IF NOT EXISTS redemption_source VARCHAR(16),
+    -- When an offline redemption reached the server; redeemed_at keeps the
+    -- time the attendant recorded at the pump.
+    ADD COLUMN IF NOT EXISTS redemption_synced_at TIMESTAMPTZ,
+    ADD CONSTRAINT discount_codes_redemption_source CHECK (
+        redemption_source IS NULL OR redemption_source IN ('online', 'offline')
+    );
+
+UPDATE discount_codes SET redemption_source = 'online' WHERE redeemed_at IS NOT NULL;
+
+COMMIT;
@@ -0,0 +1,9 @@
+BEGIN;
+
+DROP TABLE IF EXISTS discount_code_events;
+
+ALTER TABLE discount_codes
+    DROP COLUMN IF EXISTS voided_at,
+    DROP COLUMN IF EXISTS cancelled_at;
+
+COMMIT;
@@ -0,0 +1,35 @@
+BEGIN;
+
+ALTER TABLE discount_codes
+    -- Set when the driver cancels an unredeemed code.
+    ADD COLUMN IF NOT EXISTS cancelled_at TIMESTAMPTZ,
+    -- Set when an admin voids a code; voided codes never count as redeemed.
+    ADD COLUMN IF NOT EXISTS voided_at TIMESTAMPTZ;
+
+-- Every state change of a discount code, including redemptions a station
+-- later reversed.
+CREATE TABLE IF NOT EXISTS discount_code_events (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    discount_code_id UUID NOT NULL REFERENCES discount_codes (id) ON DELETE CASCADE,
+    event_type VARCHAR(16) NOT NULL CHECK (
+        event_type IN ('issued', 'redeemed', 'cancelled', 'reversed', 'voided')
+    ),
+    actor_type VARCHAR(16) NOT NULL CHECK (actor_type IN ('driver', 'station', 'admin')),
+    actor_id UUID,
+    reason TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS idx_discount_code_events_code
+    ON discount_code_events (discount_code_id, created_at);
+
+INSERT INTO discount_code_events (discount_code_id, event_type, actor_type, created_at)
+SELECT id, 'issued', 'driver', created_at
+FROM discount_codes;
+
+INSERT INTO discount_code_events (discount_code_id, event_type, actor_type, actor_id, created_at)
+SELECT id, 'redeemed', 'station', redeemed_by_station_id, redeemed_at
+FROM discount_codes
+WHERE redeemed_at IS NOT NULL;
+
+COMMIT;
@@ -0,0 +1,7 @@
+BEGIN;
+
+DROP FUNCTION IF EXISTS refresh_discount_rollups(TIMESTAMPTZ);
+DROP TABLE IF EXISTS discount_rollup_refreshes;
+DROP TABLE IF EXISTS discount_hourly_rollups;
+
+COMMIT;
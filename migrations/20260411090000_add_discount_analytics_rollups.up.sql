@@ -0,0 +1,77 @@
+BEGIN;
+
+-- Discount activity per Africa/Lagos hour, station and commodity. Built from
+-- discount_code_generation_logs and discount_codes by refresh_discount_rollups
+-- so analytics never scan the raw tables.
+CREATE TABLE IF NOT EXISTS discount_hourly_rollups (
+    bucket_start TIMESTAMP NOT NULL,
+    station_id UUID NOT NULL REFERENCES stations (id) ON DELETE CASCADE,
+    commodity_id UUID NOT NULL REFERENCES commodities (id) ON DELETE CASCADE,
+    codes_created INTEGER NOT NULL DEFAULT 0,
+    -- Redemptions in this hour; reversed and voided redemptions are excluded.
+    codes_redeemed INTEGER NOT NULL DEFAULT 0,
+    naira_discounted BIGINT NOT NULL DEFAULT 0,
+    PRIMARY KEY (bucket_start, station_id, commodity_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_discount_hourly_rollups_station
+    ON discount_hourly_rollups (station_id, bucket_start);
+
+CREATE TABLE IF NOT EXISTS discount_rollup_refreshes (
+    singleton BOOLEAN PRIMARY KEY DEFAULT TRUE CHECK (singleton),
+    refreshed_at TIMESTAMPTZ NOT NULL
+);
+
+-- Rebuilds every bucket from p_since onwards. Existing history is rolled up
+-- by the first scheduled refresh rather than here, so the migration stays fast.
+CREATE OR REPLACE FUNCTION refresh_discount_rollups(p_since TIMESTAMPTZ)
+RETURNS TIMESTAMPTZ AS $$
+DECLARE
+    since_local TIMESTAMP := date_trunc('hour', p_since AT TIME ZONE 'Africa/Lagos');
+BEGIN
+    -- Overlapping refreshes would otherwise collide on the primary key.
+    PERFORM pg_advisory_xact_lock(hashtext('discount_hourly_rollups'));
+
+    DELETE FROM discount_hourly_rollups WHERE bucket_start >= since_local;
+
+    INSERT INTO discount_hourly_rollups (
+        bucket_start, station_id, commodity_id, codes_created, codes_redeemed, naira_discounted
+    )
+    SELECT bucket_start, station_id, commodity_id, SUM(created), SUM(redeemed), SUM(discounted)
+    FROM (
+        SELECT
+            date_trunc('hour', l.created_at AT TIME ZONE 'Africa/Lagos') AS bucket_start,
+            l.station_id,
+            dc.commodity_id,
+            1 AS created,
+            0 AS redeemed,
+            0 AS discounted
+        FROM discount_code_generation_logs l
+        JOIN discount_codes dc ON dc.id = l.code_id
+        WHERE l.created_at AT TIME ZONE 'Africa/Lagos' >= since_local
+
+        UNION ALL
+
+        SELECT
+            date_trunc('hour', dc.redeemed_at AT TIME ZONE 'Africa/Lagos'),
+            dc.station_id,
+            dc.commodity_id,
+            0,
+            1,
+            dc.total_saving
+        FROM discount_codes dc
+        WHERE dc.redeemed_at IS NOT NULL
+          AND dc.voided_at IS NULL
+          AND dc.redeemed_at AT TIME ZONE 'Africa/Lagos' >= since_local
+    ) activity
+    GROUP BY bucket_start, station_id, commodity_id;
+
+    INSERT INTO discount_rollup_refreshes (singleton, refreshed_at)
+    VALUES (TRUE, now())
+    ON CONFLICT (singleton) DO UPDATE SET refreshed_at = EXCLUDED.refreshed_at;
+
+    RETURN now();
+END;
+$$ LANGUAGE plpgsql;
+
+COMMIT;
@@ -0,0 +1,10 @@
+BEGIN;
+
+DROP TABLE IF EXISTS discount_fraud_flags;
+
+ALTER TABLE stations
+    DROP COLUMN IF EXISTS discounts_suspension_lifted_at,
+    DROP COLUMN IF EXISTS discounts_suspended_reason,
+    DROP COLUMN IF EXISTS discounts_suspended_at;
+
+COMMIT;
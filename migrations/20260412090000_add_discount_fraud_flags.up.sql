@@ -0,0 +1,37 @@
+BEGIN;
+
+ALTER TABLE stations
+    -- Set while the station may not issue discount codes.
+    ADD COLUMN IF NOT EXISTS discounts_suspended_at TIMESTAMPTZ,
+    ADD COLUMN IF NOT EXISTS discounts_suspended_reason TEXT,
+    -- Flags raised before the last lift do not count towards a new
+    -- automatic suspension.
+    ADD COLUMN IF NOT EXISTS discounts_suspension_lifted_at TIMESTAMPTZ;
+
+CREATE TABLE IF NOT EXISTS discount_fraud_flags (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    station_id UUID NOT NULL REFERENCES stations (id) ON DELETE CASCADE,
+    discount_code_id UUID REFERENCES discount_codes (id) ON DELETE CASCADE,
+    rule VARCHAR(32) NOT NULL CHECK (
+        rule IN ('ip_burst', 'fast_redemption', 'full_redemption_rate', 'odd_hour_redemption')
+    ),
+    score INTEGER NOT NULL CHECK (score > 0),
+    details TEXT NOT NULL,
+    -- When the anomaly happened, as opposed to when a scan noticed it.
+    observed_at TIMESTAMPTZ NOT NULL,
+    -- Identifies the anomaly so repeated scans do not flag it twice.
+    dedup_key TEXT NOT NULL UNIQUE,
+    status VARCHAR(16) NOT NULL DEFAULT 'open' CHECK (status IN ('open', 'dismissed', 'confirmed')),
+    reviewed_by_admin UUID REFERENCES admins (id),
+    review_note TEXT,
+    reviewed_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS idx_discount_fraud_flags_station
+    ON discount_fraud_flags (station_id, observed_at DESC);
+
+CREATE INDEX IF NOT EXISTS idx_discount_fraud_flags_status
+    ON discount_fraud_flags (status, created_at DESC);
+
+COMMIT;
@@ -0,0 +1,13 @@
+BEGIN;
+
+DROP TABLE IF EXISTS loyalty_entries;
+DROP TABLE IF EXISTS loyalty_transactions;
+DROP FUNCTION IF EXISTS loyalty_transaction_is_balanced();
+DROP FUNCTION IF EXISTS loyalty_ledger_append_only();
+DROP TABLE IF EXISTS loyalty_vouchers;
+
+ALTER TABLE discount_codes DROP COLUMN IF EXISTS loyalty_account_id;
+
+DROP TABLE IF EXISTS loyalty_accounts;
+
+COMMIT;
@@ -0,0 +1,113 @@
+BEGIN;
+
+-- A driver's loyalty account. Drivers are otherwise anonymous, so the
+-- account is identified by a secret key held by the driver app; only its
+-- SHA-256 is stored.
+CREATE TABLE IF NOT EXISTS loyalty_accounts (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    key_hash VARCHAR(64) NOT NULL UNIQUE,
+    -- Lapsed points are expired up to this time; the expiry job only comes
+    -- back to the account when newer earnings lapse or points are refunded.
+    points_expiry_checked_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+ALTER TABLE discount_codes
+    ADD COLUMN IF NOT EXISTS loyalty_account_id UUID REFERENCES loyalty_accounts (id);
+
+CREATE TABLE IF NOT EXISTS loyalty_vouchers (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    loyalty_account_id UUID NOT NULL REFERENCES loyalty_accounts (id),
+    code VARCHAR(16) NOT NULL UNIQUE,
+    points INTEGER NOT NULL CHECK (points > 0),
+    -- Naira off the driver's bill.
+    value INTEGER NOT NULL CHECK (value > 0),
+    expires_at TIMESTAMPTZ NOT NULL,
+    redeemed_at TIMESTAMPTZ,
+    redeemed_by_station_id UUID REFERENCES stations (id),
+    -- Set when the voucher lapsed unredeemed and its points went back to
+    -- the driver.
+    refunded_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS idx_loyalty_vouchers_pending_refund
+    ON loyalty_vouchers (expires_at)
+    WHERE redeemed_at IS NULL AND refunded_at IS NULL;
+
+-- Append-only, double-entry points ledger. Every transaction moves points
+-- between a driver's account and one of the programme's own ledgers, and
+-- its entries always sum to zero.
+CREATE TABLE IF NOT EXISTS loyalty_transactions (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    loyalty_account_id UUID NOT NULL REFERENCES loyalty_accounts (id),
+    kind VARCHAR(16) NOT NULL CHECK (
+        kind IN ('earn', 'reversal', 'redeem', 'refund', 'expire')
+    ),
+    discount_code_id UUID REFERENCES discount_codes (id),
+    voucher_id UUID REFERENCES loyalty_vouchers (id),
+    -- Earned points lapse at this time unless spent first.
+    expires_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS idx_loyalty_transactions_account
+    ON loyalty_transactions (loyalty_account_id, created_at DESC);
+
+CREATE INDEX IF NOT EXISTS idx_loyalty_transactions_earn_expiry
+    ON loyalty_transactions (expires_at)
+    WHERE kind = 'earn';
+
+CREATE TABLE IF NOT EXISTS loyalty_entries (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    transaction_id UUID NOT NULL REFERENCES loyalty_transactions (id),
+    ledger VARCHAR(24) NOT NULL CHECK (
+        ledger IN ('driver', 'points_issued', 'points_redeemed', 'points_expired')
+    ),
+    -- Set on, and only on, driver entries.
+    loyalty_account_id UUID REFERENCES loyalty_accounts (id),
+    amount BIGINT NOT NULL CHECK (amount <> 0),
+    CONSTRAINT loyalty_entries_driver_account CHECK (
+        (ledger = 'driver') = (loyalty_account_id IS NOT NULL)
+    )
+);
+
+CREATE INDEX IF NOT EXISTS idx_loyalty_entries_account
+    ON loyalty_entries (loyalty_account_id)
+    WHERE ledger = 'driver';
+
+CREATE INDEX IF NOT EXISTS idx_loyalty_entries_transaction
+    ON loyalty_entries (transaction_id);
+
+CREATE OR REPLACE FUNCTION loyalty_ledger_append_only()
+RETURNS TRIGGER AS $$
+BEGIN
+    RAISE EXCEPTION 'the loyalty ledger is append-only';
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER loyalty_transactions_append_only
+    BEFORE UPDATE OR DELETE ON loyalty_transactions
+    FOR EACH ROW EXECUTE FUNCTION loyalty_ledger_append_only();
+
+CREATE TRIGGER loyalty_entries_append_only
+    BEFORE UPDATE OR DELETE ON loyalty_entries
+    FOR EACH ROW EXECUTE FUNCTION loyalty_ledger_append_only();
+
+CREATE OR REPLACE FUNCTION loyalty_transaction_is_balanced()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF (SELECT SUM(amount) FROM loyalty_entries WHERE transaction_id = NEW.transaction_id) <> 0 THEN
+        RAISE EXCEPTION 'loyalty transaction % does not balance', NEW.transaction_id;
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+-- Checked at commit, once every entry of the transaction is in.
+CREATE CONSTRAINT TRIGGER loyalty_entries_balanced
+    AFTER INSERT ON loyalty_entries
+    DEFERRABLE INITIALLY DEFERRED
+    FOR EACH ROW EXECUTE FUNCTION loyalty_transaction_is_balanced();
+
+COMMIT;
@@ -0,0 +1,11 @@
+BEGIN;
+
+DROP TABLE IF EXISTS subscription_invoices;
+DROP SEQUENCE IF EXISTS subscription_invoice_number_seq;
+
+ALTER TABLE subscriptions
+    DROP COLUMN IF EXISTS plan_id;
+
+DROP TABLE IF EXISTS subscription_plans;
+
+COMMIT;
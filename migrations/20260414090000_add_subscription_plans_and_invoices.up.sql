@@ -0,0 +1,73 @@
+BEGIN;
+
+CREATE TABLE IF NOT EXISTS subscription_plans (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    code VARCHAR(32) NOT NULL UNIQUE,
+    name VARCHAR(100) NOT NULL,
+    period_days INTEGER NOT NULL CHECK (period_days > 0),
+    -- Whole naira.
+    price INTEGER NOT NULL CHECK (price >= 0),
+    discounts_enabled BOOLEAN NOT NULL DEFAULT false,
+    analytics_enabled BOOLEAN NOT NULL DEFAULT false,
+    -- Hidden plans (the signup trial) cannot be invoiced.
+    is_public BOOLEAN NOT NULL DEFAULT true,
+    is_active BOOLEAN NOT NULL DEFAULT true,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+INSERT INTO subscription_plans
+    (code, name, period_days, price, discounts_enabled, analytics_enabled, is_public)
+VALUES
+    ('trial', 'Free trial', 30, 0, true, true, false),
+    ('monthly', 'Monthly', 30, 15000, true, false, true),
+    ('quarterly', 'Quarterly', 90, 40000, true, true, true),
+    ('annual', 'Annual', 365, 150000, true, true, true)
+ON CONFLICT (code) DO NOTHING;
+
+ALTER TABLE subscriptions
+    ADD COLUMN IF NOT EXISTS plan_id UUID REFERENCES subscription_plans (id);
+
+-- Subscriptions from before plans existed keep everything they had.
+UPDATE subscriptions
+SET plan_id = (SELECT id FROM subscription_plans WHERE code = 'trial')
+WHERE plan_id IS NULL;
+
+ALTER TABLE subscriptions
+    ALTER COLUMN plan_id SET NOT NULL;
+
+CREATE SEQUENCE IF NOT EXISTS subscription_invoice_number_seq;
+
+CREATE TABLE IF NOT EXISTS subscription_invoices (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    number VARCHAR(32) NOT NULL UNIQUE
+        DEFAULT 'INV-' || lpad(nextval('subscription_invoice_number_seq')::TEXT, 6, '0'),
+    station_id UUID NOT NULL REFERENCES stations (id) ON DELETE CASCADE,
+    plan_id UUID NOT NULL REFERENCES subscription_plans (id),
+    -- Price and period are copied from the plan when issued so later plan
+    -- changes do not alter an invoice.
+    amount INTEGER NOT NULL CHECK (amount >= 0),
+    period_days INTEGER NOT NULL CHECK (period_days > 0),
+    status VARCHAR(16) NOT NULL DEFAULT 'open'
+        CHECK (status IN ('open', 'paid', 'void')),
+    issued_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    due_at TIMESTAMPTZ NOT NULL,
+    paid_at TIMESTAMPTZ,
+    paid_by_admin UUID REFERENCES admins (id),
+    -- The subscription period the payment bought.
+    subscription_id UUID REFERENCES subscriptions (id) ON DELETE SET NULL,
+    voided_at TIMESTAMPTZ,
+    void_reason TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    CHECK ((status = 'paid') = (paid_at IS NOT NULL)),
+    CHECK ((status = 'void') = (voided_at IS NOT NULL))
+);
+
+CREATE INDEX IF NOT EXISTS idx_subscription_invoices_station
+    ON subscription_invoices (station_id, issued_at DESC);
+
+CREATE INDEX IF NOT EXISTS idx_subscription_invoices_open
+    ON subscription_invoices (due_at)
+    WHERE status = 'open';
+
+COMMIT;
@@ -0,0 +1,6 @@
+BEGIN;
+
+DROP TABLE IF EXISTS payment_webhook_events;
+DROP TABLE IF EXISTS subscription_payments;
+
+COMMIT;
@@ -0,0 +1,39 @@
+BEGIN;
+
+CREATE TABLE IF NOT EXISTS subscription_payments (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    invoice_id UUID NOT NULL REFERENCES subscription_invoices (id) ON DELETE CASCADE,
+    station_id UUID NOT NULL REFERENCES stations (id) ON DELETE CASCADE,
+    -- `paystack`, `local` or `bank_transfer`.
+    provider VARCHAR(32) NOT NULL,
+    reference VARCHAR(100) NOT NULL UNIQUE,
+    -- Whole naira.
+    amount INTEGER NOT NULL CHECK (amount >= 0),
+    status VARCHAR(16) NOT NULL DEFAULT 'pending'
+        CHECK (status IN ('pending', 'succeeded', 'failed')),
+    authorization_url TEXT,
+    failure_reason TEXT,
+    confirmed_by_admin UUID REFERENCES admins (id),
+    paid_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS idx_subscription_payments_invoice
+    ON subscription_payments (invoice_id);
+
+-- Every verified webhook delivery, so redelivered events are recognised
+-- and only processed once.
+CREATE TABLE IF NOT EXISTS payment_webhook_events (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    provider VARCHAR(32) NOT NULL,
+    event_id VARCHAR(200) NOT NULL,
+    event_type VARCHAR(64) NOT NULL,
+    reference VARCHAR(100),
+    outcome VARCHAR(32) NOT NULL,
+    payload JSONB NOT NULL,
+    received_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    UNIQUE (provider, event_id)
+);
+
+COMMIT;
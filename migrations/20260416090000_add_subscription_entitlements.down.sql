@@ -0,0 +1,6 @@
+BEGIN;
+
+DROP INDEX IF EXISTS idx_subscriptions_station_created;
+DROP FUNCTION IF EXISTS station_subscription_is_current(UUID);
+
+COMMIT;
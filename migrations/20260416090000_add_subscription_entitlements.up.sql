@@ -0,0 +1,22 @@
+BEGIN;
+
+-- Whether a station is paid up right now; used to keep lapsed stations out
+-- of (or at the bottom of) driver search results.
+CREATE OR REPLACE FUNCTION station_subscription_is_current(p_station_id UUID)
+RETURNS BOOLEAN
+LANGUAGE sql
+STABLE
+AS $$
+    SELECT EXISTS (
+        SELECT 1
+        FROM subscriptions
+        WHERE station_id = p_station_id
+          AND status = 'active'
+          AND ends_at > now()
+    );
+$$;
+
+CREATE INDEX IF NOT EXISTS idx_subscriptions_station_created
+    ON subscriptions (station_id, created_at DESC);
+
+COMMIT;
@@ -0,0 +1,29 @@
+BEGIN;
+
+DROP TABLE IF EXISTS subscription_events;
+
+ALTER TABLE subscriptions
+    DROP CONSTRAINT IF EXISTS subscriptions_paused_at_check;
+
+-- Paused subscriptions have no equivalent; they become active again.
+UPDATE subscriptions
+SET status = 'active'
+WHERE status = 'paused';
+
+DROP INDEX IF EXISTS uniq_current_subscription_per_station;
+
+CREATE UNIQUE INDEX IF NOT EXISTS uniq_active_subscription_per_station
+    ON subscriptions (station_id)
+    WHERE status = 'active';
+
+ALTER TABLE subscriptions
+    DROP COLUMN IF EXISTS paused_at,
+    DROP COLUMN IF EXISTS cancelled_at,
+    DROP COLUMN IF EXISTS cancel_at_period_end,
+    DROP CONSTRAINT IF EXISTS subscriptions_status_check;
+
+ALTER TABLE subscriptions
+    ADD CONSTRAINT subscriptions_status_check
+        CHECK (status IN ('active', 'expired', 'cancelled'));
+
+COMMIT;
@@ -0,0 +1,63 @@
+BEGIN;
+
+ALTER TABLE subscriptions
+    DROP CONSTRAINT IF EXISTS subscriptions_status_check;
+
+ALTER TABLE subscriptions
+    ADD CONSTRAINT subscriptions_status_check
+        CHECK (status IN ('active', 'paused', 'expired', 'cancelled')),
+    -- Set when the station asked to stop at the end of the period; the
+    -- subscription is then cancelled rather than expired.
+    ADD COLUMN IF NOT EXISTS cancel_at_period_end BOOLEAN NOT NULL DEFAULT FALSE,
+    ADD COLUMN IF NOT EXISTS cancelled_at TIMESTAMPTZ,
+    -- While paused the clock stops: resuming pushes ends_at back by the
+    -- time spent paused.
+    ADD COLUMN IF NOT EXISTS paused_at TIMESTAMPTZ,
+    ADD CONSTRAINT subscriptions_paused_at_check
+        CHECK ((status = 'paused') = (paused_at IS NOT NULL));
+
+-- A paused subscription is still the station's current one.
+DROP INDEX IF EXISTS uniq_active_subscription_per_station;
+
+CREATE UNIQUE INDEX IF NOT EXISTS uniq_current_subscription_per_station
+    ON subscriptions (station_id)
+    WHERE status IN ('active', 'paused');
+
+-- Every lifecycle transition, validated before it is written.
+CREATE TABLE IF NOT EXISTS subscription_events (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    subscription_id UUID NOT NULL REFERENCES subscriptions (id) ON DELETE CASCADE,
+    station_id UUID NOT NULL REFERENCES stations (id) ON DELETE CASCADE,
+    event_type VARCHAR(32) NOT NULL CHECK (
+        event_type IN (
+            'started', 'renewed', 'cancel_scheduled', 'cancel_withdrawn', 'cancelled',
+            'paused', 'resumed', 'plan_changed', 'expired'
+        )
+    ),
+    from_status VARCHAR(32),
+    to_status VARCHAR(32) NOT NULL,
+    from_plan_id UUID REFERENCES subscription_plans (id),
+    to_plan_id UUID REFERENCES subscription_plans (id),
+    previous_ends_at TIMESTAMPTZ,
+    ends_at TIMESTAMPTZ NOT NULL,
+    -- Naira of unused time carried into a plan change.
+    proration_credit INTEGER,
+    actor_type VARCHAR(16) NOT NULL CHECK (actor_type IN ('station', 'admin', 'system')),
+    actor_id UUID,
+    reason TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS idx_subscription_events_station
+    ON subscription_events (station_id, created_at DESC);
+
+INSERT INTO subscription_events (
+    subscription_id, station_id, event_type, to_status, to_plan_id, ends_at, actor_type,
+    actor_id, created_at
+)
+SELECT id, station_id, 'started', status, plan_id, ends_at,
+       CASE WHEN created_by_admin IS NULL THEN 'system' ELSE 'admin' END,
+       created_by_admin, created_at
+FROM subscriptions;
+
+COMMIT;
@@ -0,0 +1,6 @@
+BEGIN;
+
+DROP TABLE IF EXISTS job_schedules;
+DROP TABLE IF EXISTS jobs;
+
+COMMIT;
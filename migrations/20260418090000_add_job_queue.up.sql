@@ -0,0 +1,63 @@
+BEGIN;
+
+-- Background work, claimed with FOR UPDATE SKIP LOCKED so any number of
+-- replicas can run workers without doing a job twice.
+CREATE TABLE IF NOT EXISTS jobs (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    kind VARCHAR(64) NOT NULL,
+    payload JSONB NOT NULL DEFAULT '{}'::jsonb,
+    -- queued → running → succeeded, or back to queued to retry, or dead once
+    -- out of attempts.
+    status VARCHAR(16) NOT NULL DEFAULT 'queued'
+        CHECK (status IN ('queued', 'running', 'succeeded', 'dead')),
+    attempts INTEGER NOT NULL DEFAULT 0 CHECK (attempts >= 0),
+    max_attempts INTEGER NOT NULL DEFAULT 5 CHECK (max_attempts > 0),
+    run_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    locked_at TIMESTAMPTZ,
+    locked_by VARCHAR(128),
+    last_error TEXT,
+    -- Enqueuing the same key again while a job is pending is a no-op.
+    dedupe_key VARCHAR(255),
+    finished_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    CHECK ((status = 'running') = (locked_at IS NOT NULL))
+);
+
+CREATE INDEX IF NOT EXISTS idx_jobs_due
+    ON jobs (run_at)
+    WHERE status = 'queued';
+
+CREATE INDEX IF NOT EXISTS idx_jobs_running
+    ON jobs (locked_at)
+    WHERE status = 'running';
+
+CREATE INDEX IF NOT EXISTS idx_jobs_status_created
+    ON jobs (status, created_at DESC);
+
+CREATE UNIQUE INDEX IF NOT EXISTS uniq_pending_job_dedupe_key
+    ON jobs (dedupe_key)
+    WHERE status IN ('queued', 'running');
+
+-- Recurring jobs. Whichever replica claims a due row enqueues the job and
+-- moves next_run_at on, in one transaction, so each run is enqueued once.
+CREATE TABLE IF NOT EXISTS job_schedules (
+    name VARCHAR(64) PRIMARY KEY,
+    kind VARCHAR(64) NOT NULL,
+    -- Six fields, seconds first: `0 0 * * * *` is hourly.
+    cron VARCHAR(128) NOT NULL,
+    is_enabled BOOLEAN NOT NULL DEFAULT TRUE,
+    next_run_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    last_enqueued_at TIMESTAMPTZ,
+    last_job_id UUID
+);
+
+INSERT INTO job_schedules (name, kind, cron)
+VALUES
+    ('subscription_reminders', 'subscription_reminders', '0 0 * * * *'),
+    ('discount_rollups', 'discount_rollups', '0 */10 * * * *'),
+    ('fraud_scan', 'fraud_scan', '0 5/10 * * * *'),
+    ('loyalty_points_expiry', 'loyalty_points_expiry', '0 30 * * * *')
+ON CONFLICT (name) DO NOTHING;
+
+COMMIT;
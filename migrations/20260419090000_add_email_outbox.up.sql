@@ -0,0 +1,35 @@
+BEGIN;
+
+-- Every transactional email, rendered when queued and delivered by the job
+-- worker, so a failed send is retried and each message's fate is on record.
+CREATE TABLE IF NOT EXISTS email_outbox (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    station_id UUID REFERENCES stations(id) ON DELETE SET NULL,
+    template VARCHAR(64) NOT NULL,
+    recipient VARCHAR(255) NOT NULL,
+    variables JSONB NOT NULL DEFAULT '{}'::jsonb,
+    subject TEXT NOT NULL,
+    html_body TEXT NOT NULL,
+    text_body TEXT NOT NULL,
+    -- pending until the transport accepts it; failed once the job gives up.
+    status VARCHAR(16) NOT NULL DEFAULT 'pending'
+        CHECK (status IN ('pending', 'sent', 'failed')),
+    attempts INTEGER NOT NULL DEFAULT 0 CHECK (attempts >= 0),
+    last_error TEXT,
+    -- Which transport accepted it, e.g. `smtp`.
+    transport VARCHAR(32),
+    -- Queuing the same key twice keeps the first message.
+    dedupe_key VARCHAR(255) UNIQUE,
+    sent_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    CHECK ((status = 'sent') = (sent_at IS NOT NULL))
+);
+
+CREATE INDEX IF NOT EXISTS idx_email_outbox_status_created
+    ON email_outbox (status, created_at DESC);
+
+CREATE INDEX IF NOT EXISTS idx_email_outbox_recipient
+    ON email_outbox (recipient, created_at DESC);
+
+COMMIT;
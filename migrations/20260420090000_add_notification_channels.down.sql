@@ -0,0 +1,6 @@
+BEGIN;
+
+DROP TABLE IF EXISTS outbound_messages;
+DROP TABLE IF EXISTS station_notification_channels;
+
+COMMIT;
@@ -0,0 +1,40 @@
+BEGIN;
+
+-- Which channels a station wants notices on, beyond the dashboard. Stations
+-- without a row get email only.
+CREATE TABLE IF NOT EXISTS station_notification_channels (
+    station_id UUID PRIMARY KEY REFERENCES stations(id) ON DELETE CASCADE,
+    email_enabled BOOLEAN NOT NULL DEFAULT TRUE,
+    sms_enabled BOOLEAN NOT NULL DEFAULT FALSE,
+    whatsapp_enabled BOOLEAN NOT NULL DEFAULT FALSE,
+    -- Overrides the station's listed phone for SMS and WhatsApp.
+    phone VARCHAR(32),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+-- SMS and WhatsApp messages, delivered by the job worker like the email
+-- outbox.
+CREATE TABLE IF NOT EXISTS outbound_messages (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    station_id UUID REFERENCES stations(id) ON DELETE SET NULL,
+    channel VARCHAR(16) NOT NULL CHECK (channel IN ('sms', 'whatsapp')),
+    -- International format without the plus, e.g. 2348012345678.
+    recipient VARCHAR(32) NOT NULL,
+    body TEXT NOT NULL,
+    status VARCHAR(16) NOT NULL DEFAULT 'pending'
+        CHECK (status IN ('pending', 'sent', 'failed')),
+    attempts INTEGER NOT NULL DEFAULT 0 CHECK (attempts >= 0),
+    last_error TEXT,
+    provider VARCHAR(32),
+    provider_message_id VARCHAR(255),
+    dedupe_key VARCHAR(255) UNIQUE,
+    sent_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    CHECK ((status = 'sent') = (sent_at IS NOT NULL))
+);
+
+CREATE INDEX IF NOT EXISTS idx_outbound_messages_station_created
+    ON outbound_messages (station_id, created_at DESC);
+
+COMMIT;
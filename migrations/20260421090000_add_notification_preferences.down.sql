@@ -0,0 +1,7 @@
+BEGIN;
+
+DROP TABLE IF EXISTS station_notification_preferences;
+DROP INDEX IF EXISTS idx_notifications_station_created;
+ALTER TABLE notifications DROP COLUMN IF EXISTS archived_at;
+
+COMMIT;
@@ -0,0 +1,22 @@
+BEGIN;
+
+ALTER TABLE notifications
+    ADD COLUMN IF NOT EXISTS archived_at TIMESTAMPTZ;
+
+CREATE INDEX IF NOT EXISTS idx_notifications_station_created
+    ON notifications (station_id, created_at DESC, id DESC);
+
+-- Per-kind opt-outs. A missing row, or a missing kind, means everything is
+-- on; the station-wide channel choices still apply on top.
+CREATE TABLE IF NOT EXISTS station_notification_preferences (
+    station_id UUID NOT NULL REFERENCES stations(id) ON DELETE CASCADE,
+    kind VARCHAR(64) NOT NULL,
+    in_app BOOLEAN NOT NULL DEFAULT TRUE,
+    email BOOLEAN NOT NULL DEFAULT TRUE,
+    sms BOOLEAN NOT NULL DEFAULT TRUE,
+    whatsapp BOOLEAN NOT NULL DEFAULT TRUE,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (station_id, kind)
+);
+
+COMMIT;
@@ -0,0 +1,6 @@
+BEGIN;
+
+DROP TABLE IF EXISTS broadcast_deliveries;
+DROP TABLE IF EXISTS broadcasts;
+
+COMMIT;
@@ -0,0 +1,51 @@
+BEGIN;
+
+CREATE TABLE IF NOT EXISTS broadcasts (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    title VARCHAR(200) NOT NULL,
+    body TEXT NOT NULL,
+    audience VARCHAR(16) NOT NULL
+        CHECK (audience IN ('all', 'station_type', 'area', 'stations')),
+    station_type TEXT CHECK (station_type IS NULL OR station_type IN ('petrol', 'gas')),
+    -- A circle around a point, for 'area' broadcasts.
+    area_latitude DOUBLE PRECISION,
+    area_longitude DOUBLE PRECISION,
+    area_radius_km DOUBLE PRECISION CHECK (area_radius_km IS NULL OR area_radius_km > 0),
+    station_ids UUID[],
+    send_email BOOLEAN NOT NULL DEFAULT FALSE,
+    status VARCHAR(16) NOT NULL DEFAULT 'scheduled'
+        CHECK (status IN ('scheduled', 'sent', 'cancelled')),
+    scheduled_for TIMESTAMPTZ NOT NULL DEFAULT now(),
+    sent_at TIMESTAMPTZ,
+    created_by_admin UUID REFERENCES admins (id),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    CONSTRAINT broadcasts_audience_target CHECK (
+        CASE audience
+            WHEN 'station_type' THEN station_type IS NOT NULL
+            WHEN 'area' THEN area_latitude IS NOT NULL
+                AND area_longitude IS NOT NULL
+                AND area_radius_km IS NOT NULL
+            WHEN 'stations' THEN cardinality(station_ids) > 0
+            ELSE TRUE
+        END
+    ),
+    CONSTRAINT broadcasts_sent_at CHECK ((status = 'sent') = (sent_at IS NOT NULL))
+);
+
+CREATE INDEX IF NOT EXISTS idx_broadcasts_created ON broadcasts (created_at DESC);
+
+-- One row per station a broadcast reached. Read rates come from the
+-- linked notification; a station deleting it keeps the delivery row.
+CREATE TABLE IF NOT EXISTS broadcast_deliveries (
+    broadcast_id UUID NOT NULL REFERENCES broadcasts (id) ON DELETE CASCADE,
+    station_id UUID NOT NULL REFERENCES stations (id) ON DELETE CASCADE,
+    -- FALSE when the station had turned announcements off on the dashboard.
+    in_app BOOLEAN NOT NULL,
+    notification_id UUID REFERENCES notifications (id) ON DELETE SET NULL,
+    email_id UUID REFERENCES email_outbox (id) ON DELETE SET NULL,
+    delivered_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (broadcast_id, station_id)
+);
+
+COMMIT;
@@ -0,0 +1,11 @@
+BEGIN;
+
+DROP TRIGGER IF EXISTS commodities_station_event ON commodities;
+DROP TRIGGER IF EXISTS discount_codes_station_event ON discount_codes;
+DROP TRIGGER IF EXISTS notifications_station_event ON notifications;
+DROP FUNCTION IF EXISTS commodities_station_event();
+DROP FUNCTION IF EXISTS discount_codes_station_event();
+DROP FUNCTION IF EXISTS notifications_station_event();
+DROP FUNCTION IF EXISTS notify_station_event(UUID, TEXT, JSONB);
+
+COMMIT;
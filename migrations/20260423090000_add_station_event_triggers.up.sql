@@ -0,0 +1,78 @@
+BEGIN;
+
+-- Station dashboard events go out on one channel; every server instance
+-- listens and forwards to its own connected dashboards. NOTIFY is only
+-- delivered on commit, so rolled-back writes never reach a dashboard.
+CREATE OR REPLACE FUNCTION notify_station_event(p_station_id UUID, p_event TEXT, p_data JSONB)
+RETURNS VOID AS $$
+BEGIN
+    PERFORM pg_notify(
+        'station_events',
+        jsonb_build_object('station_id', p_station_id, 'event', p_event, 'data', p_data)::text
+    );
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE FUNCTION notifications_station_event()
+RETURNS TRIGGER AS $$
+BEGIN
+    -- NOTIFY payloads are capped at 8000 bytes; the dashboard can fetch
+    -- the full body.
+    PERFORM notify_station_event(NEW.station_id, 'notification', jsonb_build_object(
+        'id', NEW.id,
+        'title', NEW.title,
+        'body', left(NEW.body, 1000),
+        'kind', NEW.kind,
+        'is_read', NEW.is_read,
+        'created_at', NEW.created_at
+    ));
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER notifications_station_event
+    AFTER INSERT ON notifications
+    FOR EACH ROW EXECUTE FUNCTION notifications_station_event();
+
+CREATE OR REPLACE FUNCTION discount_codes_station_event()
+RETURNS TRIGGER AS $$
+BEGIN
+    PERFORM notify_station_event(NEW.station_id, 'discount_code', jsonb_build_object(
+        'id', NEW.id,
+        'code', NEW.code,
+        'commodity_id', NEW.commodity_id,
+        'discounted_price', NEW.discounted_price,
+        'total_discounted_price', NEW.total_discounted_price,
+        'expires_at', NEW.expires_at,
+        'created_at', NEW.created_at
+    ));
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER discount_codes_station_event
+    AFTER INSERT ON discount_codes
+    FOR EACH ROW EXECUTE FUNCTION discount_codes_station_event();
+
+CREATE OR REPLACE FUNCTION commodities_station_event()
+RETURNS TRIGGER AS $$
+BEGIN
+    PERFORM notify_station_event(NEW.station_id, 'price_change', jsonb_build_object(
+        'commodity_id', NEW.id,
+        'name', NEW.name,
+        'previous_price', OLD.price,
+        'price', NEW.price,
+        'is_available', NEW.is_available,
+        'updated_at', NEW.updated_at
+    ));
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER commodities_station_event
+    AFTER UPDATE OF price, is_available ON commodities
+    FOR EACH ROW
+    WHEN (OLD.price IS DISTINCT FROM NEW.price OR OLD.is_available IS DISTINCT FROM NEW.is_available)
+    EXECUTE FUNCTION commodities_station_event();
+
+COMMIT;
@@ -1,13 +1,40 @@
 use sqlx::{PgPool, postgres::PgPoolOptions};
-use std::time::Duration;
+use std::{sync::Arc, time::Duration};
+
+use crate::domain::{
+    discounts::token::DiscountTokenSigner,
+    events::hub::StationEvents,
+    media::storage::{LocalFsStorage, MediaStorage},
+    payments::provider::{PaymentProvider, provider_from_env},
+    subscriptions::entitlements::grace_period_from_env,
+    utils::client_ip::ClientIpResolver,
+};
 
 #[derive(Clone)]
 pub struct AppState {
     pub pool: PgPool,
+    pub media: Arc<dyn MediaStorage>,
+    /// Private storage for verification documents; never served publicly.
+    pub documents: Arc<dyn MediaStorage>,
+    /// When set, `/stations/closest` only returns verified stations.
+    pub hide_unverified_stations: bool,
+    /// Resolves client addresses behind the proxies listed in `TRUSTED_PROXIES`.
+    pub client_ip: Arc<ClientIpResolver>,
+    /// Checkout and webhooks for self-service renewals.
+    pub payments: Arc<dyn PaymentProvider>,
+    /// How long a lapsed station keeps read-only dashboard access.
+    pub subscription_grace: chrono::Duration,
+    /// When set, `/stations/closest` lists stations whose subscription lapsed
+    /// after the paid-up ones instead of hiding them.
+    pub demote_lapsed_stations: bool,
+    /// Live events for connected station dashboards.
+    pub events: StationEvents,
+    /// Signs discount QR tokens and checks the ones drivers present.
+    pub discount_tokens: Arc<DiscountTokenSigner>,
 }
 
 impl AppState {
-    pub async fn init() -> sqlx::Result<Self> {
+    pub async fn init() -> anyhow::Result<Self> {
         println!("Attempting to connect with DATABASE_URL");
         let database_url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set");
         let pool = PgPoolOptions::new()
@@ -24,6 +51,33 @@ impl AppState {
         // Run migrations
         sqlx::migrate!("./migrations").run(&pool).await?;
 
-        Ok(Self { pool })
+        let state = Self::with_pool(pool)?;
+        state.events.listen().await?;
+
+        Ok(state)
+    }
+
+    /// Builds the state around an existing pool, reading the remaining
+    /// configuration from the environment. Fails when a required service,
+    /// such as the payment provider or the discount signing key, is not
+    /// configured. Station events only flow once `events.listen()` has been
+    /// awaited.
+    pub fn with_pool(pool: PgPool) -> anyhow::Result<Self> {
+        Ok(Self {
+            events: StationEvents::new(pool.clone()),
+            pool,
+            media: Arc::new(LocalFsStorage::from_env()),
+            documents: Arc::new(LocalFsStorage::private_from_env()),
+            hide_unverified_stations: std::env::var("HIDE_UNVERIFIED_STATIONS")
+                .map(|v| v == "true" || v == "1")
+                .unwrap_or(false),
+            client_ip: Arc::new(ClientIpResolver::from_env()),
+            payments: provider_from_env()?,
+            subscription_grace: grace_period_from_env(),
+            demote_lapsed_stations: std::env::var("DEMOTE_LAPSED_STATIONS")
+                .map(|v| v == "true" || v == "1")
+                .unwrap_or(false),
+            discount_tokens: Arc::new(DiscountTokenSigner::from_env()?),
+        })
     }
 }
@@ -17,4 +17,39 @@ pub struct UpdateCommodityDiscountDto {
     pub commodity_id: Uuid,
     pub enabled: bool,
     pub percentage: Option<i32>,
-}
\ No newline at end of file
+    /// Keeps the station from changing this discount. Defaults to `true`.
+    pub locked: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateDiscountLimitsDto {
+    pub min_percentage: i32,
+    pub max_percentage: i32,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct VoidDiscountCodeDto {
+    pub reason: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AdminRelocationsQuery {
+    pub status: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReviewRelocationDto {
+    pub approved: bool,
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AdminVerificationsQuery {
+    pub status: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReviewVerificationDto {
+    pub approved: bool,
+    pub reason: Option<String>,
+}
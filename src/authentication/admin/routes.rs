@@ -1,4 +1,4 @@
-use axum::{Router, routing::{get, patch}};
+use axum::{Router, routing::{get, patch, post, put}};
 
 use crate::{app_state::AppState, authentication::admin::service::AdminService};
 
@@ -6,8 +6,99 @@ pub fn admin_routes() -> Router<AppState> {
     Router::new()
         .route("/stations", get(AdminService::get_stations))
         .route("/discounts/stats", get(AdminService::get_discount_stats))
+        .route(
+            "/discounts/analytics",
+            get(AdminService::get_discount_analytics),
+        )
+        .route(
+            "/discounts/analytics/refresh",
+            post(AdminService::refresh_discount_analytics),
+        )
         .route(
             "/discounts/{commodity_id}",
             patch(AdminService::update_discount_config),
         )
+        .route(
+            "/discounts/{commodity_id}/changes",
+            get(AdminService::get_discount_changes),
+        )
+        .route(
+            "/discount-codes/{code}/events",
+            get(AdminService::get_discount_code_events),
+        )
+        .route(
+            "/discount-codes/{code}/void",
+            post(AdminService::void_discount_code),
+        )
+        .route(
+            "/stations/{station_id}/discount-limits",
+            put(AdminService::update_discount_limits),
+        )
+        .route(
+            "/stations/{station_id}/discount-suspension",
+            put(AdminService::update_discount_suspension),
+        )
+        .route(
+            "/stations/{station_id}/invoices",
+            post(AdminService::create_invoice),
+        )
+        .route(
+            "/stations/{station_id}/subscription",
+            post(AdminService::update_station_subscription),
+        )
+        .route(
+            "/stations/{station_id}/subscription/history",
+            get(AdminService::get_subscription_history),
+        )
+        .route("/subscription-plans", get(AdminService::get_subscription_plans))
+        .route(
+            "/subscription-plans/{plan_id}",
+            patch(AdminService::update_subscription_plan),
+        )
+        .route("/invoices", get(AdminService::get_invoices))
+        .route("/invoices/{invoice_id}/pay", post(AdminService::pay_invoice))
+        .route("/invoices/{invoice_id}/void", post(AdminService::void_invoice))
+        .route("/fraud/flags", get(AdminService::get_fraud_flags))
+        .route(
+            "/fraud/flags/{flag_id}",
+            patch(AdminService::review_fraud_flag),
+        )
+        .route("/fraud/scan", post(AdminService::run_fraud_scan))
+        .route("/jobs", get(AdminService::get_jobs))
+        .route("/jobs/{job_id}", get(AdminService::get_job))
+        .route("/jobs/{job_id}/retry", post(AdminService::retry_job))
+        .route("/job-schedules", get(AdminService::get_job_schedules))
+        .route("/emails", get(AdminService::get_emails))
+        .route("/emails/{email_id}", get(AdminService::get_email))
+        .route(
+            "/campaigns",
+            get(AdminService::get_campaigns).post(AdminService::create_campaign),
+        )
+        .route(
+            "/campaigns/{campaign_id}",
+            patch(AdminService::update_campaign),
+        )
+        .route(
+            "/broadcasts",
+            get(AdminService::get_broadcasts).post(AdminService::create_broadcast),
+        )
+        .route("/broadcasts/{broadcast_id}", get(AdminService::get_broadcast))
+        .route(
+            "/broadcasts/{broadcast_id}/cancel",
+            post(AdminService::cancel_broadcast),
+        )
+        .route("/relocations", get(AdminService::get_relocation_requests))
+        .route(
+            "/relocations/{request_id}",
+            patch(AdminService::review_relocation_request),
+        )
+        .route("/verifications", get(AdminService::get_verification_requests))
+        .route(
+            "/verifications/{request_id}",
+            patch(AdminService::review_verification_request),
+        )
+        .route(
+            "/verifications/documents/{document_id}",
+            get(AdminService::get_verification_document),
+        )
 }
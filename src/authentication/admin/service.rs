@@ -1,7 +1,7 @@
 use axum::{
     Json,
     extract::{Path, Query, State},
-    http::{HeaderMap, StatusCode},
+    http::{HeaderMap, StatusCode, header},
     response::IntoResponse,
 };
 use bcrypt;
@@ -13,14 +13,61 @@ use uuid::Uuid;
 use crate::{
     app_state::AppState,
     authentication::admin::{
-        dto::{AdminStationsQuery, UpdateCommodityDiscountDto},
+        dto::{
+            AdminRelocationsQuery, AdminStationsQuery, AdminVerificationsQuery,
+            ReviewRelocationDto, ReviewVerificationDto, UpdateCommodityDiscountDto,
+            UpdateDiscountLimitsDto, VoidDiscountCodeDto,
+        },
         model::Admins,
     },
+    domain::analytics::{
+        dto::{DiscountAnalyticsQuery, RefreshAnalyticsQuery, RefreshAnalyticsResponse},
+        service::{discount_analytics, refresh_discount_rollups},
+    },
+    domain::broadcasts::{
+        dto::{BroadcastsQuery, CreateBroadcastDto},
+        service::{cancel_broadcast, create_broadcast, list_broadcasts, load_broadcast},
+    },
+    domain::campaigns::{
+        dto::{CampaignsQuery, CreateCampaignDto, UpdateCampaignDto},
+        service::{create_campaign, list_campaigns, update_campaign},
+    },
     domain::discounts::{
         dto::AdminDiscountStatsResponse,
-        service::admin_discount_stats,
+        service::{
+            DiscountActor, DiscountChangeScope, admin_discount_stats, apply_discount_change,
+            list_code_events, list_discount_changes, upsert_discount_limits, void_code,
+        },
+    },
+    domain::fraud::{
+        dto::{FraudFlagsQuery, ReviewFraudFlagDto, UpdateDiscountSuspensionDto},
+        service::{list_fraud_flags, review_fraud_flag, run_fraud_scan, set_discount_suspension},
+    },
+    domain::emails::{
+        dto::EmailsQuery,
+        service::{find_email, list_emails},
+    },
+    domain::jobs::{
+        dto::JobsQuery,
+        service::{find_job, list_jobs, list_schedules, retry_job},
+    },
+    domain::payments::service::confirm_bank_transfer,
+    domain::stations::service::{list_relocation_requests, review_relocation_request},
+    domain::subscriptions::{
+        dto::{
+            AdminCreateInvoiceDto, InvoicesQuery, SubscriptionActionDto, UpdatePlanDto,
+            VoidInvoiceDto,
+        },
+        lifecycle::{LifecycleAction, SubscriptionActor, apply_action, subscription_history},
+        service::{
+            DEFAULT_INVOICE_DUE_DAYS, current_subscription, issue_invoice, list_invoices,
+            list_plans, update_plan, void_invoice,
+        },
     },
     domain::utils::errors::station_errors::StationError,
+    domain::verification::service::{
+        find_verification_document, list_verification_requests, review_verification_request,
+    },
 };
 
 #[derive(Debug, Serialize, FromRow)]
@@ -106,12 +153,13 @@ impl AdminService {
                 SELECT
                     station_id,
                     COUNT(*)::BIGINT AS created_count,
-                    COUNT(*) FILTER (WHERE redeemed_at IS NOT NULL)::BIGINT AS redeemed_count
+                    COUNT(*) FILTER (WHERE redeemed_at IS NOT NULL AND voided_at IS NULL)::BIGINT
+                        AS redeemed_count
                 FROM discount_codes
                 GROUP BY station_id
             ) dc ON dc.station_id = s.id
             LEFT JOIN subscriptions sub
-                   ON sub.station_id = s.id AND sub.status = 'active'
+                   ON sub.station_id = s.id AND sub.status IN ('active', 'paused')
             ORDER BY s.created_at DESC
             "#,
         )
@@ -160,7 +208,7 @@ impl AdminService {
 
         let admin_id = Self::verify_admin_request(&app_state.pool, &headers).await?;
 
-        if body.enabled {
+        let percentage = if body.enabled {
             let percentage = body.percentage.ok_or_else(|| {
                 StationError::WrongCredentials("percentage is required when enabling discount".to_string())
             })?;
@@ -171,45 +219,363 @@ impl AdminService {
                 ));
             }
 
-            sqlx::query(
-                r#"
-                INSERT INTO commodity_discounts (commodity_id, is_enabled, percentage, updated_by_admin, updated_at)
-                VALUES ($1, TRUE, $2, $3, now())
-                ON CONFLICT (commodity_id)
-                DO UPDATE SET
-                    is_enabled = EXCLUDED.is_enabled,
-                    percentage = EXCLUDED.percentage,
-                    updated_by_admin = EXCLUDED.updated_by_admin,
-                    updated_at = now()
-                "#,
-            )
-            .bind(commodity_id)
-            .bind(percentage)
-            .bind(admin_id)
-            .execute(&app_state.pool)
-            .await
-            .map_err(StationError::DatabaseError)?;
+            Some(percentage)
         } else {
-            sqlx::query(
-                r#"
-                INSERT INTO commodity_discounts (commodity_id, is_enabled, percentage, updated_by_admin, updated_at)
-                VALUES ($1, FALSE, NULL, $2, now())
-                ON CONFLICT (commodity_id)
-                DO UPDATE SET
-                    is_enabled = FALSE,
-                    percentage = NULL,
-                    updated_by_admin = EXCLUDED.updated_by_admin,
-                    updated_at = now()
-                "#,
-            )
-            .bind(commodity_id)
-            .bind(admin_id)
-            .execute(&app_state.pool)
+            None
+        };
+
+        // Admin changes take precedence: unless told otherwise, the station
+        // cannot change this discount until an admin unlocks it.
+        apply_discount_change(
+            &app_state.pool,
+            commodity_id,
+            DiscountActor::Admin(admin_id),
+            percentage,
+            body.locked.unwrap_or(true),
+        )
+        .await?;
+
+        Ok(StatusCode::NO_CONTENT)
+    }
+
+    pub async fn get_discount_changes(
+        State(app_state): State<AppState>,
+        Path(commodity_id): Path<Uuid>,
+        headers: HeaderMap,
+    ) -> Result<impl IntoResponse, StationError> {
+        Self::verify_admin_request(&app_state.pool, &headers).await?;
+
+        let changes =
+            list_discount_changes(&app_state.pool, DiscountChangeScope::Commodity(commodity_id))
+                .await
+                .map_err(StationError::DatabaseError)?;
+
+        Ok((StatusCode::OK, Json(changes)))
+    }
+
+    pub async fn update_discount_limits(
+        State(app_state): State<AppState>,
+        Path(station_id): Path<Uuid>,
+        headers: HeaderMap,
+        Json(body): Json<UpdateDiscountLimitsDto>,
+    ) -> Result<impl IntoResponse, StationError> {
+        let admin_id = Self::verify_admin_request(&app_state.pool, &headers).await?;
+
+        let limits = upsert_discount_limits(
+            &app_state.pool,
+            station_id,
+            admin_id,
+            body.min_percentage,
+            body.max_percentage,
+        )
+        .await?;
+
+        Ok((StatusCode::OK, Json(limits)))
+    }
+
+    pub async fn void_discount_code(
+        State(app_state): State<AppState>,
+        Path(code): Path<String>,
+        headers: HeaderMap,
+        Json(body): Json<VoidDiscountCodeDto>,
+    ) -> Result<impl IntoResponse, StationError> {
+        let admin_id = Self::verify_admin_request(&app_state.pool, &headers).await?;
+
+        let voided = void_code(&app_state.pool, &code, admin_id, &body.reason).await?;
+
+        Ok((StatusCode::OK, Json(voided)))
+    }
+
+    pub async fn get_discount_code_events(
+        State(app_state): State<AppState>,
+        Path(code): Path<String>,
+        headers: HeaderMap,
+    ) -> Result<impl IntoResponse, StationError> {
+        Self::verify_admin_request(&app_state.pool, &headers).await?;
+
+        let events = list_code_events(&app_state.pool, &code).await?;
+
+        Ok((StatusCode::OK, Json(events)))
+    }
+
+    pub async fn get_discount_analytics(
+        State(app_state): State<AppState>,
+        Query(query): Query<DiscountAnalyticsQuery>,
+        headers: HeaderMap,
+    ) -> Result<impl IntoResponse, StationError> {
+        Self::verify_admin_request(&app_state.pool, &headers).await?;
+
+        let analytics = discount_analytics(&app_state.pool, query).await?;
+
+        Ok((StatusCode::OK, Json(analytics)))
+    }
+
+    pub async fn refresh_discount_analytics(
+        State(app_state): State<AppState>,
+        Query(query): Query<RefreshAnalyticsQuery>,
+        headers: HeaderMap,
+    ) -> Result<impl IntoResponse, StationError> {
+        Self::verify_admin_request(&app_state.pool, &headers).await?;
+
+        let refreshed_at = refresh_discount_rollups(&app_state.pool, query.since)
             .await
             .map_err(StationError::DatabaseError)?;
-        }
 
-        Ok(StatusCode::NO_CONTENT)
+        Ok((StatusCode::OK, Json(RefreshAnalyticsResponse { refreshed_at })))
+    }
+
+    pub async fn get_fraud_flags(
+        State(app_state): State<AppState>,
+        Query(query): Query<FraudFlagsQuery>,
+        headers: HeaderMap,
+    ) -> Result<impl IntoResponse, StationError> {
+        Self::verify_admin_request(&app_state.pool, &headers).await?;
+
+        let status = query.status.as_deref().unwrap_or("open");
+        let flags = list_fraud_flags(&app_state.pool, status, query.station_id).await?;
+
+        Ok((StatusCode::OK, Json(flags)))
+    }
+
+    pub async fn review_fraud_flag(
+        State(app_state): State<AppState>,
+        Path(flag_id): Path<Uuid>,
+        headers: HeaderMap,
+        Json(body): Json<ReviewFraudFlagDto>,
+    ) -> Result<impl IntoResponse, StationError> {
+        let admin_id = Self::verify_admin_request(&app_state.pool, &headers).await?;
+
+        let flag =
+            review_fraud_flag(&app_state.pool, flag_id, admin_id, &body.status, body.note).await?;
+
+        Ok((StatusCode::OK, Json(flag)))
+    }
+
+    pub async fn run_fraud_scan(
+        State(app_state): State<AppState>,
+        headers: HeaderMap,
+    ) -> Result<impl IntoResponse, StationError> {
+        Self::verify_admin_request(&app_state.pool, &headers).await?;
+
+        let summary = run_fraud_scan(&app_state.pool)
+            .await
+            .map_err(|err| StationError::WrongCredentials(err.to_string()))?;
+
+        Ok((StatusCode::OK, Json(summary)))
+    }
+
+    pub async fn get_jobs(
+        State(app_state): State<AppState>,
+        Query(query): Query<JobsQuery>,
+        headers: HeaderMap,
+    ) -> Result<impl IntoResponse, StationError> {
+        Self::verify_admin_request(&app_state.pool, &headers).await?;
+
+        let jobs = list_jobs(
+            &app_state.pool,
+            query.status.as_deref(),
+            query.kind.as_deref(),
+        )
+        .await?;
+
+        Ok((StatusCode::OK, Json(jobs)))
+    }
+
+    pub async fn get_job(
+        State(app_state): State<AppState>,
+        Path(job_id): Path<Uuid>,
+        headers: HeaderMap,
+    ) -> Result<impl IntoResponse, StationError> {
+        Self::verify_admin_request(&app_state.pool, &headers).await?;
+
+        let job = find_job(&app_state.pool, job_id).await?;
+
+        Ok((StatusCode::OK, Json(job)))
+    }
+
+    /// Requeues a dead-lettered job with a fresh set of attempts.
+    pub async fn retry_job(
+        State(app_state): State<AppState>,
+        Path(job_id): Path<Uuid>,
+        headers: HeaderMap,
+    ) -> Result<impl IntoResponse, StationError> {
+        Self::verify_admin_request(&app_state.pool, &headers).await?;
+
+        let job = retry_job(&app_state.pool, job_id).await?;
+
+        Ok((StatusCode::OK, Json(job)))
+    }
+
+    pub async fn get_emails(
+        State(app_state): State<AppState>,
+        Query(query): Query<EmailsQuery>,
+        headers: HeaderMap,
+    ) -> Result<impl IntoResponse, StationError> {
+        Self::verify_admin_request(&app_state.pool, &headers).await?;
+
+        let emails = list_emails(
+            &app_state.pool,
+            query.status.as_deref(),
+            query.recipient.as_deref(),
+        )
+        .await?;
+
+        Ok((StatusCode::OK, Json(emails)))
+    }
+
+    pub async fn get_email(
+        State(app_state): State<AppState>,
+        Path(email_id): Path<Uuid>,
+        headers: HeaderMap,
+    ) -> Result<impl IntoResponse, StationError> {
+        Self::verify_admin_request(&app_state.pool, &headers).await?;
+
+        let email = find_email(&app_state.pool, email_id).await?;
+
+        Ok((StatusCode::OK, Json(email)))
+    }
+
+    pub async fn get_job_schedules(
+        State(app_state): State<AppState>,
+        headers: HeaderMap,
+    ) -> Result<impl IntoResponse, StationError> {
+        Self::verify_admin_request(&app_state.pool, &headers).await?;
+
+        let schedules = list_schedules(&app_state.pool).await?;
+
+        Ok((StatusCode::OK, Json(schedules)))
+    }
+
+    pub async fn update_discount_suspension(
+        State(app_state): State<AppState>,
+        Path(station_id): Path<Uuid>,
+        headers: HeaderMap,
+        Json(body): Json<UpdateDiscountSuspensionDto>,
+    ) -> Result<impl IntoResponse, StationError> {
+        Self::verify_admin_request(&app_state.pool, &headers).await?;
+
+        let suspension =
+            set_discount_suspension(&app_state.pool, station_id, body.suspended, body.reason)
+                .await?;
+
+        Ok((StatusCode::OK, Json(suspension)))
+    }
+
+    pub async fn get_subscription_plans(
+        State(app_state): State<AppState>,
+        headers: HeaderMap,
+    ) -> Result<impl IntoResponse, StationError> {
+        Self::verify_admin_request(&app_state.pool, &headers).await?;
+
+        let plans = list_plans(&app_state.pool, false).await?;
+
+        Ok((StatusCode::OK, Json(plans)))
+    }
+
+    pub async fn update_subscription_plan(
+        State(app_state): State<AppState>,
+        Path(plan_id): Path<Uuid>,
+        headers: HeaderMap,
+        Json(body): Json<UpdatePlanDto>,
+    ) -> Result<impl IntoResponse, StationError> {
+        Self::verify_admin_request(&app_state.pool, &headers).await?;
+
+        let plan = update_plan(&app_state.pool, plan_id, body).await?;
+
+        Ok((StatusCode::OK, Json(plan)))
+    }
+
+    pub async fn get_invoices(
+        State(app_state): State<AppState>,
+        Query(query): Query<InvoicesQuery>,
+        headers: HeaderMap,
+    ) -> Result<impl IntoResponse, StationError> {
+        Self::verify_admin_request(&app_state.pool, &headers).await?;
+
+        let invoices =
+            list_invoices(&app_state.pool, query.status.as_deref(), query.station_id).await?;
+
+        Ok((StatusCode::OK, Json(invoices)))
+    }
+
+    pub async fn create_invoice(
+        State(app_state): State<AppState>,
+        Path(station_id): Path<Uuid>,
+        headers: HeaderMap,
+        Json(body): Json<AdminCreateInvoiceDto>,
+    ) -> Result<impl IntoResponse, StationError> {
+        Self::verify_admin_request(&app_state.pool, &headers).await?;
+
+        let invoice = issue_invoice(
+            &app_state.pool,
+            station_id,
+            &body.plan_code,
+            body.due_in_days.unwrap_or(DEFAULT_INVOICE_DUE_DAYS),
+        )
+        .await?;
+
+        Ok((StatusCode::CREATED, Json(invoice)))
+    }
+
+    pub async fn update_station_subscription(
+        State(app_state): State<AppState>,
+        Path(station_id): Path<Uuid>,
+        headers: HeaderMap,
+        Json(body): Json<SubscriptionActionDto>,
+    ) -> Result<impl IntoResponse, StationError> {
+        let admin_id = Self::verify_admin_request(&app_state.pool, &headers).await?;
+
+        let action = LifecycleAction::from_dto(&body)?;
+        apply_action(
+            &app_state.pool,
+            station_id,
+            action,
+            SubscriptionActor::Admin(admin_id),
+            body.reason.as_deref(),
+        )
+        .await?;
+
+        let subscription = current_subscription(&app_state.pool, station_id).await?;
+
+        Ok((StatusCode::OK, Json(subscription)))
+    }
+
+    pub async fn get_subscription_history(
+        State(app_state): State<AppState>,
+        Path(station_id): Path<Uuid>,
+        headers: HeaderMap,
+    ) -> Result<impl IntoResponse, StationError> {
+        Self::verify_admin_request(&app_state.pool, &headers).await?;
+
+        let history = subscription_history(&app_state.pool, station_id).await?;
+
+        Ok((StatusCode::OK, Json(history)))
+    }
+
+    /// Confirms a manual bank transfer against an invoice.
+    pub async fn pay_invoice(
+        State(app_state): State<AppState>,
+        Path(invoice_id): Path<Uuid>,
+        headers: HeaderMap,
+    ) -> Result<impl IntoResponse, StationError> {
+        let admin_id = Self::verify_admin_request(&app_state.pool, &headers).await?;
+
+        let invoice = confirm_bank_transfer(&app_state.pool, invoice_id, admin_id).await?;
+
+        Ok((StatusCode::OK, Json(invoice)))
+    }
+
+    pub async fn void_invoice(
+        State(app_state): State<AppState>,
+        Path(invoice_id): Path<Uuid>,
+        headers: HeaderMap,
+        Json(body): Json<VoidInvoiceDto>,
+    ) -> Result<impl IntoResponse, StationError> {
+        Self::verify_admin_request(&app_state.pool, &headers).await?;
+
+        let invoice = void_invoice(&app_state.pool, invoice_id, &body.reason).await?;
+
+        Ok((StatusCode::OK, Json(invoice)))
     }
 
     pub async fn get_discount_stats(
@@ -227,7 +593,208 @@ impl AdminService {
             Json(AdminDiscountStatsResponse {
                 created_codes: stats.created_codes,
                 redeemed_codes: stats.redeemed_codes,
+                cancelled_codes: stats.cancelled_codes,
+                voided_codes: stats.voided_codes,
+                reversed_redemptions: stats.reversed_redemptions,
             }),
         ))
     }
+
+    pub async fn get_campaigns(
+        State(app_state): State<AppState>,
+        Query(query): Query<CampaignsQuery>,
+        headers: HeaderMap,
+    ) -> Result<impl IntoResponse, StationError> {
+        Self::verify_admin_request(&app_state.pool, &headers).await?;
+
+        let campaigns = list_campaigns(&app_state.pool, query.station_id)
+            .await
+            .map_err(StationError::DatabaseError)?;
+
+        Ok((StatusCode::OK, Json(campaigns)))
+    }
+
+    pub async fn create_campaign(
+        State(app_state): State<AppState>,
+        headers: HeaderMap,
+        Json(body): Json<CreateCampaignDto>,
+    ) -> Result<impl IntoResponse, StationError> {
+        let admin_id = Self::verify_admin_request(&app_state.pool, &headers).await?;
+
+        let campaign = create_campaign(&app_state.pool, body, Some(admin_id)).await?;
+
+        Ok((StatusCode::CREATED, Json(campaign)))
+    }
+
+    pub async fn update_campaign(
+        State(app_state): State<AppState>,
+        Path(campaign_id): Path<Uuid>,
+        headers: HeaderMap,
+        Json(body): Json<UpdateCampaignDto>,
+    ) -> Result<impl IntoResponse, StationError> {
+        Self::verify_admin_request(&app_state.pool, &headers).await?;
+
+        let campaign = update_campaign(&app_state.pool, campaign_id, body).await?;
+
+        Ok((StatusCode::OK, Json(campaign)))
+    }
+
+    pub async fn get_broadcasts(
+        State(app_state): State<AppState>,
+        Query(query): Query<BroadcastsQuery>,
+        headers: HeaderMap,
+    ) -> Result<impl IntoResponse, StationError> {
+        Self::verify_admin_request(&app_state.pool, &headers).await?;
+
+        let broadcasts = list_broadcasts(&app_state.pool, query.status.as_deref()).await?;
+
+        Ok((StatusCode::OK, Json(broadcasts)))
+    }
+
+    pub async fn get_broadcast(
+        State(app_state): State<AppState>,
+        Path(broadcast_id): Path<Uuid>,
+        headers: HeaderMap,
+    ) -> Result<impl IntoResponse, StationError> {
+        Self::verify_admin_request(&app_state.pool, &headers).await?;
+
+        let broadcast = load_broadcast(&app_state.pool, broadcast_id)
+            .await
+            .map_err(StationError::DatabaseError)?
+            .ok_or_else(|| StationError::NotFound(broadcast_id.to_string()))?;
+
+        Ok((StatusCode::OK, Json(broadcast)))
+    }
+
+    pub async fn create_broadcast(
+        State(app_state): State<AppState>,
+        headers: HeaderMap,
+        Json(body): Json<CreateBroadcastDto>,
+    ) -> Result<impl IntoResponse, StationError> {
+        let admin_id = Self::verify_admin_request(&app_state.pool, &headers).await?;
+
+        let broadcast = create_broadcast(&app_state.pool, body, Some(admin_id)).await?;
+
+        Ok((StatusCode::CREATED, Json(broadcast)))
+    }
+
+    pub async fn cancel_broadcast(
+        State(app_state): State<AppState>,
+        Path(broadcast_id): Path<Uuid>,
+        headers: HeaderMap,
+    ) -> Result<impl IntoResponse, StationError> {
+        Self::verify_admin_request(&app_state.pool, &headers).await?;
+
+        let broadcast = cancel_broadcast(&app_state.pool, broadcast_id).await?;
+
+        Ok((StatusCode::OK, Json(broadcast)))
+    }
+
+    pub async fn get_relocation_requests(
+        State(app_state): State<AppState>,
+        Query(query): Query<AdminRelocationsQuery>,
+        headers: HeaderMap,
+    ) -> Result<impl IntoResponse, StationError> {
+        Self::verify_admin_request(&app_state.pool, &headers).await?;
+
+        let status = query.status.as_deref().unwrap_or("pending");
+        let status = (status != "all").then_some(status);
+
+        let requests = list_relocation_requests(&app_state.pool, status)
+            .await
+            .map_err(StationError::DatabaseError)?;
+
+        Ok((StatusCode::OK, Json(requests)))
+    }
+
+    pub async fn review_relocation_request(
+        State(app_state): State<AppState>,
+        Path(request_id): Path<Uuid>,
+        headers: HeaderMap,
+        Json(body): Json<ReviewRelocationDto>,
+    ) -> Result<impl IntoResponse, StationError> {
+        let admin_id = Self::verify_admin_request(&app_state.pool, &headers).await?;
+
+        let reason = body
+            .reason
+            .map(|r| r.trim().to_string())
+            .filter(|r| !r.is_empty());
+
+        let request =
+            review_relocation_request(&app_state.pool, request_id, admin_id, body.approved, reason)
+                .await?;
+
+        Ok((StatusCode::OK, Json(request)))
+    }
+
+    pub async fn get_verification_requests(
+        State(app_state): State<AppState>,
+        Query(query): Query<AdminVerificationsQuery>,
+        headers: HeaderMap,
+    ) -> Result<impl IntoResponse, StationError> {
+        Self::verify_admin_request(&app_state.pool, &headers).await?;
+
+        let status = query.status.as_deref().unwrap_or("pending");
+        let status = (status != "all").then_some(status);
+
+        let requests = list_verification_requests(&app_state.pool, status)
+            .await
+            .map_err(StationError::DatabaseError)?;
+
+        Ok((StatusCode::OK, Json(requests)))
+    }
+
+    pub async fn review_verification_request(
+        State(app_state): State<AppState>,
+        Path(request_id): Path<Uuid>,
+        headers: HeaderMap,
+        Json(body): Json<ReviewVerificationDto>,
+    ) -> Result<impl IntoResponse, StationError> {
+        let admin_id = Self::verify_admin_request(&app_state.pool, &headers).await?;
+
+        let reason = body
+            .reason
+            .map(|r| r.trim().to_string())
+            .filter(|r| !r.is_empty());
+
+        let request = review_verification_request(
+            &app_state.pool,
+            request_id,
+            admin_id,
+            body.approved,
+            reason,
+        )
+        .await?;
+
+        Ok((StatusCode::OK, Json(request)))
+    }
+
+    pub async fn get_verification_document(
+        State(app_state): State<AppState>,
+        Path(document_id): Path<Uuid>,
+        headers: HeaderMap,
+    ) -> Result<impl IntoResponse, StationError> {
+        Self::verify_admin_request(&app_state.pool, &headers).await?;
+
+        let document = find_verification_document(&app_state.pool, document_id)
+            .await
+            .map_err(StationError::DatabaseError)?
+            .ok_or_else(|| StationError::NotFound(document_id.to_string()))?;
+
+        let bytes = app_state
+            .documents
+            .get(&document.storage_key)
+            .await
+            .map_err(|err| {
+                StationError::Internal(format!(
+                    "failed to read verification document {document_id}: {err}"
+                ))
+            })?;
+
+        Ok((
+            StatusCode::OK,
+            [(header::CONTENT_TYPE, document.content_type)],
+            bytes,
+        ))
+    }
 }
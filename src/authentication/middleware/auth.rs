@@ -1,13 +1,23 @@
-use axum::{extract::Request, middleware::Next, response::Response};
+use axum::{extract::Request, http::HeaderMap, middleware::Next, response::Response};
 
 use crate::{
-    authentication::station::authenticate::token::service::TokenService,
+    authentication::station::authenticate::token::service::{Claims, TokenService},
     domain::utils::errors::station_errors::StationError,
 };
 
 pub async fn authorize(mut request: Request, next: Next) -> Result<Response, StationError> {
-    let auth_header = request
-        .headers()
+    let claims = claims_from_headers(request.headers())?;
+    //TODO retrieve station by id(columns role, is_logged_in).
+    //If token expired, id not found or false to is_logged_in throw StationError::WrongCredentials()
+    //
+    request.extensions_mut().insert(claims);
+
+    Ok(next.run(request).await)
+}
+
+/// Decodes the station's bearer token.
+pub fn claims_from_headers(headers: &HeaderMap) -> Result<Claims, StationError> {
+    let auth_header = headers
         .get(axum::http::header::AUTHORIZATION)
         .and_then(|h| h.to_str().ok());
 
@@ -26,14 +36,8 @@ pub async fn authorize(mut request: Request, next: Next) -> Result<Response, Sta
     let secret = std::env::var("JWT_SECRET").expect("JWT_SECRET not set");
     let token_service = TokenService::new(&secret);
     let decoded = token_service.decode(access_token);
-    let claims = match decoded {
-        Ok(decoded) => decoded.claims,
-        Err(_) => return Err(StationError::WrongCredentials(String::from("token"))),
-    };
-    //TODO retrieve station by id(columns role, is_logged_in).
-    //If token expired, id not found or false to is_logged_in throw StationError::WrongCredentials()
-    //
-    request.extensions_mut().insert(claims);
-
-    Ok(next.run(request).await)
+    match decoded {
+        Ok(decoded) => Ok(decoded.claims),
+        Err(_) => Err(StationError::WrongCredentials(String::from("token"))),
+    }
 }
@@ -0,0 +1,59 @@
+use axum::{
+    extract::{Request, State},
+    middleware::Next,
+    response::Response,
+};
+
+use crate::{
+    app_state::AppState,
+    authentication::middleware::auth::claims_from_headers,
+    domain::{
+        subscriptions::entitlements::{AccessLevel, station_entitlements},
+        utils::errors::station_errors::StationError,
+    },
+};
+
+/// Looks up the subscription behind a station bearer token and enforces
+/// it: a lapsed station is blocked, one in its grace period may only read.
+/// The [`Entitlements`](crate::domain::subscriptions::entitlements::Entitlements)
+/// are left in the request extensions for handlers that gate plan features.
+///
+/// Requests without a valid token pass straight through; `authorize`
+/// decides what to do with them. Applied as a route layer, so billing routes
+/// are simply left off it.
+pub async fn enforce_entitlements(
+    State(app_state): State<AppState>,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, StationError> {
+    let Ok(claims) = claims_from_headers(request.headers()) else {
+        return Ok(next.run(request).await);
+    };
+
+    let entitlements = station_entitlements(
+        &app_state.pool,
+        claims.station_res.id,
+        app_state.subscription_grace,
+    )
+    .await
+    .map_err(StationError::DatabaseError)?;
+
+    match entitlements.access {
+        AccessLevel::Full => {}
+        AccessLevel::ReadOnly if request.method().is_safe() => {}
+        AccessLevel::ReadOnly => {
+            return Err(StationError::WrongCredentials(
+                "subscription expired; the dashboard is read-only until you renew".to_string(),
+            ));
+        }
+        AccessLevel::Lapsed => {
+            return Err(StationError::WrongCredentials(
+                "subscription expired".to_string(),
+            ));
+        }
+    }
+
+    request.extensions_mut().insert(entitlements);
+
+    Ok(next.run(request).await)
+}
@@ -1,2 +1,3 @@
 pub mod auth;
 pub mod authorize_role;
+pub mod entitlements;
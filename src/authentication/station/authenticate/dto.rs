@@ -23,8 +23,8 @@ pub struct CreateStationDto {
 
 #[derive(Debug, Deserialize)]
 pub struct RenewSubscriptionDto {
-    pub station_id: Uuid,
-    pub days: i64,
+    /// The open invoice being paid; its plan decides the renewal period.
+    pub invoice_id: Uuid,
     pub super_password: String,
 }
 
@@ -9,10 +9,11 @@ use crate::{
     domain::{
         commodities::model::Commodity,
         registration_code::dto::CodeCreatedMessage,
-        stations::model::Station,
-        subscriptions::service::{
-            create_expired_signin_notification, create_trial_subscription,
-            is_station_subscription_expired, renew_subscription_manual,
+        stations::{model::Station, service::hydrate_station_responses},
+        payments::service::confirm_bank_transfer,
+        subscriptions::{
+            entitlements::{AccessLevel, station_entitlements},
+            service::{create_expired_signin_notification, create_trial_subscription},
         },
         utils::{errors::station_errors::StationError, schemas::{CommoditiesResponse, StationResponse, StationWithCommodity, map_rows_to_stations}}
     },
@@ -135,6 +136,11 @@ impl Authentication {
             discount_enabled: None,
             discount_percentage: None,
         }];
+
+        hydrate_station_responses(&app_state, std::slice::from_mut(&mut new_station))
+            .await
+            .map_err(StationError::DatabaseError)?;
+
         Ok((StatusCode::CREATED, Json(new_station)))
     }
 
@@ -189,11 +195,17 @@ impl Authentication {
 
             Ok(true) => {
                 let station_id = rows[0].id;
-                let is_expired = is_station_subscription_expired(&app_state.pool, station_id)
-                    .await
-                    .map_err(|err| StationError::WrongCredentials(err.to_string()))?;
+                let entitlements = station_entitlements(
+                    &app_state.pool,
+                    station_id,
+                    app_state.subscription_grace,
+                )
+                .await
+                .map_err(StationError::DatabaseError)?;
 
-                if is_expired {
+                // Within the grace period the station may still sign in to a
+                // read-only dashboard and renew.
+                if entitlements.access == AccessLevel::Lapsed {
                     create_expired_signin_notification(&app_state.pool, station_id)
                         .await
                         .map_err(|err| StationError::WrongCredentials(err.to_string()))?;
@@ -241,8 +253,7 @@ impl Authentication {
         Json(body): Json<RenewSubscriptionDto>,
     ) -> Result<impl IntoResponse, StationError> {
         let RenewSubscriptionDto {
-            station_id,
-            days,
+            invoice_id,
             super_password,
         } = body;
 
@@ -267,9 +278,7 @@ impl Authentication {
             return Err(StationError::WrongCredentials("admin password".to_string()));
         }
 
-        renew_subscription_manual(&app_state.pool, station_id, admin.id, days)
-            .await
-            .map_err(|err| StationError::WrongCredentials(err.to_string()))?;
+        confirm_bank_transfer(&app_state.pool, invoice_id, admin.id).await?;
 
         Ok((
             StatusCode::OK,
@@ -0,0 +1,7 @@
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateAmenitiesDto {
+    pub amenities: Vec<String>,
+    pub payment_methods: Vec<String>,
+}
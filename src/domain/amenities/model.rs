@@ -0,0 +1,26 @@
+use serde::Serialize;
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Amenity keys accepted in `stations.amenities` (mirrors the table's check constraint).
+pub const AMENITIES: [&str; 6] = [
+    "atm",
+    "shop",
+    "air_pump",
+    "open_24_hours",
+    "car_wash",
+    "restroom",
+];
+
+/// Derived from the weekly opening hours rather than set by the station.
+pub const OPEN_24_HOURS: &str = "open_24_hours";
+
+/// Payment method keys accepted in `stations.payment_methods`.
+pub const PAYMENT_METHODS: [&str; 3] = ["cash", "transfer", "pos"];
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct StationAmenities {
+    pub station_id: Uuid,
+    pub amenities: Vec<String>,
+    pub payment_methods: Vec<String>,
+}
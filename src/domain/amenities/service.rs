@@ -0,0 +1,181 @@
+use std::collections::HashMap;
+
+use axum::{
+    Json,
+    extract::{Extension, State},
+};
+use sqlx::{PgExecutor, PgPool};
+use uuid::Uuid;
+
+use crate::{
+    app_state::AppState,
+    authentication::station::authenticate::token::service::Claims,
+    domain::{
+        amenities::{
+            dto::UpdateAmenitiesDto,
+            model::{AMENITIES, OPEN_24_HOURS, PAYMENT_METHODS, StationAmenities},
+        },
+        utils::{errors::station_errors::StationError, schemas::StationResponse},
+    },
+};
+
+pub struct AmenitiesService;
+
+impl AmenitiesService {
+    pub async fn update_amenities(
+        State(app_state): State<AppState>,
+        Extension(claims): Extension<Claims>,
+        Json(body): Json<UpdateAmenitiesDto>,
+    ) -> Result<Json<StationAmenities>, StationError> {
+        let station_id = claims.station_res.id;
+
+        let amenities = normalize_keys(body.amenities, &AMENITIES, "amenity")?;
+        let payment_methods = normalize_keys(body.payment_methods, &PAYMENT_METHODS, "payment method")?;
+
+        if amenities.iter().any(|amenity| amenity == OPEN_24_HOURS) {
+            return Err(StationError::WrongCredentials(format!(
+                "{OPEN_24_HOURS} is set from the opening hours, not the amenities list"
+            )));
+        }
+
+        let updated = sqlx::query_as::<_, StationAmenities>(&format!(
+            r#"
+            UPDATE stations
+            SET amenities = {}, payment_methods = $2, updated_at = NOW()
+            WHERE id = $3
+            RETURNING id AS station_id, amenities, payment_methods
+            "#,
+            derived_amenities("$1::text[]", "$3")
+        ))
+        .bind(&amenities)
+        .bind(&payment_methods)
+        .bind(station_id)
+        .fetch_optional(&app_state.pool)
+        .await
+        .map_err(StationError::DatabaseError)?
+        .ok_or_else(|| StationError::NotFound(station_id.to_string()))?;
+
+        Ok(Json(updated))
+    }
+}
+
+/// SQL for `base` plus `open_24_hours` when the station's weekly schedule
+/// covers all seven days around the clock (opens_at = closes_at), sorted like
+/// `normalize_keys` output.
+fn derived_amenities(base: &str, station_id: &str) -> String {
+    format!(
+        r#"
+        ARRAY(
+            SELECT DISTINCT amenity
+            FROM unnest(
+                array_remove({base}, 'open_24_hours') || CASE
+                    WHEN (
+                        SELECT COUNT(*) FROM station_opening_hours h
+                        WHERE h.station_id = {station_id} AND h.opens_at = h.closes_at
+                    ) = 7 THEN ARRAY['open_24_hours']
+                    ELSE ARRAY[]::text[]
+                END
+            ) AS amenity
+            ORDER BY amenity
+        )
+        "#
+    )
+}
+
+/// Re-derives `open_24_hours` after the station's opening hours change.
+pub async fn sync_open_24_hours<'e, E: PgExecutor<'e>>(
+    executor: E,
+    station_id: Uuid,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(&format!(
+        "UPDATE stations SET amenities = {} WHERE id = $1",
+        derived_amenities("amenities", "$1")
+    ))
+    .bind(station_id)
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
+
+/// Trims, lowercases, de-duplicates and validates a list of amenity or
+/// payment-method keys against `allowed`.
+fn normalize_keys(
+    keys: Vec<String>,
+    allowed: &[&str],
+    label: &str,
+) -> Result<Vec<String>, StationError> {
+    let mut normalized = Vec::with_capacity(keys.len());
+
+    for key in keys {
+        let key = key.trim().to_ascii_lowercase();
+
+        if !allowed.contains(&key.as_str()) {
+            return Err(StationError::WrongCredentials(format!(
+                "unknown {label} '{key}', expected one of: {}",
+                allowed.join(", ")
+            )));
+        }
+
+        if !normalized.contains(&key) {
+            normalized.push(key);
+        }
+    }
+
+    normalized.sort();
+    Ok(normalized)
+}
+
+/// Parses a comma-separated query filter such as `atm,shop` into validated keys.
+pub fn parse_filter(
+    raw: Option<&str>,
+    allowed: &[&str],
+    label: &str,
+) -> Result<Vec<String>, StationError> {
+    let keys = raw
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|k| !k.is_empty())
+        .map(ToString::to_string)
+        .collect();
+
+    normalize_keys(keys, allowed, label)
+}
+
+/// Fills in `amenities` and `payment_methods` on each station.
+pub async fn attach_amenities(
+    pool: &PgPool,
+    stations: &mut [StationResponse],
+) -> Result<(), sqlx::Error> {
+    if stations.is_empty() {
+        return Ok(());
+    }
+
+    let ids: Vec<Uuid> = stations.iter().map(|s| s.id).collect();
+
+    let rows = sqlx::query_as::<_, StationAmenities>(
+        r#"
+        SELECT id AS station_id, amenities, payment_methods
+        FROM stations
+        WHERE id = ANY($1)
+        "#,
+    )
+    .bind(&ids)
+    .fetch_all(pool)
+    .await?;
+
+    let mut rows: HashMap<Uuid, StationAmenities> = rows
+        .into_iter()
+        .map(|row| (row.station_id, row))
+        .collect();
+
+    for station in stations.iter_mut() {
+        if let Some(row) = rows.remove(&station.id) {
+            station.amenities = row.amenities;
+            station.payment_methods = row.payment_methods;
+        }
+    }
+
+    Ok(())
+}
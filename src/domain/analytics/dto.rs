@@ -0,0 +1,52 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::model::{AnalyticsBucket, HeatmapCell, TopStation};
+
+#[derive(Debug, Default, Deserialize)]
+pub struct DiscountAnalyticsQuery {
+    /// `daily` (default), `weekly` or `monthly`.
+    pub period: Option<String>,
+    /// Inclusive date range in Africa/Lagos time; defaults to the last 30 days.
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    /// Ignored for stations, which only ever see their own figures.
+    pub station_id: Option<Uuid>,
+    pub commodity_id: Option<Uuid>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RefreshAnalyticsQuery {
+    /// Rebuild buckets from this time on; everything when omitted.
+    pub since: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AnalyticsBucketResponse {
+    #[serde(flatten)]
+    pub bucket: AnalyticsBucket,
+    pub redemption_rate: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DiscountAnalyticsResponse {
+    pub period: &'static str,
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub codes_created: i64,
+    pub codes_redeemed: i64,
+    /// Redeemed codes as a share of created codes, between 0 and 1.
+    pub redemption_rate: f64,
+    pub naira_discounted: i64,
+    pub series: Vec<AnalyticsBucketResponse>,
+    pub heatmap: Vec<HeatmapCell>,
+    pub top_stations: Vec<TopStation>,
+    /// When the rollup the figures come from was last rebuilt.
+    pub refreshed_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RefreshAnalyticsResponse {
+    pub refreshed_at: DateTime<Utc>,
+}
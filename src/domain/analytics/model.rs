@@ -0,0 +1,36 @@
+use chrono::NaiveDate;
+use serde::Serialize;
+use sqlx::FromRow;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct AnalyticsTotals {
+    pub codes_created: i64,
+    pub codes_redeemed: i64,
+    pub naira_discounted: i64,
+}
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct AnalyticsBucket {
+    /// First day of the day, ISO week or month, in Africa/Lagos time.
+    pub bucket_start: NaiveDate,
+    pub codes_created: i64,
+    pub codes_redeemed: i64,
+    pub naira_discounted: i64,
+}
+
+/// Redemptions by ISO weekday (1 = Monday) and hour of day.
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct HeatmapCell {
+    pub day_of_week: i32,
+    pub hour: i32,
+    pub codes_redeemed: i64,
+}
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct TopStation {
+    pub station_id: Uuid,
+    pub station_name: String,
+    pub codes_redeemed: i64,
+    pub naira_discounted: i64,
+}
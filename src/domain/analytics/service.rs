@@ -0,0 +1,275 @@
+use axum::{
+    Json,
+    extract::{Extension, Query, State},
+};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use sqlx::PgPool;
+
+use crate::{
+    app_state::AppState,
+    authentication::station::authenticate::token::service::Claims,
+    domain::{
+        analytics::{
+            dto::{AnalyticsBucketResponse, DiscountAnalyticsQuery, DiscountAnalyticsResponse},
+            model::{AnalyticsBucket, AnalyticsTotals, HeatmapCell, TopStation},
+        },
+        discounts::service::MAX_SYNC_AGE_DAYS,
+        subscriptions::entitlements::{Entitlements, Feature},
+        utils::errors::station_errors::StationError,
+    },
+};
+
+const DEFAULT_RANGE_DAYS: i64 = 30;
+const MAX_RANGE_DAYS: i64 = 366;
+const TOP_STATIONS_LIMIT: i64 = 10;
+
+/// Shared filter for every analytics query: $1 from, $2 to (inclusive),
+/// $3 station, $4 commodity.
+const ROLLUP_FILTER: &str = r#"
+    r.bucket_start >= $1::date
+    AND r.bucket_start < $2::date + 1
+    AND ($3::uuid IS NULL OR r.station_id = $3)
+    AND ($4::uuid IS NULL OR r.commodity_id = $4)
+"#;
+
+#[derive(Debug, Clone, Copy)]
+enum AnalyticsPeriod {
+    Daily,
+    Weekly,
+    Monthly,
+}
+
+impl AnalyticsPeriod {
+    fn parse(value: Option<&str>) -> Result<Self, StationError> {
+        match value.unwrap_or("daily") {
+            "daily" => Ok(Self::Daily),
+            "weekly" => Ok(Self::Weekly),
+            "monthly" => Ok(Self::Monthly),
+            _ => Err(StationError::WrongCredentials(
+                "period must be one of: daily, weekly, monthly".to_string(),
+            )),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Daily => "daily",
+            Self::Weekly => "weekly",
+            Self::Monthly => "monthly",
+        }
+    }
+
+    /// Unit understood by Postgres `date_trunc` and intervals.
+    fn unit(self) -> &'static str {
+        match self {
+            Self::Daily => "day",
+            Self::Weekly => "week",
+            Self::Monthly => "month",
+        }
+    }
+}
+
+pub struct AnalyticsService;
+
+impl AnalyticsService {
+    pub async fn station_analytics(
+        State(app_state): State<AppState>,
+        Extension(claims): Extension<Claims>,
+        Extension(entitlements): Extension<Entitlements>,
+        Query(query): Query<DiscountAnalyticsQuery>,
+    ) -> Result<Json<DiscountAnalyticsResponse>, StationError> {
+        entitlements.require(Feature::Analytics)?;
+
+        let query = DiscountAnalyticsQuery {
+            station_id: Some(claims.station_res.id),
+            ..query
+        };
+
+        Ok(Json(discount_analytics(&app_state.pool, query).await?))
+    }
+}
+
+fn redemption_rate(created: i64, redeemed: i64) -> f64 {
+    if created == 0 {
+        0.0
+    } else {
+        redeemed as f64 / created as f64
+    }
+}
+
+pub async fn discount_analytics(
+    pool: &PgPool,
+    query: DiscountAnalyticsQuery,
+) -> Result<DiscountAnalyticsResponse, StationError> {
+    let period = AnalyticsPeriod::parse(query.period.as_deref())?;
+
+    let today: NaiveDate = sqlx::query_scalar("SELECT (now() AT TIME ZONE 'Africa/Lagos')::date")
+        .fetch_one(pool)
+        .await
+        .map_err(StationError::DatabaseError)?;
+
+    let to = query.to.unwrap_or(today);
+    let from = query
+        .from
+        .unwrap_or(to - Duration::days(DEFAULT_RANGE_DAYS - 1));
+
+    if from > to || (to - from).num_days() >= MAX_RANGE_DAYS {
+        return Err(StationError::WrongCredentials(format!(
+            "from must not be after to, and the range may span at most {MAX_RANGE_DAYS} days"
+        )));
+    }
+
+    let totals = sqlx::query_as::<_, AnalyticsTotals>(&format!(
+        r#"
+        SELECT
+            COALESCE(SUM(r.codes_created), 0)::BIGINT AS codes_created,
+            COALESCE(SUM(r.codes_redeemed), 0)::BIGINT AS codes_redeemed,
+            COALESCE(SUM(r.naira_discounted), 0)::BIGINT AS naira_discounted
+        FROM discount_hourly_rollups r
+        WHERE {ROLLUP_FILTER}
+        "#
+    ))
+    .bind(from)
+    .bind(to)
+    .bind(query.station_id)
+    .bind(query.commodity_id)
+    .fetch_one(pool)
+    .await
+    .map_err(StationError::DatabaseError)?;
+
+    // Empty buckets are filled in so charts don't have to.
+    let series = sqlx::query_as::<_, AnalyticsBucket>(&format!(
+        r#"
+        SELECT
+            buckets.bucket_start::date AS bucket_start,
+            COALESCE(activity.codes_created, 0)::BIGINT AS codes_created,
+            COALESCE(activity.codes_redeemed, 0)::BIGINT AS codes_redeemed,
+            COALESCE(activity.naira_discounted, 0)::BIGINT AS naira_discounted
+        FROM generate_series(
+            date_trunc($5, $1::date::timestamp),
+            $2::date::timestamp,
+            ('1 ' || $5)::interval
+        ) AS buckets (bucket_start)
+        LEFT JOIN (
+            SELECT
+                date_trunc($5, r.bucket_start) AS bucket_start,
+                SUM(r.codes_created) AS codes_created,
+                SUM(r.codes_redeemed) AS codes_redeemed,
+                SUM(r.naira_discounted) AS naira_discounted
+            FROM discount_hourly_rollups r
+            WHERE {ROLLUP_FILTER}
+            GROUP BY 1
+        ) activity ON activity.bucket_start = buckets.bucket_start
+        ORDER BY buckets.bucket_start
+        "#
+    ))
+    .bind(from)
+    .bind(to)
+    .bind(query.station_id)
+    .bind(query.commodity_id)
+    .bind(period.unit())
+    .fetch_all(pool)
+    .await
+    .map_err(StationError::DatabaseError)?;
+
+    let heatmap = sqlx::query_as::<_, HeatmapCell>(&format!(
+        r#"
+        SELECT
+            EXTRACT(ISODOW FROM r.bucket_start)::INTEGER AS day_of_week,
+            EXTRACT(HOUR FROM r.bucket_start)::INTEGER AS hour,
+            SUM(r.codes_redeemed)::BIGINT AS codes_redeemed
+        FROM discount_hourly_rollups r
+        WHERE {ROLLUP_FILTER}
+        GROUP BY 1, 2
+        HAVING SUM(r.codes_redeemed) > 0
+        ORDER BY 1, 2
+        "#
+    ))
+    .bind(from)
+    .bind(to)
+    .bind(query.station_id)
+    .bind(query.commodity_id)
+    .fetch_all(pool)
+    .await
+    .map_err(StationError::DatabaseError)?;
+
+    let top_stations = sqlx::query_as::<_, TopStation>(&format!(
+        r#"
+        SELECT
+            s.id AS station_id,
+            s.name AS station_name,
+            SUM(r.codes_redeemed)::BIGINT AS codes_redeemed,
+            SUM(r.naira_discounted)::BIGINT AS naira_discounted
+        FROM discount_hourly_rollups r
+        JOIN stations s ON s.id = r.station_id
+        WHERE {ROLLUP_FILTER}
+        GROUP BY s.id, s.name
+        HAVING SUM(r.codes_redeemed) > 0
+        ORDER BY codes_redeemed DESC, naira_discounted DESC, s.name
+        LIMIT $5
+        "#
+    ))
+    .bind(from)
+    .bind(to)
+    .bind(query.station_id)
+    .bind(query.commodity_id)
+    .bind(TOP_STATIONS_LIMIT)
+    .fetch_all(pool)
+    .await
+    .map_err(StationError::DatabaseError)?;
+
+    let refreshed_at: Option<DateTime<Utc>> =
+        sqlx::query_scalar("SELECT refreshed_at FROM discount_rollup_refreshes")
+            .fetch_optional(pool)
+            .await
+            .map_err(StationError::DatabaseError)?;
+
+    Ok(DiscountAnalyticsResponse {
+        period: period.name(),
+        from,
+        to,
+        codes_created: totals.codes_created,
+        codes_redeemed: totals.codes_redeemed,
+        redemption_rate: redemption_rate(totals.codes_created, totals.codes_redeemed),
+        naira_discounted: totals.naira_discounted,
+        series: series
+            .into_iter()
+            .map(|bucket| AnalyticsBucketResponse {
+                redemption_rate: redemption_rate(bucket.codes_created, bucket.codes_redeemed),
+                bucket,
+            })
+            .collect(),
+        heatmap,
+        top_stations,
+        refreshed_at,
+    })
+}
+
+/// Rebuilds the hourly rollup from `since` onwards, or entirely when `None`.
+pub async fn refresh_discount_rollups(
+    pool: &PgPool,
+    since: Option<DateTime<Utc>>,
+) -> Result<DateTime<Utc>, sqlx::Error> {
+    sqlx::query_scalar("SELECT refresh_discount_rollups(COALESCE($1, '-infinity'))")
+        .bind(since)
+        .fetch_one(pool)
+        .await
+}
+
+/// Days of buckets rebuilt by the scheduled refresh: every bucket an offline
+/// sync can still change, plus a day of slack for the refresh interval.
+/// Admins can rebuild further back on demand.
+const SCHEDULED_REFRESH_DAYS: i64 = MAX_SYNC_AGE_DAYS + 1;
+
+/// Rebuilds the recent buckets, or everything on the first run so existing
+/// history is rolled up without the migration having to do it.
+pub async fn refresh_recent_rollups(pool: &PgPool) -> Result<DateTime<Utc>, sqlx::Error> {
+    let last_refresh: Option<DateTime<Utc>> =
+        sqlx::query_scalar("SELECT refreshed_at FROM discount_rollup_refreshes")
+            .fetch_optional(pool)
+            .await?;
+
+    let since = last_refresh.map(|_| Utc::now() - Duration::days(SCHEDULED_REFRESH_DAYS));
+
+    refresh_discount_rollups(pool, since).await
+}
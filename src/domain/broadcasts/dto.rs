@@ -0,0 +1,26 @@
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use uuid::Uuid;
+
+/// Only the fields for the chosen `audience` are used: `station_type`,
+/// `latitude`/`longitude`/`radius_km` for `area`, or `station_ids`.
+#[derive(Debug, Deserialize)]
+pub struct CreateBroadcastDto {
+    pub title: String,
+    pub body: String,
+    pub audience: String,
+    pub station_type: Option<String>,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    pub radius_km: Option<f64>,
+    pub station_ids: Option<Vec<Uuid>>,
+    #[serde(default)]
+    pub send_email: bool,
+    /// Defaults to now.
+    pub scheduled_for: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BroadcastsQuery {
+    pub status: Option<String>,
+}
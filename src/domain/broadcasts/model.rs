@@ -0,0 +1,50 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::FromRow;
+use uuid::Uuid;
+
+pub const BROADCAST_AUDIENCES: [&str; 4] = ["all", "station_type", "area", "stations"];
+pub const BROADCAST_STATUSES: [&str; 3] = ["scheduled", "sent", "cancelled"];
+pub const STATION_TYPES: [&str; 2] = ["petrol", "gas"];
+
+/// Dashboard notification kind for broadcasts.
+pub const ANNOUNCEMENT_KIND: &str = "announcement";
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct Broadcast {
+    pub id: Uuid,
+    pub title: String,
+    pub body: String,
+    pub audience: String,
+    pub station_type: Option<String>,
+    pub area_latitude: Option<f64>,
+    pub area_longitude: Option<f64>,
+    pub area_radius_km: Option<f64>,
+    pub station_ids: Option<Vec<Uuid>>,
+    pub send_email: bool,
+    pub status: String,
+    pub scheduled_for: DateTime<Utc>,
+    pub sent_at: Option<DateTime<Utc>>,
+    pub created_by_admin: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A broadcast with how far it got.
+///
+/// `read_rate` is the share of dashboard deliveries that were read; a
+/// station that deleted the notice unread, or muted announcements, does
+/// not count as a read.
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct BroadcastReport {
+    #[serde(flatten)]
+    #[sqlx(flatten)]
+    pub broadcast: Broadcast,
+    pub recipients: i64,
+    pub in_app_delivered: i64,
+    pub read_count: i64,
+    pub read_rate: f64,
+    pub emails_queued: i64,
+    pub emails_sent: i64,
+    pub emails_failed: i64,
+}
@@ -0,0 +1,376 @@
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sqlx::{PgExecutor, PgPool};
+use uuid::Uuid;
+
+use crate::domain::{
+    broadcasts::{
+        dto::CreateBroadcastDto,
+        model::{
+            ANNOUNCEMENT_KIND, BROADCAST_AUDIENCES, BROADCAST_STATUSES, Broadcast, BroadcastReport,
+            STATION_TYPES,
+        },
+    },
+    jobs::{
+        service::{EnqueueOptions, enqueue},
+        worker::SEND_BROADCAST_JOB,
+    },
+    notifications::{
+        channels::Channel,
+        service::{Notice, notify_station_via},
+    },
+    utils::errors::station_errors::StationError,
+};
+
+const MAX_TITLE_LENGTH: usize = 200;
+const MAX_LISTED_STATIONS: usize = 1000;
+/// Stations delivered to per transaction when sending a broadcast.
+const DELIVERY_BATCH_SIZE: i64 = 200;
+
+const BROADCAST_COLUMNS: &str = r#"
+    b.id, b.title, b.body, b.audience, b.station_type, b.area_latitude, b.area_longitude,
+    b.area_radius_km, b.station_ids, b.send_email, b.status, b.scheduled_for, b.sent_at,
+    b.created_by_admin, b.created_at, b.updated_at
+"#;
+
+fn report_query(filter: &str) -> String {
+    format!(
+        r#"
+        SELECT
+            {BROADCAST_COLUMNS},
+            delivery.recipients,
+            delivery.in_app_delivered,
+            delivery.read_count,
+            COALESCE(
+                delivery.read_count::float8 / NULLIF(delivery.in_app_delivered, 0),
+                0
+            ) AS read_rate,
+            delivery.emails_queued,
+            delivery.emails_sent,
+            delivery.emails_failed
+        FROM broadcasts b
+        CROSS JOIN LATERAL (
+            SELECT
+                COUNT(*) AS recipients,
+                COUNT(*) FILTER (WHERE d.in_app) AS in_app_delivered,
+                COUNT(*) FILTER (WHERE n.is_read) AS read_count,
+                COUNT(d.email_id) AS emails_queued,
+                COUNT(*) FILTER (WHERE e.status = 'sent') AS emails_sent,
+                COUNT(*) FILTER (WHERE e.status = 'failed') AS emails_failed
+            FROM broadcast_deliveries d
+            LEFT JOIN notifications n ON n.id = d.notification_id
+            LEFT JOIN email_outbox e ON e.id = d.email_id
+            WHERE d.broadcast_id = b.id
+        ) delivery
+        {filter}
+        "#
+    )
+}
+
+/// Payload of a `send_broadcast` job.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SendBroadcastJob {
+    pub broadcast_id: Uuid,
+}
+
+pub async fn list_broadcasts(
+    pool: &PgPool,
+    status: Option<&str>,
+) -> Result<Vec<BroadcastReport>, StationError> {
+    if let Some(status) = status
+        && !BROADCAST_STATUSES.contains(&status)
+    {
+        return Err(StationError::WrongCredentials(format!(
+            "status must be one of {}",
+            BROADCAST_STATUSES.join(", ")
+        )));
+    }
+
+    sqlx::query_as::<_, BroadcastReport>(&report_query(
+        "WHERE ($1::varchar IS NULL OR b.status = $1) ORDER BY b.created_at DESC",
+    ))
+    .bind(status)
+    .fetch_all(pool)
+    .await
+    .map_err(StationError::DatabaseError)
+}
+
+pub async fn load_broadcast<'e, E: PgExecutor<'e>>(
+    executor: E,
+    broadcast_id: Uuid,
+) -> Result<Option<BroadcastReport>, sqlx::Error> {
+    sqlx::query_as::<_, BroadcastReport>(&report_query("WHERE b.id = $1"))
+        .bind(broadcast_id)
+        .fetch_optional(executor)
+        .await
+}
+
+fn validate_broadcast(body: &CreateBroadcastDto) -> Result<(), StationError> {
+    if body.title.trim().is_empty() || body.title.trim().len() > MAX_TITLE_LENGTH {
+        return Err(StationError::WrongCredentials(format!(
+            "title must be between 1 and {MAX_TITLE_LENGTH} characters"
+        )));
+    }
+
+    if body.body.trim().is_empty() {
+        return Err(StationError::WrongCredentials(
+            "body must not be empty".to_string(),
+        ));
+    }
+
+    match body.audience.as_str() {
+        "all" => Ok(()),
+        "station_type" => match body.station_type.as_deref() {
+            Some(station_type) if STATION_TYPES.contains(&station_type) => Ok(()),
+            _ => Err(StationError::WrongCredentials(format!(
+                "station_type must be one of {}",
+                STATION_TYPES.join(", ")
+            ))),
+        },
+        "area" => match (body.latitude, body.longitude, body.radius_km) {
+            (Some(latitude), Some(longitude), Some(radius_km))
+                if (-90.0..=90.0).contains(&latitude)
+                    && (-180.0..=180.0).contains(&longitude)
+                    && radius_km > 0.0 =>
+            {
+                Ok(())
+            }
+            _ => Err(StationError::WrongCredentials(
+                "area broadcasts need latitude, longitude and a positive radius_km".to_string(),
+            )),
+        },
+        "stations" => match body.station_ids.as_deref() {
+            Some(ids) if !ids.is_empty() && ids.len() <= MAX_LISTED_STATIONS => Ok(()),
+            _ => Err(StationError::WrongCredentials(format!(
+                "station_ids must list between 1 and {MAX_LISTED_STATIONS} stations"
+            ))),
+        },
+        _ => Err(StationError::WrongCredentials(format!(
+            "audience must be one of {}",
+            BROADCAST_AUDIENCES.join(", ")
+        ))),
+    }
+}
+
+/// Stores the broadcast and queues its delivery for `scheduled_for`.
+pub async fn create_broadcast(
+    pool: &PgPool,
+    body: CreateBroadcastDto,
+    created_by_admin: Option<Uuid>,
+) -> Result<BroadcastReport, StationError> {
+    validate_broadcast(&body)?;
+
+    let audience = body.audience.as_str();
+    let station_ids = match body.station_ids.filter(|_| audience == "stations") {
+        Some(mut ids) => {
+            ids.sort_unstable();
+            ids.dedup();
+
+            let known: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM stations WHERE id = ANY($1)")
+                .bind(&ids)
+                .fetch_one(pool)
+                .await
+                .map_err(StationError::DatabaseError)?;
+            if known != ids.len() as i64 {
+                return Err(StationError::NotFound(
+                    "one or more station_ids do not exist".to_string(),
+                ));
+            }
+
+            Some(ids)
+        }
+        None => None,
+    };
+    let area = audience == "area";
+    let scheduled_for = body.scheduled_for.unwrap_or_else(Utc::now);
+
+    let mut tx = pool.begin().await.map_err(StationError::DatabaseError)?;
+
+    let broadcast_id: Uuid = sqlx::query_scalar(
+        r#"
+        INSERT INTO broadcasts (
+            title, body, audience, station_type, area_latitude, area_longitude,
+            area_radius_km, station_ids, send_email, scheduled_for, created_by_admin
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+        RETURNING id
+        "#,
+    )
+    .bind(body.title.trim())
+    .bind(body.body.trim())
+    .bind(audience)
+    .bind(body.station_type.filter(|_| audience == "station_type"))
+    .bind(body.latitude.filter(|_| area))
+    .bind(body.longitude.filter(|_| area))
+    .bind(body.radius_km.filter(|_| area))
+    .bind(station_ids)
+    .bind(body.send_email)
+    .bind(scheduled_for)
+    .bind(created_by_admin)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(StationError::DatabaseError)?;
+
+    enqueue(
+        &mut *tx,
+        SEND_BROADCAST_JOB,
+        &json!(SendBroadcastJob { broadcast_id }),
+        EnqueueOptions {
+            run_at: Some(scheduled_for),
+            dedupe_key: Some(format!("broadcast:{broadcast_id}")),
+            ..Default::default()
+        },
+    )
+    .await
+    .map_err(StationError::DatabaseError)?;
+
+    tx.commit().await.map_err(StationError::DatabaseError)?;
+
+    load_broadcast(pool, broadcast_id)
+        .await
+        .map_err(StationError::DatabaseError)?
+        .ok_or_else(|| StationError::NotFound(broadcast_id.to_string()))
+}
+
+/// Stops a scheduled broadcast; its queued job then does nothing.
+pub async fn cancel_broadcast(
+    pool: &PgPool,
+    broadcast_id: Uuid,
+) -> Result<BroadcastReport, StationError> {
+    let cancelled = sqlx::query(
+        r#"
+        UPDATE broadcasts
+        SET status = 'cancelled', updated_at = now()
+        WHERE id = $1 AND status = 'scheduled'
+        "#,
+    )
+    .bind(broadcast_id)
+    .execute(pool)
+    .await
+    .map_err(StationError::DatabaseError)?;
+
+    let report = load_broadcast(pool, broadcast_id)
+        .await
+        .map_err(StationError::DatabaseError)?
+        .ok_or_else(|| StationError::NotFound(broadcast_id.to_string()))?;
+
+    if cancelled.rows_affected() == 0 {
+        return Err(StationError::WrongCredentials(format!(
+            "only scheduled broadcasts can be cancelled; this one is {}",
+            report.broadcast.status
+        )));
+    }
+
+    Ok(report)
+}
+
+/// Writes the broadcast into every targeted station's notifications, and
+/// queues the emails when asked. Stations are delivered to in batches, each
+/// committed with its `broadcast_deliveries` rows, so the broadcast row is
+/// never locked for long and a retry picks up after the last full batch.
+/// Cancelling part way stops the remaining batches.
+pub async fn send_broadcast(pool: &PgPool, broadcast_id: Uuid) -> anyhow::Result<()> {
+    while !send_broadcast_batch(pool, broadcast_id).await? {}
+
+    Ok(())
+}
+
+/// Delivers the next batch of stations still missing a delivery row.
+/// Returns `true` once there is nothing left to do.
+async fn send_broadcast_batch(pool: &PgPool, broadcast_id: Uuid) -> anyhow::Result<bool> {
+    let mut tx = pool.begin().await?;
+
+    let broadcast = sqlx::query_as::<_, Broadcast>(&format!(
+        "SELECT {BROADCAST_COLUMNS} FROM broadcasts b WHERE b.id = $1 FOR UPDATE"
+    ))
+    .bind(broadcast_id)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or_else(|| anyhow::anyhow!("broadcast {broadcast_id} not found"))?;
+
+    if broadcast.status != "scheduled" {
+        return Ok(true);
+    }
+
+    let station_ids: Vec<Uuid> = sqlx::query_scalar(
+        r#"
+        SELECT s.id
+        FROM stations s
+        WHERE CASE $1
+            WHEN 'station_type' THEN s.station_type = $2
+            WHEN 'area' THEN haversine($3, $4, s.latitude, s.longitude) <= $5
+            WHEN 'stations' THEN s.id = ANY($6)
+            ELSE TRUE
+        END
+          AND NOT EXISTS (
+              SELECT 1 FROM broadcast_deliveries d
+              WHERE d.broadcast_id = $7 AND d.station_id = s.id
+          )
+        ORDER BY s.created_at, s.id
+        LIMIT $8
+        "#,
+    )
+    .bind(&broadcast.audience)
+    .bind(&broadcast.station_type)
+    .bind(broadcast.area_latitude)
+    .bind(broadcast.area_longitude)
+    .bind(broadcast.area_radius_km)
+    .bind(&broadcast.station_ids)
+    .bind(broadcast_id)
+    .bind(DELIVERY_BATCH_SIZE)
+    .fetch_all(&mut *tx)
+    .await?;
+    let finished = (station_ids.len() as i64) < DELIVERY_BATCH_SIZE;
+
+    let channels: &[Channel] = if broadcast.send_email {
+        &[Channel::Email]
+    } else {
+        &[]
+    };
+
+    for station_id in station_ids {
+        let delivery = notify_station_via(
+            &mut tx,
+            station_id,
+            Notice {
+                kind: ANNOUNCEMENT_KIND,
+                title: &broadcast.title,
+                body: &broadcast.body,
+                email: None,
+                dedupe_key: Some(format!("broadcast:{broadcast_id}:{station_id}")),
+            },
+            channels,
+        )
+        .await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO broadcast_deliveries (
+                broadcast_id, station_id, in_app, notification_id, email_id
+            )
+            VALUES ($1, $2, $3, $4, $5)
+            "#,
+        )
+        .bind(broadcast_id)
+        .bind(station_id)
+        .bind(delivery.notification_id.is_some())
+        .bind(delivery.notification_id)
+        .bind(delivery.email_id)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    if finished {
+        sqlx::query(
+            "UPDATE broadcasts SET status = 'sent', sent_at = now(), updated_at = now() WHERE id = $1",
+        )
+        .bind(broadcast_id)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await?;
+
+    Ok(finished)
+}
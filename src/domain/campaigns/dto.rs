@@ -0,0 +1,31 @@
+use chrono::{DateTime, NaiveTime, Utc};
+use serde::Deserialize;
+use uuid::Uuid;
+
+#[derive(Debug, Deserialize)]
+pub struct CreateCampaignDto {
+    pub commodity_id: Uuid,
+    pub name: String,
+    pub discount_type: String,
+    pub discount_value: i32,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: Option<DateTime<Utc>>,
+    pub recurrence_days: Option<Vec<i16>>,
+    pub daily_starts_at: Option<NaiveTime>,
+    pub daily_ends_at: Option<NaiveTime>,
+    pub max_codes: Option<i32>,
+    pub budget: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateCampaignDto {
+    pub is_active: Option<bool>,
+    pub ends_at: Option<DateTime<Utc>>,
+    pub max_codes: Option<i32>,
+    pub budget: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CampaignsQuery {
+    pub station_id: Option<Uuid>,
+}
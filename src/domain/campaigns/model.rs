@@ -0,0 +1,46 @@
+use chrono::{DateTime, NaiveTime, Utc};
+use serde::Serialize;
+use sqlx::FromRow;
+use uuid::Uuid;
+
+pub const DISCOUNT_TYPES: [&str; 2] = ["percentage", "fixed"];
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct DiscountCampaign {
+    pub id: Uuid,
+    pub station_id: Uuid,
+    pub commodity_id: Uuid,
+    pub name: String,
+    pub discount_type: String,
+    pub discount_value: i32,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: Option<DateTime<Utc>>,
+    pub recurrence_days: Option<Vec<i16>>,
+    pub daily_starts_at: Option<NaiveTime>,
+    pub daily_ends_at: Option<NaiveTime>,
+    pub max_codes: Option<i32>,
+    pub budget: Option<i32>,
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A campaign with how much of its code allowance and budget is used.
+///
+/// Issued codes count against `max_codes` unless cancelled or voided.
+/// Against the naira budget, a code's saving is committed while the code is
+/// live and spent once redeemed; an expired, cancelled or voided code
+/// releases its share.
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct CampaignBurnDown {
+    #[serde(flatten)]
+    #[sqlx(flatten)]
+    pub campaign: DiscountCampaign,
+    pub is_live: bool,
+    pub codes_issued: i64,
+    pub codes_redeemed: i64,
+    pub budget_committed: i64,
+    pub budget_spent: i64,
+    pub codes_remaining: Option<i64>,
+    pub budget_remaining: Option<i64>,
+}
@@ -0,0 +1,286 @@
+use axum::{
+    Json,
+    extract::{Extension, State},
+};
+use sqlx::{PgExecutor, PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::{
+    app_state::AppState,
+    authentication::station::authenticate::token::service::Claims,
+    domain::{
+        campaigns::{
+            dto::{CreateCampaignDto, UpdateCampaignDto},
+            model::{CampaignBurnDown, DISCOUNT_TYPES},
+        },
+        utils::errors::station_errors::StationError,
+    },
+};
+
+const CAMPAIGN_COLUMNS: &str = r#"
+    c.id, c.station_id, c.commodity_id, c.name, c.discount_type, c.discount_value,
+    c.starts_at, c.ends_at, c.recurrence_days, c.daily_starts_at, c.daily_ends_at,
+    c.max_codes, c.budget, c.is_active, c.created_at, c.updated_at
+"#;
+
+fn burn_down_query(filter: &str) -> String {
+    format!(
+        r#"
+        SELECT
+            {CAMPAIGN_COLUMNS},
+            discount_campaign_is_live(c.id, now()) AS is_live,
+            usage.codes_issued,
+            usage.codes_redeemed,
+            usage.budget_committed,
+            usage.budget_spent,
+            CASE WHEN c.max_codes IS NULL THEN NULL
+                 ELSE GREATEST(c.max_codes - usage.codes_issued, 0) END AS codes_remaining,
+            CASE WHEN c.budget IS NULL THEN NULL
+                 ELSE GREATEST(c.budget - usage.budget_committed, 0) END AS budget_remaining
+        FROM discount_campaigns c
+        CROSS JOIN LATERAL (
+            SELECT
+                COUNT(*)::BIGINT AS codes_issued,
+                COUNT(*) FILTER (WHERE dc.redeemed_at IS NOT NULL)::BIGINT AS codes_redeemed,
+                COALESCE(SUM(dc.total_saving)
+                    FILTER (WHERE dc.redeemed_at IS NOT NULL OR dc.expires_at > now()), 0)::BIGINT
+                    AS budget_committed,
+                COALESCE(SUM(dc.total_saving)
+                    FILTER (WHERE dc.redeemed_at IS NOT NULL), 0)::BIGINT AS budget_spent
+            FROM discount_codes dc
+            -- Cancelled and voided codes give their share back to the campaign.
+            WHERE dc.campaign_id = c.id
+              AND dc.cancelled_at IS NULL
+              AND dc.voided_at IS NULL
+        ) usage
+        {filter}
+        "#
+    )
+}
+
+pub struct CampaignService;
+
+impl CampaignService {
+    pub async fn station_campaigns(
+        State(app_state): State<AppState>,
+        Extension(claims): Extension<Claims>,
+    ) -> Result<Json<Vec<CampaignBurnDown>>, StationError> {
+        let campaigns = list_campaigns(&app_state.pool, Some(claims.station_res.id))
+            .await
+            .map_err(StationError::DatabaseError)?;
+
+        Ok(Json(campaigns))
+    }
+}
+
+pub async fn list_campaigns(
+    pool: &PgPool,
+    station_id: Option<Uuid>,
+) -> Result<Vec<CampaignBurnDown>, sqlx::Error> {
+    sqlx::query_as::<_, CampaignBurnDown>(&burn_down_query(
+        "WHERE ($1::uuid IS NULL OR c.station_id = $1) ORDER BY c.created_at DESC",
+    ))
+    .bind(station_id)
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn load_campaign<'e, E: PgExecutor<'e>>(
+    executor: E,
+    campaign_id: Uuid,
+) -> Result<Option<CampaignBurnDown>, sqlx::Error> {
+    sqlx::query_as::<_, CampaignBurnDown>(&burn_down_query("WHERE c.id = $1"))
+        .bind(campaign_id)
+        .fetch_optional(executor)
+        .await
+}
+
+/// Picks the newest live campaign for a commodity and locks it so that
+/// concurrent code generation cannot overspend its budget.
+pub async fn lock_live_campaign(
+    tx: &mut Transaction<'_, Postgres>,
+    commodity_id: Uuid,
+) -> Result<Option<CampaignBurnDown>, sqlx::Error> {
+    let campaign_id: Option<Uuid> = sqlx::query_scalar(
+        r#"
+        SELECT id
+        FROM discount_campaigns
+        WHERE commodity_id = $1
+          AND discount_campaign_is_live(id, now())
+        ORDER BY created_at DESC
+        LIMIT 1
+        FOR UPDATE
+        "#,
+    )
+    .bind(commodity_id)
+    .fetch_optional(&mut **tx)
+    .await?;
+
+    match campaign_id {
+        Some(campaign_id) => load_campaign(&mut **tx, campaign_id).await,
+        None => Ok(None),
+    }
+}
+
+fn validate_campaign(body: &CreateCampaignDto) -> Result<(), StationError> {
+    let name = body.name.trim();
+    if name.is_empty() || name.len() > 120 {
+        return Err(StationError::WrongCredentials(
+            "name is required (max 120 characters)".to_string(),
+        ));
+    }
+
+    if !DISCOUNT_TYPES.contains(&body.discount_type.as_str()) {
+        return Err(StationError::WrongCredentials(format!(
+            "discount_type must be one of: {}",
+            DISCOUNT_TYPES.join(", ")
+        )));
+    }
+
+    if body.discount_type == "percentage" && !(1..=10).contains(&body.discount_value) {
+        return Err(StationError::WrongCredentials(
+            "percentage must be between 1 and 10".to_string(),
+        ));
+    }
+
+    if body.discount_value <= 0 {
+        return Err(StationError::WrongCredentials(
+            "discount_value must be greater than zero".to_string(),
+        ));
+    }
+
+    if body.ends_at.is_some_and(|ends_at| ends_at <= body.starts_at) {
+        return Err(StationError::WrongCredentials(
+            "ends_at must be after starts_at".to_string(),
+        ));
+    }
+
+    if body
+        .recurrence_days
+        .as_ref()
+        .is_some_and(|days| days.is_empty() || days.iter().any(|day| !(1..=7).contains(day)))
+    {
+        return Err(StationError::WrongCredentials(
+            "recurrence_days must list ISO weekdays between 1 and 7".to_string(),
+        ));
+    }
+
+    match (body.daily_starts_at, body.daily_ends_at) {
+        (None, None) => {}
+        (Some(starts), Some(ends)) if ends > starts => {}
+        _ => {
+            return Err(StationError::WrongCredentials(
+                "daily_starts_at and daily_ends_at must be set together, with the end after the start"
+                    .to_string(),
+            ));
+        }
+    }
+
+    validate_limits(body.max_codes, body.budget)
+}
+
+fn validate_limits(max_codes: Option<i32>, budget: Option<i32>) -> Result<(), StationError> {
+    if max_codes.is_some_and(|max| max <= 0) || budget.is_some_and(|budget| budget <= 0) {
+        return Err(StationError::WrongCredentials(
+            "max_codes and budget must be greater than zero".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+pub async fn create_campaign(
+    pool: &PgPool,
+    body: CreateCampaignDto,
+    created_by_admin: Option<Uuid>,
+) -> Result<CampaignBurnDown, StationError> {
+    validate_campaign(&body)?;
+
+    let station_id: Uuid = sqlx::query_scalar("SELECT station_id FROM commodities WHERE id = $1")
+        .bind(body.commodity_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(StationError::DatabaseError)?
+        .ok_or_else(|| StationError::NotFound(body.commodity_id.to_string()))?;
+
+    let mut recurrence_days = body.recurrence_days;
+    if let Some(days) = recurrence_days.as_mut() {
+        days.sort_unstable();
+        days.dedup();
+    }
+
+    let campaign_id: Uuid = sqlx::query_scalar(
+        r#"
+        INSERT INTO discount_campaigns (
+            station_id, commodity_id, name, discount_type, discount_value,
+            starts_at, ends_at, recurrence_days, daily_starts_at, daily_ends_at,
+            max_codes, budget, created_by_admin
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+        RETURNING id
+        "#,
+    )
+    .bind(station_id)
+    .bind(body.commodity_id)
+    .bind(body.name.trim())
+    .bind(&body.discount_type)
+    .bind(body.discount_value)
+    .bind(body.starts_at)
+    .bind(body.ends_at)
+    .bind(recurrence_days)
+    .bind(body.daily_starts_at)
+    .bind(body.daily_ends_at)
+    .bind(body.max_codes)
+    .bind(body.budget)
+    .bind(created_by_admin)
+    .fetch_one(pool)
+    .await
+    .map_err(StationError::DatabaseError)?;
+
+    load_campaign(pool, campaign_id)
+        .await
+        .map_err(StationError::DatabaseError)?
+        .ok_or_else(|| StationError::NotFound(campaign_id.to_string()))
+}
+
+pub async fn update_campaign(
+    pool: &PgPool,
+    campaign_id: Uuid,
+    body: UpdateCampaignDto,
+) -> Result<CampaignBurnDown, StationError> {
+    validate_limits(body.max_codes, body.budget)?;
+
+    let updated = sqlx::query(
+        r#"
+        UPDATE discount_campaigns
+        SET is_active = COALESCE($1, is_active),
+            ends_at = COALESCE($2, ends_at),
+            max_codes = COALESCE($3, max_codes),
+            budget = COALESCE($4, budget),
+            updated_at = now()
+        WHERE id = $5
+        "#,
+    )
+    .bind(body.is_active)
+    .bind(body.ends_at)
+    .bind(body.max_codes)
+    .bind(body.budget)
+    .bind(campaign_id)
+    .execute(pool)
+    .await
+    .map_err(|err| match &err {
+        sqlx::Error::Database(db_err) if db_err.is_check_violation() => {
+            StationError::WrongCredentials("ends_at must be after starts_at".to_string())
+        }
+        _ => StationError::DatabaseError(err),
+    })?;
+
+    if updated.rows_affected() == 0 {
+        return Err(StationError::NotFound(campaign_id.to_string()));
+    }
+
+    load_campaign(pool, campaign_id)
+        .await
+        .map_err(StationError::DatabaseError)?
+        .ok_or_else(|| StationError::NotFound(campaign_id.to_string()))
+}
@@ -2,9 +2,17 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use super::model::{CommodityDiscountConfig, StationDiscountLimits};
+
 #[derive(Debug, Deserialize)]
 pub struct GenerateDiscountCodeDto {
     pub station_id: Uuid,
+    /// Commodity to discount. Defaults to the station's discounted commodity.
+    pub commodity_id: Option<Uuid>,
+    /// Litres the driver intends to buy. Mutually exclusive with `amount`.
+    pub litres: Option<f64>,
+    /// Naira worth of fuel, at pump price, the driver intends to buy.
+    pub amount: Option<i32>,
 }
 
 #[derive(Debug, Serialize)]
@@ -16,13 +24,46 @@ pub struct DiscountCodeResponse {
     pub original_price: i32,
     pub discounted_price: i32,
     pub is_expired: bool,
+    pub commodity_id: Uuid,
+    pub commodity_name: String,
+    pub quantity_unit: String,
+    pub quantity_litres: f64,
+    pub total_price: i32,
+    pub total_discounted_price: i32,
+    pub total_saving: i32,
+    pub discount_type: String,
+    pub campaign_id: Option<Uuid>,
+    /// Signed token to encode in a QR code. Only ever returned here; the
+    /// driver presents it to render the QR or cancel the code.
+    pub qr_token: String,
+    /// Tier of the loyalty account the code earns points for, if any.
+    pub loyalty_tier: Option<&'static str>,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct RedeemDiscountCodeDto {
+    /// Either the short code or a scanned QR token.
     pub code: String,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct DiscountQrQuery {
+    /// `png` (default) or `svg`.
+    pub format: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DiscountSigningKeyResponse {
+    pub algorithm: &'static str,
+    pub public_key: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OfflineSyncKeyResponse {
+    pub algorithm: &'static str,
+    pub key: String,
+}
+
 #[derive(Debug, Serialize)]
 pub struct RedeemDiscountCodeResponse {
     pub message: String,
@@ -32,15 +73,94 @@ pub struct RedeemDiscountCodeResponse {
     pub discount_percentage: Option<i32>,
     pub discounted_price: Option<i32>,
     pub is_expired: bool,
+    pub commodity_name: Option<String>,
+    pub quantity_litres: Option<f64>,
+    pub total_price: Option<i32>,
+    /// What the attendant should charge the driver.
+    pub amount_to_charge: Option<i32>,
+    pub total_saving: Option<i32>,
 }
 
 #[derive(Debug, Serialize)]
 pub struct StationDiscountStatsResponse {
+    /// Redeemed codes, excluding reversed and voided redemptions.
     pub redeemed_codes: i64,
+    pub cancelled_codes: i64,
+    pub voided_codes: i64,
+    pub reversed_redemptions: i64,
 }
 
 #[derive(Debug, Serialize)]
 pub struct AdminDiscountStatsResponse {
     pub created_codes: i64,
+    /// Redeemed codes, excluding reversed and voided redemptions.
     pub redeemed_codes: i64,
+    pub cancelled_codes: i64,
+    pub voided_codes: i64,
+    pub reversed_redemptions: i64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateStationDiscountDto {
+    pub enabled: bool,
+    pub percentage: Option<i32>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StationDiscountConfigResponse {
+    /// Range the station may choose from; `None` until an admin sets one.
+    pub limits: Option<StationDiscountLimits>,
+    pub commodities: Vec<CommodityDiscountConfig>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OfflineRedemptionDto {
+    /// Short code or scanned QR token, as captured at the pump.
+    pub code: String,
+    /// When the attendant redeemed the code, by the app's clock.
+    pub redeemed_at: DateTime<Utc>,
+    /// The app's signature over `code` and `redeemed_at`, made with the
+    /// station's offline sync key; see `GET /discounts/station/sync-key`.
+    pub signature: String,
+    /// Opaque id the app uses to match results to its queue.
+    pub client_reference: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SyncRedemptionsDto {
+    pub redemptions: Vec<OfflineRedemptionDto>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SyncRedemptionResult {
+    /// Position of the redemption in the uploaded batch.
+    pub index: usize,
+    pub client_reference: Option<String>,
+    pub code: Option<String>,
+    /// `redeemed`, `duplicate`, `expired` or `invalid`.
+    pub status: &'static str,
+    pub message: String,
+    /// Redemption time the server kept for the code.
+    pub redeemed_at: Option<DateTime<Utc>>,
+    pub amount_to_charge: Option<i32>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SyncRedemptionsResponse {
+    pub results: Vec<SyncRedemptionResult>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReverseRedemptionDto {
+    /// Either the short code or a scanned QR token.
+    pub code: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DiscountCodeStateResponse {
+    pub code: String,
+    /// `active`, `redeemed`, `expired`, `cancelled` or `voided`.
+    pub status: &'static str,
+    pub message: String,
 }
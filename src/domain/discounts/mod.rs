@@ -2,3 +2,4 @@ pub mod dto;
 pub mod model;
 pub mod routes;
 pub mod service;
+pub mod token;
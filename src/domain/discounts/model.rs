@@ -16,15 +16,78 @@ pub struct DiscountCode {
     pub expires_at: DateTime<Utc>,
     pub redeemed_at: Option<DateTime<Utc>>,
     pub redeemed_by_station_id: Option<Uuid>,
+    pub quantity_unit: String,
+    pub quantity_litres: f64,
+    pub total_price: i32,
+    pub total_discounted_price: i32,
+    pub total_saving: i32,
+    pub campaign_id: Option<Uuid>,
+    pub discount_type: String,
+    pub redemption_source: Option<String>,
+    pub redemption_synced_at: Option<DateTime<Utc>>,
+    pub cancelled_at: Option<DateTime<Utc>>,
+    pub voided_at: Option<DateTime<Utc>>,
+    pub loyalty_account_id: Option<Uuid>,
 }
 
 #[derive(Debug, Clone, Serialize, FromRow)]
 pub struct StationDiscountStats {
     pub redeemed_codes: i64,
+    pub cancelled_codes: i64,
+    pub voided_codes: i64,
+    pub reversed_redemptions: i64,
 }
 
 #[derive(Debug, Clone, Serialize, FromRow)]
 pub struct AdminDiscountStats {
     pub created_codes: i64,
     pub redeemed_codes: i64,
+    pub cancelled_codes: i64,
+    pub voided_codes: i64,
+    pub reversed_redemptions: i64,
+}
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct StationDiscountLimits {
+    pub station_id: Uuid,
+    pub min_percentage: i32,
+    pub max_percentage: i32,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct CommodityDiscountConfig {
+    pub commodity_id: Uuid,
+    pub commodity_name: String,
+    pub is_enabled: bool,
+    pub percentage: Option<i32>,
+    pub admin_locked: bool,
+    pub updated_by_type: Option<String>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct DiscountChange {
+    pub id: Uuid,
+    pub commodity_id: Uuid,
+    pub station_id: Uuid,
+    pub actor_type: String,
+    pub actor_id: Uuid,
+    pub previous_enabled: Option<bool>,
+    pub previous_percentage: Option<i32>,
+    pub new_enabled: bool,
+    pub new_percentage: Option<i32>,
+    pub admin_locked: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct DiscountCodeEvent {
+    pub id: Uuid,
+    pub discount_code_id: Uuid,
+    pub event_type: String,
+    pub actor_type: String,
+    pub actor_id: Option<Uuid>,
+    pub reason: Option<String>,
+    pub created_at: DateTime<Utc>,
 }
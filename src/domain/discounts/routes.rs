@@ -1,20 +1,76 @@
-use axum::{Router, middleware::from_fn, routing::{get, post}};
+use axum::{
+    Router,
+    middleware::{from_fn, from_fn_with_state},
+    routing::{get, post, put},
+};
+use std::time::Duration;
 
 use crate::{
     app_state::AppState,
     authentication::middleware::auth::authorize,
-    domain::discounts::service::DiscountService,
+    domain::{
+        analytics::service::AnalyticsService, campaigns::service::CampaignService,
+        discounts::service::DiscountService,
+        utils::rate_limiter::{RateLimiter, rate_limit},
+    },
 };
 
+/// 10 cancellations per 60 seconds per IP; cancelling needs no account.
+const CANCEL_MAX_REQUESTS: u32 = 10;
+const CANCEL_WINDOW_SECS: u64 = 60;
+
 pub fn discounts_route() -> Router<AppState> {
+    let cancel_limiter =
+        RateLimiter::new(CANCEL_MAX_REQUESTS, Duration::from_secs(CANCEL_WINDOW_SECS));
+    cancel_limiter.spawn_cleanup();
+
     Router::new()
         .route("/generate", post(DiscountService::generate_code))
+        .route("/signing-key", get(DiscountService::signing_key))
+        .route("/{code}/qr", get(DiscountService::qr_code))
+        .route(
+            "/{code}/cancel",
+            post(DiscountService::cancel_code)
+                .route_layer(from_fn_with_state(cancel_limiter, rate_limit)),
+        )
         .route(
             "/redeem",
             post(DiscountService::redeem_code).route_layer(from_fn(authorize)),
         )
+        .route(
+            "/redeem/sync",
+            post(DiscountService::sync_redemptions).route_layer(from_fn(authorize)),
+        )
+        .route(
+            "/redeem/reverse",
+            post(DiscountService::reverse_redemption).route_layer(from_fn(authorize)),
+        )
         .route(
             "/station/stats",
             get(DiscountService::station_stats).route_layer(from_fn(authorize)),
         )
+        .route(
+            "/station/analytics",
+            get(AnalyticsService::station_analytics).route_layer(from_fn(authorize)),
+        )
+        .route(
+            "/station/sync-key",
+            get(DiscountService::station_sync_key).route_layer(from_fn(authorize)),
+        )
+        .route(
+            "/station/config",
+            get(DiscountService::station_discount_config).route_layer(from_fn(authorize)),
+        )
+        .route(
+            "/station/config/changes",
+            get(DiscountService::station_discount_changes).route_layer(from_fn(authorize)),
+        )
+        .route(
+            "/station/config/{commodity_id}",
+            put(DiscountService::update_station_discount).route_layer(from_fn(authorize)),
+        )
+        .route(
+            "/station/campaigns",
+            get(CampaignService::station_campaigns).route_layer(from_fn(authorize)),
+        )
 }
@@ -1,24 +1,39 @@
 use axum::{
     Json,
-    extract::{Extension, State},
-    http::{HeaderMap, StatusCode},
+    extract::{Extension, Path, Query, State},
+    http::{HeaderMap, StatusCode, header},
+    response::IntoResponse,
 };
 use chrono::{Duration, Utc};
-use sqlx::PgPool;
+use sqlx::{PgExecutor, PgPool, Postgres, Transaction};
 use uuid::Uuid;
 
 use crate::{
     app_state::AppState,
     authentication::station::authenticate::token::service::Claims,
     domain::{
+        campaigns::service::lock_live_campaign,
         discounts::{
             dto::{
-                DiscountCodeResponse, GenerateDiscountCodeDto, RedeemDiscountCodeDto,
-                RedeemDiscountCodeResponse, StationDiscountStatsResponse,
+                DiscountCodeResponse, DiscountCodeStateResponse, DiscountQrQuery, DiscountSigningKeyResponse,
+                OfflineSyncKeyResponse,
+                GenerateDiscountCodeDto, OfflineRedemptionDto, RedeemDiscountCodeDto,
+                RedeemDiscountCodeResponse, ReverseRedemptionDto, StationDiscountConfigResponse,
+                StationDiscountStatsResponse, SyncRedemptionResult, SyncRedemptionsDto,
+                SyncRedemptionsResponse, UpdateStationDiscountDto,
             },
-            model::{AdminDiscountStats, DiscountCode, StationDiscountStats},
+            model::{
+                AdminDiscountStats, CommodityDiscountConfig, DiscountChange, DiscountCode,
+                DiscountCodeEvent, StationDiscountLimits, StationDiscountStats,
+            },
+            token::{DiscountTokenClaims, DiscountTokenSigner, looks_like_token},
+        },
+        loyalty::{
+            model::LoyaltyTier,
+            service::{account_tier, find_account, post_code_earnings, reverse_code_earnings},
         },
-        utils::errors::station_errors::StationError,
+        subscriptions::entitlements::{AccessLevel, Entitlements, Feature, station_entitlements},
+        utils::{client_ip::ClientIp, errors::station_errors::StationError},
     },
 };
 
@@ -26,6 +41,217 @@ pub struct DiscountService;
 
 const MAX_CODES_PER_IP_PER_STATION_PER_DAY: i64 = 3;
 const MAX_CODE_GENERATE_RETRY: i64 = 8;
+const MAX_LITRES_PER_CODE: f64 = 200.0;
+const MAX_DISCOUNT_PERCENTAGE: i32 = 10;
+const QR_MIN_DIMENSION: u32 = 256;
+const MAX_SYNC_BATCH: usize = 100;
+const MAX_SYNC_CLOCK_SKEW_MINUTES: i64 = 5;
+/// Offline redemptions older than this are refused, which bounds how far
+/// back a sync can change the analytics rollup.
+pub const MAX_SYNC_AGE_DAYS: i64 = 7;
+const REVERSAL_GRACE_MINUTES: i64 = 30;
+const MAX_REASON_LENGTH: usize = 500;
+/// Header carrying the signed token a driver received with their code.
+pub const DRIVER_TOKEN_HEADER: &str = "x-discount-token";
+
+const DISCOUNT_CODE_COLUMNS: &str = r#"
+    id, code, station_id, commodity_id, created_price, discount_percentage,
+    discounted_price, created_at, expires_at, redeemed_at, redeemed_by_station_id,
+    quantity_unit, quantity_litres, total_price, total_discounted_price, total_saving,
+    campaign_id, discount_type, redemption_source, redemption_synced_at, cancelled_at,
+    voided_at,
+    loyalty_account_id
+"#;
+
+/// How a code's discount is worked out.
+#[derive(Debug, Clone, Copy)]
+enum DiscountRule {
+    Percentage(i32),
+    /// Naira off each litre.
+    FixedPerLitre(i32),
+}
+
+impl DiscountRule {
+    fn discount_type(self) -> &'static str {
+        match self {
+            DiscountRule::Percentage(_) => "percentage",
+            DiscountRule::FixedPerLitre(_) => "fixed",
+        }
+    }
+
+    /// Percentage recorded on the code; fixed-amount codes record zero.
+    fn percentage(self) -> i32 {
+        match self {
+            DiscountRule::Percentage(percentage) => percentage,
+            DiscountRule::FixedPerLitre(_) => 0,
+        }
+    }
+
+    /// Adds a loyalty tier's bonus to a percentage discount, within the
+    /// usual ceiling. Fixed-amount campaign discounts are left alone.
+    fn with_loyalty_bonus(self, bonus: i32) -> Self {
+        match self {
+            DiscountRule::Percentage(percentage) => {
+                DiscountRule::Percentage((percentage + bonus).min(MAX_DISCOUNT_PERCENTAGE))
+            }
+            DiscountRule::FixedPerLitre(_) => self,
+        }
+    }
+
+    fn unit_price_after(self, unit_price: i32) -> i32 {
+        match self {
+            DiscountRule::Percentage(percentage) => unit_price * (100 - percentage) / 100,
+            DiscountRule::FixedPerLitre(amount) => (unit_price - amount).max(0),
+        }
+    }
+}
+
+/// Totals for the quantity a driver asked for.
+struct QuantityQuote {
+    unit: &'static str,
+    litres: f64,
+    total_price: i32,
+    total_discounted_price: i32,
+    total_saving: i32,
+}
+
+/// Works out how many litres a request covers and what they cost before and
+/// after the discount. Without a quantity the code covers a single litre.
+fn quote_quantity(
+    unit_price: i32,
+    rule: DiscountRule,
+    litres: Option<f64>,
+    amount: Option<i32>,
+) -> Result<QuantityQuote, StationError> {
+    let (unit, litres, total_price) = match (litres, amount) {
+        (Some(_), Some(_)) => {
+            return Err(StationError::WrongCredentials(
+                "provide either litres or amount, not both".to_string(),
+            ));
+        }
+        (Some(litres), None) => {
+            if !litres.is_finite() || litres <= 0.0 {
+                return Err(StationError::WrongCredentials(
+                    "litres must be greater than zero".to_string(),
+                ));
+            }
+            ("litres", (litres * 100.0).round() / 100.0, None)
+        }
+        (None, Some(amount)) => {
+            if amount <= 0 {
+                return Err(StationError::WrongCredentials(
+                    "amount must be greater than zero".to_string(),
+                ));
+            }
+            if unit_price <= 0 {
+                return Err(StationError::WrongCredentials(
+                    "commodity price is not set".to_string(),
+                ));
+            }
+            let litres = (amount as f64 / unit_price as f64 * 100.0).round() / 100.0;
+            ("amount", litres, Some(i64::from(amount)))
+        }
+        (None, None) => ("litres", 1.0, Some(i64::from(unit_price))),
+    };
+
+    if litres <= 0.0 || litres > MAX_LITRES_PER_CODE {
+        return Err(StationError::WrongCredentials(format!(
+            "a discount code covers between 0.01 and {MAX_LITRES_PER_CODE} litres"
+        )));
+    }
+
+    // Litres are bounded above, so these products stay well inside i64; the
+    // totals still have to fit the i32 price columns.
+    let total_price =
+        total_price.unwrap_or_else(|| (f64::from(unit_price) * litres).round() as i64);
+    let total_discounted_price = match rule {
+        DiscountRule::Percentage(percentage) => total_price * i64::from(100 - percentage) / 100,
+        DiscountRule::FixedPerLitre(amount) => {
+            (total_price - (f64::from(amount) * litres).round() as i64).max(0)
+        }
+    };
+
+    Ok(QuantityQuote {
+        unit,
+        litres,
+        total_price: to_price(total_price)?,
+        total_discounted_price: to_price(total_discounted_price)?,
+        total_saving: to_price(total_price - total_discounted_price)?,
+    })
+}
+
+fn to_price(value: i64) -> Result<i32, StationError> {
+    i32::try_from(value).map_err(|_| {
+        StationError::WrongCredentials("the requested quantity is too large to price".to_string())
+    })
+}
+
+fn token_claims(code: &DiscountCode) -> DiscountTokenClaims {
+    DiscountTokenClaims {
+        cid: code.id,
+        code: code.code.clone(),
+        sid: code.station_id,
+        mid: code.commodity_id,
+        exp: code.expires_at.timestamp(),
+    }
+}
+
+/// Checks the signed token the driver was handed when `code` was generated.
+/// Short codes are guessable, so the token is the only proof the caller is
+/// the driver the code was issued to. Returns the verified token.
+fn verify_driver_token<'h>(
+    signer: &DiscountTokenSigner,
+    headers: &'h HeaderMap,
+    code: &str,
+) -> Result<&'h str, StationError> {
+    let token = headers
+        .get(DRIVER_TOKEN_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(str::trim)
+        .ok_or_else(|| StationError::WrongCredentials("missing discount token".to_string()))?;
+
+    let claims = signer
+        .verify(token)
+        .map_err(|_| StationError::WrongCredentials("invalid discount token".to_string()))?;
+
+    if claims.code != code.trim().to_uppercase() {
+        return Err(StationError::WrongCredentials(
+            "discount token does not match this code".to_string(),
+        ));
+    }
+
+    Ok(token)
+}
+
+/// Renders `data` as a QR code, either as a PNG or an SVG document.
+fn render_qr(data: &str, format: &str) -> Result<(&'static str, Vec<u8>), StationError> {
+    let qr = qrcode::QrCode::new(data.as_bytes())
+        .map_err(|err| StationError::WrongCredentials(err.to_string()))?;
+
+    match format {
+        "svg" => {
+            let svg = qr
+                .render::<qrcode::render::svg::Color>()
+                .min_dimensions(QR_MIN_DIMENSION, QR_MIN_DIMENSION)
+                .build();
+            Ok(("image/svg+xml", svg.into_bytes()))
+        }
+        "png" => {
+            let image = qr
+                .render::<image::Luma<u8>>()
+                .min_dimensions(QR_MIN_DIMENSION, QR_MIN_DIMENSION)
+                .build();
+            let mut png = Vec::new();
+            image
+                .write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+                .map_err(|err| StationError::WrongCredentials(err.to_string()))?;
+            Ok(("image/png", png))
+        }
+        _ => Err(StationError::WrongCredentials(
+            "format must be png or svg".to_string(),
+        )),
+    }
+}
 
 fn station_code_prefix(station_name: &str) -> String {
     let mut chars = station_name
@@ -63,47 +289,22 @@ fn generate_candidate_code(station_name: &str, station_type: &str) -> String {
     format!("{prefix}{random_body}{suffix}")
 }
 
-fn extract_request_ip(headers: &HeaderMap) -> Option<String> {
-    let forwarded_for = headers
-        .get("x-forwarded-for")
-        .and_then(|v| v.to_str().ok())
-        .and_then(|raw| raw.split(',').next())
-        .map(str::trim)
-        .filter(|s| !s.is_empty())
-        .map(ToString::to_string);
-
-    if forwarded_for.is_some() {
-        return forwarded_for;
-    }
-
-    headers
-        .get("cf-connecting-ip")
-        .and_then(|v| v.to_str().ok())
-        .map(str::trim)
-        .filter(|s| !s.is_empty())
-        .map(ToString::to_string)
-        .or_else(|| {
-            headers
-                .get("x-real-ip")
-                .and_then(|v| v.to_str().ok())
-                .map(str::trim)
-                .filter(|s| !s.is_empty())
-                .map(ToString::to_string)
-        })
-}
-
 impl DiscountService {
     pub async fn generate_code(
         State(app_state): State<AppState>,
+        Extension(ClientIp(client_ip)): Extension<ClientIp>,
         headers: HeaderMap,
         Json(body): Json<GenerateDiscountCodeDto>,
     ) -> Result<(StatusCode, Json<DiscountCodeResponse>), StationError> {
-        let ip = extract_request_ip(&headers)
+        let ip = client_ip
+            .map(|ip| ip.to_string())
             .ok_or_else(|| StationError::WrongCredentials("unable to determine caller ip".to_string()))?;
 
-        let station = sqlx::query_as::<_, (Uuid, String, String)>(
+        let loyalty_account = find_account(&app_state.pool, &headers).await?;
+
+        let station = sqlx::query_as::<_, (Uuid, String, String, bool)>(
             r#"
-            SELECT id, name, station_type
+            SELECT id, name, station_type, discounts_suspended_at IS NOT NULL
             FROM stations
             WHERE id = $1
             "#,
@@ -114,44 +315,57 @@ impl DiscountService {
         .map_err(StationError::DatabaseError)?
         .ok_or_else(|| StationError::NotFound(body.station_id.to_string()))?;
 
-        let commodity = sqlx::query_as::<_, (Uuid, i32, bool, Option<i32>)>(
+        // Without an explicit commodity, prefer one with a live campaign, then
+        // one the station discounts.
+        let commodity = sqlx::query_as::<_, (Uuid, String, i32, bool, Option<i32>)>(
             r#"
             SELECT
                 c.id,
+                c.name,
                 c.price,
                 COALESCE(cd.is_enabled, FALSE) AS is_enabled,
                 cd.percentage
             FROM commodities c
             LEFT JOIN commodity_discounts cd ON cd.commodity_id = c.id
             WHERE c.station_id = $1
+              AND ($2::uuid IS NULL OR c.id = $2)
+            ORDER BY
+                EXISTS (
+                    SELECT 1 FROM discount_campaigns dc
+                    WHERE dc.commodity_id = c.id AND discount_campaign_is_live(dc.id, now())
+                ) DESC,
+                COALESCE(cd.is_enabled, FALSE) DESC,
+                c.name
             LIMIT 1
             "#,
         )
         .bind(body.station_id)
+        .bind(body.commodity_id)
         .fetch_optional(&app_state.pool)
         .await
         .map_err(StationError::DatabaseError)?
         .ok_or_else(|| StationError::NotFound("station commodity not found".to_string()))?;
 
-        let (_station_id, station_name, station_type) = station;
-        let (commodity_id, original_price, is_enabled, percentage) = commodity;
+        let (_station_id, station_name, station_type, discounts_suspended) = station;
 
-        if !is_enabled {
+        if discounts_suspended {
             return Err(StationError::WrongCredentials(
-                "discount is not enabled for this station".to_string(),
+                "discounts are suspended for this station".to_string(),
             ));
         }
 
-        let discount_percentage = percentage.ok_or_else(|| {
-            StationError::WrongCredentials("discount percentage is not configured".to_string())
-        })?;
-
-        if !(1..=10).contains(&discount_percentage) {
+        let entitlements =
+            station_entitlements(&app_state.pool, body.station_id, app_state.subscription_grace)
+                .await
+                .map_err(StationError::DatabaseError)?;
+        if entitlements.access != AccessLevel::Full || !entitlements.has(Feature::Discounts) {
             return Err(StationError::WrongCredentials(
-                "discount percentage must be between 1 and 10".to_string(),
+                "this station is not offering discounts".to_string(),
             ));
         }
 
+        let (commodity_id, commodity_name, original_price, is_enabled, percentage) = commodity;
+
         let generated_today: i64 = sqlx::query_scalar(
             r#"
             SELECT COUNT(*)
@@ -173,16 +387,83 @@ impl DiscountService {
             ));
         }
 
+        let mut tx = app_state
+            .pool
+            .begin()
+            .await
+            .map_err(StationError::DatabaseError)?;
+
+        // A live campaign takes precedence over the commodity's flat discount.
+        let campaign = lock_live_campaign(&mut tx, commodity_id)
+            .await
+            .map_err(StationError::DatabaseError)?;
+
+        let rule = match &campaign {
+            Some(campaign) if campaign.campaign.discount_type == "fixed" => {
+                DiscountRule::FixedPerLitre(campaign.campaign.discount_value)
+            }
+            Some(campaign) => DiscountRule::Percentage(campaign.campaign.discount_value),
+            None => {
+                if !is_enabled {
+                    return Err(StationError::WrongCredentials(
+                        "discount is not enabled for this commodity".to_string(),
+                    ));
+                }
+
+                let discount_percentage = percentage.ok_or_else(|| {
+                    StationError::WrongCredentials(
+                        "discount percentage is not configured".to_string(),
+                    )
+                })?;
+
+                if !(1..=10).contains(&discount_percentage) {
+                    return Err(StationError::WrongCredentials(
+                        "discount percentage must be between 1 and 10".to_string(),
+                    ));
+                }
+
+                DiscountRule::Percentage(discount_percentage)
+            }
+        };
+
+        let loyalty_tier = match loyalty_account {
+            Some(account_id) => Some(
+                account_tier(&mut *tx, account_id)
+                    .await
+                    .map_err(StationError::DatabaseError)?,
+            ),
+            None => None,
+        };
+        let rule = match loyalty_tier {
+            Some(tier) => rule.with_loyalty_bonus(tier.bonus_percentage()),
+            None => rule,
+        };
+
+        let quote = quote_quantity(original_price, rule, body.litres, body.amount)?;
+
+        if let Some(campaign) = &campaign {
+            let out_of_codes = campaign.codes_remaining.is_some_and(|remaining| remaining <= 0);
+            let out_of_budget = campaign
+                .budget_remaining
+                .is_some_and(|remaining| remaining < quote.total_saving as i64);
+
+            if out_of_codes || out_of_budget {
+                return Err(StationError::WrongCredentials(
+                    "discount campaign budget is exhausted".to_string(),
+                ));
+            }
+        }
+
         let created_at = Utc::now();
         let expires_at = created_at + Duration::hours(24);
-        let discounted_price = (original_price * (100 - discount_percentage)) / 100;
+        let discounted_price = rule.unit_price_after(original_price);
 
         let mut inserted: Option<DiscountCode> = None;
 
         for _ in 0..MAX_CODE_GENERATE_RETRY {
             let candidate_code = generate_candidate_code(&station_name, &station_type);
 
-            let maybe_code = sqlx::query_as::<_, DiscountCode>(
+            let maybe_code = sqlx::query_as::<_, DiscountCode>(&format!(
                 r#"
                 INSERT INTO discount_codes (
                     code,
@@ -192,33 +473,38 @@ impl DiscountService {
                     discount_percentage,
                     discounted_price,
                     created_at,
-                    expires_at
+                    expires_at,
+                    quantity_unit,
+                    quantity_litres,
+                    total_price,
+                    total_discounted_price,
+                    total_saving,
+                    campaign_id,
+                    discount_type,
+                    loyalty_account_id
                 )
-                VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16)
                 ON CONFLICT (code) DO NOTHING
-                RETURNING
-                    id,
-                    code,
-                    station_id,
-                    commodity_id,
-                    created_price,
-                    discount_percentage,
-                    discounted_price,
-                    created_at,
-                    expires_at,
-                    redeemed_at,
-                    redeemed_by_station_id
-                "#,
-            )
+                RETURNING {DISCOUNT_CODE_COLUMNS}
+                "#
+            ))
             .bind(candidate_code)
             .bind(body.station_id)
             .bind(commodity_id)
             .bind(original_price)
-            .bind(discount_percentage)
+            .bind(rule.percentage())
             .bind(discounted_price)
             .bind(created_at)
             .bind(expires_at)
-            .fetch_optional(&app_state.pool)
+            .bind(quote.unit)
+            .bind(quote.litres)
+            .bind(quote.total_price)
+            .bind(quote.total_discounted_price)
+            .bind(quote.total_saving)
+            .bind(campaign.as_ref().map(|c| c.campaign.id))
+            .bind(rule.discount_type())
+            .bind(loyalty_account)
+            .fetch_optional(&mut *tx)
             .await
             .map_err(StationError::DatabaseError)?;
 
@@ -241,10 +527,23 @@ impl DiscountService {
         .bind(inserted.id)
         .bind(body.station_id)
         .bind(ip)
-        .execute(&app_state.pool)
+        .execute(&mut *tx)
         .await
         .map_err(StationError::DatabaseError)?;
 
+        record_code_event(&mut *tx, inserted.id, "issued", CodeEventActor::Driver, None)
+            .await
+            .map_err(StationError::DatabaseError)?;
+
+        // Signed before committing so a code is never issued without the
+        // token that proves ownership of it.
+        let qr_token = app_state
+            .discount_tokens
+            .sign(&token_claims(&inserted))
+            .map_err(|err| StationError::Internal(err.to_string()))?;
+
+        tx.commit().await.map_err(StationError::DatabaseError)?;
+
         Ok((
             StatusCode::CREATED,
             Json(DiscountCodeResponse {
@@ -255,6 +554,17 @@ impl DiscountService {
                 original_price: inserted.created_price,
                 discounted_price: inserted.discounted_price,
                 is_expired: Utc::now() >= inserted.expires_at,
+                commodity_id: inserted.commodity_id,
+                commodity_name,
+                quantity_unit: inserted.quantity_unit,
+                quantity_litres: inserted.quantity_litres,
+                total_price: inserted.total_price,
+                total_discounted_price: inserted.total_discounted_price,
+                total_saving: inserted.total_saving,
+                discount_type: inserted.discount_type,
+                campaign_id: inserted.campaign_id,
+                qr_token,
+                loyalty_tier: loyalty_tier.map(LoyaltyTier::name),
             }),
         ))
     }
@@ -262,9 +572,14 @@ impl DiscountService {
     pub async fn redeem_code(
         State(app_state): State<AppState>,
         Extension(claims): Extension<Claims>,
+        Extension(entitlements): Extension<Entitlements>,
         Json(body): Json<RedeemDiscountCodeDto>,
     ) -> Result<(StatusCode, Json<RedeemDiscountCodeResponse>), StationError> {
+        entitlements.require(Feature::Discounts)?;
         let station_id = claims.station_res.id;
+        let signer = &app_state.discount_tokens;
+
+        let input = resolve_redemption_input(signer, &body.code, station_id)?;
 
         let mut tx = app_state
             .pool
@@ -272,50 +587,33 @@ impl DiscountService {
             .await
             .map_err(StationError::DatabaseError)?;
 
-        let code = sqlx::query_as::<_, DiscountCode>(
-            r#"
-            SELECT
-                id,
-                code,
-                station_id,
-                commodity_id,
-                created_price,
-                discount_percentage,
-                discounted_price,
-                created_at,
-                expires_at,
-                redeemed_at,
-                redeemed_by_station_id
-            FROM discount_codes
-            WHERE code = $1
-            FOR UPDATE
-            "#,
-        )
-        .bind(body.code.trim().to_uppercase())
-        .fetch_optional(&mut *tx)
-        .await
-        .map_err(StationError::DatabaseError)?
-        .ok_or_else(|| StationError::NotFound("discount code not found".to_string()))?;
+        let code = lock_code_for_redemption(&mut tx, &input, station_id).await?;
 
-        if code.station_id != station_id {
-            return Err(StationError::WrongCredentials(
-                "discount code does not belong to your station".to_string(),
+        let commodity_name: String = sqlx::query_scalar("SELECT name FROM commodities WHERE id = $1")
+            .bind(code.commodity_id)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(StationError::DatabaseError)?;
+
+        if code.voided_at.is_some() || code.cancelled_at.is_some() {
+            tx.commit().await.map_err(StationError::DatabaseError)?;
+            let message = match code.voided_at {
+                Some(_) => "code was voided",
+                None => "code was cancelled",
+            };
+            let is_expired = Utc::now() >= code.expires_at;
+            return Ok((
+                StatusCode::OK,
+                Json(redeem_response(message, code, commodity_name, is_expired)),
             ));
         }
 
         if code.redeemed_at.is_some() {
             tx.commit().await.map_err(StationError::DatabaseError)?;
+            let is_expired = Utc::now() >= code.expires_at;
             return Ok((
                 StatusCode::OK,
-                Json(RedeemDiscountCodeResponse {
-                    message: "code already redeemed".to_string(),
-                    code: Some(code.code),
-                    created_at: Some(code.created_at),
-                    expires_at: Some(code.expires_at),
-                    discount_percentage: Some(code.discount_percentage),
-                    discounted_price: Some(code.discounted_price),
-                    is_expired: Utc::now() >= code.expires_at,
-                }),
+                Json(redeem_response("code already redeemed", code, commodity_name, is_expired)),
             ));
         }
 
@@ -325,15 +623,7 @@ impl DiscountService {
             tx.commit().await.map_err(StationError::DatabaseError)?;
             return Ok((
                 StatusCode::OK,
-                Json(RedeemDiscountCodeResponse {
-                    message: "code is expired".to_string(),
-                    code: Some(code.code),
-                    created_at: Some(code.created_at),
-                    expires_at: Some(code.expires_at),
-                    discount_percentage: Some(code.discount_percentage),
-                    discounted_price: Some(code.discounted_price),
-                    is_expired: true,
-                }),
+                Json(redeem_response("code is expired", code, commodity_name, true)),
             ));
         }
 
@@ -343,7 +633,8 @@ impl DiscountService {
             r#"
             UPDATE discount_codes
             SET redeemed_at = $1,
-                redeemed_by_station_id = $2
+                redeemed_by_station_id = $2,
+                redemption_source = 'online'
             WHERE id = $3
             "#,
         )
@@ -354,22 +645,387 @@ impl DiscountService {
         .await
         .map_err(StationError::DatabaseError)?;
 
+        record_code_event(
+            &mut *tx,
+            code.id,
+            "redeemed",
+            CodeEventActor::Station(station_id),
+            None,
+        )
+        .await
+        .map_err(StationError::DatabaseError)?;
+
+        post_code_earnings(&mut tx, &code)
+            .await
+            .map_err(StationError::DatabaseError)?;
+
         tx.commit().await.map_err(StationError::DatabaseError)?;
 
         Ok((
             StatusCode::OK,
-            Json(RedeemDiscountCodeResponse {
-                message: "code redeemed successfully".to_string(),
-                code: Some(code.code),
-                created_at: Some(code.created_at),
-                expires_at: Some(code.expires_at),
-                discount_percentage: Some(code.discount_percentage),
-                discounted_price: Some(code.discounted_price),
-                is_expired: false,
-            }),
+            Json(redeem_response(
+                "code redeemed successfully",
+                code,
+                commodity_name,
+                false,
+            )),
         ))
     }
 
+    /// Accepts redemptions an attendant app queued while offline.
+    ///
+    /// Each redemption is checked against the code's expiry at the time the
+    /// attendant recorded, not the upload time. When a code was redeemed more
+    /// than once, the earliest recorded redemption wins regardless of upload
+    /// order; later ones come back as `duplicate`.
+    pub async fn sync_redemptions(
+        State(app_state): State<AppState>,
+        Extension(claims): Extension<Claims>,
+        Extension(entitlements): Extension<Entitlements>,
+        Json(body): Json<SyncRedemptionsDto>,
+    ) -> Result<Json<SyncRedemptionsResponse>, StationError> {
+        entitlements.require(Feature::Discounts)?;
+        let station_id = claims.station_res.id;
+
+        if body.redemptions.is_empty() || body.redemptions.len() > MAX_SYNC_BATCH {
+            return Err(StationError::WrongCredentials(format!(
+                "a sync batch must contain between 1 and {MAX_SYNC_BATCH} redemptions"
+            )));
+        }
+
+        let signer = &app_state.discount_tokens;
+        let synced_at = Utc::now();
+
+        let mut ordered: Vec<(usize, OfflineRedemptionDto)> =
+            body.redemptions.into_iter().enumerate().collect();
+        ordered.sort_by(|(a_index, a), (b_index, b)| {
+            a.redeemed_at.cmp(&b.redeemed_at).then(a_index.cmp(b_index))
+        });
+
+        let mut results = Vec::with_capacity(ordered.len());
+
+        for (index, redemption) in ordered {
+            let result =
+                sync_redemption(&app_state.pool, signer, station_id, synced_at, &redemption)
+                    .await;
+
+            results.push(match result {
+                Ok(outcome) => SyncRedemptionResult {
+                    index,
+                    client_reference: redemption.client_reference,
+                    code: Some(outcome.code),
+                    status: outcome.status,
+                    message: outcome.message,
+                    redeemed_at: outcome.redeemed_at,
+                    amount_to_charge: Some(outcome.amount_to_charge),
+                },
+                Err(StationError::DatabaseError(err)) => {
+                    return Err(StationError::DatabaseError(err));
+                }
+                Err(StationError::WrongCredentials(message) | StationError::NotFound(message)) => {
+                    SyncRedemptionResult {
+                        index,
+                        client_reference: redemption.client_reference,
+                        code: None,
+                        status: "invalid",
+                        message,
+                        redeemed_at: None,
+                        amount_to_charge: None,
+                    }
+                }
+                Err(err) => SyncRedemptionResult {
+                    index,
+                    client_reference: redemption.client_reference,
+                    code: None,
+                    status: "invalid",
+                    message: err.to_string(),
+                    redeemed_at: None,
+                    amount_to_charge: None,
+                },
+            });
+        }
+
+        results.sort_by_key(|result| result.index);
+
+        Ok(Json(SyncRedemptionsResponse { results }))
+    }
+
+    /// Lets a station undo its own redemption shortly after making it. The
+    /// code becomes redeemable again until it expires.
+    pub async fn reverse_redemption(
+        State(app_state): State<AppState>,
+        Extension(claims): Extension<Claims>,
+        Json(body): Json<ReverseRedemptionDto>,
+    ) -> Result<Json<DiscountCodeStateResponse>, StationError> {
+        let station_id = claims.station_res.id;
+        let reason = validate_reason(&body.reason)?;
+        let signer = &app_state.discount_tokens;
+
+        let input = resolve_redemption_input(signer, &body.code, station_id)?;
+
+        let mut tx = app_state
+            .pool
+            .begin()
+            .await
+            .map_err(StationError::DatabaseError)?;
+
+        let code = lock_code_for_redemption(&mut tx, &input, station_id).await?;
+
+        if code.voided_at.is_some() {
+            return Err(StationError::WrongCredentials(
+                "voided codes cannot be reversed".to_string(),
+            ));
+        }
+
+        let Some(redeemed_at) = code.redeemed_at else {
+            return Err(StationError::WrongCredentials(
+                "code has not been redeemed".to_string(),
+            ));
+        };
+
+        // Offline redemptions get the grace window from when they reached us.
+        let recorded_at = code.redemption_synced_at.unwrap_or(redeemed_at).max(redeemed_at);
+        if Utc::now() - recorded_at > Duration::minutes(REVERSAL_GRACE_MINUTES) {
+            return Err(StationError::WrongCredentials(format!(
+                "redemptions can only be reversed within {REVERSAL_GRACE_MINUTES} minutes"
+            )));
+        }
+
+        sqlx::query(
+            r#"
+            UPDATE discount_codes
+            SET redeemed_at = NULL,
+                redeemed_by_station_id = NULL,
+                redemption_source = NULL,
+                redemption_synced_at = NULL
+            WHERE id = $1
+            "#,
+        )
+        .bind(code.id)
+        .execute(&mut *tx)
+        .await
+        .map_err(StationError::DatabaseError)?;
+
+        record_code_event(
+            &mut *tx,
+            code.id,
+            "reversed",
+            CodeEventActor::Station(station_id),
+            Some(reason),
+        )
+        .await
+        .map_err(StationError::DatabaseError)?;
+
+        reverse_code_earnings(&mut tx, &code)
+            .await
+            .map_err(StationError::DatabaseError)?;
+
+        tx.commit().await.map_err(StationError::DatabaseError)?;
+
+        let status = if Utc::now() >= code.expires_at {
+            "expired"
+        } else {
+            "active"
+        };
+
+        Ok(Json(DiscountCodeStateResponse {
+            code: code.code,
+            status,
+            message: "redemption reversed".to_string(),
+        }))
+    }
+
+    /// Lets the driver who generated a code cancel it, proven by the token
+    /// they were handed with the code.
+    pub async fn cancel_code(
+        State(app_state): State<AppState>,
+        Path(code): Path<String>,
+        headers: HeaderMap,
+    ) -> Result<Json<DiscountCodeStateResponse>, StationError> {
+        verify_driver_token(&app_state.discount_tokens, &headers, &code)?;
+
+        let mut tx = app_state
+            .pool
+            .begin()
+            .await
+            .map_err(StationError::DatabaseError)?;
+
+        let code = lock_code(&mut tx, &code).await?;
+
+        let status = code_status(&code);
+        if status != "active" {
+            return Err(StationError::WrongCredentials(format!(
+                "only active codes can be cancelled; this code is {status}"
+            )));
+        }
+
+        sqlx::query("UPDATE discount_codes SET cancelled_at = now() WHERE id = $1")
+            .bind(code.id)
+            .execute(&mut *tx)
+            .await
+            .map_err(StationError::DatabaseError)?;
+
+        record_code_event(&mut *tx, code.id, "cancelled", CodeEventActor::Driver, None)
+            .await
+            .map_err(StationError::DatabaseError)?;
+
+        tx.commit().await.map_err(StationError::DatabaseError)?;
+
+        Ok(Json(DiscountCodeStateResponse {
+            code: code.code,
+            status: "cancelled",
+            message: "code cancelled".to_string(),
+        }))
+    }
+
+    /// Renders the driver's own token as a QR code. Nothing is minted here:
+    /// the token comes from the generation response and must be presented.
+    pub async fn qr_code(
+        State(app_state): State<AppState>,
+        Path(code): Path<String>,
+        Query(query): Query<DiscountQrQuery>,
+        headers: HeaderMap,
+    ) -> Result<impl IntoResponse, StationError> {
+        let token = verify_driver_token(&app_state.discount_tokens, &headers, &code)?;
+
+        let format = query.format.as_deref().unwrap_or("png").to_ascii_lowercase();
+        let (content_type, body) = render_qr(token, &format)?;
+
+        Ok((StatusCode::OK, [(header::CONTENT_TYPE, content_type)], body))
+    }
+
+    pub async fn signing_key(
+        State(app_state): State<AppState>,
+    ) -> Result<Json<DiscountSigningKeyResponse>, StationError> {
+        Ok(Json(DiscountSigningKeyResponse {
+            algorithm: "Ed25519",
+            public_key: app_state.discount_tokens.public_key(),
+        }))
+    }
+
+    /// Hands an authenticated station the key its attendant app signs
+    /// offline redemptions with, to be cached while the app is online.
+    pub async fn station_sync_key(
+        State(app_state): State<AppState>,
+        Extension(claims): Extension<Claims>,
+    ) -> Result<Json<OfflineSyncKeyResponse>, StationError> {
+        Ok(Json(OfflineSyncKeyResponse {
+            algorithm: "HMAC-SHA256",
+            key: app_state.discount_tokens.offline_sync_key(claims.station_res.id),
+        }))
+    }
+
+    pub async fn station_discount_config(
+        State(app_state): State<AppState>,
+        Extension(claims): Extension<Claims>,
+    ) -> Result<Json<StationDiscountConfigResponse>, StationError> {
+        let station_id = claims.station_res.id;
+
+        let limits = find_discount_limits(&app_state.pool, station_id)
+            .await
+            .map_err(StationError::DatabaseError)?;
+
+        let commodities = sqlx::query_as::<_, CommodityDiscountConfig>(
+            r#"
+            SELECT
+                c.id AS commodity_id,
+                c.name AS commodity_name,
+                COALESCE(cd.is_enabled, FALSE) AS is_enabled,
+                cd.percentage,
+                COALESCE(cd.admin_locked, FALSE) AS admin_locked,
+                cd.updated_by_type,
+                cd.updated_at
+            FROM commodities c
+            LEFT JOIN commodity_discounts cd ON cd.commodity_id = c.id
+            WHERE c.station_id = $1
+            ORDER BY c.name
+            "#,
+        )
+        .bind(station_id)
+        .fetch_all(&app_state.pool)
+        .await
+        .map_err(StationError::DatabaseError)?;
+
+        Ok(Json(StationDiscountConfigResponse {
+            limits,
+            commodities,
+        }))
+    }
+
+    pub async fn update_station_discount(
+        State(app_state): State<AppState>,
+        Extension(claims): Extension<Claims>,
+        Extension(entitlements): Extension<Entitlements>,
+        Path(commodity_id): Path<Uuid>,
+        Json(body): Json<UpdateStationDiscountDto>,
+    ) -> Result<StatusCode, StationError> {
+        entitlements.require(Feature::Discounts)?;
+        let station_id = claims.station_res.id;
+
+        let owner: Option<Uuid> =
+            sqlx::query_scalar("SELECT station_id FROM commodities WHERE id = $1")
+                .bind(commodity_id)
+                .fetch_optional(&app_state.pool)
+                .await
+                .map_err(StationError::DatabaseError)?;
+
+        if owner != Some(station_id) {
+            return Err(StationError::NotFound(commodity_id.to_string()));
+        }
+
+        let percentage = if body.enabled {
+            let percentage = body.percentage.ok_or_else(|| {
+                StationError::WrongCredentials(
+                    "percentage is required when enabling discount".to_string(),
+                )
+            })?;
+
+            let limits = find_discount_limits(&app_state.pool, station_id)
+                .await
+                .map_err(StationError::DatabaseError)?
+                .ok_or_else(|| {
+                    StationError::WrongCredentials(
+                        "discount limits have not been set for this station".to_string(),
+                    )
+                })?;
+
+            if !(limits.min_percentage..=limits.max_percentage).contains(&percentage) {
+                return Err(StationError::WrongCredentials(format!(
+                    "percentage must be between {} and {}",
+                    limits.min_percentage, limits.max_percentage
+                )));
+            }
+
+            Some(percentage)
+        } else {
+            None
+        };
+
+        apply_discount_change(
+            &app_state.pool,
+            commodity_id,
+            DiscountActor::Station(station_id),
+            percentage,
+            false,
+        )
+        .await?;
+
+        Ok(StatusCode::NO_CONTENT)
+    }
+
+    pub async fn station_discount_changes(
+        State(app_state): State<AppState>,
+        Extension(claims): Extension<Claims>,
+    ) -> Result<Json<Vec<DiscountChange>>, StationError> {
+        let scope = DiscountChangeScope::Station(claims.station_res.id);
+
+        let changes = list_discount_changes(&app_state.pool, scope)
+            .await
+            .map_err(StationError::DatabaseError)?;
+
+        Ok(Json(changes))
+    }
+
     pub async fn station_stats(
         State(app_state): State<AppState>,
         Extension(claims): Extension<Claims>,
@@ -382,10 +1038,408 @@ impl DiscountService {
 
         Ok(Json(StationDiscountStatsResponse {
             redeemed_codes: stats.redeemed_codes,
+            cancelled_codes: stats.cancelled_codes,
+            voided_codes: stats.voided_codes,
+            reversed_redemptions: stats.reversed_redemptions,
         }))
     }
 }
 
+/// Who changed a discount code's state.
+#[derive(Debug, Clone, Copy)]
+pub enum CodeEventActor {
+    Driver,
+    Station(Uuid),
+    Admin(Uuid),
+}
+
+impl CodeEventActor {
+    fn kind(self) -> &'static str {
+        match self {
+            CodeEventActor::Driver => "driver",
+            CodeEventActor::Station(_) => "station",
+            CodeEventActor::Admin(_) => "admin",
+        }
+    }
+
+    fn id(self) -> Option<Uuid> {
+        match self {
+            CodeEventActor::Driver => None,
+            CodeEventActor::Station(id) | CodeEventActor::Admin(id) => Some(id),
+        }
+    }
+}
+
+async fn record_code_event<'e, E: PgExecutor<'e>>(
+    executor: E,
+    code_id: Uuid,
+    event_type: &str,
+    actor: CodeEventActor,
+    reason: Option<&str>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO discount_code_events (discount_code_id, event_type, actor_type, actor_id, reason)
+        VALUES ($1, $2, $3, $4, $5)
+        "#,
+    )
+    .bind(code_id)
+    .bind(event_type)
+    .bind(actor.kind())
+    .bind(actor.id())
+    .bind(reason)
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
+
+/// Current state of a code. Voiding and cancelling override everything else.
+fn code_status(code: &DiscountCode) -> &'static str {
+    if code.voided_at.is_some() {
+        "voided"
+    } else if code.cancelled_at.is_some() {
+        "cancelled"
+    } else if code.redeemed_at.is_some() {
+        "redeemed"
+    } else if Utc::now() >= code.expires_at {
+        "expired"
+    } else {
+        "active"
+    }
+}
+
+fn validate_reason(reason: &str) -> Result<&str, StationError> {
+    let reason = reason.trim();
+    if reason.is_empty() || reason.len() > MAX_REASON_LENGTH {
+        return Err(StationError::WrongCredentials(format!(
+            "reason is required (max {MAX_REASON_LENGTH} characters)"
+        )));
+    }
+
+    Ok(reason)
+}
+
+async fn lock_code(
+    tx: &mut Transaction<'_, Postgres>,
+    code: &str,
+) -> Result<DiscountCode, StationError> {
+    sqlx::query_as::<_, DiscountCode>(&format!(
+        r#"
+        SELECT {DISCOUNT_CODE_COLUMNS}
+        FROM discount_codes
+        WHERE code = $1
+        FOR UPDATE
+        "#
+    ))
+    .bind(code.trim().to_uppercase())
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(StationError::DatabaseError)?
+    .ok_or_else(|| StationError::NotFound("discount code not found".to_string()))
+}
+
+/// Voids a code on an admin's behalf. A voided redemption no longer counts
+/// towards stats or campaign budgets.
+pub async fn void_code(
+    pool: &PgPool,
+    code: &str,
+    admin_id: Uuid,
+    reason: &str,
+) -> Result<DiscountCodeStateResponse, StationError> {
+    let reason = validate_reason(reason)?;
+
+    let mut tx = pool.begin().await.map_err(StationError::DatabaseError)?;
+
+    let code = lock_code(&mut tx, code).await?;
+
+    if code.voided_at.is_some() {
+        return Err(StationError::WrongCredentials(
+            "code is already voided".to_string(),
+        ));
+    }
+
+    sqlx::query("UPDATE discount_codes SET voided_at = now() WHERE id = $1")
+        .bind(code.id)
+        .execute(&mut *tx)
+        .await
+        .map_err(StationError::DatabaseError)?;
+
+    record_code_event(
+        &mut *tx,
+        code.id,
+        "voided",
+        CodeEventActor::Admin(admin_id),
+        Some(reason),
+    )
+    .await
+    .map_err(StationError::DatabaseError)?;
+
+    reverse_code_earnings(&mut tx, &code)
+        .await
+        .map_err(StationError::DatabaseError)?;
+
+    tx.commit().await.map_err(StationError::DatabaseError)?;
+
+    Ok(DiscountCodeStateResponse {
+        code: code.code,
+        status: "voided",
+        message: "code voided".to_string(),
+    })
+}
+
+pub async fn list_code_events(
+    pool: &PgPool,
+    code: &str,
+) -> Result<Vec<DiscountCodeEvent>, StationError> {
+    let code_id: Uuid = sqlx::query_scalar("SELECT id FROM discount_codes WHERE code = $1")
+        .bind(code.trim().to_uppercase())
+        .fetch_optional(pool)
+        .await
+        .map_err(StationError::DatabaseError)?
+        .ok_or_else(|| StationError::NotFound("discount code not found".to_string()))?;
+
+    sqlx::query_as::<_, DiscountCodeEvent>(
+        r#"
+        SELECT id, discount_code_id, event_type, actor_type, actor_id, reason, created_at
+        FROM discount_code_events
+        WHERE discount_code_id = $1
+        ORDER BY created_at, id
+        "#,
+    )
+    .bind(code_id)
+    .fetch_all(pool)
+    .await
+    .map_err(StationError::DatabaseError)
+}
+
+/// A redemption input resolved to its short code. Scanned tokens are checked
+/// before any database work, exactly as an attendant app would offline.
+struct RedemptionInput {
+    short_code: String,
+    token: Option<DiscountTokenClaims>,
+}
+
+fn resolve_redemption_input(
+    signer: &DiscountTokenSigner,
+    raw: &str,
+    station_id: Uuid,
+) -> Result<RedemptionInput, StationError> {
+    if !looks_like_token(raw) {
+        return Ok(RedemptionInput {
+            short_code: raw.trim().to_uppercase(),
+            token: None,
+        });
+    }
+
+    let token = signer
+        .verify(raw)
+        .map_err(|_| StationError::WrongCredentials("invalid discount token".to_string()))?;
+
+    if token.sid != station_id {
+        return Err(StationError::WrongCredentials(
+            "discount code does not belong to your station".to_string(),
+        ));
+    }
+
+    Ok(RedemptionInput {
+        short_code: token.code.clone(),
+        token: Some(token),
+    })
+}
+
+/// Loads and row-locks a code for redemption by `station_id`.
+async fn lock_code_for_redemption(
+    tx: &mut Transaction<'_, Postgres>,
+    input: &RedemptionInput,
+    station_id: Uuid,
+) -> Result<DiscountCode, StationError> {
+    let code = sqlx::query_as::<_, DiscountCode>(&format!(
+        r#"
+        SELECT {DISCOUNT_CODE_COLUMNS}
+        FROM discount_codes
+        WHERE code = $1
+        FOR UPDATE
+        "#
+    ))
+    .bind(&input.short_code)
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(StationError::DatabaseError)?
+    .ok_or_else(|| StationError::NotFound("discount code not found".to_string()))?;
+
+    if input.token.as_ref().is_some_and(|token| token.cid != code.id) {
+        return Err(StationError::WrongCredentials(
+            "invalid discount token".to_string(),
+        ));
+    }
+
+    if code.station_id != station_id {
+        return Err(StationError::WrongCredentials(
+            "discount code does not belong to your station".to_string(),
+        ));
+    }
+
+    Ok(code)
+}
+
+struct SyncOutcome {
+    code: String,
+    status: &'static str,
+    message: String,
+    redeemed_at: Option<chrono::DateTime<Utc>>,
+    amount_to_charge: i32,
+}
+
+async fn sync_redemption(
+    pool: &PgPool,
+    signer: &DiscountTokenSigner,
+    station_id: Uuid,
+    synced_at: chrono::DateTime<Utc>,
+    redemption: &OfflineRedemptionDto,
+) -> Result<SyncOutcome, StationError> {
+    signer
+        .verify_offline_redemption(
+            station_id,
+            &redemption.code,
+            redemption.redeemed_at,
+            &redemption.signature,
+        )
+        .map_err(|_| StationError::WrongCredentials("invalid redemption signature".to_string()))?;
+
+    let input = resolve_redemption_input(signer, &redemption.code, station_id)?;
+    let recorded_at = redemption.redeemed_at;
+
+    if recorded_at > synced_at + Duration::minutes(MAX_SYNC_CLOCK_SKEW_MINUTES) {
+        return Err(StationError::WrongCredentials(
+            "redemption time is in the future".to_string(),
+        ));
+    }
+
+    if recorded_at < synced_at - Duration::days(MAX_SYNC_AGE_DAYS) {
+        return Err(StationError::WrongCredentials(format!(
+            "redemptions older than {MAX_SYNC_AGE_DAYS} days can no longer be synced"
+        )));
+    }
+
+    let mut tx = pool.begin().await.map_err(StationError::DatabaseError)?;
+
+    let code = lock_code_for_redemption(&mut tx, &input, station_id).await?;
+
+    if code.voided_at.is_some() || code.cancelled_at.is_some() {
+        return Err(StationError::WrongCredentials(format!(
+            "code was {}",
+            code_status(&code)
+        )));
+    }
+
+    if recorded_at < code.created_at {
+        return Err(StationError::WrongCredentials(
+            "redemption time is before the code was issued".to_string(),
+        ));
+    }
+
+    let outcome = |status, message: String, redeemed_at| SyncOutcome {
+        code: code.code.clone(),
+        status,
+        message,
+        redeemed_at,
+        amount_to_charge: code.total_discounted_price,
+    };
+
+    if recorded_at >= code.expires_at {
+        return Ok(outcome(
+            "expired",
+            "code had expired when it was redeemed".to_string(),
+            code.redeemed_at,
+        ));
+    }
+
+    if let Some(existing) = code.redeemed_at.filter(|existing| *existing <= recorded_at) {
+        return Ok(outcome(
+            "duplicate",
+            "code was already redeemed earlier".to_string(),
+            Some(existing),
+        ));
+    }
+
+    // A redemption the server confirmed at the pump stands; only another
+    // offline redemption can be replaced by an earlier one.
+    if code.redeemed_at.is_some() && code.redemption_source.as_deref() != Some("offline") {
+        return Ok(outcome(
+            "duplicate",
+            "code was already redeemed online".to_string(),
+            code.redeemed_at,
+        ));
+    }
+
+    sqlx::query(
+        r#"
+        UPDATE discount_codes
+        SET redeemed_at = $1,
+            redeemed_by_station_id = $2,
+            redemption_source = 'offline',
+            redemption_synced_at = $3
+        WHERE id = $4
+        "#,
+    )
+    .bind(recorded_at)
+    .bind(station_id)
+    .bind(synced_at)
+    .bind(code.id)
+    .execute(&mut *tx)
+    .await
+    .map_err(StationError::DatabaseError)?;
+
+    record_code_event(
+        &mut *tx,
+        code.id,
+        "redeemed",
+        CodeEventActor::Station(station_id),
+        None,
+    )
+    .await
+    .map_err(StationError::DatabaseError)?;
+
+    // An earlier redemption replacing a later one has already earned.
+    if code.redeemed_at.is_none() {
+        post_code_earnings(&mut tx, &code)
+            .await
+            .map_err(StationError::DatabaseError)?;
+    }
+
+    tx.commit().await.map_err(StationError::DatabaseError)?;
+
+    let message = match code.redeemed_at {
+        Some(_) => "redeemed; replaces a later redemption of the same code".to_string(),
+        None => "code redeemed successfully".to_string(),
+    };
+
+    Ok(outcome("redeemed", message, Some(recorded_at)))
+}
+
+fn redeem_response(
+    message: &str,
+    code: DiscountCode,
+    commodity_name: String,
+    is_expired: bool,
+) -> RedeemDiscountCodeResponse {
+    RedeemDiscountCodeResponse {
+        message: message.to_string(),
+        code: Some(code.code),
+        created_at: Some(code.created_at),
+        expires_at: Some(code.expires_at),
+        discount_percentage: Some(code.discount_percentage),
+        discounted_price: Some(code.discounted_price),
+        is_expired,
+        commodity_name: Some(commodity_name),
+        quantity_litres: Some(code.quantity_litres),
+        total_price: Some(code.total_price),
+        amount_to_charge: Some(code.total_discounted_price),
+        total_saving: Some(code.total_saving),
+    }
+}
+
 pub async fn station_discount_stats(
     pool: &PgPool,
     station_id: Uuid,
@@ -393,9 +1447,18 @@ pub async fn station_discount_stats(
     sqlx::query_as::<_, StationDiscountStats>(
         r#"
         SELECT
-            COUNT(*) FILTER (WHERE redeemed_at IS NOT NULL)::BIGINT AS redeemed_codes
-        FROM discount_codes
-        WHERE station_id = $1
+            COUNT(*) FILTER (WHERE dc.redeemed_at IS NOT NULL AND dc.voided_at IS NULL)::BIGINT
+                AS redeemed_codes,
+            COUNT(*) FILTER (WHERE dc.cancelled_at IS NOT NULL)::BIGINT AS cancelled_codes,
+            COUNT(*) FILTER (WHERE dc.voided_at IS NOT NULL)::BIGINT AS voided_codes,
+            COALESCE(SUM(reversals.count), 0)::BIGINT AS reversed_redemptions
+        FROM discount_codes dc
+        CROSS JOIN LATERAL (
+            SELECT COUNT(*) AS count
+            FROM discount_code_events e
+            WHERE e.discount_code_id = dc.id AND e.event_type = 'reversed'
+        ) reversals
+        WHERE dc.station_id = $1
         "#,
     )
     .bind(station_id)
@@ -408,10 +1471,256 @@ pub async fn admin_discount_stats(pool: &PgPool) -> Result<AdminDiscountStats, s
         r#"
         SELECT
             COUNT(*)::BIGINT AS created_codes,
-            COUNT(*) FILTER (WHERE redeemed_at IS NOT NULL)::BIGINT AS redeemed_codes
+            COUNT(*) FILTER (WHERE redeemed_at IS NOT NULL AND voided_at IS NULL)::BIGINT
+                AS redeemed_codes,
+            COUNT(*) FILTER (WHERE cancelled_at IS NOT NULL)::BIGINT AS cancelled_codes,
+            COUNT(*) FILTER (WHERE voided_at IS NOT NULL)::BIGINT AS voided_codes,
+            (SELECT COUNT(*) FROM discount_code_events WHERE event_type = 'reversed')::BIGINT
+                AS reversed_redemptions
         FROM discount_codes
         "#,
     )
     .fetch_one(pool)
     .await
 }
+
+/// Who changed a commodity discount.
+#[derive(Debug, Clone, Copy)]
+pub enum DiscountActor {
+    Admin(Uuid),
+    Station(Uuid),
+}
+
+impl DiscountActor {
+    fn kind(self) -> &'static str {
+        match self {
+            DiscountActor::Admin(_) => "admin",
+            DiscountActor::Station(_) => "station",
+        }
+    }
+
+    fn id(self) -> Uuid {
+        match self {
+            DiscountActor::Admin(id) | DiscountActor::Station(id) => id,
+        }
+    }
+}
+
+pub enum DiscountChangeScope {
+    Station(Uuid),
+    Commodity(Uuid),
+}
+
+pub async fn find_discount_limits(
+    pool: &PgPool,
+    station_id: Uuid,
+) -> Result<Option<StationDiscountLimits>, sqlx::Error> {
+    sqlx::query_as::<_, StationDiscountLimits>(
+        r#"
+        SELECT station_id, min_percentage, max_percentage, updated_at
+        FROM station_discount_limits
+        WHERE station_id = $1
+        "#,
+    )
+    .bind(station_id)
+    .fetch_optional(pool)
+    .await
+}
+
+pub async fn upsert_discount_limits(
+    pool: &PgPool,
+    station_id: Uuid,
+    admin_id: Uuid,
+    min_percentage: i32,
+    max_percentage: i32,
+) -> Result<StationDiscountLimits, StationError> {
+    if !(1..=10).contains(&min_percentage)
+        || !(1..=10).contains(&max_percentage)
+        || min_percentage > max_percentage
+    {
+        return Err(StationError::WrongCredentials(
+            "limits must be between 1 and 10, with the minimum not above the maximum".to_string(),
+        ));
+    }
+
+    let mut tx = pool.begin().await.map_err(StationError::DatabaseError)?;
+
+    let limits = sqlx::query_as::<_, StationDiscountLimits>(
+        r#"
+        INSERT INTO station_discount_limits (station_id, min_percentage, max_percentage, updated_by_admin, updated_at)
+        SELECT id, $2, $3, $4, now() FROM stations WHERE id = $1
+        ON CONFLICT (station_id)
+        DO UPDATE SET
+            min_percentage = EXCLUDED.min_percentage,
+            max_percentage = EXCLUDED.max_percentage,
+            updated_by_admin = EXCLUDED.updated_by_admin,
+            updated_at = now()
+        RETURNING station_id, min_percentage, max_percentage, updated_at
+        "#,
+    )
+    .bind(station_id)
+    .bind(min_percentage)
+    .bind(max_percentage)
+    .bind(admin_id)
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(StationError::DatabaseError)?
+    .ok_or_else(|| StationError::NotFound(station_id.to_string()))?;
+
+    // Discounts the station picked under the old limits are pulled into the
+    // new range and logged as the admin's change. Admin-set discounts are
+    // left alone; the limits only bound what the station may choose.
+    sqlx::query(
+        r#"
+        WITH out_of_range AS (
+            SELECT cd.commodity_id, cd.percentage
+            FROM commodity_discounts cd
+            INNER JOIN commodities c ON c.id = cd.commodity_id
+            WHERE c.station_id = $1
+              AND cd.is_enabled
+              AND cd.updated_by_type = 'station'
+              AND cd.percentage NOT BETWEEN $2 AND $3
+            FOR UPDATE OF cd
+        ),
+        clamped AS (
+            UPDATE commodity_discounts cd
+            SET percentage = LEAST(GREATEST(o.percentage, $2), $3),
+                updated_by_type = 'admin',
+                updated_by_id = $4,
+                updated_at = now()
+            FROM out_of_range o
+            WHERE cd.commodity_id = o.commodity_id
+            RETURNING cd.commodity_id, o.percentage AS previous_percentage,
+                cd.percentage AS new_percentage, cd.admin_locked
+        )
+        INSERT INTO commodity_discount_changes (
+            commodity_id, station_id, actor_type, actor_id, previous_enabled,
+            previous_percentage, new_enabled, new_percentage, admin_locked
+        )
+        SELECT commodity_id, $1, 'admin', $4, TRUE, previous_percentage, TRUE,
+            new_percentage, admin_locked
+        FROM clamped
+        "#,
+    )
+    .bind(station_id)
+    .bind(min_percentage)
+    .bind(max_percentage)
+    .bind(admin_id)
+    .execute(&mut *tx)
+    .await
+    .map_err(StationError::DatabaseError)?;
+
+    tx.commit().await.map_err(StationError::DatabaseError)?;
+
+    Ok(limits)
+}
+
+/// Enables (`Some(percentage)`) or disables (`None`) a commodity discount and
+/// records the change. Stations cannot change a discount an admin has locked.
+pub async fn apply_discount_change(
+    pool: &PgPool,
+    commodity_id: Uuid,
+    actor: DiscountActor,
+    percentage: Option<i32>,
+    admin_locked: bool,
+) -> Result<(), StationError> {
+    let mut tx = pool.begin().await.map_err(StationError::DatabaseError)?;
+
+    let (station_id, previous_enabled, previous_percentage, currently_locked) =
+        sqlx::query_as::<_, (Uuid, Option<bool>, Option<i32>, Option<bool>)>(
+            r#"
+            SELECT c.station_id, cd.is_enabled, cd.percentage, cd.admin_locked
+            FROM commodities c
+            LEFT JOIN commodity_discounts cd ON cd.commodity_id = c.id
+            WHERE c.id = $1
+            FOR UPDATE OF c
+            "#,
+        )
+        .bind(commodity_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(StationError::DatabaseError)?
+        .ok_or_else(|| StationError::NotFound(commodity_id.to_string()))?;
+
+    if matches!(actor, DiscountActor::Station(_)) && currently_locked == Some(true) {
+        return Err(StationError::WrongCredentials(
+            "this discount is locked by an admin".to_string(),
+        ));
+    }
+
+    sqlx::query(
+        r#"
+        INSERT INTO commodity_discounts (
+            commodity_id, is_enabled, percentage, updated_by_type, updated_by_id, admin_locked, updated_at
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, now())
+        ON CONFLICT (commodity_id)
+        DO UPDATE SET
+            is_enabled = EXCLUDED.is_enabled,
+            percentage = EXCLUDED.percentage,
+            updated_by_type = EXCLUDED.updated_by_type,
+            updated_by_id = EXCLUDED.updated_by_id,
+            admin_locked = EXCLUDED.admin_locked,
+            updated_at = now()
+        "#,
+    )
+    .bind(commodity_id)
+    .bind(percentage.is_some())
+    .bind(percentage)
+    .bind(actor.kind())
+    .bind(actor.id())
+    .bind(admin_locked)
+    .execute(&mut *tx)
+    .await
+    .map_err(StationError::DatabaseError)?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO commodity_discount_changes (
+            commodity_id, station_id, actor_type, actor_id, previous_enabled,
+            previous_percentage, new_enabled, new_percentage, admin_locked
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+        "#,
+    )
+    .bind(commodity_id)
+    .bind(station_id)
+    .bind(actor.kind())
+    .bind(actor.id())
+    .bind(previous_enabled)
+    .bind(previous_percentage)
+    .bind(percentage.is_some())
+    .bind(percentage)
+    .bind(admin_locked)
+    .execute(&mut *tx)
+    .await
+    .map_err(StationError::DatabaseError)?;
+
+    tx.commit().await.map_err(StationError::DatabaseError)
+}
+
+pub async fn list_discount_changes(
+    pool: &PgPool,
+    scope: DiscountChangeScope,
+) -> Result<Vec<DiscountChange>, sqlx::Error> {
+    let (station_id, commodity_id) = match scope {
+        DiscountChangeScope::Station(id) => (Some(id), None),
+        DiscountChangeScope::Commodity(id) => (None, Some(id)),
+    };
+
+    sqlx::query_as::<_, DiscountChange>(
+        r#"
+        SELECT
+            id, commodity_id, station_id, actor_type, actor_id, previous_enabled,
+            previous_percentage, new_enabled, new_percentage, admin_locked, created_at
+        FROM commodity_discount_changes
+        WHERE ($1::uuid IS NULL OR station_id = $1)
+          AND ($2::uuid IS NULL OR commodity_id = $2)
+        ORDER BY created_at DESC
+        "#,
+    )
+    .bind(station_id)
+    .bind(commodity_id)
+    .fetch_all(pool)
+    .await
+}
@@ -0,0 +1,153 @@
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use chrono::{DateTime, Utc};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use crate::domain::utils::environment::is_development;
+
+/// Prefix that tells a scanned token apart from a hand-typed short code.
+pub const TOKEN_PREFIX: &str = "FFD1";
+
+/// What a discount QR code carries. Field names are kept short so the QR
+/// stays small enough to scan reliably from a phone screen.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DiscountTokenClaims {
+    /// Discount code id.
+    pub cid: Uuid,
+    /// Short code, so attendants can fall back to typing it.
+    pub code: String,
+    /// Station the code is valid at.
+    pub sid: Uuid,
+    /// Commodity the code discounts.
+    pub mid: Uuid,
+    /// Expiry as a unix timestamp.
+    pub exp: i64,
+}
+
+/// Signs and verifies discount tokens with Ed25519, so attendant apps can
+/// check a token offline with only the public key.
+///
+/// Tokens look like `FFD1.<payload>.<signature>`, both parts base64url.
+pub struct DiscountTokenSigner {
+    signing_key: SigningKey,
+}
+
+impl DiscountTokenSigner {
+    pub fn new(seed: [u8; 32]) -> Self {
+        Self {
+            signing_key: SigningKey::from_bytes(&seed),
+        }
+    }
+
+    /// Reads the 32-byte key seed from `DISCOUNT_SIGNING_KEY` (base64url).
+    /// Only development and test deployments may fall back to a seed derived
+    /// from `JWT_SECRET`; anywhere else the two secrets must stay separate.
+    pub fn from_env() -> anyhow::Result<Self> {
+        if let Ok(encoded) = std::env::var("DISCOUNT_SIGNING_KEY") {
+            let bytes = URL_SAFE_NO_PAD.decode(encoded.trim())?;
+            let seed: [u8; 32] = bytes
+                .try_into()
+                .map_err(|_| anyhow::anyhow!("DISCOUNT_SIGNING_KEY must be 32 bytes"))?;
+            return Ok(Self::new(seed));
+        }
+
+        if !is_development() {
+            anyhow::bail!("DISCOUNT_SIGNING_KEY must be set outside development");
+        }
+
+        let secret = std::env::var("JWT_SECRET")
+            .map_err(|_| anyhow::anyhow!("DISCOUNT_SIGNING_KEY or JWT_SECRET must be set"))?;
+        let seed: [u8; 32] = Sha256::digest(format!("discount-token:{secret}")).into();
+
+        Ok(Self::new(seed))
+    }
+
+    pub fn verifying_key(&self) -> VerifyingKey {
+        self.signing_key.verifying_key()
+    }
+
+    /// Public key, base64url encoded, for attendant apps.
+    pub fn public_key(&self) -> String {
+        URL_SAFE_NO_PAD.encode(self.verifying_key().as_bytes())
+    }
+
+    pub fn sign(&self, claims: &DiscountTokenClaims) -> anyhow::Result<String> {
+        let payload = URL_SAFE_NO_PAD.encode(serde_json::to_vec(claims)?);
+        let signing_input = format!("{TOKEN_PREFIX}.{payload}");
+        let signature = self.signing_key.sign(signing_input.as_bytes());
+
+        Ok(format!(
+            "{signing_input}.{}",
+            URL_SAFE_NO_PAD.encode(signature.to_bytes())
+        ))
+    }
+
+    /// Checks the signature and returns the claims. Expiry is left to the
+    /// caller, which may still want to report on an expired code.
+    pub fn verify(&self, token: &str) -> anyhow::Result<DiscountTokenClaims> {
+        let mut parts = token.trim().split('.');
+        let (Some(prefix), Some(payload), Some(signature), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            anyhow::bail!("malformed discount token");
+        };
+
+        if prefix != TOKEN_PREFIX {
+            anyhow::bail!("unsupported discount token version");
+        }
+
+        let signature_bytes: [u8; 64] = URL_SAFE_NO_PAD
+            .decode(signature)?
+            .try_into()
+            .map_err(|_| anyhow::anyhow!("malformed discount token signature"))?;
+
+        self.verifying_key().verify(
+            format!("{prefix}.{payload}").as_bytes(),
+            &Signature::from_bytes(&signature_bytes),
+        )?;
+
+        Ok(serde_json::from_slice(&URL_SAFE_NO_PAD.decode(payload)?)?)
+    }
+
+    /// Key a station's attendant app signs offline redemptions with,
+    /// base64url encoded. It is derived from the signing seed, so every
+    /// station gets its own key without anything being stored.
+    pub fn offline_sync_key(&self, station_id: Uuid) -> String {
+        URL_SAFE_NO_PAD.encode(self.offline_sync_mac(station_id).finalize().into_bytes())
+    }
+
+    /// Checks the attendant app's HMAC-SHA256 over `<code>.<redeemed_at>`,
+    /// where `code` is exactly what was captured at the pump and
+    /// `redeemed_at` is in unix milliseconds. The signature is base64url.
+    pub fn verify_offline_redemption(
+        &self,
+        station_id: Uuid,
+        code: &str,
+        redeemed_at: DateTime<Utc>,
+        signature: &str,
+    ) -> anyhow::Result<()> {
+        let signature = URL_SAFE_NO_PAD.decode(signature.trim())?;
+        let key = self.offline_sync_mac(station_id).finalize().into_bytes();
+
+        let mut mac = Hmac::<Sha256>::new_from_slice(&key)
+            .map_err(|_| anyhow::anyhow!("invalid offline sync key"))?;
+        mac.update(format!("{}.{}", code.trim(), redeemed_at.timestamp_millis()).as_bytes());
+        mac.verify_slice(&signature)
+            .map_err(|_| anyhow::anyhow!("invalid redemption signature"))
+    }
+
+    fn offline_sync_mac(&self, station_id: Uuid) -> Hmac<Sha256> {
+        let mut mac = Hmac::<Sha256>::new_from_slice(self.signing_key.as_bytes())
+            .expect("hmac accepts any key");
+        mac.update(format!("offline-sync:{station_id}").as_bytes());
+        mac
+    }
+}
+
+/// Whether a redemption input is a scanned token rather than a short code.
+pub fn looks_like_token(input: &str) -> bool {
+    input.trim().starts_with(&format!("{TOKEN_PREFIX}."))
+}
@@ -0,0 +1,8 @@
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct EmailsQuery {
+    /// `pending`, `sent` or `failed`.
+    pub status: Option<String>,
+    pub recipient: Option<String>,
+}
@@ -0,0 +1,5 @@
+pub mod dto;
+pub mod model;
+pub mod service;
+pub mod templates;
+pub mod transport;
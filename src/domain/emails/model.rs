@@ -0,0 +1,27 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::Value;
+use sqlx::FromRow;
+use uuid::Uuid;
+
+pub const EMAIL_STATUSES: [&str; 3] = ["pending", "sent", "failed"];
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct OutboxEmail {
+    pub id: Uuid,
+    pub station_id: Option<Uuid>,
+    pub template: String,
+    pub recipient: String,
+    pub variables: Value,
+    pub subject: String,
+    pub html_body: String,
+    pub text_body: String,
+    pub status: String,
+    pub attempts: i32,
+    pub last_error: Option<String>,
+    pub transport: Option<String>,
+    pub dedupe_key: Option<String>,
+    pub sent_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
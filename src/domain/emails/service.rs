@@ -0,0 +1,190 @@
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+use super::{
+    model::{EMAIL_STATUSES, OutboxEmail},
+    templates::{EmailTemplate, TemplateVars},
+    transport::{EmailTransport, OutgoingEmail},
+};
+use crate::domain::{
+    jobs::{
+        service::{EnqueueOptions, enqueue},
+        worker::SEND_EMAIL_JOB,
+    },
+    utils::errors::station_errors::StationError,
+};
+
+const EMAIL_COLUMNS: &str = r#"
+    id, station_id, template, recipient, variables, subject, html_body, text_body, status,
+    attempts, last_error, transport, dedupe_key, sent_at, created_at, updated_at
+"#;
+
+/// Payload of a `send_email` job.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SendEmailJob {
+    pub email_id: Uuid,
+}
+
+/// Renders `template` into the outbox and queues its delivery, inside the
+/// caller's transaction so the email exists only if the change that
+/// prompted it commits. Returns `None` when `dedupe_key` was already used.
+pub async fn queue_email(
+    tx: &mut Transaction<'_, Postgres>,
+    template: &EmailTemplate,
+    recipient: &str,
+    variables: TemplateVars,
+    station_id: Option<Uuid>,
+    dedupe_key: Option<String>,
+) -> anyhow::Result<Option<Uuid>> {
+    let rendered = template.render(&variables)?;
+
+    let email_id = sqlx::query_scalar::<_, Uuid>(
+        r#"
+        INSERT INTO email_outbox (
+            station_id, template, recipient, variables, subject, html_body, text_body, dedupe_key
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        ON CONFLICT (dedupe_key) DO NOTHING
+        RETURNING id
+        "#,
+    )
+    .bind(station_id)
+    .bind(template.name)
+    .bind(recipient)
+    .bind(serde_json::Value::Object(variables))
+    .bind(&rendered.subject)
+    .bind(&rendered.html)
+    .bind(&rendered.text)
+    .bind(dedupe_key)
+    .fetch_optional(&mut **tx)
+    .await?;
+
+    let Some(email_id) = email_id else {
+        return Ok(None);
+    };
+
+    enqueue(
+        &mut **tx,
+        SEND_EMAIL_JOB,
+        &serde_json::to_value(SendEmailJob { email_id })?,
+        EnqueueOptions {
+            dedupe_key: Some(format!("email:{email_id}")),
+            ..Default::default()
+        },
+    )
+    .await?;
+
+    Ok(Some(email_id))
+}
+
+/// Sends one outbox message and records the outcome. A message that was
+/// already sent is left alone, so a retried job never sends twice. When
+/// `final_attempt` is set a failure marks the message failed for good.
+pub async fn deliver_email(
+    pool: &PgPool,
+    transport: &dyn EmailTransport,
+    email_id: Uuid,
+    final_attempt: bool,
+) -> anyhow::Result<()> {
+    let email = find_email(pool, email_id).await?;
+    if email.status == "sent" {
+        return Ok(());
+    }
+
+    let outgoing = OutgoingEmail {
+        id: email.id,
+        to: email.recipient,
+        subject: email.subject,
+        text: email.text_body,
+        html: email.html_body,
+    };
+
+    match transport.send(&outgoing).await {
+        Ok(()) => {
+            sqlx::query(
+                r#"
+                UPDATE email_outbox
+                SET status = 'sent',
+                    attempts = attempts + 1,
+                    last_error = NULL,
+                    transport = $2,
+                    sent_at = now(),
+                    updated_at = now()
+                WHERE id = $1
+                "#,
+            )
+            .bind(email_id)
+            .bind(transport.name())
+            .execute(pool)
+            .await?;
+
+            Ok(())
+        }
+        Err(err) => {
+            let error = format!("{err:#}");
+
+            sqlx::query(
+                r#"
+                UPDATE email_outbox
+                SET status = CASE WHEN $3 THEN 'failed' ELSE 'pending' END,
+                    attempts = attempts + 1,
+                    last_error = $2,
+                    transport = $4,
+                    updated_at = now()
+                WHERE id = $1
+                "#,
+            )
+            .bind(email_id)
+            .bind(&error)
+            .bind(final_attempt)
+            .bind(transport.name())
+            .execute(pool)
+            .await?;
+
+            Err(err)
+        }
+    }
+}
+
+pub async fn list_emails(
+    pool: &PgPool,
+    status: Option<&str>,
+    recipient: Option<&str>,
+) -> Result<Vec<OutboxEmail>, StationError> {
+    if let Some(status) = status
+        && !EMAIL_STATUSES.contains(&status)
+    {
+        return Err(StationError::WrongCredentials(format!(
+            "email status must be one of {}",
+            EMAIL_STATUSES.join(", ")
+        )));
+    }
+
+    sqlx::query_as::<_, OutboxEmail>(&format!(
+        r#"
+        SELECT {EMAIL_COLUMNS}
+        FROM email_outbox
+        WHERE ($1::VARCHAR IS NULL OR status = $1)
+          AND ($2::VARCHAR IS NULL OR lower(recipient) = lower($2))
+        ORDER BY created_at DESC
+        LIMIT 200
+        "#
+    ))
+    .bind(status)
+    .bind(recipient)
+    .fetch_all(pool)
+    .await
+    .map_err(StationError::DatabaseError)
+}
+
+pub async fn find_email(pool: &PgPool, email_id: Uuid) -> Result<OutboxEmail, StationError> {
+    sqlx::query_as::<_, OutboxEmail>(&format!(
+        "SELECT {EMAIL_COLUMNS} FROM email_outbox WHERE id = $1"
+    ))
+    .bind(email_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(StationError::DatabaseError)?
+    .ok_or_else(|| StationError::NotFound(email_id.to_string()))
+}
@@ -0,0 +1,118 @@
+use serde_json::{Map, Value};
+
+pub type TemplateVars = Map<String, Value>;
+
+/// A named email. Subject, text and HTML may use `{{variable}}`
+/// placeholders; values are HTML-escaped in the HTML part.
+pub struct EmailTemplate {
+    pub name: &'static str,
+    subject: &'static str,
+    text: &'static str,
+    html: &'static str,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RenderedEmail {
+    pub subject: String,
+    pub text: String,
+    pub html: String,
+}
+
+pub const SUBSCRIPTION_REMINDER: EmailTemplate = EmailTemplate {
+    name: "subscription_reminder",
+    subject: "Subscription expires in {{days_left}} day(s)",
+    text: "Hello {{station_name}},\n\n\
+        Your subscription expires in {{days_left}} day(s). \
+        Please renew to avoid interruption.\n",
+    html: "<p>Hello {{station_name}},</p>\
+        <p>Your subscription expires in <strong>{{days_left}} day(s)</strong>. \
+        Please renew to avoid interruption.</p>",
+};
+
+pub const SUBSCRIPTION_EXPIRED: EmailTemplate = EmailTemplate {
+    name: "subscription_expired",
+    subject: "Subscription expired",
+    text: "Hello {{station_name}},\n\n\
+        Your subscription has expired. Please renew from your dashboard.\n",
+    html: "<p>Hello {{station_name}},</p>\
+        <p>Your subscription has expired. Please renew from your dashboard.</p>",
+};
+
+/// Any dashboard notice, for events without a template of their own.
+pub const STATION_NOTICE: EmailTemplate = EmailTemplate {
+    name: "station_notice",
+    subject: "{{title}}",
+    text: "Hello {{station_name}},\n\n{{body}}\n",
+    html: "<p>Hello {{station_name}},</p><p>{{body}}</p>",
+};
+
+const TEMPLATES: [&EmailTemplate; 3] = [
+    &SUBSCRIPTION_REMINDER,
+    &SUBSCRIPTION_EXPIRED,
+    &STATION_NOTICE,
+];
+
+pub fn find_template(name: &str) -> Option<&'static EmailTemplate> {
+    TEMPLATES.into_iter().find(|template| template.name == name)
+}
+
+impl EmailTemplate {
+    /// Fails if a placeholder has no matching variable, so a typo never
+    /// reaches an inbox as `{{station_nmae}}`.
+    pub fn render(&self, vars: &TemplateVars) -> anyhow::Result<RenderedEmail> {
+        Ok(RenderedEmail {
+            subject: fill(self.subject, vars, false)?,
+            text: fill(self.text, vars, false)?,
+            html: fill(self.html, vars, true)?,
+        })
+    }
+}
+
+fn fill(source: &str, vars: &TemplateVars, escape: bool) -> anyhow::Result<String> {
+    let mut output = String::with_capacity(source.len());
+    let mut rest = source;
+
+    while let Some(start) = rest.find("{{") {
+        output.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let end = after
+            .find("}}")
+            .ok_or_else(|| anyhow::anyhow!("unclosed placeholder in template"))?;
+
+        let name = after[..end].trim();
+        let value = match vars.get(name) {
+            Some(Value::String(value)) => value.clone(),
+            Some(Value::Null) | None => anyhow::bail!("missing template variable {name}"),
+            Some(value) => value.to_string(),
+        };
+
+        if escape {
+            output.push_str(&escape_html(&value));
+        } else {
+            output.push_str(&value);
+        }
+
+        rest = &after[end + 2..];
+    }
+
+    output.push_str(rest);
+
+    Ok(output)
+}
+
+fn escape_html(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+
+    for ch in value.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(ch),
+        }
+    }
+
+    escaped
+}
@@ -0,0 +1,209 @@
+use std::{
+    future::Future,
+    path::PathBuf,
+    pin::Pin,
+    sync::{Arc, Mutex},
+};
+
+use anyhow::Context;
+use lettre::{
+    AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
+    message::{Mailbox, MultiPart},
+    transport::smtp::authentication::Credentials,
+};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::domain::utils::environment::is_development;
+
+pub type EmailFuture<'a, T> = Pin<Box<dyn Future<Output = anyhow::Result<T>> + Send + 'a>>;
+
+/// A rendered message ready to hand to a transport.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct OutgoingEmail {
+    pub id: Uuid,
+    pub to: String,
+    pub subject: String,
+    pub text: String,
+    pub html: String,
+}
+
+/// Delivers outbox messages. An error means the message was not accepted
+/// and the job sending it will retry.
+pub trait EmailTransport: Send + Sync {
+    /// Stored with each sent message, e.g. `smtp`.
+    fn name(&self) -> &'static str;
+
+    fn send<'a>(&'a self, email: &'a OutgoingEmail) -> EmailFuture<'a, ()>;
+}
+
+/// Picks the transport named by `EMAIL_TRANSPORT` (`smtp`, `file` or `log`).
+/// Without it, SMTP is used when `SMTP_HOST` is set and emails are only
+/// logged otherwise. Neither `file` nor `log` delivers anything, so outside
+/// development only SMTP is accepted; otherwise the outbox would record
+/// messages as sent that nobody received.
+pub fn transport_from_env() -> anyhow::Result<Arc<dyn EmailTransport>> {
+    let configured = std::env::var("EMAIL_TRANSPORT").ok();
+    let smtp_host = std::env::var("SMTP_HOST").ok();
+
+    match (configured.as_deref(), smtp_host) {
+        (Some("smtp"), _) | (None, Some(_)) => Ok(Arc::new(SmtpEmailTransport::from_env()?)),
+        (Some("file"), _) if is_development() => Ok(Arc::new(FileEmailTransport::from_env())),
+        (Some("log"), _) | (None, None) if is_development() => Ok(Arc::new(LogEmailTransport)),
+        (Some("file" | "log"), _) | (None, None) => {
+            anyhow::bail!("SMTP_HOST must be set outside development")
+        }
+        (Some(other), _) => anyhow::bail!("unknown EMAIL_TRANSPORT {other}"),
+    }
+}
+
+// ─── SMTP ────────────────────────────────────────────────────────────────────
+
+/// One pooled connection to the relay, shared by every send.
+pub struct SmtpEmailTransport {
+    mailer: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+}
+
+impl SmtpEmailTransport {
+    /// Reads `SMTP_HOST`, `SMTP_PORT` (default 587), `SMTP_USERNAME`,
+    /// `SMTP_PASSWORD` and `SMTP_FROM`.
+    pub fn from_env() -> anyhow::Result<Self> {
+        let host = std::env::var("SMTP_HOST").context("SMTP_HOST must be set for smtp email")?;
+        let port = std::env::var("SMTP_PORT")
+            .ok()
+            .and_then(|v| v.parse::<u16>().ok())
+            .unwrap_or(587);
+        let username = std::env::var("SMTP_USERNAME").unwrap_or_default();
+        let password = std::env::var("SMTP_PASSWORD").unwrap_or_default();
+        let from = std::env::var("SMTP_FROM")
+            .unwrap_or_else(|_| "noreply@fuelgetter.local".to_string())
+            .parse()
+            .context("SMTP_FROM is not a valid mailbox")?;
+
+        let mailer = AsyncSmtpTransport::<Tokio1Executor>::relay(&host)?
+            .credentials(Credentials::new(username, password))
+            .port(port)
+            .build();
+
+        Ok(Self { mailer, from })
+    }
+}
+
+impl EmailTransport for SmtpEmailTransport {
+    fn name(&self) -> &'static str {
+        "smtp"
+    }
+
+    fn send<'a>(&'a self, email: &'a OutgoingEmail) -> EmailFuture<'a, ()> {
+        Box::pin(async move {
+            let message = Message::builder()
+                .from(self.from.clone())
+                .to(email.to.parse()?)
+                .subject(&email.subject)
+                .multipart(MultiPart::alternative_plain_html(
+                    email.text.clone(),
+                    email.html.clone(),
+                ))?;
+
+            self.mailer.send(message).await?;
+
+            Ok(())
+        })
+    }
+}
+
+// ─── File ────────────────────────────────────────────────────────────────────
+
+/// Writes each message to `<dir>/<id>.json`, for local development.
+pub struct FileEmailTransport {
+    dir: PathBuf,
+}
+
+impl FileEmailTransport {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Reads `EMAIL_FILE_DIR` (default `./emails`).
+    pub fn from_env() -> Self {
+        Self::new(std::env::var("EMAIL_FILE_DIR").unwrap_or_else(|_| "./emails".to_string()))
+    }
+}
+
+impl EmailTransport for FileEmailTransport {
+    fn name(&self) -> &'static str {
+        "file"
+    }
+
+    fn send<'a>(&'a self, email: &'a OutgoingEmail) -> EmailFuture<'a, ()> {
+        Box::pin(async move {
+            tokio::fs::create_dir_all(&self.dir).await?;
+            tokio::fs::write(
+                self.dir.join(format!("{}.json", email.id)),
+                serde_json::to_vec_pretty(email)?,
+            )
+            .await?;
+
+            Ok(())
+        })
+    }
+}
+
+// ─── Memory ──────────────────────────────────────────────────────────────────
+
+/// Keeps sent messages in memory so tests can assert on what went out.
+#[derive(Default)]
+pub struct MemoryEmailTransport {
+    sent: Mutex<Vec<OutgoingEmail>>,
+}
+
+impl MemoryEmailTransport {
+    pub fn sent(&self) -> Vec<OutgoingEmail> {
+        self.sent
+            .lock()
+            .map(|sent| sent.clone())
+            .unwrap_or_default()
+    }
+}
+
+impl EmailTransport for MemoryEmailTransport {
+    fn name(&self) -> &'static str {
+        "memory"
+    }
+
+    fn send<'a>(&'a self, email: &'a OutgoingEmail) -> EmailFuture<'a, ()> {
+        Box::pin(async move {
+            self.sent
+                .lock()
+                .map_err(|_| anyhow::anyhow!("memory transport poisoned"))?
+                .push(email.clone());
+
+            Ok(())
+        })
+    }
+}
+
+// ─── Log ─────────────────────────────────────────────────────────────────────
+
+/// Used in development when no relay is configured: the message is logged,
+/// not delivered.
+pub struct LogEmailTransport;
+
+impl EmailTransport for LogEmailTransport {
+    fn name(&self) -> &'static str {
+        "log"
+    }
+
+    fn send<'a>(&'a self, email: &'a OutgoingEmail) -> EmailFuture<'a, ()> {
+        Box::pin(async move {
+            tracing::warn!(
+                "no email transport configured; not sending {:?} to {}",
+                email.subject,
+                email.to
+            );
+
+            Ok(())
+        })
+    }
+}
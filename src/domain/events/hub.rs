@@ -0,0 +1,87 @@
+use std::{sync::Arc, time::Duration};
+
+use sqlx::{PgPool, postgres::PgListener};
+use tokio::sync::broadcast;
+
+use super::model::{STATION_EVENTS_CHANNEL, StationEvent};
+
+/// Events a single slow dashboard may fall behind by before it is told to
+/// refetch.
+const EVENT_BUFFER: usize = 1024;
+
+/// What the hub fans out to the connected dashboards.
+#[derive(Debug, Clone)]
+pub enum HubEvent {
+    Station(Arc<StationEvent>),
+    /// The `LISTEN` connection dropped and was re-established; every
+    /// dashboard may have missed events.
+    Resync,
+}
+
+/// Fans station events out to the dashboards connected to this instance.
+/// Events arrive over Postgres `LISTEN`, so a write on any instance reaches
+/// dashboards on every instance.
+#[derive(Clone)]
+pub struct StationEvents {
+    pool: PgPool,
+    sender: broadcast::Sender<HubEvent>,
+}
+
+impl StationEvents {
+    pub fn new(pool: PgPool) -> Self {
+        let (sender, _) = broadcast::channel(EVENT_BUFFER);
+
+        Self { pool, sender }
+    }
+
+    /// Starts listening. Returns once `LISTEN` is in place, so nothing
+    /// committed after this is missed.
+    pub async fn listen(&self) -> sqlx::Result<()> {
+        let mut listener = PgListener::connect_with(&self.pool).await?;
+        listener.listen(STATION_EVENTS_CHANNEL).await?;
+
+        tokio::spawn(forward(listener, self.sender.clone()));
+
+        Ok(())
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<HubEvent> {
+        self.sender.subscribe()
+    }
+}
+
+async fn forward(mut listener: PgListener, sender: broadcast::Sender<HubEvent>) {
+    // Set when a receive failed; the connection may have been lost without
+    // `try_recv` getting to report it.
+    let mut interrupted = false;
+
+    // Nobody may be connected, so send errors are ignored throughout.
+    loop {
+        match listener.try_recv().await {
+            Ok(Some(notification)) => {
+                if std::mem::take(&mut interrupted) {
+                    let _ = sender.send(HubEvent::Resync);
+                }
+
+                match serde_json::from_str::<StationEvent>(notification.payload()) {
+                    Ok(event) => {
+                        let _ = sender.send(HubEvent::Station(Arc::new(event)));
+                    }
+                    Err(err) => tracing::warn!("ignoring malformed station event: {err}"),
+                }
+            }
+            // The connection was lost and has been re-established and
+            // re-listened; events sent while it was down are gone.
+            Ok(None) => {
+                tracing::warn!("station event listener reconnected");
+                interrupted = false;
+                let _ = sender.send(HubEvent::Resync);
+            }
+            Err(err) => {
+                tracing::error!("station event listener failed: {err}");
+                interrupted = true;
+                tokio::time::sleep(Duration::from_secs(1)).await;
+            }
+        }
+    }
+}
@@ -0,0 +1,3 @@
+pub mod hub;
+pub mod model;
+pub mod service;
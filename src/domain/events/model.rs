@@ -0,0 +1,23 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+/// Postgres channel the station event triggers notify on.
+pub const STATION_EVENTS_CHANNEL: &str = "station_events";
+
+pub const NOTIFICATION_EVENT: &str = "notification";
+pub const DISCOUNT_CODE_EVENT: &str = "discount_code";
+pub const PRICE_CHANGE_EVENT: &str = "price_change";
+/// Sent when a dashboard fell behind and missed events; it should refetch.
+pub const LAGGED_EVENT: &str = "lagged";
+/// Sent to every dashboard after the `LISTEN` connection was re-established;
+/// events committed while it was down are lost, so it should refetch.
+pub const RESYNC_EVENT: &str = "resync";
+
+/// Something that happened to a station, as sent by `notify_station_event`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StationEvent {
+    pub station_id: Uuid,
+    pub event: String,
+    pub data: Value,
+}
@@ -0,0 +1,115 @@
+use std::{convert::Infallible, time::Duration};
+
+use axum::{
+    extract::{Extension, State},
+    response::sse::{Event, KeepAlive, Sse},
+};
+use chrono::Utc;
+use tokio::{
+    sync::{broadcast::error::RecvError, mpsc},
+    time::{Instant, interval_at, sleep_until},
+};
+use tokio_stream::{Stream, wrappers::ReceiverStream};
+use uuid::Uuid;
+
+use super::{
+    hub::HubEvent,
+    model::{LAGGED_EVENT, RESYNC_EVENT},
+};
+use crate::{
+    app_state::AppState,
+    authentication::station::authenticate::token::service::Claims,
+    domain::subscriptions::entitlements::{AccessLevel, station_entitlements},
+};
+
+/// How often an idle stream re-checks that the station may still use the
+/// dashboard.
+const ACCESS_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+pub struct EventsService;
+
+impl EventsService {
+    /// Server-sent events for the signed-in station: new notifications,
+    /// generated discount codes and price changes, each named after its
+    /// kind with the details as JSON data.
+    ///
+    /// The stream ends when the token expires or the station's subscription
+    /// lapses; the dashboard reconnects with a fresh token.
+    pub async fn stream(
+        State(app_state): State<AppState>,
+        Extension(claims): Extension<Claims>,
+    ) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+        let expires_in = (claims.exp as i64 - Utc::now().timestamp()).max(0) as u64;
+        let (sender, receiver) = mpsc::channel(16);
+
+        tokio::spawn(forward_station_events(
+            app_state,
+            claims.station_res.id,
+            Instant::now() + Duration::from_secs(expires_in),
+            sender,
+        ));
+
+        Sse::new(ReceiverStream::new(receiver)).keep_alive(KeepAlive::default())
+    }
+}
+
+async fn forward_station_events(
+    app_state: AppState,
+    station_id: Uuid,
+    expires_at: Instant,
+    sender: mpsc::Sender<Result<Event, Infallible>>,
+) {
+    let mut events = app_state.events.subscribe();
+    let expiry = sleep_until(expires_at);
+    tokio::pin!(expiry);
+    let mut access_checks = interval_at(
+        Instant::now() + ACCESS_CHECK_INTERVAL,
+        ACCESS_CHECK_INTERVAL,
+    );
+
+    loop {
+        let event = tokio::select! {
+            _ = &mut expiry => break,
+            _ = sender.closed() => break,
+            _ = access_checks.tick() => {
+                if !has_access(&app_state, station_id).await {
+                    break;
+                }
+                continue;
+            }
+            received = events.recv() => match received {
+                Ok(HubEvent::Station(event)) if event.station_id == station_id => {
+                    // Nothing new reaches a station that lost access since
+                    // the last check.
+                    if !has_access(&app_state, station_id).await {
+                        break;
+                    }
+                    Event::default().event(&event.event).data(event.data.to_string())
+                }
+                Ok(HubEvent::Station(_)) => continue,
+                Ok(HubEvent::Resync) => Event::default().event(RESYNC_EVENT).data("{}"),
+                Err(RecvError::Lagged(missed)) => {
+                    Event::default().event(LAGGED_EVENT).data(missed.to_string())
+                }
+                Err(RecvError::Closed) => break,
+            }
+        };
+
+        if sender.send(Ok(event)).await.is_err() {
+            break;
+        }
+    }
+}
+
+/// A lapsed station loses the stream; one in its grace period keeps it. A
+/// failed lookup keeps it too, rather than dropping every dashboard while
+/// the database is unreachable.
+async fn has_access(app_state: &AppState, station_id: Uuid) -> bool {
+    match station_entitlements(&app_state.pool, station_id, app_state.subscription_grace).await {
+        Ok(entitlements) => !matches!(entitlements.access, AccessLevel::Lapsed),
+        Err(err) => {
+            tracing::warn!("failed to re-check dashboard access for station {station_id}: {err}");
+            true
+        }
+    }
+}
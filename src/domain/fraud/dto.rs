@@ -0,0 +1,28 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Deserialize)]
+pub struct FraudFlagsQuery {
+    /// Defaults to `open`.
+    pub status: Option<String>,
+    pub station_id: Option<Uuid>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReviewFraudFlagDto {
+    /// `dismissed` or `confirmed`.
+    pub status: String,
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateDiscountSuspensionDto {
+    pub suspended: bool,
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FraudScanResponse {
+    pub flags_created: u64,
+    pub stations_suspended: Vec<Uuid>,
+}
@@ -0,0 +1,34 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::FromRow;
+use uuid::Uuid;
+
+pub const FLAG_STATUSES: [&str; 3] = ["open", "dismissed", "confirmed"];
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct FraudFlag {
+    pub id: Uuid,
+    pub station_id: Uuid,
+    pub station_name: String,
+    pub discount_code_id: Option<Uuid>,
+    pub code: Option<String>,
+    /// `ip_burst`, `fast_redemption`, `full_redemption_rate` or
+    /// `odd_hour_redemption`.
+    pub rule: String,
+    pub score: i32,
+    pub details: String,
+    pub observed_at: DateTime<Utc>,
+    pub status: String,
+    pub reviewed_by_admin: Option<Uuid>,
+    pub review_note: Option<String>,
+    pub reviewed_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct DiscountSuspension {
+    pub station_id: Uuid,
+    pub discounts_suspended_at: Option<DateTime<Utc>>,
+    pub discounts_suspended_reason: Option<String>,
+    pub discounts_suspension_lifted_at: Option<DateTime<Utc>>,
+}
@@ -0,0 +1,458 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    net::IpAddr,
+};
+
+use chrono::{DateTime, Duration, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::domain::{
+    fraud::{
+        dto::FraudScanResponse,
+        model::{DiscountSuspension, FLAG_STATUSES, FraudFlag},
+    },
+    notifications::service::{Notice, notify_station},
+    utils::errors::station_errors::StationError,
+};
+
+const FRAUD_KIND: &str = "fraud";
+
+/// How far back each scan looks for new anomalies.
+const SCAN_WINDOW_HOURS: i64 = 24;
+
+/// Codes from one /24 (IPv4) or /48 (IPv6) network within an hour.
+const IP_BURST_MIN_CODES: usize = 5;
+const IP_BURST_MIN_ADDRESSES: usize = 2;
+const IP_BURST_SCORE: i32 = 30;
+
+/// (station, network, hour) → (codes generated, distinct addresses).
+type IpBursts = BTreeMap<(Uuid, String, DateTime<Utc>), (usize, BTreeSet<String>)>;
+
+/// Nobody drives to the pump this quickly after generating a code.
+const FAST_REDEMPTION_SECONDS: i64 = 60;
+const FAST_REDEMPTION_SCORE: i32 = 40;
+
+/// Every settled code redeemed, over enough codes to be meaningful.
+const FULL_RATE_WINDOW_DAYS: i64 = 7;
+const FULL_RATE_MIN_CODES: i64 = 20;
+const FULL_RATE_SCORE: i32 = 50;
+
+/// Redemptions before this Africa/Lagos hour.
+const ODD_HOURS_END: i32 = 5;
+const ODD_HOUR_SCORE: i32 = 20;
+
+/// A station is suspended once its unreviewed or confirmed flags reach this
+/// score, spread over at least `SUSPENSION_MIN_DAYS` days of the window.
+const SUSPENSION_WINDOW_DAYS: i64 = 7;
+const SUSPENSION_SCORE: i64 = 150;
+const SUSPENSION_MIN_DAYS: i64 = 2;
+
+const FRAUD_FLAG_COLUMNS: &str = r#"
+    f.id, f.station_id, s.name AS station_name, f.discount_code_id, dc.code, f.rule,
+    f.score, f.details, f.observed_at, f.status, f.reviewed_by_admin, f.review_note,
+    f.reviewed_at, f.created_at
+"#;
+
+/// Runs every rule over recent activity, then suspends stations whose
+/// anomalies are sustained. Safe to run repeatedly: each anomaly is only
+/// flagged once.
+pub async fn run_fraud_scan(pool: &PgPool) -> anyhow::Result<FraudScanResponse> {
+    let since = Utc::now() - Duration::hours(SCAN_WINDOW_HOURS);
+
+    let flags_created = flag_ip_bursts(pool, since).await?
+        + flag_fast_redemptions(pool, since).await?
+        + flag_odd_hour_redemptions(pool, since).await?
+        + flag_full_redemption_rates(pool).await?;
+
+    let stations_suspended = suspend_sustained_anomalies(pool).await?;
+
+    Ok(FraudScanResponse {
+        flags_created,
+        stations_suspended,
+    })
+}
+
+/// The network an address belongs to, so neighbouring addresses from one
+/// carrier or household count together.
+fn network_prefix(ip: &str) -> String {
+    match ip.parse::<IpAddr>() {
+        Ok(IpAddr::V4(v4)) => {
+            let [a, b, c, _] = v4.octets();
+            format!("{a}.{b}.{c}.0/24")
+        }
+        Ok(IpAddr::V6(v6)) => {
+            let segments = v6.segments();
+            format!("{:x}:{:x}:{:x}::/48", segments[0], segments[1], segments[2])
+        }
+        Err(_) => ip.to_string(),
+    }
+}
+
+async fn flag_ip_bursts(pool: &PgPool, since: DateTime<Utc>) -> anyhow::Result<u64> {
+    let logs = sqlx::query_as::<_, (Uuid, String, DateTime<Utc>)>(
+        r#"
+        SELECT station_id, ip_address, date_trunc('hour', created_at)
+        FROM discount_code_generation_logs
+        WHERE created_at >= $1
+        "#,
+    )
+    .bind(since)
+    .fetch_all(pool)
+    .await?;
+
+    let mut bursts = IpBursts::new();
+    for (station_id, ip, hour) in logs {
+        let entry = bursts
+            .entry((station_id, network_prefix(&ip), hour))
+            .or_default();
+        entry.0 += 1;
+        entry.1.insert(ip);
+    }
+
+    let mut created = 0;
+    for ((station_id, prefix, hour), (codes, addresses)) in bursts {
+        if codes < IP_BURST_MIN_CODES || addresses.len() < IP_BURST_MIN_ADDRESSES {
+            continue;
+        }
+
+        created += sqlx::query(
+            r#"
+            INSERT INTO discount_fraud_flags (station_id, rule, score, details, observed_at, dedup_key)
+            VALUES ($1, 'ip_burst', $2, $3, $4, $5)
+            ON CONFLICT (dedup_key) DO NOTHING
+            "#,
+        )
+        .bind(station_id)
+        .bind(IP_BURST_SCORE)
+        .bind(format!(
+            "{codes} codes from {} addresses in {prefix} within an hour",
+            addresses.len()
+        ))
+        .bind(hour)
+        .bind(format!("ip_burst:{station_id}:{prefix}:{}", hour.timestamp()))
+        .execute(pool)
+        .await?
+        .rows_affected();
+    }
+
+    Ok(created)
+}
+
+/// Offline redemptions are judged when they reach the server, so the scan
+/// window is matched against the sync time when there is one.
+async fn flag_fast_redemptions(pool: &PgPool, since: DateTime<Utc>) -> anyhow::Result<u64> {
+    let result = sqlx::query(
+        r#"
+        INSERT INTO discount_fraud_flags (
+            station_id, discount_code_id, rule, score, details, observed_at, dedup_key
+        )
+        SELECT
+            dc.station_id,
+            dc.id,
+            'fast_redemption',
+            $1,
+            format(
+                'code %s was redeemed %s seconds after it was issued',
+                dc.code,
+                EXTRACT(EPOCH FROM dc.redeemed_at - dc.created_at)::INTEGER
+            ),
+            dc.redeemed_at,
+            'fast_redemption:' || dc.id
+        FROM discount_codes dc
+        WHERE dc.redeemed_at IS NOT NULL
+          AND dc.voided_at IS NULL
+          AND COALESCE(dc.redemption_synced_at, dc.redeemed_at) >= $2
+          AND dc.redeemed_at - dc.created_at < make_interval(secs => $3)
+        ON CONFLICT (dedup_key) DO NOTHING
+        "#,
+    )
+    .bind(FAST_REDEMPTION_SCORE)
+    .bind(since)
+    .bind(FAST_REDEMPTION_SECONDS as f64)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+async fn flag_odd_hour_redemptions(pool: &PgPool, since: DateTime<Utc>) -> anyhow::Result<u64> {
+    let result = sqlx::query(
+        r#"
+        INSERT INTO discount_fraud_flags (
+            station_id, discount_code_id, rule, score, details, observed_at, dedup_key
+        )
+        SELECT
+            dc.station_id,
+            dc.id,
+            'odd_hour_redemption',
+            $1,
+            format(
+                'code %s was redeemed at %s',
+                dc.code,
+                to_char(dc.redeemed_at AT TIME ZONE 'Africa/Lagos', 'HH24:MI')
+            ),
+            dc.redeemed_at,
+            'odd_hour_redemption:' || dc.id
+        FROM discount_codes dc
+        WHERE dc.redeemed_at IS NOT NULL
+          AND dc.voided_at IS NULL
+          AND COALESCE(dc.redemption_synced_at, dc.redeemed_at) >= $2
+          AND EXTRACT(HOUR FROM dc.redeemed_at AT TIME ZONE 'Africa/Lagos') < $3
+        ON CONFLICT (dedup_key) DO NOTHING
+        "#,
+    )
+    .bind(ODD_HOUR_SCORE)
+    .bind(since)
+    .bind(ODD_HOURS_END)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Flags stations that redeemed every code that could have lapsed. At most
+/// once per station per day.
+async fn flag_full_redemption_rates(pool: &PgPool) -> anyhow::Result<u64> {
+    let result = sqlx::query(
+        r#"
+        INSERT INTO discount_fraud_flags (station_id, rule, score, details, observed_at, dedup_key)
+        SELECT
+            dc.station_id,
+            'full_redemption_rate',
+            $1,
+            format('all %s settled codes in the last %s days were redeemed', COUNT(*), $2::INTEGER),
+            now(),
+            'full_redemption_rate:' || dc.station_id || ':'
+                || (now() AT TIME ZONE 'Africa/Lagos')::date
+        FROM discount_codes dc
+        WHERE dc.created_at >= now() - make_interval(days => $2::INTEGER)
+          AND dc.cancelled_at IS NULL
+          AND dc.voided_at IS NULL
+          AND (dc.redeemed_at IS NOT NULL OR dc.expires_at <= now())
+        GROUP BY dc.station_id
+        HAVING COUNT(*) >= $3
+           AND COUNT(*) FILTER (WHERE dc.redeemed_at IS NULL) = 0
+        ON CONFLICT (dedup_key) DO NOTHING
+        "#,
+    )
+    .bind(FULL_RATE_SCORE)
+    .bind(FULL_RATE_WINDOW_DAYS as i32)
+    .bind(FULL_RATE_MIN_CODES)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+async fn suspend_sustained_anomalies(pool: &PgPool) -> anyhow::Result<Vec<Uuid>> {
+    let suspended = sqlx::query_as::<_, (Uuid, i64)>(
+        r#"
+        WITH sustained AS (
+            SELECT f.station_id, SUM(f.score)::BIGINT AS score
+            FROM discount_fraud_flags f
+            INNER JOIN stations s ON s.id = f.station_id
+            WHERE f.status <> 'dismissed'
+              AND f.observed_at >= now() - make_interval(days => $1)
+              AND f.created_at > COALESCE(s.discounts_suspension_lifted_at, '-infinity')
+              AND s.discounts_suspended_at IS NULL
+            GROUP BY f.station_id
+            HAVING SUM(f.score) >= $2
+               AND COUNT(DISTINCT (f.observed_at AT TIME ZONE 'Africa/Lagos')::date) >= $3
+        )
+        UPDATE stations s
+        SET discounts_suspended_at = now(),
+            discounts_suspended_reason = format(
+                'automatic: fraud score %s over the last %s days', sustained.score, $1::INTEGER
+            )
+        FROM sustained
+        WHERE s.id = sustained.station_id
+        RETURNING s.id, sustained.score
+        "#,
+    )
+    .bind(SUSPENSION_WINDOW_DAYS as i32)
+    .bind(SUSPENSION_SCORE)
+    .bind(SUSPENSION_MIN_DAYS)
+    .fetch_all(pool)
+    .await?;
+
+    // The suspensions are already committed; one station's notice failing
+    // must not hide the rest from the scan result.
+    for (station_id, score) in &suspended {
+        tracing::warn!("suspended discounts for station {station_id} (fraud score {score})");
+
+        if let Err(err) = notify_suspension(pool, *station_id).await {
+            tracing::error!("failed to notify station {station_id} of fraud suspension: {err:?}");
+        }
+    }
+
+    Ok(suspended.into_iter().map(|(station_id, _)| station_id).collect())
+}
+
+async fn notify_suspension(pool: &PgPool, station_id: Uuid) -> anyhow::Result<()> {
+    let mut tx = pool.begin().await?;
+    notify_station(
+        &mut tx,
+        station_id,
+        Notice {
+            kind: FRAUD_KIND,
+            title: "Discounts suspended",
+            body: "Discount codes are paused for your station while unusual activity is reviewed. Please contact admin.",
+            email: None,
+            dedupe_key: None,
+        },
+    )
+    .await?;
+    tx.commit().await?;
+
+    Ok(())
+}
+
+pub async fn list_fraud_flags(
+    pool: &PgPool,
+    status: &str,
+    station_id: Option<Uuid>,
+) -> Result<Vec<FraudFlag>, StationError> {
+    if !FLAG_STATUSES.contains(&status) {
+        return Err(StationError::WrongCredentials(format!(
+            "status must be one of: {}",
+            FLAG_STATUSES.join(", ")
+        )));
+    }
+
+    sqlx::query_as::<_, FraudFlag>(&format!(
+        r#"
+        SELECT {FRAUD_FLAG_COLUMNS}
+        FROM discount_fraud_flags f
+        INNER JOIN stations s ON s.id = f.station_id
+        LEFT JOIN discount_codes dc ON dc.id = f.discount_code_id
+        WHERE f.status = $1
+          AND ($2::uuid IS NULL OR f.station_id = $2)
+        ORDER BY f.score DESC, f.observed_at DESC
+        "#
+    ))
+    .bind(status)
+    .bind(station_id)
+    .fetch_all(pool)
+    .await
+    .map_err(StationError::DatabaseError)
+}
+
+pub async fn review_fraud_flag(
+    pool: &PgPool,
+    flag_id: Uuid,
+    admin_id: Uuid,
+    status: &str,
+    note: Option<String>,
+) -> Result<FraudFlag, StationError> {
+    if !matches!(status, "dismissed" | "confirmed") {
+        return Err(StationError::WrongCredentials(
+            "status must be dismissed or confirmed".to_string(),
+        ));
+    }
+
+    sqlx::query_as::<_, FraudFlag>(&format!(
+        r#"
+        WITH f AS (
+            UPDATE discount_fraud_flags
+            SET status = $1,
+                review_note = $2,
+                reviewed_by_admin = $3,
+                reviewed_at = now()
+            WHERE id = $4
+            RETURNING *
+        )
+        SELECT {FRAUD_FLAG_COLUMNS}
+        FROM f
+        INNER JOIN stations s ON s.id = f.station_id
+        LEFT JOIN discount_codes dc ON dc.id = f.discount_code_id
+        "#
+    ))
+    .bind(status)
+    .bind(note.map(|note| note.trim().to_string()).filter(|note| !note.is_empty()))
+    .bind(admin_id)
+    .bind(flag_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(StationError::DatabaseError)?
+    .ok_or_else(|| StationError::NotFound(flag_id.to_string()))
+}
+
+/// Suspends or reinstates a station's discounts by hand.
+pub async fn set_discount_suspension(
+    pool: &PgPool,
+    station_id: Uuid,
+    suspended: bool,
+    reason: Option<String>,
+) -> Result<DiscountSuspension, StationError> {
+    let reason = reason
+        .map(|reason| reason.trim().to_string())
+        .filter(|reason| !reason.is_empty());
+
+    if suspended && reason.is_none() {
+        return Err(StationError::WrongCredentials(
+            "reason is required when suspending discounts".to_string(),
+        ));
+    }
+
+    // The notice is written in the same transaction, so the station hears
+    // about exactly the suspension that was committed.
+    let mut tx = pool.begin().await.map_err(StationError::DatabaseError)?;
+
+    let suspension = sqlx::query_as::<_, DiscountSuspension>(
+        r#"
+        UPDATE stations
+        SET discounts_suspended_at = CASE WHEN $1 THEN COALESCE(discounts_suspended_at, now()) END,
+            discounts_suspended_reason = CASE WHEN $1 THEN $2 END,
+            discounts_suspension_lifted_at = CASE
+                WHEN $1 THEN discounts_suspension_lifted_at
+                WHEN discounts_suspended_at IS NOT NULL THEN now()
+                ELSE discounts_suspension_lifted_at
+            END
+        WHERE id = $3
+        RETURNING
+            id AS station_id, discounts_suspended_at, discounts_suspended_reason,
+            discounts_suspension_lifted_at
+        "#,
+    )
+    .bind(suspended)
+    .bind(&reason)
+    .bind(station_id)
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(StationError::DatabaseError)?
+    .ok_or_else(|| StationError::NotFound(station_id.to_string()))?;
+
+    let (title, body) = if suspended {
+        (
+            "Discounts suspended",
+            format!(
+                "Discount codes are paused for your station: {}",
+                reason.unwrap_or_default()
+            ),
+        )
+    } else {
+        (
+            "Discounts reinstated",
+            "Your station can issue discount codes again.".to_string(),
+        )
+    };
+
+    notify_station(
+        &mut tx,
+        station_id,
+        Notice {
+            kind: FRAUD_KIND,
+            title,
+            body: &body,
+            email: None,
+            dedupe_key: None,
+        },
+    )
+    .await
+    .map_err(|err| StationError::Internal(err.to_string()))?;
+
+    tx.commit().await.map_err(StationError::DatabaseError)?;
+
+    Ok(suspension)
+}
@@ -0,0 +1,8 @@
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct JobsQuery {
+    /// `queued`, `running`, `succeeded` or `dead`.
+    pub status: Option<String>,
+    pub kind: Option<String>,
+}
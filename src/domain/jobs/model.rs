@@ -0,0 +1,37 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::Value;
+use sqlx::FromRow;
+use uuid::Uuid;
+
+pub const JOB_STATUSES: [&str; 4] = ["queued", "running", "succeeded", "dead"];
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct Job {
+    pub id: Uuid,
+    pub kind: String,
+    pub payload: Value,
+    pub status: String,
+    pub attempts: i32,
+    pub max_attempts: i32,
+    pub run_at: DateTime<Utc>,
+    pub locked_at: Option<DateTime<Utc>>,
+    pub locked_by: Option<String>,
+    pub last_error: Option<String>,
+    pub dedupe_key: Option<String>,
+    pub finished_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A recurring job and when it next runs.
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct JobSchedule {
+    pub name: String,
+    pub kind: String,
+    pub cron: String,
+    pub is_enabled: bool,
+    pub next_run_at: DateTime<Utc>,
+    pub last_enqueued_at: Option<DateTime<Utc>>,
+    pub last_job_id: Option<Uuid>,
+}
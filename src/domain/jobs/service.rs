@@ -0,0 +1,337 @@
+use std::str::FromStr;
+
+use anyhow::Context;
+use chrono::{DateTime, Duration, Utc};
+use serde_json::Value;
+use sqlx::{PgExecutor, PgPool};
+use uuid::Uuid;
+
+use super::model::{JOB_STATUSES, Job, JobSchedule};
+use crate::domain::utils::errors::station_errors::StationError;
+
+pub const DEFAULT_MAX_ATTEMPTS: i32 = 5;
+
+/// A running job whose worker has not finished it by then is assumed lost
+/// and handed to another worker.
+pub const LOCK_TIMEOUT_SECS: i64 = 15 * 60;
+
+const BACKOFF_BASE_SECS: i64 = 30;
+const BACKOFF_MAX_SECS: i64 = 60 * 60;
+
+const JOB_COLUMNS: &str = r#"
+    id, kind, payload, status, attempts, max_attempts, run_at, locked_at, locked_by,
+    last_error, dedupe_key, finished_at, created_at, updated_at
+"#;
+
+#[derive(Debug, Clone, Default)]
+pub struct EnqueueOptions {
+    /// Defaults to now.
+    pub run_at: Option<DateTime<Utc>>,
+    pub max_attempts: Option<i32>,
+    /// While a job with this key is queued or running, enqueuing another
+    /// does nothing.
+    pub dedupe_key: Option<String>,
+}
+
+/// Adds a job to the queue. Returns `None` when a pending job already has
+/// the same dedupe key. Takes any executor so callers can enqueue inside
+/// their own transaction.
+pub async fn enqueue<'e, E: PgExecutor<'e>>(
+    executor: E,
+    kind: &str,
+    payload: &Value,
+    options: EnqueueOptions,
+) -> Result<Option<Uuid>, sqlx::Error> {
+    sqlx::query_scalar(
+        r#"
+        INSERT INTO jobs (kind, payload, run_at, max_attempts, dedupe_key)
+        VALUES ($1, $2, COALESCE($3, now()), $4, $5)
+        ON CONFLICT (dedupe_key) WHERE status IN ('queued', 'running') DO NOTHING
+        RETURNING id
+        "#,
+    )
+    .bind(kind)
+    .bind(payload)
+    .bind(options.run_at)
+    .bind(options.max_attempts.unwrap_or(DEFAULT_MAX_ATTEMPTS))
+    .bind(options.dedupe_key)
+    .fetch_optional(executor)
+    .await
+}
+
+/// Takes the next due job, or one whose worker has gone quiet, and marks it
+/// running. `SKIP LOCKED` lets concurrent workers each take a different job.
+pub async fn claim_job(pool: &PgPool, worker_id: &str) -> Result<Option<Job>, sqlx::Error> {
+    sqlx::query_as::<_, Job>(&format!(
+        r#"
+        UPDATE jobs
+        SET status = 'running',
+            attempts = attempts + 1,
+            locked_at = now(),
+            locked_by = $1,
+            updated_at = now()
+        WHERE id = (
+            SELECT id
+            FROM jobs
+            WHERE (status = 'queued' AND run_at <= now())
+               OR (status = 'running'
+                   AND attempts < max_attempts
+                   AND locked_at < now() - make_interval(secs => $2))
+            ORDER BY run_at
+            LIMIT 1
+            FOR UPDATE SKIP LOCKED
+        )
+        RETURNING {JOB_COLUMNS}
+        "#
+    ))
+    .bind(worker_id)
+    .bind(LOCK_TIMEOUT_SECS as f64)
+    .fetch_optional(pool)
+    .await
+}
+
+pub async fn complete_job(pool: &PgPool, job_id: Uuid) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE jobs
+        SET status = 'succeeded',
+            locked_at = NULL,
+            last_error = NULL,
+            finished_at = now(),
+            updated_at = now()
+        WHERE id = $1
+        "#,
+    )
+    .bind(job_id)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Wait before the next attempt: doubling from 30 seconds, capped at an hour.
+pub fn backoff(attempts: i32) -> Duration {
+    let exponent = attempts.saturating_sub(1).clamp(0, 16) as u32;
+    Duration::seconds((BACKOFF_BASE_SECS << exponent).min(BACKOFF_MAX_SECS))
+}
+
+/// Schedules a retry after [`backoff`], or dead-letters the job once it is
+/// out of attempts. Returns true when the job is dead.
+pub async fn fail_job(pool: &PgPool, job: &Job, error: &str) -> Result<bool, sqlx::Error> {
+    let dead = job.attempts >= job.max_attempts;
+
+    sqlx::query(
+        r#"
+        UPDATE jobs
+        SET status = CASE WHEN $2 THEN 'dead' ELSE 'queued' END,
+            run_at = CASE WHEN $2 THEN run_at ELSE now() + make_interval(secs => $3) END,
+            finished_at = CASE WHEN $2 THEN now() ELSE NULL END,
+            locked_at = NULL,
+            last_error = $4,
+            updated_at = now()
+        WHERE id = $1
+        "#,
+    )
+    .bind(job.id)
+    .bind(dead)
+    .bind(backoff(job.attempts).num_seconds() as f64)
+    .bind(error)
+    .execute(pool)
+    .await?;
+
+    Ok(dead)
+}
+
+/// Dead-letters jobs whose worker vanished on their last attempt; without
+/// this a job that crashes the process would be picked up forever.
+pub async fn bury_abandoned_jobs(pool: &PgPool) -> Result<u64, sqlx::Error> {
+    let buried = sqlx::query(
+        r#"
+        UPDATE jobs
+        SET status = 'dead',
+            locked_at = NULL,
+            last_error = 'worker stopped before finishing the last attempt',
+            finished_at = now(),
+            updated_at = now()
+        WHERE status = 'running'
+          AND attempts >= max_attempts
+          AND locked_at < now() - make_interval(secs => $1)
+        "#,
+    )
+    .bind(LOCK_TIMEOUT_SECS as f64)
+    .execute(pool)
+    .await?
+    .rows_affected();
+
+    Ok(buried)
+}
+
+pub fn next_run(cron: &str, after: DateTime<Utc>) -> anyhow::Result<DateTime<Utc>> {
+    cron::Schedule::from_str(cron)
+        .with_context(|| format!("invalid cron expression {cron:?}"))?
+        .after(&after)
+        .next()
+        .with_context(|| format!("cron expression {cron:?} never fires again"))
+}
+
+/// Enqueues every schedule that is due and moves it to its next run. Rows
+/// are claimed with `SKIP LOCKED`, so with several replicas each run is
+/// enqueued by exactly one of them, and the dedupe key keeps a slow job
+/// from piling up behind itself.
+pub async fn enqueue_due_schedules(pool: &PgPool) -> anyhow::Result<usize> {
+    let mut tx = pool.begin().await?;
+
+    let due = sqlx::query_as::<_, (String, String, String)>(
+        r#"
+        SELECT name, kind, cron
+        FROM job_schedules
+        WHERE is_enabled AND next_run_at <= now()
+        FOR UPDATE SKIP LOCKED
+        "#,
+    )
+    .fetch_all(&mut *tx)
+    .await?;
+
+    let now = Utc::now();
+    let mut enqueued = 0;
+
+    for (name, kind, cron) in due {
+        let next_run_at = match next_run(&cron, now) {
+            Ok(next_run_at) => next_run_at,
+            Err(err) => {
+                tracing::error!("job schedule {name} disabled: {err:?}");
+                sqlx::query("UPDATE job_schedules SET is_enabled = FALSE WHERE name = $1")
+                    .bind(&name)
+                    .execute(&mut *tx)
+                    .await?;
+                continue;
+            }
+        };
+
+        let job_id = enqueue(
+            &mut *tx,
+            &kind,
+            &Value::Object(Default::default()),
+            EnqueueOptions {
+                dedupe_key: Some(format!("schedule:{name}")),
+                ..Default::default()
+            },
+        )
+        .await?;
+
+        if job_id.is_some() {
+            enqueued += 1;
+        }
+
+        sqlx::query(
+            r#"
+            UPDATE job_schedules
+            SET next_run_at = $2,
+                last_enqueued_at = CASE WHEN $3::UUID IS NULL THEN last_enqueued_at ELSE now() END,
+                last_job_id = COALESCE($3, last_job_id)
+            WHERE name = $1
+            "#,
+        )
+        .bind(&name)
+        .bind(next_run_at)
+        .bind(job_id)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await?;
+
+    Ok(enqueued)
+}
+
+pub async fn list_jobs(
+    pool: &PgPool,
+    status: Option<&str>,
+    kind: Option<&str>,
+) -> Result<Vec<Job>, StationError> {
+    if let Some(status) = status
+        && !JOB_STATUSES.contains(&status)
+    {
+        return Err(StationError::WrongCredentials(format!(
+            "job status must be one of {}",
+            JOB_STATUSES.join(", ")
+        )));
+    }
+
+    sqlx::query_as::<_, Job>(&format!(
+        r#"
+        SELECT {JOB_COLUMNS}
+        FROM jobs
+        WHERE ($1::VARCHAR IS NULL OR status = $1)
+          AND ($2::VARCHAR IS NULL OR kind = $2)
+        ORDER BY created_at DESC
+        LIMIT 200
+        "#
+    ))
+    .bind(status)
+    .bind(kind)
+    .fetch_all(pool)
+    .await
+    .map_err(StationError::DatabaseError)
+}
+
+pub async fn find_job(pool: &PgPool, job_id: Uuid) -> Result<Job, StationError> {
+    sqlx::query_as::<_, Job>(&format!("SELECT {JOB_COLUMNS} FROM jobs WHERE id = $1"))
+        .bind(job_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(StationError::DatabaseError)?
+        .ok_or_else(|| StationError::NotFound(job_id.to_string()))
+}
+
+/// Puts a dead job back on the queue with a fresh set of attempts.
+pub async fn retry_job(pool: &PgPool, job_id: Uuid) -> Result<Job, StationError> {
+    let job = find_job(pool, job_id).await?;
+    if job.status != "dead" {
+        return Err(StationError::WrongCredentials(format!(
+            "only dead jobs can be retried; this one is {}",
+            job.status
+        )));
+    }
+
+    let retried = sqlx::query_as::<_, Job>(&format!(
+        r#"
+        UPDATE jobs
+        SET status = 'queued',
+            attempts = 0,
+            run_at = now(),
+            finished_at = NULL,
+            updated_at = now()
+        WHERE id = $1 AND status = 'dead'
+        RETURNING {JOB_COLUMNS}
+        "#
+    ))
+    .bind(job_id)
+    .fetch_optional(pool)
+    .await;
+
+    match retried {
+        Ok(Some(job)) => Ok(job),
+        Ok(None) => Err(StationError::WrongCredentials(
+            "job was retried concurrently".to_string(),
+        )),
+        // The same dedupe key is already pending again.
+        Err(sqlx::Error::Database(err)) if err.is_unique_violation() => {
+            Err(StationError::AlreadyExists)
+        }
+        Err(err) => Err(StationError::DatabaseError(err)),
+    }
+}
+
+pub async fn list_schedules(pool: &PgPool) -> Result<Vec<JobSchedule>, StationError> {
+    sqlx::query_as::<_, JobSchedule>(
+        r#"
+        SELECT name, kind, cron, is_enabled, next_run_at, last_enqueued_at, last_job_id
+        FROM job_schedules
+        ORDER BY name
+        "#,
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(StationError::DatabaseError)
+}
@@ -0,0 +1,142 @@
+use std::time::Duration;
+
+use sqlx::PgPool;
+use tokio::time::{interval, timeout};
+use uuid::Uuid;
+
+use super::{
+    model::Job,
+    service::{bury_abandoned_jobs, claim_job, complete_job, enqueue_due_schedules, fail_job},
+};
+use crate::domain::{
+    analytics::service::refresh_recent_rollups,
+    broadcasts::service::{SendBroadcastJob, send_broadcast},
+    emails::service::{SendEmailJob, deliver_email},
+    fraud::service::run_fraud_scan,
+    loyalty::service::run_points_expiry,
+    notifications::{
+        channels::Channels,
+        service::{SendMessageJob, deliver_message},
+    },
+    subscriptions::service::run_subscription_reminder_cycle,
+};
+
+pub const SEND_EMAIL_JOB: &str = "send_email";
+pub const SEND_MESSAGE_JOB: &str = "send_message";
+pub const SEND_BROADCAST_JOB: &str = "send_broadcast";
+pub const SUBSCRIPTION_REMINDERS_JOB: &str = "subscription_reminders";
+pub const DISCOUNT_ROLLUPS_JOB: &str = "discount_rollups";
+pub const FRAUD_SCAN_JOB: &str = "fraud_scan";
+pub const LOYALTY_POINTS_EXPIRY_JOB: &str = "loyalty_points_expiry";
+
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+/// Jobs run back to back before the worker checks the schedules again.
+const MAX_JOBS_PER_TICK: usize = 50;
+/// Comfortably inside the lock timeout, so a hung job is failed here
+/// before another worker would take it over.
+const JOB_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
+/// Runs on every replica: enqueues due schedules and works the queue.
+pub async fn start(pool: PgPool, channels: Channels) {
+    let worker_id = format!(
+        "{}-{}",
+        std::env::var("HOSTNAME").unwrap_or_else(|_| "worker".to_string()),
+        Uuid::new_v4().simple()
+    );
+    let mut ticker = interval(POLL_INTERVAL);
+
+    loop {
+        ticker.tick().await;
+
+        if let Err(err) = enqueue_due_schedules(&pool).await {
+            tracing::error!("job scheduling failed: {:?}", err);
+        }
+        if let Err(err) = bury_abandoned_jobs(&pool).await {
+            tracing::error!("dead-lettering abandoned jobs failed: {:?}", err);
+        }
+        if let Err(err) = run_pending_jobs(&pool, &channels, &worker_id, MAX_JOBS_PER_TICK).await {
+            tracing::error!("job queue polling failed: {:?}", err);
+        }
+    }
+}
+
+/// Claims and runs due jobs one at a time until the queue is empty or
+/// `limit` jobs have run. Returns how many ran.
+pub async fn run_pending_jobs(
+    pool: &PgPool,
+    channels: &Channels,
+    worker_id: &str,
+    limit: usize,
+) -> Result<usize, sqlx::Error> {
+    let mut ran = 0;
+
+    while ran < limit {
+        let Some(job) = claim_job(pool, worker_id).await? else {
+            break;
+        };
+        ran += 1;
+
+        let outcome = match timeout(JOB_TIMEOUT, run_job(pool, channels, &job)).await {
+            Ok(outcome) => outcome,
+            Err(_) => Err(anyhow::anyhow!("timed out after {:?}", JOB_TIMEOUT)),
+        };
+
+        match outcome {
+            Ok(()) => complete_job(pool, job.id).await?,
+            Err(err) => {
+                let error = format!("{err:#}");
+                if fail_job(pool, &job, &error).await? {
+                    tracing::error!("job {} ({}) is dead: {error}", job.id, job.kind);
+                } else {
+                    tracing::warn!(
+                        "job {} ({}) failed attempt {}: {error}",
+                        job.id,
+                        job.kind,
+                        job.attempts
+                    );
+                }
+            }
+        }
+    }
+
+    Ok(ran)
+}
+
+async fn run_job(pool: &PgPool, channels: &Channels, job: &Job) -> anyhow::Result<()> {
+    let final_attempt = job.attempts >= job.max_attempts;
+
+    match job.kind.as_str() {
+        SEND_EMAIL_JOB => {
+            let payload: SendEmailJob = serde_json::from_value(job.payload.clone())?;
+            deliver_email(
+                pool,
+                channels.email.as_ref(),
+                payload.email_id,
+                final_attempt,
+            )
+            .await
+        }
+        SEND_MESSAGE_JOB => {
+            let payload: SendMessageJob = serde_json::from_value(job.payload.clone())?;
+            deliver_message(pool, channels, payload.message_id, final_attempt).await
+        }
+        SEND_BROADCAST_JOB => {
+            let payload: SendBroadcastJob = serde_json::from_value(job.payload.clone())?;
+            send_broadcast(pool, payload.broadcast_id).await
+        }
+        SUBSCRIPTION_REMINDERS_JOB => run_subscription_reminder_cycle(pool).await,
+        DISCOUNT_ROLLUPS_JOB => {
+            refresh_recent_rollups(pool).await?;
+            Ok(())
+        }
+        FRAUD_SCAN_JOB => {
+            run_fraud_scan(pool).await?;
+            Ok(())
+        }
+        LOYALTY_POINTS_EXPIRY_JOB => {
+            run_points_expiry(pool).await?;
+            Ok(())
+        }
+        other => anyhow::bail!("unknown job kind {other}"),
+    }
+}
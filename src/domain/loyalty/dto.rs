@@ -0,0 +1,42 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::model::{LoyaltyTransaction, LoyaltyVoucher};
+
+#[derive(Debug, Serialize)]
+pub struct CreateLoyaltyAccountResponse {
+    pub account_id: Uuid,
+    /// Secret the driver app sends as `x-loyalty-key`. Shown only once.
+    pub key: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LoyaltyAccountResponse {
+    pub account_id: Uuid,
+    pub balance: i64,
+    pub tier: &'static str,
+    /// Percentage points added to a station's percentage discount.
+    pub tier_bonus_percentage: i32,
+    pub points_earned_last_year: i64,
+    pub points_to_next_tier: Option<i64>,
+    pub transactions: Vec<LoyaltyTransaction>,
+    pub vouchers: Vec<LoyaltyVoucher>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateVoucherDto {
+    pub points: i32,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RedeemVoucherDto {
+    pub code: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RedeemVoucherResponse {
+    pub message: String,
+    pub code: String,
+    /// Naira to take off the driver's bill.
+    pub value: i32,
+}
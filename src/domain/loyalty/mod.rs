@@ -0,0 +1,4 @@
+pub mod dto;
+pub mod model;
+pub mod routes;
+pub mod service;
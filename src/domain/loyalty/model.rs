@@ -0,0 +1,80 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Tiers by points earned over the trailing year. Higher tiers add
+/// percentage points to a station's percentage discount.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoyaltyTier {
+    Bronze,
+    Silver,
+    Gold,
+}
+
+impl LoyaltyTier {
+    pub const SILVER_POINTS: i64 = 1_000;
+    pub const GOLD_POINTS: i64 = 5_000;
+
+    pub fn for_points(points_last_year: i64) -> Self {
+        if points_last_year >= Self::GOLD_POINTS {
+            Self::Gold
+        } else if points_last_year >= Self::SILVER_POINTS {
+            Self::Silver
+        } else {
+            Self::Bronze
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Bronze => "bronze",
+            Self::Silver => "silver",
+            Self::Gold => "gold",
+        }
+    }
+
+    pub fn bonus_percentage(self) -> i32 {
+        match self {
+            Self::Bronze => 0,
+            Self::Silver => 1,
+            Self::Gold => 2,
+        }
+    }
+
+    /// Points still needed to reach the next tier, if there is one.
+    pub fn points_to_next(self, points_last_year: i64) -> Option<i64> {
+        match self {
+            Self::Bronze => Some(Self::SILVER_POINTS - points_last_year),
+            Self::Silver => Some(Self::GOLD_POINTS - points_last_year),
+            Self::Gold => None,
+        }
+    }
+}
+
+/// A ledger transaction, seen from the driver's side: `points` is positive
+/// when points were added to the account.
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct LoyaltyTransaction {
+    pub id: Uuid,
+    /// `earn`, `reversal`, `redeem`, `refund` or `expire`.
+    pub kind: String,
+    pub points: i64,
+    pub discount_code_id: Option<Uuid>,
+    pub voucher_id: Option<Uuid>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct LoyaltyVoucher {
+    pub id: Uuid,
+    pub code: String,
+    pub points: i32,
+    pub value: i32,
+    pub expires_at: DateTime<Utc>,
+    pub redeemed_at: Option<DateTime<Utc>>,
+    /// When the voucher lapsed unredeemed and its points were returned.
+    pub refunded_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
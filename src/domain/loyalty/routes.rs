@@ -0,0 +1,38 @@
+use axum::{
+    Router,
+    middleware::{from_fn, from_fn_with_state},
+    routing::{get, post},
+};
+use std::time::Duration;
+
+use crate::{
+    app_state::AppState,
+    authentication::middleware::auth::authorize,
+    domain::{
+        loyalty::service::LoyaltyService,
+        utils::rate_limiter::{RateLimiter, rate_limit},
+    },
+};
+
+/// 5 new accounts per hour per IP; opening one needs no credentials.
+const ACCOUNT_MAX_REQUESTS: u32 = 5;
+const ACCOUNT_WINDOW_SECS: u64 = 60 * 60;
+
+pub fn loyalty_route() -> Router<AppState> {
+    let account_limiter =
+        RateLimiter::new(ACCOUNT_MAX_REQUESTS, Duration::from_secs(ACCOUNT_WINDOW_SECS));
+    account_limiter.spawn_cleanup();
+
+    Router::new()
+        .route(
+            "/accounts",
+            post(LoyaltyService::create_account)
+                .route_layer(from_fn_with_state(account_limiter, rate_limit)),
+        )
+        .route("/account", get(LoyaltyService::get_account))
+        .route("/vouchers", post(LoyaltyService::create_voucher))
+        .route(
+            "/vouchers/redeem",
+            post(LoyaltyService::redeem_voucher).route_layer(from_fn(authorize)),
+        )
+}
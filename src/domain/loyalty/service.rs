@@ -0,0 +1,631 @@
+use axum::{
+    Json,
+    extract::{Extension, State},
+    http::{HeaderMap, StatusCode},
+};
+use chrono::{Duration, Utc};
+use sha2::{Digest, Sha256};
+use sqlx::{PgExecutor, PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::{
+    app_state::AppState,
+    authentication::station::authenticate::token::service::Claims,
+    domain::{
+        discounts::model::DiscountCode,
+        loyalty::{
+            dto::{
+                CreateLoyaltyAccountResponse, CreateVoucherDto, LoyaltyAccountResponse,
+                RedeemVoucherDto, RedeemVoucherResponse,
+            },
+            model::{LoyaltyTier, LoyaltyTransaction, LoyaltyVoucher},
+        },
+        utils::errors::station_errors::StationError,
+    },
+};
+
+pub const LOYALTY_KEY_HEADER: &str = "x-loyalty-key";
+
+/// One point for every ₦100 actually paid on a redeemed code.
+const NAIRA_PER_POINT: i32 = 100;
+const POINTS_LIFETIME_DAYS: i64 = 365;
+const TIER_WINDOW_DAYS: i64 = 365;
+
+/// A point is worth ₦1 off when spent on a voucher.
+const VOUCHER_NAIRA_PER_POINT: i32 = 1;
+const VOUCHER_MIN_POINTS: i32 = 500;
+const VOUCHER_VALIDITY_DAYS: i64 = 30;
+const RECENT_TRANSACTIONS_LIMIT: i64 = 50;
+
+/// The programme's side of a ledger transaction.
+#[derive(Debug, Clone, Copy)]
+enum LoyaltyLedger {
+    Issued,
+    Redeemed,
+    Expired,
+}
+
+impl LoyaltyLedger {
+    fn name(self) -> &'static str {
+        match self {
+            LoyaltyLedger::Issued => "points_issued",
+            LoyaltyLedger::Redeemed => "points_redeemed",
+            LoyaltyLedger::Expired => "points_expired",
+        }
+    }
+}
+
+/// A ledger transaction to post; `points` is the change to the driver's
+/// balance and the programme ledger takes the opposite amount.
+struct Posting {
+    account_id: Uuid,
+    kind: &'static str,
+    points: i64,
+    counterpart: LoyaltyLedger,
+    discount_code_id: Option<Uuid>,
+    voucher_id: Option<Uuid>,
+    expires_at: Option<chrono::DateTime<Utc>>,
+}
+
+fn hash_key(key: &str) -> String {
+    format!("{:x}", Sha256::digest(key.trim().as_bytes()))
+}
+
+pub struct LoyaltyService;
+
+impl LoyaltyService {
+    pub async fn create_account(
+        State(app_state): State<AppState>,
+    ) -> Result<(StatusCode, Json<CreateLoyaltyAccountResponse>), StationError> {
+        let key = format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple());
+
+        let account_id: Uuid =
+            sqlx::query_scalar("INSERT INTO loyalty_accounts (key_hash) VALUES ($1) RETURNING id")
+                .bind(hash_key(&key))
+                .fetch_one(&app_state.pool)
+                .await
+                .map_err(StationError::DatabaseError)?;
+
+        Ok((
+            StatusCode::CREATED,
+            Json(CreateLoyaltyAccountResponse { account_id, key }),
+        ))
+    }
+
+    pub async fn get_account(
+        State(app_state): State<AppState>,
+        headers: HeaderMap,
+    ) -> Result<Json<LoyaltyAccountResponse>, StationError> {
+        let account_id = require_account(&app_state.pool, &headers).await?;
+
+        Ok(Json(account_summary(&app_state.pool, account_id).await?))
+    }
+
+    /// Spends points on a voucher any station can take off a driver's bill.
+    pub async fn create_voucher(
+        State(app_state): State<AppState>,
+        headers: HeaderMap,
+        Json(body): Json<CreateVoucherDto>,
+    ) -> Result<(StatusCode, Json<LoyaltyVoucher>), StationError> {
+        let account_id = require_account(&app_state.pool, &headers).await?;
+
+        if body.points < VOUCHER_MIN_POINTS {
+            return Err(StationError::WrongCredentials(format!(
+                "vouchers need at least {VOUCHER_MIN_POINTS} points"
+            )));
+        }
+
+        let mut tx = app_state
+            .pool
+            .begin()
+            .await
+            .map_err(StationError::DatabaseError)?;
+
+        lock_account(&mut tx, account_id)
+            .await
+            .map_err(StationError::DatabaseError)?;
+
+        let balance = account_balance(&mut *tx, account_id)
+            .await
+            .map_err(StationError::DatabaseError)?;
+        if balance < body.points as i64 {
+            return Err(StationError::WrongCredentials(
+                "not enough loyalty points".to_string(),
+            ));
+        }
+
+        let voucher = sqlx::query_as::<_, LoyaltyVoucher>(
+            r#"
+            INSERT INTO loyalty_vouchers (loyalty_account_id, code, points, value, expires_at)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING id, code, points, value, expires_at, redeemed_at, refunded_at, created_at
+            "#,
+        )
+        .bind(account_id)
+        .bind(format!(
+            "LV{}",
+            Uuid::new_v4().simple().to_string()[..8].to_ascii_uppercase()
+        ))
+        .bind(body.points)
+        .bind(body.points * VOUCHER_NAIRA_PER_POINT)
+        .bind(Utc::now() + Duration::days(VOUCHER_VALIDITY_DAYS))
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(StationError::DatabaseError)?;
+
+        post(
+            &mut tx,
+            Posting {
+                account_id,
+                kind: "redeem",
+                points: -(body.points as i64),
+                counterpart: LoyaltyLedger::Redeemed,
+                discount_code_id: None,
+                voucher_id: Some(voucher.id),
+                expires_at: None,
+            },
+        )
+        .await
+        .map_err(StationError::DatabaseError)?;
+
+        tx.commit().await.map_err(StationError::DatabaseError)?;
+
+        Ok((StatusCode::CREATED, Json(voucher)))
+    }
+
+    pub async fn redeem_voucher(
+        State(app_state): State<AppState>,
+        Extension(claims): Extension<Claims>,
+        Json(body): Json<RedeemVoucherDto>,
+    ) -> Result<Json<RedeemVoucherResponse>, StationError> {
+        let mut tx = app_state
+            .pool
+            .begin()
+            .await
+            .map_err(StationError::DatabaseError)?;
+
+        let voucher = sqlx::query_as::<_, LoyaltyVoucher>(
+            r#"
+            SELECT id, code, points, value, expires_at, redeemed_at, refunded_at, created_at
+            FROM loyalty_vouchers
+            WHERE code = $1
+            FOR UPDATE
+            "#,
+        )
+        .bind(body.code.trim().to_uppercase())
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(StationError::DatabaseError)?
+        .ok_or_else(|| StationError::NotFound("loyalty voucher not found".to_string()))?;
+
+        let (message, value) = if voucher.redeemed_at.is_some() {
+            ("voucher already redeemed", 0)
+        } else if Utc::now() >= voucher.expires_at {
+            ("voucher is expired", 0)
+        } else {
+            sqlx::query(
+                r#"
+                UPDATE loyalty_vouchers
+                SET redeemed_at = now(),
+                    redeemed_by_station_id = $1
+                WHERE id = $2
+                "#,
+            )
+            .bind(claims.station_res.id)
+            .bind(voucher.id)
+            .execute(&mut *tx)
+            .await
+            .map_err(StationError::DatabaseError)?;
+
+            ("voucher redeemed successfully", voucher.value)
+        };
+
+        tx.commit().await.map_err(StationError::DatabaseError)?;
+
+        Ok(Json(RedeemVoucherResponse {
+            message: message.to_string(),
+            code: voucher.code,
+            value,
+        }))
+    }
+}
+
+/// Resolves the optional `x-loyalty-key` header to an account.
+pub async fn find_account(pool: &PgPool, headers: &HeaderMap) -> Result<Option<Uuid>, StationError> {
+    let Some(key) = headers.get(LOYALTY_KEY_HEADER) else {
+        return Ok(None);
+    };
+
+    let key = key
+        .to_str()
+        .map_err(|_| StationError::WrongCredentials("invalid loyalty key".to_string()))?;
+
+    sqlx::query_scalar("SELECT id FROM loyalty_accounts WHERE key_hash = $1")
+        .bind(hash_key(key))
+        .fetch_optional(pool)
+        .await
+        .map_err(StationError::DatabaseError)?
+        .map(Some)
+        .ok_or_else(|| StationError::WrongCredentials("invalid loyalty key".to_string()))
+}
+
+async fn require_account(pool: &PgPool, headers: &HeaderMap) -> Result<Uuid, StationError> {
+    find_account(pool, headers).await?.ok_or_else(|| {
+        StationError::WrongCredentials(format!("{LOYALTY_KEY_HEADER} header is required"))
+    })
+}
+
+/// Serialises balance-changing work on one account.
+async fn lock_account(
+    tx: &mut Transaction<'_, Postgres>,
+    account_id: Uuid,
+) -> Result<(), sqlx::Error> {
+    sqlx::query("SELECT id FROM loyalty_accounts WHERE id = $1 FOR UPDATE")
+        .bind(account_id)
+        .fetch_one(&mut **tx)
+        .await?;
+
+    Ok(())
+}
+
+async fn post(tx: &mut Transaction<'_, Postgres>, posting: Posting) -> Result<(), sqlx::Error> {
+    let transaction_id: Uuid = sqlx::query_scalar(
+        r#"
+        INSERT INTO loyalty_transactions (
+            loyalty_account_id, kind, discount_code_id, voucher_id, expires_at
+        )
+        VALUES ($1, $2, $3, $4, $5)
+        RETURNING id
+        "#,
+    )
+    .bind(posting.account_id)
+    .bind(posting.kind)
+    .bind(posting.discount_code_id)
+    .bind(posting.voucher_id)
+    .bind(posting.expires_at)
+    .fetch_one(&mut **tx)
+    .await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO loyalty_entries (transaction_id, ledger, loyalty_account_id, amount)
+        VALUES ($1, 'driver', $2, $3), ($1, $4, NULL, -$3)
+        "#,
+    )
+    .bind(transaction_id)
+    .bind(posting.account_id)
+    .bind(posting.points)
+    .bind(posting.counterpart.name())
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(())
+}
+
+async fn account_balance<'e, E: PgExecutor<'e>>(
+    executor: E,
+    account_id: Uuid,
+) -> Result<i64, sqlx::Error> {
+    sqlx::query_scalar(
+        r#"
+        SELECT COALESCE(SUM(amount), 0)::BIGINT
+        FROM loyalty_entries
+        WHERE ledger = 'driver' AND loyalty_account_id = $1
+        "#,
+    )
+    .bind(account_id)
+    .fetch_one(executor)
+    .await
+}
+
+/// Points earned over the tier window, net of reversed earnings.
+async fn points_earned_last_year<'e, E: PgExecutor<'e>>(
+    executor: E,
+    account_id: Uuid,
+) -> Result<i64, sqlx::Error> {
+    sqlx::query_scalar(
+        r#"
+        SELECT COALESCE(SUM(e.amount), 0)::BIGINT
+        FROM loyalty_entries e
+        INNER JOIN loyalty_transactions t ON t.id = e.transaction_id
+        WHERE e.ledger = 'driver'
+          AND e.loyalty_account_id = $1
+          AND t.kind IN ('earn', 'reversal')
+          AND t.created_at >= now() - make_interval(days => $2)
+        "#,
+    )
+    .bind(account_id)
+    .bind(TIER_WINDOW_DAYS as i32)
+    .fetch_one(executor)
+    .await
+}
+
+pub async fn account_tier<'e, E: PgExecutor<'e>>(
+    executor: E,
+    account_id: Uuid,
+) -> Result<LoyaltyTier, sqlx::Error> {
+    Ok(LoyaltyTier::for_points(
+        points_earned_last_year(executor, account_id).await?,
+    ))
+}
+
+async fn account_summary(
+    pool: &PgPool,
+    account_id: Uuid,
+) -> Result<LoyaltyAccountResponse, StationError> {
+    let balance = account_balance(pool, account_id)
+        .await
+        .map_err(StationError::DatabaseError)?;
+    let points_last_year = points_earned_last_year(pool, account_id)
+        .await
+        .map_err(StationError::DatabaseError)?;
+    let tier = LoyaltyTier::for_points(points_last_year);
+
+    let transactions = sqlx::query_as::<_, LoyaltyTransaction>(
+        r#"
+        SELECT t.id, t.kind, e.amount AS points, t.discount_code_id, t.voucher_id,
+               t.expires_at, t.created_at
+        FROM loyalty_transactions t
+        INNER JOIN loyalty_entries e ON e.transaction_id = t.id AND e.ledger = 'driver'
+        WHERE t.loyalty_account_id = $1
+        ORDER BY t.created_at DESC, t.id
+        LIMIT $2
+        "#,
+    )
+    .bind(account_id)
+    .bind(RECENT_TRANSACTIONS_LIMIT)
+    .fetch_all(pool)
+    .await
+    .map_err(StationError::DatabaseError)?;
+
+    let vouchers = sqlx::query_as::<_, LoyaltyVoucher>(
+        r#"
+        SELECT id, code, points, value, expires_at, redeemed_at, refunded_at, created_at
+        FROM loyalty_vouchers
+        WHERE loyalty_account_id = $1
+        ORDER BY created_at DESC
+        "#,
+    )
+    .bind(account_id)
+    .fetch_all(pool)
+    .await
+    .map_err(StationError::DatabaseError)?;
+
+    Ok(LoyaltyAccountResponse {
+        account_id,
+        balance,
+        tier: tier.name(),
+        tier_bonus_percentage: tier.bonus_percentage(),
+        points_earned_last_year: points_last_year,
+        points_to_next_tier: tier.points_to_next(points_last_year),
+        transactions,
+        vouchers,
+    })
+}
+
+/// Posts the points a redeemed code earns, inside the redemption's
+/// transaction so a redemption never lands without its points.
+pub async fn post_code_earnings(
+    tx: &mut Transaction<'_, Postgres>,
+    code: &DiscountCode,
+) -> Result<(), sqlx::Error> {
+    let Some(account_id) = code.loyalty_account_id else {
+        return Ok(());
+    };
+
+    let points = (code.total_discounted_price / NAIRA_PER_POINT) as i64;
+    if points <= 0 {
+        return Ok(());
+    }
+
+    post(
+        tx,
+        Posting {
+            account_id,
+            kind: "earn",
+            points,
+            counterpart: LoyaltyLedger::Issued,
+            discount_code_id: Some(code.id),
+            voucher_id: None,
+            expires_at: Some(Utc::now() + Duration::days(POINTS_LIFETIME_DAYS)),
+        },
+    )
+    .await
+}
+
+/// Takes back whatever a code has earned so far, when its redemption is
+/// reversed or the code is voided.
+pub async fn reverse_code_earnings(
+    tx: &mut Transaction<'_, Postgres>,
+    code: &DiscountCode,
+) -> Result<(), sqlx::Error> {
+    let Some(account_id) = code.loyalty_account_id else {
+        return Ok(());
+    };
+
+    let earned: i64 = sqlx::query_scalar(
+        r#"
+        SELECT COALESCE(SUM(e.amount), 0)::BIGINT
+        FROM loyalty_entries e
+        INNER JOIN loyalty_transactions t ON t.id = e.transaction_id
+        WHERE e.ledger = 'driver' AND t.discount_code_id = $1
+        "#,
+    )
+    .bind(code.id)
+    .fetch_one(&mut **tx)
+    .await?;
+
+    if earned <= 0 {
+        return Ok(());
+    }
+
+    post(
+        tx,
+        Posting {
+            account_id,
+            kind: "reversal",
+            points: -earned,
+            counterpart: LoyaltyLedger::Issued,
+            discount_code_id: Some(code.id),
+            voucher_id: None,
+            expires_at: None,
+        },
+    )
+    .await
+}
+
+/// Gives back the points of vouchers that lapsed unredeemed, one ledger
+/// transaction per voucher.
+pub async fn refund_expired_vouchers(pool: &PgPool) -> anyhow::Result<u64> {
+    let mut refunded = 0;
+
+    loop {
+        let mut tx = pool.begin().await?;
+
+        let voucher: Option<(Uuid, Uuid, i32)> = sqlx::query_as(
+            r#"
+            SELECT id, loyalty_account_id, points
+            FROM loyalty_vouchers
+            WHERE redeemed_at IS NULL
+              AND refunded_at IS NULL
+              AND expires_at <= now()
+            ORDER BY expires_at
+            LIMIT 1
+            FOR UPDATE SKIP LOCKED
+            "#,
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let Some((voucher_id, account_id, points)) = voucher else {
+            break;
+        };
+
+        lock_account(&mut tx, account_id).await?;
+
+        sqlx::query("UPDATE loyalty_vouchers SET refunded_at = now() WHERE id = $1")
+            .bind(voucher_id)
+            .execute(&mut *tx)
+            .await?;
+
+        post(
+            &mut tx,
+            Posting {
+                account_id,
+                kind: "refund",
+                points: points as i64,
+                counterpart: LoyaltyLedger::Redeemed,
+                discount_code_id: None,
+                voucher_id: Some(voucher_id),
+                expires_at: None,
+            },
+        )
+        .await?;
+
+        tx.commit().await?;
+        refunded += 1;
+    }
+
+    Ok(refunded)
+}
+
+/// Refunds lapsed vouchers, then expires lapsed points. Spending is taken
+/// from the oldest points first, so what is left of lapsed earnings is their
+/// net of reversals minus everything already spent, refunded or expired.
+///
+/// Only accounts with an earning that lapsed, or a refund posted, since
+/// their last check are visited.
+pub async fn run_points_expiry(pool: &PgPool) -> anyhow::Result<u64> {
+    refund_expired_vouchers(pool).await?;
+
+    // The database clock, so it compares cleanly with ledger timestamps.
+    let checked_at: chrono::DateTime<Utc> = sqlx::query_scalar("SELECT now()")
+        .fetch_one(pool)
+        .await?;
+    let account_ids: Vec<Uuid> = sqlx::query_scalar(
+        r#"
+        SELECT a.id
+        FROM loyalty_accounts a
+        WHERE EXISTS (
+            SELECT 1
+            FROM loyalty_transactions t
+            WHERE t.loyalty_account_id = a.id
+              AND (
+                  (t.kind = 'earn' AND t.expires_at <= $1)
+                  OR t.kind = 'refund'
+              )
+              AND (
+                  a.points_expiry_checked_at IS NULL
+                  OR CASE WHEN t.kind = 'earn' THEN t.expires_at ELSE t.created_at END
+                      > a.points_expiry_checked_at
+              )
+        )
+        "#,
+    )
+    .bind(checked_at)
+    .fetch_all(pool)
+    .await?;
+
+    let mut expired_accounts = 0;
+
+    for account_id in account_ids {
+        let mut tx = pool.begin().await?;
+        lock_account(&mut tx, account_id).await?;
+
+        let lapsed: i64 = sqlx::query_scalar(
+            r#"
+            SELECT GREATEST(
+                COALESCE(SUM(e.amount) FILTER (
+                    WHERE t.kind IN ('earn', 'reversal')
+                      AND NOT EXISTS (
+                          SELECT 1
+                          FROM loyalty_transactions earn
+                          WHERE earn.discount_code_id = t.discount_code_id
+                            AND earn.kind = 'earn'
+                            AND earn.expires_at > $2
+                      )
+                ), 0)
+                + COALESCE(
+                    SUM(e.amount) FILTER (WHERE t.kind IN ('redeem', 'refund', 'expire')),
+                    0
+                ),
+                0
+            )::BIGINT
+            FROM loyalty_entries e
+            INNER JOIN loyalty_transactions t ON t.id = e.transaction_id
+            WHERE e.ledger = 'driver' AND e.loyalty_account_id = $1
+            "#,
+        )
+        .bind(account_id)
+        .bind(checked_at)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        if lapsed > 0 {
+            post(
+                &mut tx,
+                Posting {
+                    account_id,
+                    kind: "expire",
+                    points: -lapsed,
+                    counterpart: LoyaltyLedger::Expired,
+                    discount_code_id: None,
+                    voucher_id: None,
+                    expires_at: None,
+                },
+            )
+            .await?;
+            expired_accounts += 1;
+        }
+
+        sqlx::query("UPDATE loyalty_accounts SET points_expiry_checked_at = $1 WHERE id = $2")
+            .bind(checked_at)
+            .bind(account_id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+    }
+
+    Ok(expired_accounts)
+}
@@ -0,0 +1,21 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Image kinds a station can upload. A station has at most one logo.
+pub const IMAGE_KINDS: [&str; 2] = ["storefront", "logo"];
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct StationImage {
+    pub id: Uuid,
+    pub station_id: Uuid,
+    pub kind: String,
+    pub storage_key: String,
+    pub thumbnail_key: String,
+    pub content_type: String,
+    pub size_bytes: i32,
+    pub width: i32,
+    pub height: i32,
+    pub created_at: DateTime<Utc>,
+}
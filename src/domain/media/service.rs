@@ -0,0 +1,382 @@
+use std::{collections::HashMap, io::Cursor};
+
+use axum::{
+    Json,
+    extract::{Extension, Multipart, Path, State},
+    http::StatusCode,
+};
+use image::{ImageFormat, ImageReader};
+use uuid::Uuid;
+
+use crate::{
+    app_state::AppState,
+    authentication::station::authenticate::token::service::Claims,
+    domain::{
+        media::{
+            dto::StationImageResponse,
+            model::{IMAGE_KINDS, StationImage},
+            storage::MediaStorage,
+        },
+        utils::{errors::station_errors::StationError, schemas::StationResponse},
+    },
+};
+
+/// Largest accepted upload, before thumbnailing.
+pub const MAX_IMAGE_BYTES: usize = 5 * 1024 * 1024;
+const MAX_STOREFRONT_PHOTOS: i64 = 6;
+const THUMBNAIL_MAX_DIMENSION: u32 = 256;
+const ALLOWED_CONTENT_TYPES: [(&str, ImageFormat); 3] = [
+    ("image/jpeg", ImageFormat::Jpeg),
+    ("image/png", ImageFormat::Png),
+    ("image/webp", ImageFormat::WebP),
+];
+
+const STATION_IMAGE_COLUMNS: &str = r#"
+    id, station_id, kind, storage_key, thumbnail_key, content_type,
+    size_bytes, width, height, created_at
+"#;
+
+struct ProcessedImage {
+    width: u32,
+    height: u32,
+    thumbnail: Vec<u8>,
+}
+
+struct NewImage {
+    id: Uuid,
+    station_id: Uuid,
+    kind: String,
+    storage_key: String,
+    thumbnail_key: String,
+    content_type: String,
+    size_bytes: i32,
+    width: i32,
+    height: i32,
+}
+
+pub struct MediaService;
+
+impl MediaService {
+    pub async fn upload_image(
+        State(app_state): State<AppState>,
+        Extension(claims): Extension<Claims>,
+        mut multipart: Multipart,
+    ) -> Result<(StatusCode, Json<StationImageResponse>), StationError> {
+        let station_id = claims.station_res.id;
+
+        let mut kind: Option<String> = None;
+        let mut file: Option<(String, Vec<u8>)> = None;
+
+        while let Some(field) = multipart
+            .next_field()
+            .await
+            .map_err(|err| StationError::WrongCredentials(err.body_text()))?
+        {
+            match field.name() {
+                Some("kind") => {
+                    let value = field
+                        .text()
+                        .await
+                        .map_err(|err| StationError::WrongCredentials(err.body_text()))?;
+                    kind = Some(value.trim().to_ascii_lowercase());
+                }
+                Some("file") => {
+                    let content_type = field.content_type().unwrap_or_default().to_string();
+                    let bytes = field
+                        .bytes()
+                        .await
+                        .map_err(|err| StationError::WrongCredentials(err.body_text()))?;
+                    file = Some((content_type, bytes.to_vec()));
+                }
+                _ => {}
+            }
+        }
+
+        let kind = kind
+            .filter(|k| IMAGE_KINDS.contains(&k.as_str()))
+            .ok_or_else(|| {
+                StationError::WrongCredentials(format!(
+                    "kind must be one of: {}",
+                    IMAGE_KINDS.join(", ")
+                ))
+            })?;
+        let (content_type, bytes) =
+            file.ok_or_else(|| StationError::WrongCredentials("file is required".to_string()))?;
+
+        if bytes.is_empty() || bytes.len() > MAX_IMAGE_BYTES {
+            return Err(StationError::WrongCredentials(format!(
+                "image must be between 1 byte and {} MB",
+                MAX_IMAGE_BYTES / (1024 * 1024)
+            )));
+        }
+
+        let (_, format) = ALLOWED_CONTENT_TYPES
+            .iter()
+            .find(|(allowed, _)| *allowed == content_type)
+            .ok_or_else(|| {
+                StationError::WrongCredentials("image must be a JPEG, PNG or WebP file".to_string())
+            })?;
+
+        if kind == "storefront" {
+            let photo_count: i64 = sqlx::query_scalar(
+                "SELECT COUNT(*) FROM station_images WHERE station_id = $1 AND kind = 'storefront'",
+            )
+            .bind(station_id)
+            .fetch_one(&app_state.pool)
+            .await
+            .map_err(StationError::DatabaseError)?;
+
+            if photo_count >= MAX_STOREFRONT_PHOTOS {
+                return Err(StationError::WrongCredentials(format!(
+                    "a station can have at most {MAX_STOREFRONT_PHOTOS} storefront photos"
+                )));
+            }
+        }
+
+        let format = *format;
+        let original = bytes.clone();
+        let processed = tokio::task::spawn_blocking(move || process_image(&original, format))
+            .await
+            .map_err(|err| {
+                StationError::Internal(format!("image processing task failed: {err}"))
+            })??;
+
+        let image_id = Uuid::new_v4();
+        let extension = format.extensions_str().first().copied().unwrap_or("img");
+        let storage_key = format!("stations/{station_id}/{image_id}.{extension}");
+        let thumbnail_key = format!("stations/{station_id}/{image_id}_thumb.png");
+        let size_bytes = bytes.len() as i32;
+
+        app_state
+            .media
+            .put(&storage_key, bytes, &content_type)
+            .await
+            .map_err(|err| StationError::Internal(format!("failed to store image: {err}")))?;
+        if let Err(err) = app_state
+            .media
+            .put(&thumbnail_key, processed.thumbnail, "image/png")
+            .await
+        {
+            delete_keys(app_state.media.as_ref(), &[&storage_key]).await;
+            return Err(StationError::Internal(format!(
+                "failed to store thumbnail: {err}"
+            )));
+        }
+
+        let saved = save_image_row(
+            &app_state,
+            NewImage {
+                id: image_id,
+                station_id,
+                kind,
+                storage_key: storage_key.clone(),
+                thumbnail_key: thumbnail_key.clone(),
+                content_type,
+                size_bytes,
+                width: processed.width as i32,
+                height: processed.height as i32,
+            },
+        )
+        .await;
+
+        // The files are already stored; don't leave them behind without a row.
+        let (image, replaced) = match saved {
+            Ok(saved) => saved,
+            Err(err) => {
+                delete_keys(app_state.media.as_ref(), &[&storage_key, &thumbnail_key]).await;
+                return Err(err);
+            }
+        };
+
+        if let Some(replaced) = replaced {
+            delete_stored_files(app_state.media.as_ref(), &replaced).await;
+        }
+
+        Ok((
+            StatusCode::CREATED,
+            Json(to_response(app_state.media.as_ref(), &image)),
+        ))
+    }
+
+    pub async fn delete_image(
+        State(app_state): State<AppState>,
+        Extension(claims): Extension<Claims>,
+        Path(image_id): Path<Uuid>,
+    ) -> Result<StatusCode, StationError> {
+        let station_id = claims.station_res.id;
+
+        let image = sqlx::query_as::<_, StationImage>(&format!(
+            r#"
+            DELETE FROM station_images
+            WHERE id = $1 AND station_id = $2
+            RETURNING {STATION_IMAGE_COLUMNS}
+            "#
+        ))
+        .bind(image_id)
+        .bind(station_id)
+        .fetch_optional(&app_state.pool)
+        .await
+        .map_err(StationError::DatabaseError)?
+        .ok_or_else(|| StationError::NotFound(image_id.to_string()))?;
+
+        delete_stored_files(app_state.media.as_ref(), &image).await;
+
+        Ok(StatusCode::NO_CONTENT)
+    }
+}
+
+/// Inserts the image row, removing the previous logo when a new one is
+/// uploaded. Returns the stored row and the replaced logo, if any.
+async fn save_image_row(
+    app_state: &AppState,
+    image: NewImage,
+) -> Result<(StationImage, Option<StationImage>), StationError> {
+    let mut tx = app_state
+        .pool
+        .begin()
+        .await
+        .map_err(StationError::DatabaseError)?;
+
+    // A new logo replaces the previous one.
+    let replaced = if image.kind == "logo" {
+        sqlx::query_as::<_, StationImage>(&format!(
+            r#"
+            DELETE FROM station_images
+            WHERE station_id = $1 AND kind = 'logo'
+            RETURNING {STATION_IMAGE_COLUMNS}
+            "#
+        ))
+        .bind(image.station_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(StationError::DatabaseError)?
+    } else {
+        None
+    };
+
+    let image = sqlx::query_as::<_, StationImage>(&format!(
+        r#"
+        INSERT INTO station_images (
+            id, station_id, kind, storage_key, thumbnail_key, content_type,
+            size_bytes, width, height
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+        RETURNING {STATION_IMAGE_COLUMNS}
+        "#
+    ))
+    .bind(image.id)
+    .bind(image.station_id)
+    .bind(&image.kind)
+    .bind(&image.storage_key)
+    .bind(&image.thumbnail_key)
+    .bind(&image.content_type)
+    .bind(image.size_bytes)
+    .bind(image.width)
+    .bind(image.height)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(StationError::DatabaseError)?;
+
+    tx.commit().await.map_err(StationError::DatabaseError)?;
+
+    Ok((image, replaced))
+}
+
+/// Decodes the upload to confirm it really is an image of the declared type
+/// and renders a PNG thumbnail that fits in a 256x256 box.
+fn process_image(bytes: &[u8], declared: ImageFormat) -> Result<ProcessedImage, StationError> {
+    let reader = ImageReader::new(Cursor::new(bytes))
+        .with_guessed_format()
+        .map_err(|_| StationError::WrongCredentials("unreadable image".to_string()))?;
+
+    if reader.format() != Some(declared) {
+        return Err(StationError::WrongCredentials(
+            "image content does not match its declared type".to_string(),
+        ));
+    }
+
+    let decoded = reader
+        .decode()
+        .map_err(|_| StationError::WrongCredentials("unreadable image".to_string()))?;
+
+    let mut thumbnail = Vec::new();
+    decoded
+        .thumbnail(THUMBNAIL_MAX_DIMENSION, THUMBNAIL_MAX_DIMENSION)
+        .write_to(&mut Cursor::new(&mut thumbnail), ImageFormat::Png)
+        .map_err(|err| StationError::Internal(format!("failed to encode thumbnail: {err}")))?;
+
+    Ok(ProcessedImage {
+        width: decoded.width(),
+        height: decoded.height(),
+        thumbnail,
+    })
+}
+
+async fn delete_stored_files(storage: &dyn MediaStorage, image: &StationImage) {
+    delete_keys(storage, &[&image.storage_key, &image.thumbnail_key]).await;
+}
+
+async fn delete_keys(storage: &dyn MediaStorage, keys: &[&str]) {
+    for key in keys {
+        if let Err(err) = storage.delete(key).await {
+            tracing::warn!("failed to delete stored image {}: {:?}", key, err);
+        }
+    }
+}
+
+fn to_response(storage: &dyn MediaStorage, image: &StationImage) -> StationImageResponse {
+    StationImageResponse {
+        id: image.id,
+        kind: image.kind.clone(),
+        url: storage.public_url(&image.storage_key),
+        thumbnail_url: storage.public_url(&image.thumbnail_key),
+        width: image.width,
+        height: image.height,
+    }
+}
+
+/// Fills in `logo` and `photos` on each station.
+pub async fn attach_images(
+    app_state: &AppState,
+    stations: &mut [StationResponse],
+) -> Result<(), sqlx::Error> {
+    if stations.is_empty() {
+        return Ok(());
+    }
+
+    let ids: Vec<Uuid> = stations.iter().map(|s| s.id).collect();
+
+    let images = sqlx::query_as::<_, StationImage>(&format!(
+        r#"
+        SELECT {STATION_IMAGE_COLUMNS}
+        FROM station_images
+        WHERE station_id = ANY($1)
+        ORDER BY created_at
+        "#
+    ))
+    .bind(&ids)
+    .fetch_all(&app_state.pool)
+    .await?;
+
+    let mut by_station: HashMap<Uuid, Vec<StationImage>> = HashMap::new();
+    for image in images {
+        by_station.entry(image.station_id).or_default().push(image);
+    }
+
+    for station in stations.iter_mut() {
+        let Some(images) = by_station.remove(&station.id) else {
+            continue;
+        };
+
+        for image in images {
+            let response = to_response(app_state.media.as_ref(), &image);
+            if image.kind == "logo" {
+                station.logo = Some(response);
+            } else {
+                station.photos.push(response);
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,128 @@
+use std::{
+    future::Future,
+    path::{Path, PathBuf},
+    pin::Pin,
+};
+
+use anyhow::Context;
+
+pub type StorageFuture<'a, T> = Pin<Box<dyn Future<Output = anyhow::Result<T>> + Send + 'a>>;
+
+/// Where uploaded station images live. Keys are relative, slash-separated
+/// paths such as `stations/<id>/<image>.png`; backends decide how to store
+/// them and how clients reach them.
+///
+/// The local filesystem backend is the only one today. An S3-compatible
+/// backend only needs to implement `put`, `get`, `delete` and `public_url`.
+pub trait MediaStorage: Send + Sync {
+    fn put<'a>(&'a self, key: &'a str, bytes: Vec<u8>, content_type: &'a str)
+    -> StorageFuture<'a, ()>;
+
+    fn get<'a>(&'a self, key: &'a str) -> StorageFuture<'a, Vec<u8>>;
+
+    fn delete<'a>(&'a self, key: &'a str) -> StorageFuture<'a, ()>;
+
+    fn public_url(&self, key: &str) -> String;
+
+    /// Directory to serve under `/media` when the backend stores files locally.
+    fn local_root(&self) -> Option<&Path> {
+        None
+    }
+}
+
+pub struct LocalFsStorage {
+    root: PathBuf,
+    public_base_url: String,
+}
+
+impl LocalFsStorage {
+    pub fn new(root: impl Into<PathBuf>, public_base_url: impl Into<String>) -> Self {
+        Self {
+            root: root.into(),
+            public_base_url: public_base_url.into().trim_end_matches('/').to_string(),
+        }
+    }
+
+    /// Reads `MEDIA_STORAGE_DIR` (default `./uploads`) and
+    /// `MEDIA_PUBLIC_BASE_URL` (default `/media`).
+    pub fn from_env() -> Self {
+        let root = std::env::var("MEDIA_STORAGE_DIR").unwrap_or_else(|_| "./uploads".to_string());
+        let base_url =
+            std::env::var("MEDIA_PUBLIC_BASE_URL").unwrap_or_else(|_| "/media".to_string());
+
+        Self::new(root, base_url)
+    }
+
+    /// Storage for files that must never be publicly served, such as
+    /// verification documents. Reads `DOCUMENT_STORAGE_DIR` (default
+    /// `./uploads-private`).
+    pub fn private_from_env() -> Self {
+        let root = std::env::var("DOCUMENT_STORAGE_DIR")
+            .unwrap_or_else(|_| "./uploads-private".to_string());
+
+        Self::new(root, "")
+    }
+
+    fn path_for(&self, key: &str) -> anyhow::Result<PathBuf> {
+        if key.split('/').any(|part| part.is_empty() || part == "." || part == "..") {
+            anyhow::bail!("invalid media key {key}");
+        }
+
+        Ok(self.root.join(key))
+    }
+}
+
+impl MediaStorage for LocalFsStorage {
+    fn put<'a>(
+        &'a self,
+        key: &'a str,
+        bytes: Vec<u8>,
+        _content_type: &'a str,
+    ) -> StorageFuture<'a, ()> {
+        Box::pin(async move {
+            let path = self.path_for(key)?;
+
+            if let Some(parent) = path.parent() {
+                tokio::fs::create_dir_all(parent)
+                    .await
+                    .with_context(|| format!("failed to create {}", parent.display()))?;
+            }
+
+            tokio::fs::write(&path, bytes)
+                .await
+                .with_context(|| format!("failed to write {}", path.display()))?;
+
+            Ok(())
+        })
+    }
+
+    fn get<'a>(&'a self, key: &'a str) -> StorageFuture<'a, Vec<u8>> {
+        Box::pin(async move {
+            let path = self.path_for(key)?;
+
+            tokio::fs::read(&path)
+                .await
+                .with_context(|| format!("failed to read {}", path.display()))
+        })
+    }
+
+    fn delete<'a>(&'a self, key: &'a str) -> StorageFuture<'a, ()> {
+        Box::pin(async move {
+            let path = self.path_for(key)?;
+
+            match tokio::fs::remove_file(&path).await {
+                Ok(()) => Ok(()),
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(()),
+                Err(err) => Err(err).with_context(|| format!("failed to delete {}", path.display())),
+            }
+        })
+    }
+
+    fn public_url(&self, key: &str) -> String {
+        format!("{}/{}", self.public_base_url, key)
+    }
+
+    fn local_root(&self) -> Option<&Path> {
+        Some(&self.root)
+    }
+}
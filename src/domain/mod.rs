@@ -1,6 +1,20 @@
+pub mod amenities;
+pub mod analytics;
+pub mod broadcasts;
+pub mod campaigns;
 pub mod commodities;
 pub mod discounts;
+pub mod emails;
+pub mod events;
+pub mod fraud;
+pub mod jobs;
+pub mod loyalty;
+pub mod media;
+pub mod notifications;
+pub mod payments;
+pub mod opening_hours;
 pub mod registration_code;
 pub mod stations;
 pub mod subscriptions;
 pub mod utils;
+pub mod verification;
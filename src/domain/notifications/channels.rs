@@ -0,0 +1,335 @@
+use std::{future::Future, pin::Pin, sync::Arc, time::Duration};
+
+use anyhow::Context;
+use serde::Deserialize;
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::domain::{
+    emails::transport::{EmailTransport, transport_from_env},
+    utils::environment::is_development,
+};
+
+pub type MessageFuture<'a, T> = Pin<Box<dyn Future<Output = anyhow::Result<T>> + Send + 'a>>;
+
+/// Channels a notice can go out on besides the dashboard.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Channel {
+    Email,
+    Sms,
+    WhatsApp,
+}
+
+impl Channel {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Channel::Email => "email",
+            Channel::Sms => "sms",
+            Channel::WhatsApp => "whatsapp",
+        }
+    }
+
+    pub fn from_db(value: &str) -> Option<Self> {
+        match value {
+            "email" => Some(Channel::Email),
+            "sms" => Some(Channel::Sms),
+            "whatsapp" => Some(Channel::WhatsApp),
+            _ => None,
+        }
+    }
+}
+
+/// A text message ready to hand to a provider.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutgoingMessage {
+    pub id: Uuid,
+    /// International format without the plus, e.g. `2348012345678`.
+    pub to: String,
+    pub body: String,
+}
+
+/// Delivers SMS or WhatsApp messages. Returns the provider's message id when
+/// it hands one back; an error means the message was not accepted and the
+/// job will retry.
+pub trait MessageProvider: Send + Sync {
+    /// Stored with each sent message, e.g. `termii`.
+    fn name(&self) -> &'static str;
+
+    fn send<'a>(&'a self, message: &'a OutgoingMessage) -> MessageFuture<'a, Option<String>>;
+}
+
+/// Every transport the job worker delivers through. A text channel is `None`
+/// when no provider is configured for it.
+#[derive(Clone)]
+pub struct Channels {
+    pub email: Arc<dyn EmailTransport>,
+    pub sms: Option<Arc<dyn MessageProvider>>,
+    pub whatsapp: Option<Arc<dyn MessageProvider>>,
+}
+
+impl Channels {
+    /// Reads each transport's configuration. In development a text channel
+    /// without a provider only logs what it would have sent; elsewhere it is
+    /// left unconfigured and its messages are marked failed.
+    pub fn from_env() -> anyhow::Result<Self> {
+        Ok(Self {
+            email: transport_from_env()?,
+            sms: sms_provider_from_env()?,
+            whatsapp: whatsapp_provider_from_env()?,
+        })
+    }
+
+    pub fn provider(&self, channel: Channel) -> Option<&dyn MessageProvider> {
+        match channel {
+            Channel::Email => None,
+            Channel::Sms => self.sms.as_deref(),
+            Channel::WhatsApp => self.whatsapp.as_deref(),
+        }
+    }
+}
+
+/// Turns a Nigerian number as people write it (`0803 123 4567`,
+/// `+234 803 123 4567`) into `2348031234567`. Returns `None` for anything
+/// that is not a valid mobile number.
+pub fn normalize_phone(phone: &str) -> Option<String> {
+    let digits: String = phone
+        .chars()
+        .filter(|ch| !matches!(ch, ' ' | '-' | '(' | ')' | '+'))
+        .collect();
+    if !digits.chars().all(|ch| ch.is_ascii_digit()) {
+        return None;
+    }
+
+    let national = match digits.len() {
+        11 if digits.starts_with('0') => &digits[1..],
+        13 if digits.starts_with("234") => &digits[3..],
+        _ => return None,
+    };
+
+    matches!(national.as_bytes()[0], b'7'..=b'9').then(|| format!("234{national}"))
+}
+
+fn http_client() -> anyhow::Result<reqwest::Client> {
+    reqwest::Client::builder()
+        .timeout(Duration::from_secs(15))
+        .build()
+        .context("failed to build http client")
+}
+
+/// The logging stand-in for a channel without a provider, which is only
+/// allowed in development.
+fn unconfigured_provider(channel: Channel) -> Option<Arc<dyn MessageProvider>> {
+    if is_development() {
+        return Some(Arc::new(LogMessageProvider { channel }));
+    }
+
+    tracing::warn!(
+        "no {} provider configured; those messages will be marked failed",
+        channel.as_str()
+    );
+    None
+}
+
+// ─── SMS ─────────────────────────────────────────────────────────────────────
+
+/// Picks the Termii-style HTTP provider when `SMS_API_KEY` is set; see
+/// [`Channels::from_env`] for what happens otherwise.
+pub fn sms_provider_from_env() -> anyhow::Result<Option<Arc<dyn MessageProvider>>> {
+    match std::env::var("SMS_API_KEY") {
+        Ok(api_key) if !api_key.trim().is_empty() => Ok(Some(Arc::new(HttpSmsProvider::new(
+            &std::env::var("SMS_BASE_URL")
+                .unwrap_or_else(|_| "https://api.ng.termii.com".to_string()),
+            api_key.trim(),
+            &std::env::var("SMS_SENDER_ID").unwrap_or_else(|_| "FuelFinder".to_string()),
+        )?))),
+        _ => Ok(unconfigured_provider(Channel::Sms)),
+    }
+}
+
+/// Sends through a Termii-compatible `POST /api/sms/send` endpoint.
+pub struct HttpSmsProvider {
+    base_url: String,
+    api_key: String,
+    sender_id: String,
+    client: reqwest::Client,
+}
+
+impl HttpSmsProvider {
+    pub fn new(base_url: &str, api_key: &str, sender_id: &str) -> anyhow::Result<Self> {
+        Ok(Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            api_key: api_key.to_string(),
+            sender_id: sender_id.to_string(),
+            client: http_client()?,
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct SmsSendResponse {
+    message_id: Option<String>,
+    message: Option<String>,
+}
+
+impl MessageProvider for HttpSmsProvider {
+    fn name(&self) -> &'static str {
+        "termii"
+    }
+
+    fn send<'a>(&'a self, message: &'a OutgoingMessage) -> MessageFuture<'a, Option<String>> {
+        Box::pin(async move {
+            let response = self
+                .client
+                .post(format!("{}/api/sms/send", self.base_url))
+                .json(&json!({
+                    "api_key": self.api_key,
+                    "to": message.to,
+                    "from": self.sender_id,
+                    "sms": message.body,
+                    "type": "plain",
+                    "channel": "generic",
+                }))
+                .send()
+                .await
+                .context("sms request failed")?;
+
+            let status = response.status();
+            let body: SmsSendResponse = response
+                .json()
+                .await
+                .context("unexpected sms provider response")?;
+
+            match body.message_id {
+                Some(message_id) if status.is_success() => Ok(Some(message_id)),
+                _ => anyhow::bail!(
+                    "sms provider rejected message ({status}): {}",
+                    body.message.unwrap_or_default()
+                ),
+            }
+        })
+    }
+}
+
+// ─── WhatsApp ────────────────────────────────────────────────────────────────
+
+/// Picks the WhatsApp Cloud API provider when `WHATSAPP_ACCESS_TOKEN` and
+/// `WHATSAPP_PHONE_NUMBER_ID` are set; see [`Channels::from_env`] for what
+/// happens otherwise.
+pub fn whatsapp_provider_from_env() -> anyhow::Result<Option<Arc<dyn MessageProvider>>> {
+    let token = std::env::var("WHATSAPP_ACCESS_TOKEN").unwrap_or_default();
+    let phone_number_id = std::env::var("WHATSAPP_PHONE_NUMBER_ID").unwrap_or_default();
+
+    if token.trim().is_empty() || phone_number_id.trim().is_empty() {
+        return Ok(unconfigured_provider(Channel::WhatsApp));
+    }
+
+    Ok(Some(Arc::new(WhatsAppCloudProvider::new(
+        &std::env::var("WHATSAPP_BASE_URL")
+            .unwrap_or_else(|_| "https://graph.facebook.com/v20.0".to_string()),
+        phone_number_id.trim(),
+        token.trim(),
+    )?)))
+}
+
+/// Sends text messages through the WhatsApp Cloud API.
+pub struct WhatsAppCloudProvider {
+    base_url: String,
+    phone_number_id: String,
+    access_token: String,
+    client: reqwest::Client,
+}
+
+impl WhatsAppCloudProvider {
+    pub fn new(base_url: &str, phone_number_id: &str, access_token: &str) -> anyhow::Result<Self> {
+        Ok(Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            phone_number_id: phone_number_id.to_string(),
+            access_token: access_token.to_string(),
+            client: http_client()?,
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct WhatsAppSendResponse {
+    #[serde(default)]
+    messages: Vec<WhatsAppMessageId>,
+    error: Option<WhatsAppError>,
+}
+
+#[derive(Deserialize)]
+struct WhatsAppMessageId {
+    id: String,
+}
+
+#[derive(Deserialize)]
+struct WhatsAppError {
+    message: String,
+}
+
+impl MessageProvider for WhatsAppCloudProvider {
+    fn name(&self) -> &'static str {
+        "whatsapp_cloud"
+    }
+
+    fn send<'a>(&'a self, message: &'a OutgoingMessage) -> MessageFuture<'a, Option<String>> {
+        Box::pin(async move {
+            let response = self
+                .client
+                .post(format!(
+                    "{}/{}/messages",
+                    self.base_url, self.phone_number_id
+                ))
+                .bearer_auth(&self.access_token)
+                .json(&json!({
+                    "messaging_product": "whatsapp",
+                    "to": message.to,
+                    "type": "text",
+                    "text": { "body": message.body },
+                }))
+                .send()
+                .await
+                .context("whatsapp request failed")?;
+
+            let status = response.status();
+            let body: WhatsAppSendResponse = response
+                .json()
+                .await
+                .context("unexpected whatsapp response")?;
+
+            match body.messages.into_iter().next() {
+                Some(sent) if status.is_success() => Ok(Some(sent.id)),
+                _ => anyhow::bail!(
+                    "whatsapp rejected message ({status}): {}",
+                    body.error.map(|error| error.message).unwrap_or_default()
+                ),
+            }
+        })
+    }
+}
+
+// ─── Log ─────────────────────────────────────────────────────────────────────
+
+/// Used in development when a channel has no provider configured: the
+/// message is logged, not delivered, and gets no provider message id.
+pub struct LogMessageProvider {
+    pub channel: Channel,
+}
+
+impl MessageProvider for LogMessageProvider {
+    fn name(&self) -> &'static str {
+        "log"
+    }
+
+    fn send<'a>(&'a self, message: &'a OutgoingMessage) -> MessageFuture<'a, Option<String>> {
+        Box::pin(async move {
+            tracing::warn!(
+                "no {} provider configured; not sending message to {}",
+                self.channel.as_str(),
+                message.to
+            );
+
+            Ok(None)
+        })
+    }
+}
@@ -0,0 +1,55 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Fields left out keep their current value.
+#[derive(Debug, Deserialize)]
+pub struct UpdateChannelPreferencesDto {
+    pub email: Option<bool>,
+    pub sms: Option<bool>,
+    pub whatsapp: Option<bool>,
+    /// An empty string goes back to the station's listed phone.
+    pub phone: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct NotificationsQuery {
+    pub kind: Option<String>,
+    /// `true` for unread only, `false` for read only.
+    pub unread: Option<bool>,
+    /// Archived notifications are hidden unless this is `true`.
+    #[serde(default)]
+    pub archived: bool,
+    pub limit: Option<i64>,
+    /// Id and `created_at` of the last notification of the previous page,
+    /// given together. The page carries on from that position even if the
+    /// notification itself has since been deleted.
+    pub before: Option<Uuid>,
+    pub before_created_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct ReadAllQuery {
+    pub kind: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BulkNotificationsDto {
+    /// `read`, `archive` or `delete`.
+    pub action: String,
+    pub ids: Vec<Uuid>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct NotificationsUpdated {
+    pub updated: u64,
+}
+
+/// Fields left out keep their current value.
+#[derive(Debug, Deserialize)]
+pub struct UpdateKindPreferenceDto {
+    pub in_app: Option<bool>,
+    pub email: Option<bool>,
+    pub sms: Option<bool>,
+    pub whatsapp: Option<bool>,
+}
@@ -0,0 +1,4 @@
+pub mod channels;
+pub mod dto;
+pub mod model;
+pub mod service;
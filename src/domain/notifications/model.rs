@@ -0,0 +1,87 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Kinds of dashboard notification a station can set preferences for.
+pub const NOTIFICATION_KINDS: [&str; 5] = [
+    "subscription",
+    "fraud",
+    "verification",
+    "profile",
+    "announcement",
+];
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct DashboardNotification {
+    pub id: Uuid,
+    pub title: String,
+    pub body: String,
+    pub kind: String,
+    pub is_read: bool,
+    pub archived_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UnreadCount {
+    pub unread: i64,
+    pub by_kind: BTreeMap<String, i64>,
+}
+
+/// Where notices of one kind may go. Kinds a station never touched are
+/// all on; channels switched off station-wide stay off regardless.
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct KindPreference {
+    pub kind: String,
+    pub in_app: bool,
+    pub email: bool,
+    pub sms: bool,
+    pub whatsapp: bool,
+}
+
+impl KindPreference {
+    pub fn all_on(kind: &str) -> Self {
+        Self {
+            kind: kind.to_string(),
+            in_app: true,
+            email: true,
+            sms: true,
+            whatsapp: true,
+        }
+    }
+}
+
+/// A station's channel choices, with defaults filled in for stations that
+/// never set any.
+#[derive(Debug, Clone, Serialize)]
+pub struct ChannelPreferences {
+    pub station_id: Uuid,
+    pub email: bool,
+    pub sms: bool,
+    pub whatsapp: bool,
+    /// Set when SMS and WhatsApp go somewhere other than the listed phone.
+    pub phone: Option<String>,
+    /// Where SMS and WhatsApp messages are sent.
+    pub destination: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct OutboundMessage {
+    pub id: Uuid,
+    pub station_id: Option<Uuid>,
+    pub channel: String,
+    pub recipient: String,
+    pub body: String,
+    pub status: String,
+    pub attempts: i32,
+    pub last_error: Option<String>,
+    pub provider: Option<String>,
+    pub provider_message_id: Option<String>,
+    pub dedupe_key: Option<String>,
+    pub sent_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
@@ -0,0 +1,815 @@
+use axum::{
+    Json,
+    extract::{Extension, Path, Query, State},
+    http::StatusCode,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgExecutor, PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+use super::{
+    channels::{Channel, Channels, OutgoingMessage, normalize_phone},
+    dto::{
+        BulkNotificationsDto, NotificationsQuery, NotificationsUpdated, ReadAllQuery,
+        UpdateChannelPreferencesDto, UpdateKindPreferenceDto,
+    },
+    model::{
+        ChannelPreferences, DashboardNotification, KindPreference, NOTIFICATION_KINDS,
+        OutboundMessage, UnreadCount,
+    },
+};
+use crate::{
+    app_state::AppState,
+    authentication::station::authenticate::token::service::Claims,
+    domain::{
+        emails::{
+            service::queue_email,
+            templates::{EmailTemplate, STATION_NOTICE, TemplateVars},
+        },
+        jobs::{
+            service::{EnqueueOptions, enqueue},
+            worker::SEND_MESSAGE_JOB,
+        },
+        utils::errors::station_errors::StationError,
+    },
+};
+
+const DEFAULT_PAGE_SIZE: i64 = 50;
+const MAX_PAGE_SIZE: i64 = 100;
+const MAX_BULK_IDS: usize = 100;
+
+const MESSAGE_COLUMNS: &str = r#"
+    id, station_id, channel, recipient, body, status, attempts, last_error, provider,
+    provider_message_id, dedupe_key, sent_at, created_at, updated_at
+"#;
+
+/// Payload of a `send_message` job.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SendMessageJob {
+    pub message_id: Uuid,
+}
+
+/// Something a station should hear about. It always lands on the
+/// dashboard and fans out to whichever other channels the station chose.
+pub struct Notice<'a> {
+    pub kind: &'a str,
+    pub title: &'a str,
+    pub body: &'a str,
+    /// The email to send; without one the generic station notice is used.
+    /// `station_name` is filled in either way.
+    pub email: Option<(&'static EmailTemplate, TemplateVars)>,
+    /// Suffixed per channel, so a retried job never sends a notice twice.
+    pub dedupe_key: Option<String>,
+}
+
+#[derive(FromRow)]
+struct StationContact {
+    station_id: Uuid,
+    name: String,
+    email: String,
+    listed_phone: String,
+    email_enabled: bool,
+    sms_enabled: bool,
+    whatsapp_enabled: bool,
+    phone: Option<String>,
+}
+
+impl StationContact {
+    fn destination(&self) -> Option<String> {
+        normalize_phone(self.phone.as_deref().unwrap_or(&self.listed_phone))
+    }
+
+    fn preferences(&self) -> ChannelPreferences {
+        ChannelPreferences {
+            station_id: self.station_id,
+            email: self.email_enabled,
+            sms: self.sms_enabled,
+            whatsapp: self.whatsapp_enabled,
+            phone: self.phone.clone(),
+            destination: self.destination(),
+        }
+    }
+}
+
+async fn station_contact<'e, E: PgExecutor<'e>>(
+    executor: E,
+    station_id: Uuid,
+) -> Result<Option<StationContact>, sqlx::Error> {
+    sqlx::query_as::<_, StationContact>(
+        r#"
+        SELECT
+            s.id AS station_id,
+            s.name,
+            s.email,
+            s.phone AS listed_phone,
+            COALESCE(p.email_enabled, TRUE) AS email_enabled,
+            COALESCE(p.sms_enabled, FALSE) AS sms_enabled,
+            COALESCE(p.whatsapp_enabled, FALSE) AS whatsapp_enabled,
+            p.phone
+        FROM stations s
+        LEFT JOIN station_notification_channels p ON p.station_id = s.id
+        WHERE s.id = $1
+        "#,
+    )
+    .bind(station_id)
+    .fetch_optional(executor)
+    .await
+}
+
+/// What a notice turned into for one station.
+#[derive(Debug, Default)]
+pub struct NoticeDelivery {
+    /// `None` when the station turned in-app notices of this kind off.
+    pub notification_id: Option<Uuid>,
+    pub email_id: Option<Uuid>,
+}
+
+/// Records the notice on the dashboard and queues it on every channel the
+/// station chose for its kind, inside the caller's transaction.
+pub async fn notify_station(
+    tx: &mut Transaction<'_, Postgres>,
+    station_id: Uuid,
+    notice: Notice<'_>,
+) -> anyhow::Result<NoticeDelivery> {
+    notify_station_via(
+        tx,
+        station_id,
+        notice,
+        &[Channel::Email, Channel::Sms, Channel::WhatsApp],
+    )
+    .await
+}
+
+/// Like [`notify_station`], but only `channels` are considered besides the
+/// dashboard; the station's own choices still apply to those.
+pub async fn notify_station_via(
+    tx: &mut Transaction<'_, Postgres>,
+    station_id: Uuid,
+    notice: Notice<'_>,
+    channels: &[Channel],
+) -> anyhow::Result<NoticeDelivery> {
+    let Some(contact) = station_contact(&mut **tx, station_id).await? else {
+        anyhow::bail!("station {station_id} not found");
+    };
+    let preference = kind_preference(&mut **tx, station_id, notice.kind).await?;
+
+    let mut delivery = NoticeDelivery {
+        notification_id: create_dashboard_notification(
+            &mut **tx,
+            station_id,
+            notice.title,
+            notice.body,
+            notice.kind,
+        )
+        .await?,
+        email_id: None,
+    };
+
+    let channel_key = |channel: Channel| {
+        notice
+            .dedupe_key
+            .as_ref()
+            .map(|key| format!("{key}:{}", channel.as_str()))
+    };
+
+    if channels.contains(&Channel::Email) && contact.email_enabled && preference.email {
+        let (template, mut variables) = notice.email.unwrap_or_else(|| {
+            (
+                &STATION_NOTICE,
+                TemplateVars::from_iter([
+                    ("title".to_string(), notice.title.into()),
+                    ("body".to_string(), notice.body.into()),
+                ]),
+            )
+        });
+        variables.insert("station_name".to_string(), contact.name.clone().into());
+
+        delivery.email_id = queue_email(
+            tx,
+            template,
+            &contact.email,
+            variables,
+            Some(station_id),
+            channel_key(Channel::Email),
+        )
+        .await?;
+    }
+
+    let text = format!("{}: {}", notice.title, notice.body);
+    let destination = contact.destination();
+
+    for (channel, enabled) in [
+        (Channel::Sms, contact.sms_enabled && preference.sms),
+        (
+            Channel::WhatsApp,
+            contact.whatsapp_enabled && preference.whatsapp,
+        ),
+    ] {
+        if !enabled || !channels.contains(&channel) {
+            continue;
+        }
+
+        let Some(destination) = &destination else {
+            tracing::warn!(
+                "station {station_id} wants {} but has no valid phone",
+                channel.as_str()
+            );
+            continue;
+        };
+
+        queue_message(
+            tx,
+            Some(station_id),
+            channel,
+            destination,
+            &text,
+            channel_key(channel),
+        )
+        .await?;
+    }
+
+    Ok(delivery)
+}
+
+/// Adds a notice to the station's dashboard unless the station turned
+/// in-app notices of this kind off. Returns the new notification's id.
+pub async fn create_dashboard_notification<'e, E: PgExecutor<'e>>(
+    executor: E,
+    station_id: Uuid,
+    title: &str,
+    body: &str,
+    kind: &str,
+) -> anyhow::Result<Option<Uuid>> {
+    let notification_id = sqlx::query_scalar::<_, Uuid>(
+        r#"
+        INSERT INTO notifications (station_id, title, body, kind)
+        SELECT $1, $2, $3, $4
+        WHERE COALESCE(
+            (
+                SELECT in_app
+                FROM station_notification_preferences
+                WHERE station_id = $1 AND kind = $4
+            ),
+            TRUE
+        )
+        RETURNING id
+        "#,
+    )
+    .bind(station_id)
+    .bind(title)
+    .bind(body)
+    .bind(kind)
+    .fetch_optional(executor)
+    .await?;
+
+    Ok(notification_id)
+}
+
+pub async fn kind_preference<'e, E: PgExecutor<'e>>(
+    executor: E,
+    station_id: Uuid,
+    kind: &str,
+) -> Result<KindPreference, sqlx::Error> {
+    let preference = sqlx::query_as::<_, KindPreference>(
+        r#"
+        SELECT kind, in_app, email, sms, whatsapp
+        FROM station_notification_preferences
+        WHERE station_id = $1 AND kind = $2
+        "#,
+    )
+    .bind(station_id)
+    .bind(kind)
+    .fetch_optional(executor)
+    .await?;
+
+    Ok(preference.unwrap_or_else(|| KindPreference::all_on(kind)))
+}
+
+/// Newest first. `before` continues from the last notification of the
+/// previous page.
+pub async fn list_notifications(
+    pool: &PgPool,
+    station_id: Uuid,
+    query: &NotificationsQuery,
+    limit: i64,
+) -> Result<Vec<DashboardNotification>, sqlx::Error> {
+    sqlx::query_as::<_, DashboardNotification>(
+        r#"
+        SELECT id, title, body, kind, is_read, archived_at, created_at
+        FROM notifications
+        WHERE station_id = $1
+          AND ($2::varchar IS NULL OR kind = $2)
+          AND ($3::boolean IS NULL OR is_read <> $3)
+          AND (archived_at IS NOT NULL) = $4
+          AND ($5::timestamptz IS NULL OR (created_at, id) < ($5, $6::uuid))
+        ORDER BY created_at DESC, id DESC
+        LIMIT $7
+        "#,
+    )
+    .bind(station_id)
+    .bind(&query.kind)
+    .bind(query.unread)
+    .bind(query.archived)
+    .bind(query.before_created_at)
+    .bind(query.before)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn unread_count(pool: &PgPool, station_id: Uuid) -> Result<UnreadCount, sqlx::Error> {
+    let rows = sqlx::query_as::<_, (String, i64)>(
+        r#"
+        SELECT kind, COUNT(*)
+        FROM notifications
+        WHERE station_id = $1 AND NOT is_read AND archived_at IS NULL
+        GROUP BY kind
+        "#,
+    )
+    .bind(station_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(UnreadCount {
+        unread: rows.iter().map(|(_, count)| count).sum(),
+        by_kind: rows.into_iter().collect(),
+    })
+}
+
+#[derive(Debug, Clone, Copy)]
+enum NotificationAction {
+    Read,
+    Archive,
+    Delete,
+}
+
+impl NotificationAction {
+    fn parse(action: &str) -> Option<Self> {
+        match action {
+            "read" => Some(NotificationAction::Read),
+            "archive" => Some(NotificationAction::Archive),
+            "delete" => Some(NotificationAction::Delete),
+            _ => None,
+        }
+    }
+}
+
+/// Returns how many of `ids` belonged to the station.
+async fn apply_action(
+    pool: &PgPool,
+    station_id: Uuid,
+    action: NotificationAction,
+    ids: &[Uuid],
+) -> Result<u64, sqlx::Error> {
+    let sql = match action {
+        NotificationAction::Read => {
+            "UPDATE notifications SET is_read = TRUE WHERE station_id = $1 AND id = ANY($2)"
+        }
+        NotificationAction::Archive => {
+            "UPDATE notifications SET archived_at = COALESCE(archived_at, now()) \
+             WHERE station_id = $1 AND id = ANY($2)"
+        }
+        NotificationAction::Delete => {
+            "DELETE FROM notifications WHERE station_id = $1 AND id = ANY($2)"
+        }
+    };
+
+    let result = sqlx::query(sql)
+        .bind(station_id)
+        .bind(ids)
+        .execute(pool)
+        .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Stores an SMS or WhatsApp message and queues its delivery. Returns
+/// `None` when `dedupe_key` was already used.
+pub async fn queue_message(
+    tx: &mut Transaction<'_, Postgres>,
+    station_id: Option<Uuid>,
+    channel: Channel,
+    recipient: &str,
+    body: &str,
+    dedupe_key: Option<String>,
+) -> anyhow::Result<Option<Uuid>> {
+    if channel == Channel::Email {
+        anyhow::bail!("emails go through the email outbox");
+    }
+
+    let message_id = sqlx::query_scalar::<_, Uuid>(
+        r#"
+        INSERT INTO outbound_messages (station_id, channel, recipient, body, dedupe_key)
+        VALUES ($1, $2, $3, $4, $5)
+        ON CONFLICT (dedupe_key) DO NOTHING
+        RETURNING id
+        "#,
+    )
+    .bind(station_id)
+    .bind(channel.as_str())
+    .bind(recipient)
+    .bind(body)
+    .bind(dedupe_key)
+    .fetch_optional(&mut **tx)
+    .await?;
+
+    let Some(message_id) = message_id else {
+        return Ok(None);
+    };
+
+    enqueue(
+        &mut **tx,
+        SEND_MESSAGE_JOB,
+        &serde_json::to_value(SendMessageJob { message_id })?,
+        EnqueueOptions {
+            dedupe_key: Some(format!("message:{message_id}")),
+            ..Default::default()
+        },
+    )
+    .await?;
+
+    Ok(Some(message_id))
+}
+
+/// Sends one stored message and records the outcome, like
+/// [`crate::domain::emails::service::deliver_email`].
+pub async fn deliver_message(
+    pool: &PgPool,
+    channels: &Channels,
+    message_id: Uuid,
+    final_attempt: bool,
+) -> anyhow::Result<()> {
+    let message = sqlx::query_as::<_, OutboundMessage>(&format!(
+        "SELECT {MESSAGE_COLUMNS} FROM outbound_messages WHERE id = $1"
+    ))
+    .bind(message_id)
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| anyhow::anyhow!("message {message_id} not found"))?;
+
+    if message.status == "sent" {
+        return Ok(());
+    }
+
+    // Retrying will not configure a provider, so give up on the message now.
+    let Some(provider) =
+        Channel::from_db(&message.channel).and_then(|channel| channels.provider(channel))
+    else {
+        sqlx::query(
+            r#"
+            UPDATE outbound_messages
+            SET status = 'failed',
+                attempts = attempts + 1,
+                last_error = $2,
+                updated_at = now()
+            WHERE id = $1
+            "#,
+        )
+        .bind(message_id)
+        .bind(format!("no provider configured for {}", message.channel))
+        .execute(pool)
+        .await?;
+
+        return Ok(());
+    };
+
+    let outgoing = OutgoingMessage {
+        id: message.id,
+        to: message.recipient,
+        body: message.body,
+    };
+
+    match provider.send(&outgoing).await {
+        Ok(provider_message_id) => {
+            sqlx::query(
+                r#"
+                UPDATE outbound_messages
+                SET status = 'sent',
+                    attempts = attempts + 1,
+                    last_error = NULL,
+                    provider = $2,
+                    provider_message_id = $3,
+                    sent_at = now(),
+                    updated_at = now()
+                WHERE id = $1
+                "#,
+            )
+            .bind(message_id)
+            .bind(provider.name())
+            .bind(provider_message_id)
+            .execute(pool)
+            .await?;
+
+            Ok(())
+        }
+        Err(err) => {
+            sqlx::query(
+                r#"
+                UPDATE outbound_messages
+                SET status = CASE WHEN $3 THEN 'failed' ELSE 'pending' END,
+                    attempts = attempts + 1,
+                    last_error = $2,
+                    provider = $4,
+                    updated_at = now()
+                WHERE id = $1
+                "#,
+            )
+            .bind(message_id)
+            .bind(format!("{err:#}"))
+            .bind(final_attempt)
+            .bind(provider.name())
+            .execute(pool)
+            .await?;
+
+            Err(err)
+        }
+    }
+}
+
+pub struct NotificationService;
+
+impl NotificationService {
+    pub async fn get_notifications(
+        State(app_state): State<AppState>,
+        Extension(claims): Extension<Claims>,
+        Query(query): Query<NotificationsQuery>,
+    ) -> Result<Json<Vec<DashboardNotification>>, StationError> {
+        let limit = query.limit.unwrap_or(DEFAULT_PAGE_SIZE);
+        if !(1..=MAX_PAGE_SIZE).contains(&limit) {
+            return Err(StationError::WrongCredentials(format!(
+                "limit must be between 1 and {MAX_PAGE_SIZE}"
+            )));
+        }
+        if query.before.is_some() != query.before_created_at.is_some() {
+            return Err(StationError::WrongCredentials(
+                "before and before_created_at must be given together".to_string(),
+            ));
+        }
+
+        let notifications =
+            list_notifications(&app_state.pool, claims.station_res.id, &query, limit)
+                .await
+                .map_err(StationError::DatabaseError)?;
+
+        Ok(Json(notifications))
+    }
+
+    pub async fn get_unread_count(
+        State(app_state): State<AppState>,
+        Extension(claims): Extension<Claims>,
+    ) -> Result<Json<UnreadCount>, StationError> {
+        let count = unread_count(&app_state.pool, claims.station_res.id)
+            .await
+            .map_err(StationError::DatabaseError)?;
+
+        Ok(Json(count))
+    }
+
+    pub async fn mark_read(
+        State(app_state): State<AppState>,
+        Extension(claims): Extension<Claims>,
+        Path(notification_id): Path<Uuid>,
+    ) -> Result<StatusCode, StationError> {
+        Self::apply_to_one(
+            &app_state.pool,
+            &claims,
+            NotificationAction::Read,
+            notification_id,
+        )
+        .await
+    }
+
+    pub async fn archive(
+        State(app_state): State<AppState>,
+        Extension(claims): Extension<Claims>,
+        Path(notification_id): Path<Uuid>,
+    ) -> Result<StatusCode, StationError> {
+        Self::apply_to_one(
+            &app_state.pool,
+            &claims,
+            NotificationAction::Archive,
+            notification_id,
+        )
+        .await
+    }
+
+    pub async fn delete_notification(
+        State(app_state): State<AppState>,
+        Extension(claims): Extension<Claims>,
+        Path(notification_id): Path<Uuid>,
+    ) -> Result<StatusCode, StationError> {
+        Self::apply_to_one(
+            &app_state.pool,
+            &claims,
+            NotificationAction::Delete,
+            notification_id,
+        )
+        .await
+    }
+
+    async fn apply_to_one(
+        pool: &PgPool,
+        claims: &Claims,
+        action: NotificationAction,
+        notification_id: Uuid,
+    ) -> Result<StatusCode, StationError> {
+        let updated = apply_action(pool, claims.station_res.id, action, &[notification_id])
+            .await
+            .map_err(StationError::DatabaseError)?;
+
+        if updated == 0 {
+            return Err(StationError::NotFound(notification_id.to_string()));
+        }
+
+        Ok(StatusCode::NO_CONTENT)
+    }
+
+    pub async fn mark_all_read(
+        State(app_state): State<AppState>,
+        Extension(claims): Extension<Claims>,
+        Query(query): Query<ReadAllQuery>,
+    ) -> Result<Json<NotificationsUpdated>, StationError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE notifications
+            SET is_read = TRUE
+            WHERE station_id = $1 AND NOT is_read AND ($2::varchar IS NULL OR kind = $2)
+            "#,
+        )
+        .bind(claims.station_res.id)
+        .bind(&query.kind)
+        .execute(&app_state.pool)
+        .await
+        .map_err(StationError::DatabaseError)?;
+
+        Ok(Json(NotificationsUpdated {
+            updated: result.rows_affected(),
+        }))
+    }
+
+    pub async fn bulk_update(
+        State(app_state): State<AppState>,
+        Extension(claims): Extension<Claims>,
+        Json(body): Json<BulkNotificationsDto>,
+    ) -> Result<Json<NotificationsUpdated>, StationError> {
+        let action = NotificationAction::parse(&body.action).ok_or_else(|| {
+            StationError::WrongCredentials("action must be read, archive or delete".to_string())
+        })?;
+        if body.ids.is_empty() || body.ids.len() > MAX_BULK_IDS {
+            return Err(StationError::WrongCredentials(format!(
+                "ids must contain between 1 and {MAX_BULK_IDS} notifications"
+            )));
+        }
+
+        let updated = apply_action(&app_state.pool, claims.station_res.id, action, &body.ids)
+            .await
+            .map_err(StationError::DatabaseError)?;
+
+        Ok(Json(NotificationsUpdated { updated }))
+    }
+
+    pub async fn get_kind_preferences(
+        State(app_state): State<AppState>,
+        Extension(claims): Extension<Claims>,
+    ) -> Result<Json<Vec<KindPreference>>, StationError> {
+        let saved = sqlx::query_as::<_, KindPreference>(
+            r#"
+            SELECT kind, in_app, email, sms, whatsapp
+            FROM station_notification_preferences
+            WHERE station_id = $1
+            "#,
+        )
+        .bind(claims.station_res.id)
+        .fetch_all(&app_state.pool)
+        .await
+        .map_err(StationError::DatabaseError)?;
+
+        let preferences = NOTIFICATION_KINDS
+            .into_iter()
+            .map(|kind| {
+                saved
+                    .iter()
+                    .find(|preference| preference.kind == kind)
+                    .cloned()
+                    .unwrap_or_else(|| KindPreference::all_on(kind))
+            })
+            .collect();
+
+        Ok(Json(preferences))
+    }
+
+    pub async fn update_kind_preference(
+        State(app_state): State<AppState>,
+        Extension(claims): Extension<Claims>,
+        Path(kind): Path<String>,
+        Json(body): Json<UpdateKindPreferenceDto>,
+    ) -> Result<Json<KindPreference>, StationError> {
+        if !NOTIFICATION_KINDS.contains(&kind.as_str()) {
+            return Err(StationError::NotFound(format!("notification kind {kind}")));
+        }
+
+        let station_id = claims.station_res.id;
+        let current = kind_preference(&app_state.pool, station_id, &kind)
+            .await
+            .map_err(StationError::DatabaseError)?;
+
+        let preference = sqlx::query_as::<_, KindPreference>(
+            r#"
+            INSERT INTO station_notification_preferences (
+                station_id, kind, in_app, email, sms, whatsapp
+            )
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (station_id, kind) DO UPDATE
+            SET in_app = EXCLUDED.in_app,
+                email = EXCLUDED.email,
+                sms = EXCLUDED.sms,
+                whatsapp = EXCLUDED.whatsapp,
+                updated_at = now()
+            RETURNING kind, in_app, email, sms, whatsapp
+            "#,
+        )
+        .bind(station_id)
+        .bind(&kind)
+        .bind(body.in_app.unwrap_or(current.in_app))
+        .bind(body.email.unwrap_or(current.email))
+        .bind(body.sms.unwrap_or(current.sms))
+        .bind(body.whatsapp.unwrap_or(current.whatsapp))
+        .fetch_one(&app_state.pool)
+        .await
+        .map_err(StationError::DatabaseError)?;
+
+        Ok(Json(preference))
+    }
+
+    pub async fn get_channel_preferences(
+        State(app_state): State<AppState>,
+        Extension(claims): Extension<Claims>,
+    ) -> Result<Json<ChannelPreferences>, StationError> {
+        let station_id = claims.station_res.id;
+
+        let contact = station_contact(&app_state.pool, station_id)
+            .await
+            .map_err(StationError::DatabaseError)?
+            .ok_or_else(|| StationError::NotFound(station_id.to_string()))?;
+
+        Ok(Json(contact.preferences()))
+    }
+
+    pub async fn update_channel_preferences(
+        State(app_state): State<AppState>,
+        Extension(claims): Extension<Claims>,
+        Json(body): Json<UpdateChannelPreferencesDto>,
+    ) -> Result<Json<ChannelPreferences>, StationError> {
+        let station_id = claims.station_res.id;
+
+        let mut contact = station_contact(&app_state.pool, station_id)
+            .await
+            .map_err(StationError::DatabaseError)?
+            .ok_or_else(|| StationError::NotFound(station_id.to_string()))?;
+
+        if let Some(phone) = body.phone {
+            contact.phone = match phone.trim() {
+                "" => None,
+                phone => Some(normalize_phone(phone).ok_or_else(|| {
+                    StationError::WrongCredentials(
+                        "phone must be a Nigerian mobile number".to_string(),
+                    )
+                })?),
+            };
+        }
+        contact.email_enabled = body.email.unwrap_or(contact.email_enabled);
+        contact.sms_enabled = body.sms.unwrap_or(contact.sms_enabled);
+        contact.whatsapp_enabled = body.whatsapp.unwrap_or(contact.whatsapp_enabled);
+
+        if (contact.sms_enabled || contact.whatsapp_enabled) && contact.destination().is_none() {
+            return Err(StationError::WrongCredentials(
+                "SMS and WhatsApp need a valid mobile number; set phone".to_string(),
+            ));
+        }
+
+        sqlx::query(
+            r#"
+            INSERT INTO station_notification_channels (
+                station_id, email_enabled, sms_enabled, whatsapp_enabled, phone
+            )
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (station_id) DO UPDATE
+            SET email_enabled = EXCLUDED.email_enabled,
+                sms_enabled = EXCLUDED.sms_enabled,
+                whatsapp_enabled = EXCLUDED.whatsapp_enabled,
+                phone = EXCLUDED.phone,
+                updated_at = now()
+            "#,
+        )
+        .bind(station_id)
+        .bind(contact.email_enabled)
+        .bind(contact.sms_enabled)
+        .bind(contact.whatsapp_enabled)
+        .bind(&contact.phone)
+        .execute(&app_state.pool)
+        .await
+        .map_err(StationError::DatabaseError)?;
+
+        Ok(Json(contact.preferences()))
+    }
+}
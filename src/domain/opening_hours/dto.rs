@@ -0,0 +1,29 @@
+use chrono::NaiveTime;
+use serde::{Deserialize, Serialize};
+
+use super::model::{OpeningHour, OpeningHourOverride};
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateOpeningHoursDto {
+    pub hours: Vec<OpeningHour>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpsertOpeningHourOverrideDto {
+    pub is_closed: bool,
+    pub opens_at: Option<NaiveTime>,
+    pub closes_at: Option<NaiveTime>,
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SetTemporarilyClosedDto {
+    pub temporarily_closed: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OpeningHoursResponse {
+    pub temporarily_closed: bool,
+    pub hours: Vec<OpeningHour>,
+    pub overrides: Vec<OpeningHourOverride>,
+}
@@ -0,0 +1,27 @@
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct OpeningHour {
+    pub day_of_week: i16,
+    pub opens_at: NaiveTime,
+    pub closes_at: NaiveTime,
+}
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct OpeningHourOverride {
+    pub override_date: NaiveDate,
+    pub is_closed: bool,
+    pub opens_at: Option<NaiveTime>,
+    pub closes_at: Option<NaiveTime>,
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct StationOpenStatus {
+    pub station_id: Uuid,
+    pub is_open: bool,
+    pub closes_at: Option<DateTime<Utc>>,
+}
@@ -0,0 +1,290 @@
+use std::collections::{HashMap, HashSet};
+
+use axum::{
+    Json,
+    extract::{Extension, Path, State},
+    http::StatusCode,
+};
+use chrono::NaiveDate;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    app_state::AppState,
+    authentication::station::authenticate::token::service::Claims,
+    domain::{
+        amenities::service::sync_open_24_hours,
+        opening_hours::{
+            dto::{
+                OpeningHoursResponse, SetTemporarilyClosedDto, UpdateOpeningHoursDto,
+                UpsertOpeningHourOverrideDto,
+            },
+            model::{OpeningHour, OpeningHourOverride, StationOpenStatus},
+        },
+        utils::{errors::station_errors::StationError, schemas::StationResponse},
+    },
+};
+
+pub struct OpeningHoursService;
+
+impl OpeningHoursService {
+    pub async fn get_opening_hours(
+        State(app_state): State<AppState>,
+        Extension(claims): Extension<Claims>,
+    ) -> Result<Json<OpeningHoursResponse>, StationError> {
+        let station_id = claims.station_res.id;
+
+        let response = load_opening_hours(&app_state.pool, station_id)
+            .await
+            .map_err(StationError::DatabaseError)?;
+
+        Ok(Json(response))
+    }
+
+    pub async fn update_opening_hours(
+        State(app_state): State<AppState>,
+        Extension(claims): Extension<Claims>,
+        Json(body): Json<UpdateOpeningHoursDto>,
+    ) -> Result<Json<OpeningHoursResponse>, StationError> {
+        let station_id = claims.station_res.id;
+
+        let mut seen_days = HashSet::new();
+        for hour in &body.hours {
+            if !(1..=7).contains(&hour.day_of_week) {
+                return Err(StationError::WrongCredentials(
+                    "day_of_week must be between 1 (Monday) and 7 (Sunday)".to_string(),
+                ));
+            }
+
+            if !seen_days.insert(hour.day_of_week) {
+                return Err(StationError::WrongCredentials(format!(
+                    "day_of_week {} is listed more than once",
+                    hour.day_of_week
+                )));
+            }
+        }
+
+        let mut tx = app_state
+            .pool
+            .begin()
+            .await
+            .map_err(StationError::DatabaseError)?;
+
+        sqlx::query("DELETE FROM station_opening_hours WHERE station_id = $1")
+            .bind(station_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(StationError::DatabaseError)?;
+
+        for hour in &body.hours {
+            sqlx::query(
+                r#"
+                INSERT INTO station_opening_hours (station_id, day_of_week, opens_at, closes_at)
+                VALUES ($1, $2, $3, $4)
+                "#,
+            )
+            .bind(station_id)
+            .bind(hour.day_of_week)
+            .bind(hour.opens_at)
+            .bind(hour.closes_at)
+            .execute(&mut *tx)
+            .await
+            .map_err(StationError::DatabaseError)?;
+        }
+
+        sync_open_24_hours(&mut *tx, station_id)
+            .await
+            .map_err(StationError::DatabaseError)?;
+
+        tx.commit().await.map_err(StationError::DatabaseError)?;
+
+        let response = load_opening_hours(&app_state.pool, station_id)
+            .await
+            .map_err(StationError::DatabaseError)?;
+
+        Ok(Json(response))
+    }
+
+    pub async fn upsert_override(
+        State(app_state): State<AppState>,
+        Extension(claims): Extension<Claims>,
+        Path(override_date): Path<NaiveDate>,
+        Json(body): Json<UpsertOpeningHourOverrideDto>,
+    ) -> Result<StatusCode, StationError> {
+        let station_id = claims.station_res.id;
+
+        let (opens_at, closes_at) = if body.is_closed {
+            (None, None)
+        } else {
+            let opens_at = body.opens_at.ok_or_else(|| {
+                StationError::WrongCredentials("opens_at is required unless is_closed".to_string())
+            })?;
+            let closes_at = body.closes_at.ok_or_else(|| {
+                StationError::WrongCredentials("closes_at is required unless is_closed".to_string())
+            })?;
+            (Some(opens_at), Some(closes_at))
+        };
+
+        sqlx::query(
+            r#"
+            INSERT INTO station_opening_hour_overrides (
+                station_id, override_date, is_closed, opens_at, closes_at, note
+            )
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (station_id, override_date)
+            DO UPDATE SET
+                is_closed = EXCLUDED.is_closed,
+                opens_at = EXCLUDED.opens_at,
+                closes_at = EXCLUDED.closes_at,
+                note = EXCLUDED.note
+            "#,
+        )
+        .bind(station_id)
+        .bind(override_date)
+        .bind(body.is_closed)
+        .bind(opens_at)
+        .bind(closes_at)
+        .bind(body.note)
+        .execute(&app_state.pool)
+        .await
+        .map_err(StationError::DatabaseError)?;
+
+        Ok(StatusCode::NO_CONTENT)
+    }
+
+    pub async fn delete_override(
+        State(app_state): State<AppState>,
+        Extension(claims): Extension<Claims>,
+        Path(override_date): Path<NaiveDate>,
+    ) -> Result<StatusCode, StationError> {
+        let station_id = claims.station_res.id;
+
+        let rows_affected = sqlx::query(
+            r#"
+            DELETE FROM station_opening_hour_overrides
+            WHERE station_id = $1 AND override_date = $2
+            "#,
+        )
+        .bind(station_id)
+        .bind(override_date)
+        .execute(&app_state.pool)
+        .await
+        .map_err(StationError::DatabaseError)?
+        .rows_affected();
+
+        if rows_affected == 0 {
+            return Err(StationError::NotFound(override_date.to_string()));
+        }
+
+        Ok(StatusCode::NO_CONTENT)
+    }
+
+    pub async fn set_temporarily_closed(
+        State(app_state): State<AppState>,
+        Extension(claims): Extension<Claims>,
+        Json(body): Json<SetTemporarilyClosedDto>,
+    ) -> Result<StatusCode, StationError> {
+        let station_id = claims.station_res.id;
+
+        let rows_affected = sqlx::query(
+            r#"
+            UPDATE stations
+            SET temporarily_closed = $1, updated_at = NOW()
+            WHERE id = $2
+            "#,
+        )
+        .bind(body.temporarily_closed)
+        .bind(station_id)
+        .execute(&app_state.pool)
+        .await
+        .map_err(StationError::DatabaseError)?
+        .rows_affected();
+
+        if rows_affected == 0 {
+            return Err(StationError::NotFound(station_id.to_string()));
+        }
+
+        Ok(StatusCode::NO_CONTENT)
+    }
+}
+
+async fn load_opening_hours(
+    pool: &PgPool,
+    station_id: Uuid,
+) -> Result<OpeningHoursResponse, sqlx::Error> {
+    let temporarily_closed: bool =
+        sqlx::query_scalar("SELECT temporarily_closed FROM stations WHERE id = $1")
+            .bind(station_id)
+            .fetch_optional(pool)
+            .await?
+            .unwrap_or(false);
+
+    let hours = sqlx::query_as::<_, OpeningHour>(
+        r#"
+        SELECT day_of_week, opens_at, closes_at
+        FROM station_opening_hours
+        WHERE station_id = $1
+        ORDER BY day_of_week
+        "#,
+    )
+    .bind(station_id)
+    .fetch_all(pool)
+    .await?;
+
+    let overrides = sqlx::query_as::<_, OpeningHourOverride>(
+        r#"
+        SELECT override_date, is_closed, opens_at, closes_at, note
+        FROM station_opening_hour_overrides
+        WHERE station_id = $1
+          AND override_date >= (now() AT TIME ZONE 'Africa/Lagos')::date
+        ORDER BY override_date
+        "#,
+    )
+    .bind(station_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(OpeningHoursResponse {
+        temporarily_closed,
+        hours,
+        overrides,
+    })
+}
+
+/// Fills in `is_open` and `closes_at` on each station using the
+/// `station_open_window` database function, evaluated at the current time.
+pub async fn attach_open_status(
+    pool: &PgPool,
+    stations: &mut [StationResponse],
+) -> Result<(), sqlx::Error> {
+    if stations.is_empty() {
+        return Ok(());
+    }
+
+    let ids: Vec<Uuid> = stations.iter().map(|s| s.id).collect();
+
+    let statuses = sqlx::query_as::<_, StationOpenStatus>(
+        r#"
+        SELECT s.id AS station_id, w.is_open, w.closes_at
+        FROM unnest($1::uuid[]) AS s(id)
+        CROSS JOIN LATERAL station_open_window(s.id, now()) AS w
+        "#,
+    )
+    .bind(&ids)
+    .fetch_all(pool)
+    .await?;
+
+    let statuses: HashMap<Uuid, StationOpenStatus> = statuses
+        .into_iter()
+        .map(|status| (status.station_id, status))
+        .collect();
+
+    for station in stations.iter_mut() {
+        if let Some(status) = statuses.get(&station.id) {
+            station.is_open = status.is_open;
+            station.closes_at = status.closes_at;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,25 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Deserialize)]
+pub struct CheckoutDto {
+    pub invoice_id: Uuid,
+    /// Where the provider sends the station after paying.
+    pub callback_url: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CheckoutResponse {
+    pub reference: String,
+    pub provider: &'static str,
+    /// Whole naira.
+    pub amount: i32,
+    pub authorization_url: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WebhookResponse {
+    /// `renewed`, `failed`, `duplicate`, `ignored`, `unknown_reference`,
+    /// `already_processed`, `amount_mismatch` or `invoice_not_open`.
+    pub outcome: &'static str,
+}
@@ -0,0 +1,4 @@
+pub mod dto;
+pub mod provider;
+pub mod routes;
+pub mod service;
@@ -0,0 +1,291 @@
+use std::{future::Future, pin::Pin, sync::Arc, time::Duration};
+
+use anyhow::Context;
+use axum::http::HeaderMap;
+use hmac::{Hmac, Mac};
+use serde::Deserialize;
+use serde_json::{Value, json};
+use sha2::{Digest, Sha256, Sha512};
+
+use crate::domain::utils::environment::is_development;
+
+pub type PaymentFuture<'a, T> = Pin<Box<dyn Future<Output = anyhow::Result<T>> + Send + 'a>>;
+
+/// What the provider needs to start a hosted checkout.
+#[derive(Debug, Clone)]
+pub struct CheckoutRequest {
+    /// Our reference; the provider echoes it back in webhooks.
+    pub reference: String,
+    /// Amount in kobo.
+    pub amount_kobo: i64,
+    pub email: String,
+    pub callback_url: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CheckoutSession {
+    /// Page the station is sent to, to pay.
+    pub authorization_url: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaymentEventKind {
+    Succeeded,
+    Failed,
+    /// Anything else the provider sends; acknowledged and recorded only.
+    Other,
+}
+
+/// A webhook delivery whose signature has been checked.
+#[derive(Debug, Clone)]
+pub struct PaymentEvent {
+    /// Unique per event, so redeliveries can be recognised.
+    pub event_id: String,
+    pub event_type: String,
+    pub kind: PaymentEventKind,
+    pub reference: Option<String>,
+    pub amount_kobo: Option<i64>,
+    pub payload: Value,
+}
+
+/// A payment provider with a hosted checkout and signed webhooks, in the
+/// style of Paystack or Flutterwave.
+pub trait PaymentProvider: Send + Sync {
+    /// Stored with each payment, e.g. `paystack`.
+    fn name(&self) -> &'static str;
+
+    fn initialize<'a>(&'a self, request: &'a CheckoutRequest) -> PaymentFuture<'a, CheckoutSession>;
+
+    /// Verifies the webhook signature over the raw body and parses the event.
+    fn parse_webhook(&self, headers: &HeaderMap, body: &[u8]) -> anyhow::Result<PaymentEvent>;
+}
+
+/// Picks Paystack when `PAYSTACK_SECRET_KEY` is set. The local provider is
+/// only allowed in development and test deployments; anywhere else a missing
+/// key stops the server from starting.
+pub fn provider_from_env() -> anyhow::Result<Arc<dyn PaymentProvider>> {
+    match std::env::var("PAYSTACK_SECRET_KEY") {
+        Ok(secret_key) if !secret_key.trim().is_empty() => {
+            Ok(Arc::new(PaystackProvider::new(secret_key.trim())?))
+        }
+        _ if is_development() => Ok(Arc::new(LocalPaymentProvider::from_env()?)),
+        _ => anyhow::bail!("PAYSTACK_SECRET_KEY must be set outside development"),
+    }
+}
+
+fn decode_hex(value: &str) -> anyhow::Result<Vec<u8>> {
+    let value = value.trim();
+    if !value.is_ascii() || !value.len().is_multiple_of(2) {
+        anyhow::bail!("invalid hex");
+    }
+
+    (0..value.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&value[i..i + 2], 16).context("invalid hex"))
+        .collect()
+}
+
+fn signature_header<'a>(headers: &'a HeaderMap, name: &str) -> anyhow::Result<&'a str> {
+    headers
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .ok_or_else(|| anyhow::anyhow!("missing {name} header"))
+}
+
+// ─── Paystack ────────────────────────────────────────────────────────────────
+
+pub struct PaystackProvider {
+    secret_key: String,
+    base_url: String,
+    client: reqwest::Client,
+}
+
+impl PaystackProvider {
+    pub fn new(secret_key: &str) -> anyhow::Result<Self> {
+        Ok(Self {
+            secret_key: secret_key.to_string(),
+            base_url: std::env::var("PAYSTACK_BASE_URL")
+                .unwrap_or_else(|_| "https://api.paystack.co".to_string())
+                .trim_end_matches('/')
+                .to_string(),
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(15))
+                .build()
+                .context("failed to build the paystack http client")?,
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct PaystackInitializeResponse {
+    status: bool,
+    message: String,
+    data: Option<PaystackInitializeData>,
+}
+
+#[derive(Deserialize)]
+struct PaystackInitializeData {
+    authorization_url: String,
+}
+
+impl PaymentProvider for PaystackProvider {
+    fn name(&self) -> &'static str {
+        "paystack"
+    }
+
+    fn initialize<'a>(&'a self, request: &'a CheckoutRequest) -> PaymentFuture<'a, CheckoutSession> {
+        Box::pin(async move {
+            let mut body = json!({
+                "email": request.email,
+                "amount": request.amount_kobo,
+                "reference": request.reference,
+            });
+            if let Some(callback_url) = &request.callback_url {
+                body["callback_url"] = json!(callback_url);
+            }
+
+            let response: PaystackInitializeResponse = self
+                .client
+                .post(format!("{}/transaction/initialize", self.base_url))
+                .bearer_auth(&self.secret_key)
+                .json(&body)
+                .send()
+                .await
+                .context("paystack initialize request failed")?
+                .json()
+                .await
+                .context("unexpected paystack initialize response")?;
+
+            match response.data {
+                Some(data) if response.status => Ok(CheckoutSession {
+                    authorization_url: data.authorization_url,
+                }),
+                _ => anyhow::bail!("paystack rejected checkout: {}", response.message),
+            }
+        })
+    }
+
+    /// Paystack signs the raw body with HMAC-SHA512 keyed by the secret key
+    /// and sends it hex encoded in `x-paystack-signature`.
+    fn parse_webhook(&self, headers: &HeaderMap, body: &[u8]) -> anyhow::Result<PaymentEvent> {
+        let signature = decode_hex(signature_header(headers, "x-paystack-signature")?)?;
+
+        let mut mac = Hmac::<Sha512>::new_from_slice(self.secret_key.as_bytes())
+            .context("invalid paystack key")?;
+        mac.update(body);
+        mac.verify_slice(&signature)
+            .map_err(|_| anyhow::anyhow!("invalid webhook signature"))?;
+
+        let payload: Value = serde_json::from_slice(body)?;
+        let event_type = payload["event"].as_str().unwrap_or_default().to_string();
+        let data = &payload["data"];
+
+        // Paystack has no event id; the transaction id plus the event type
+        // is unique per delivery we care about.
+        let event_id = match data["id"].as_i64() {
+            Some(id) => format!("{event_type}:{id}"),
+            None => format!("{event_type}:{:x}", Sha256::digest(body)),
+        };
+
+        Ok(PaymentEvent {
+            event_id,
+            kind: match event_type.as_str() {
+                "charge.success" => PaymentEventKind::Succeeded,
+                "charge.failed" => PaymentEventKind::Failed,
+                _ => PaymentEventKind::Other,
+            },
+            event_type,
+            reference: data["reference"].as_str().map(str::to_string),
+            amount_kobo: data["amount"].as_i64(),
+            payload,
+        })
+    }
+}
+
+// ─── Local ───────────────────────────────────────────────────────────────────
+
+/// A provider that never leaves the server, for development and tests. The
+/// checkout URL goes nowhere; payments are completed by posting a signed
+/// webhook by hand:
+///
+/// ```json
+/// { "id": "evt_1", "type": "payment.succeeded", "reference": "...", "amount": 1500000 }
+/// ```
+///
+/// signed with hex HMAC-SHA256 of the raw body in `x-local-signature`.
+pub struct LocalPaymentProvider {
+    secret: String,
+}
+
+impl LocalPaymentProvider {
+    pub fn new(secret: &str) -> Self {
+        Self {
+            secret: secret.to_string(),
+        }
+    }
+
+    /// Reads the webhook secret from `PAYMENT_WEBHOOK_SECRET`.
+    pub fn from_env() -> anyhow::Result<Self> {
+        let secret = std::env::var("PAYMENT_WEBHOOK_SECRET")
+            .ok()
+            .filter(|secret| !secret.trim().is_empty())
+            .context("PAYMENT_WEBHOOK_SECRET must be set for the local payment provider")?;
+
+        Ok(Self::new(secret.trim()))
+    }
+
+    pub fn sign(&self, body: &[u8]) -> String {
+        let mut mac =
+            Hmac::<Sha256>::new_from_slice(self.secret.as_bytes()).expect("hmac accepts any key");
+        mac.update(body);
+
+        mac.finalize()
+            .into_bytes()
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect()
+    }
+}
+
+impl PaymentProvider for LocalPaymentProvider {
+    fn name(&self) -> &'static str {
+        "local"
+    }
+
+    fn initialize<'a>(&'a self, request: &'a CheckoutRequest) -> PaymentFuture<'a, CheckoutSession> {
+        Box::pin(async move {
+            Ok(CheckoutSession {
+                authorization_url: format!("local://checkout/{}", request.reference),
+            })
+        })
+    }
+
+    fn parse_webhook(&self, headers: &HeaderMap, body: &[u8]) -> anyhow::Result<PaymentEvent> {
+        let signature = decode_hex(signature_header(headers, "x-local-signature")?)?;
+
+        let mut mac = Hmac::<Sha256>::new_from_slice(self.secret.as_bytes())
+            .context("invalid webhook secret")?;
+        mac.update(body);
+        mac.verify_slice(&signature)
+            .map_err(|_| anyhow::anyhow!("invalid webhook signature"))?;
+
+        let payload: Value = serde_json::from_slice(body)?;
+        let event_type = payload["type"].as_str().unwrap_or_default().to_string();
+
+        Ok(PaymentEvent {
+            event_id: payload["id"]
+                .as_str()
+                .ok_or_else(|| anyhow::anyhow!("webhook event has no id"))?
+                .to_string(),
+            kind: match event_type.as_str() {
+                "payment.succeeded" => PaymentEventKind::Succeeded,
+                "payment.failed" => PaymentEventKind::Failed,
+                _ => PaymentEventKind::Other,
+            },
+            event_type,
+            reference: payload["reference"].as_str().map(str::to_string),
+            amount_kobo: payload["amount"].as_i64(),
+            payload,
+        })
+    }
+}
@@ -0,0 +1,7 @@
+use axum::{Router, routing::post};
+
+use crate::{app_state::AppState, domain::payments::service::PaymentService};
+
+pub fn payments_route() -> Router<AppState> {
+    Router::new().route("/webhook", post(PaymentService::webhook))
+}
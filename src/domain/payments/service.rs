@@ -0,0 +1,360 @@
+use axum::{
+    Json,
+    body::Bytes,
+    extract::{Extension, State},
+    http::{HeaderMap, StatusCode},
+};
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::{
+    app_state::AppState,
+    authentication::station::authenticate::token::service::Claims,
+    domain::{
+        payments::{
+            dto::{CheckoutDto, CheckoutResponse, WebhookResponse},
+            provider::{CheckoutRequest, PaymentEvent, PaymentEventKind},
+        },
+        subscriptions::{
+            model::SubscriptionInvoice,
+            service::{InvoiceSettlement, notify_invoice_paid, settle_invoice},
+        },
+        utils::errors::station_errors::StationError,
+    },
+};
+
+const BANK_TRANSFER_PROVIDER: &str = "bank_transfer";
+
+pub struct PaymentService;
+
+impl PaymentService {
+    /// Starts a hosted checkout for one of the station's open invoices.
+    pub async fn checkout(
+        State(app_state): State<AppState>,
+        Extension(claims): Extension<Claims>,
+        Json(body): Json<CheckoutDto>,
+    ) -> Result<(StatusCode, Json<CheckoutResponse>), StationError> {
+        let station_id = claims.station_res.id;
+
+        let (amount, status, email) = sqlx::query_as::<_, (i32, String, String)>(
+            r#"
+            SELECT i.amount, i.status, s.email
+            FROM subscription_invoices i
+            INNER JOIN stations s ON s.id = i.station_id
+            WHERE i.id = $1 AND i.station_id = $2
+            "#,
+        )
+        .bind(body.invoice_id)
+        .bind(station_id)
+        .fetch_optional(&app_state.pool)
+        .await
+        .map_err(StationError::DatabaseError)?
+        .ok_or_else(|| StationError::NotFound(body.invoice_id.to_string()))?;
+
+        if status != "open" {
+            return Err(StationError::WrongCredentials(format!(
+                "invoice is already {status}"
+            )));
+        }
+        if amount <= 0 {
+            return Err(StationError::WrongCredentials(
+                "invoice has nothing to pay".to_string(),
+            ));
+        }
+
+        let provider = app_state.payments.clone();
+        let reference = format!("FFS-{}", Uuid::new_v4().simple());
+
+        let payment_id: Uuid = sqlx::query_scalar(
+            r#"
+            INSERT INTO subscription_payments (invoice_id, station_id, provider, reference, amount)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING id
+            "#,
+        )
+        .bind(body.invoice_id)
+        .bind(station_id)
+        .bind(provider.name())
+        .bind(&reference)
+        .bind(amount)
+        .fetch_one(&app_state.pool)
+        .await
+        .map_err(StationError::DatabaseError)?;
+
+        let request = CheckoutRequest {
+            reference: reference.clone(),
+            amount_kobo: amount as i64 * 100,
+            email,
+            callback_url: body.callback_url,
+        };
+
+        let session = match provider.initialize(&request).await {
+            Ok(session) => session,
+            Err(err) => {
+                tracing::error!("{} checkout failed for {reference}: {err:?}", provider.name());
+                mark_payment_failed(&app_state.pool, payment_id, "checkout could not start")
+                    .await
+                    .map_err(StationError::DatabaseError)?;
+
+                return Err(StationError::WrongCredentials(
+                    "payment provider is unavailable, please try again".to_string(),
+                ));
+            }
+        };
+
+        sqlx::query(
+            r#"
+            UPDATE subscription_payments
+            SET authorization_url = $2, updated_at = now()
+            WHERE id = $1
+            "#,
+        )
+        .bind(payment_id)
+        .bind(&session.authorization_url)
+        .execute(&app_state.pool)
+        .await
+        .map_err(StationError::DatabaseError)?;
+
+        Ok((
+            StatusCode::CREATED,
+            Json(CheckoutResponse {
+                reference,
+                provider: provider.name(),
+                amount,
+                authorization_url: session.authorization_url,
+            }),
+        ))
+    }
+
+    /// Receives the provider's webhook. Each event is processed once; any
+    /// redelivery is acknowledged without doing anything, so providers stop
+    /// retrying.
+    pub async fn webhook(
+        State(app_state): State<AppState>,
+        headers: HeaderMap,
+        body: Bytes,
+    ) -> Result<Json<WebhookResponse>, StationError> {
+        let provider = app_state.payments.clone();
+
+        let event = provider
+            .parse_webhook(&headers, &body)
+            .map_err(|err| StationError::WrongCredentials(err.to_string()))?;
+
+        let mut tx = app_state
+            .pool
+            .begin()
+            .await
+            .map_err(StationError::DatabaseError)?;
+
+        // A concurrent delivery of the same event waits here on the unique
+        // key and then finds the row already there.
+        let event_row: Option<Uuid> = sqlx::query_scalar(
+            r#"
+            INSERT INTO payment_webhook_events
+                (provider, event_id, event_type, reference, outcome, payload)
+            VALUES ($1, $2, $3, $4, 'received', $5)
+            ON CONFLICT (provider, event_id) DO NOTHING
+            RETURNING id
+            "#,
+        )
+        .bind(provider.name())
+        .bind(&event.event_id)
+        .bind(&event.event_type)
+        .bind(&event.reference)
+        .bind(&event.payload)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(StationError::DatabaseError)?;
+
+        let Some(event_row) = event_row else {
+            return Ok(Json(WebhookResponse {
+                outcome: "duplicate",
+            }));
+        };
+
+        let outcome = process_event(&mut tx, provider.name(), &event).await?;
+
+        sqlx::query("UPDATE payment_webhook_events SET outcome = $2 WHERE id = $1")
+            .bind(event_row)
+            .bind(outcome.name())
+            .execute(&mut *tx)
+            .await
+            .map_err(StationError::DatabaseError)?;
+
+        tx.commit().await.map_err(StationError::DatabaseError)?;
+
+        match &outcome {
+            WebhookOutcome::InvoiceNotOpen => tracing::warn!(
+                "payment {:?} succeeded for an invoice that is no longer open; refund may be due",
+                event.reference
+            ),
+            WebhookOutcome::Renewed(invoice) => {
+                notify_invoice_paid(&app_state.pool, invoice).await;
+            }
+            _ => {}
+        }
+
+        Ok(Json(WebhookResponse {
+            outcome: outcome.name(),
+        }))
+    }
+}
+
+/// What a webhook delivery did; its name is stored with the event and
+/// echoed back to the provider.
+enum WebhookOutcome {
+    Ignored,
+    UnknownReference,
+    AlreadyProcessed,
+    Failed,
+    AmountMismatch,
+    Renewed(Box<SubscriptionInvoice>),
+    InvoiceNotOpen,
+}
+
+impl WebhookOutcome {
+    fn name(&self) -> &'static str {
+        match self {
+            WebhookOutcome::Ignored => "ignored",
+            WebhookOutcome::UnknownReference => "unknown_reference",
+            WebhookOutcome::AlreadyProcessed => "already_processed",
+            WebhookOutcome::Failed => "failed",
+            WebhookOutcome::AmountMismatch => "amount_mismatch",
+            WebhookOutcome::Renewed(_) => "renewed",
+            WebhookOutcome::InvoiceNotOpen => "invoice_not_open",
+        }
+    }
+}
+
+async fn process_event(
+    tx: &mut Transaction<'_, Postgres>,
+    provider: &str,
+    event: &PaymentEvent,
+) -> Result<WebhookOutcome, StationError> {
+    if event.kind == PaymentEventKind::Other {
+        return Ok(WebhookOutcome::Ignored);
+    }
+
+    let Some(reference) = &event.reference else {
+        return Ok(WebhookOutcome::UnknownReference);
+    };
+
+    let payment = sqlx::query_as::<_, (Uuid, Uuid, i32, String)>(
+        r#"
+        SELECT id, invoice_id, amount, status
+        FROM subscription_payments
+        WHERE reference = $1 AND provider = $2
+        FOR UPDATE
+        "#,
+    )
+    .bind(reference)
+    .bind(provider)
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(StationError::DatabaseError)?;
+
+    let Some((payment_id, invoice_id, amount, status)) = payment else {
+        return Ok(WebhookOutcome::UnknownReference);
+    };
+
+    if status == "succeeded" {
+        return Ok(WebhookOutcome::AlreadyProcessed);
+    }
+
+    if event.kind == PaymentEventKind::Failed {
+        mark_payment_failed(&mut **tx, payment_id, &event.event_type)
+            .await
+            .map_err(StationError::DatabaseError)?;
+        return Ok(WebhookOutcome::Failed);
+    }
+
+    if event.amount_kobo != Some(amount as i64 * 100) {
+        mark_payment_failed(&mut **tx, payment_id, "amount paid does not match the invoice")
+            .await
+            .map_err(StationError::DatabaseError)?;
+        return Ok(WebhookOutcome::AmountMismatch);
+    }
+
+    sqlx::query(
+        r#"
+        UPDATE subscription_payments
+        SET status = 'succeeded', paid_at = now(), failure_reason = NULL, updated_at = now()
+        WHERE id = $1
+        "#,
+    )
+    .bind(payment_id)
+    .execute(&mut **tx)
+    .await
+    .map_err(StationError::DatabaseError)?;
+
+    match settle_invoice(tx, invoice_id, None).await? {
+        InvoiceSettlement::Paid(invoice) => Ok(WebhookOutcome::Renewed(invoice)),
+        // Paid twice, or voided while the station was paying. The money is
+        // recorded; what to do with it is an admin decision.
+        InvoiceSettlement::NotOpen { .. } => Ok(WebhookOutcome::InvoiceNotOpen),
+    }
+}
+
+async fn mark_payment_failed<'e, E: sqlx::PgExecutor<'e>>(
+    executor: E,
+    payment_id: Uuid,
+    reason: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE subscription_payments
+        SET status = 'failed', failure_reason = $2, updated_at = now()
+        WHERE id = $1
+        "#,
+    )
+    .bind(payment_id)
+    .bind(reason)
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
+
+/// Records a bank transfer an admin has checked by hand and renews the
+/// subscription it pays for.
+pub async fn confirm_bank_transfer(
+    pool: &PgPool,
+    invoice_id: Uuid,
+    admin_id: Uuid,
+) -> Result<SubscriptionInvoice, StationError> {
+    let mut tx = pool.begin().await.map_err(StationError::DatabaseError)?;
+
+    let invoice = match settle_invoice(&mut tx, invoice_id, Some(admin_id)).await? {
+        InvoiceSettlement::Paid(invoice) => *invoice,
+        InvoiceSettlement::NotOpen { status } => {
+            return Err(StationError::WrongCredentials(format!(
+                "invoice is already {status}"
+            )));
+        }
+    };
+
+    sqlx::query(
+        r#"
+        INSERT INTO subscription_payments (
+            invoice_id, station_id, provider, reference, amount, status,
+            confirmed_by_admin, paid_at
+        )
+        VALUES ($1, $2, $3, $4, $5, 'succeeded', $6, now())
+        "#,
+    )
+    .bind(invoice.id)
+    .bind(invoice.station_id)
+    .bind(BANK_TRANSFER_PROVIDER)
+    .bind(format!("BT-{}", invoice.number))
+    .bind(invoice.amount)
+    .bind(admin_id)
+    .execute(&mut *tx)
+    .await
+    .map_err(StationError::DatabaseError)?;
+
+    tx.commit().await.map_err(StationError::DatabaseError)?;
+
+    notify_invoice_paid(pool, &invoice).await;
+
+    Ok(invoice)
+}
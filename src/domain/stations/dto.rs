@@ -0,0 +1,22 @@
+use serde::{Deserialize, Serialize};
+
+use crate::domain::{stations::model::RelocationRequest, utils::schemas::StationResponse};
+
+/// Fields omitted from the payload are left unchanged. Coordinate changes are
+/// not applied directly; they open a relocation request for admin review.
+#[derive(Debug, Deserialize)]
+pub struct UpdateStationProfileDto {
+    pub name: Option<String>,
+    pub address: Option<String>,
+    pub phone: Option<String>,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UpdateStationProfileResponse {
+    pub station: StationResponse,
+    /// Fresh token carrying the updated station profile.
+    pub access_token: String,
+    pub relocation_request: Option<RelocationRequest>,
+}
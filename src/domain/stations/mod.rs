@@ -1,3 +1,4 @@
 pub mod routes;
 pub mod service;
+pub mod dto;
 pub mod model;
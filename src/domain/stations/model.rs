@@ -18,3 +18,27 @@ pub struct Station {
     pub created_at: chrono::NaiveDateTime,
     pub updated_at: chrono::NaiveDateTime,
 }
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct RelocationRequest {
+    pub id: Uuid,
+    pub station_id: Uuid,
+    pub station_name: String,
+    pub current_latitude: f64,
+    pub current_longitude: f64,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub status: String,
+    pub review_reason: Option<String>,
+    pub reviewed_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct ProfileChange {
+    pub field: String,
+    pub old_value: Option<String>,
+    pub new_value: Option<String>,
+    pub changed_by_admin: Option<Uuid>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
@@ -1,10 +1,25 @@
-use axum::{Router, middleware::{from_fn, from_fn_with_state}, routing::{get, patch}};
+use axum::{
+    Router,
+    extract::DefaultBodyLimit,
+    middleware::{from_fn, from_fn_with_state},
+    routing::{delete, get, patch, post, put},
+};
 use std::time::Duration;
 
 use crate::{
     app_state::AppState, authentication::middleware::auth::authorize,
     domain::{
+        amenities::service::AmenitiesService,
+        events::service::EventsService,
+        media::service::{MAX_IMAGE_BYTES, MediaService},
+        notifications::service::NotificationService,
+        opening_hours::service::OpeningHoursService,
+        payments::service::PaymentService,
         stations::model::Station,
+        subscriptions::service::BillingService,
+        verification::service::{
+            MAX_DOCUMENT_BYTES, MAX_DOCUMENTS_PER_REQUEST, VerificationService,
+        },
         utils::rate_limiter::{RateLimiter, closest_stations_rate_limit},
     },
 };
@@ -20,14 +35,7 @@ pub fn stations_route() -> Router<AppState> {
     );
 
     // Periodic cleanup of stale IP entries.
-    let cleanup_limiter = rate_limiter.clone();
-    tokio::spawn(async move {
-        let interval = Duration::from_secs(CLOSEST_WINDOW_SECS * 2);
-        loop {
-            tokio::time::sleep(interval).await;
-            cleanup_limiter.cleanup();
-        }
-    });
+    rate_limiter.spawn_cleanup();
 
     Router::new()
         .route("/", get(Station::get_stations))
@@ -37,11 +45,97 @@ pub fn stations_route() -> Router<AppState> {
         )
         .route(
             "/dashboard/notifications",
-            get(Station::get_dashboard_notifications).route_layer(from_fn(authorize)),
+            get(NotificationService::get_notifications).route_layer(from_fn(authorize)),
+        )
+        .route(
+            "/dashboard/notifications/unread-count",
+            get(NotificationService::get_unread_count).route_layer(from_fn(authorize)),
+        )
+        .route(
+            "/dashboard/notifications/read-all",
+            post(NotificationService::mark_all_read).route_layer(from_fn(authorize)),
+        )
+        .route(
+            "/dashboard/notifications/bulk",
+            post(NotificationService::bulk_update).route_layer(from_fn(authorize)),
+        )
+        .route(
+            "/dashboard/notifications/{notification_id}",
+            delete(NotificationService::delete_notification).route_layer(from_fn(authorize)),
         )
         .route(
             "/dashboard/notifications/{notification_id}/read",
-            patch(Station::mark_dashboard_notification_read).route_layer(from_fn(authorize)),
+            patch(NotificationService::mark_read).route_layer(from_fn(authorize)),
+        )
+        .route(
+            "/dashboard/notifications/{notification_id}/archive",
+            patch(NotificationService::archive).route_layer(from_fn(authorize)),
+        )
+        .route(
+            "/dashboard/notification-preferences",
+            get(NotificationService::get_kind_preferences).route_layer(from_fn(authorize)),
+        )
+        .route(
+            "/dashboard/notification-preferences/{kind}",
+            put(NotificationService::update_kind_preference).route_layer(from_fn(authorize)),
+        )
+        .route(
+            "/dashboard/events",
+            get(EventsService::stream).route_layer(from_fn(authorize)),
+        )
+        .route(
+            "/dashboard/notification-channels",
+            get(NotificationService::get_channel_preferences)
+                .put(NotificationService::update_channel_preferences)
+                .route_layer(from_fn(authorize)),
+        )
+        .route(
+            "/dashboard/opening-hours",
+            get(OpeningHoursService::get_opening_hours)
+                .put(OpeningHoursService::update_opening_hours)
+                .route_layer(from_fn(authorize)),
+        )
+        .route(
+            "/dashboard/opening-hours/overrides/{date}",
+            put(OpeningHoursService::upsert_override)
+                .delete(OpeningHoursService::delete_override)
+                .route_layer(from_fn(authorize)),
+        )
+        .route(
+            "/dashboard/temporarily-closed",
+            patch(OpeningHoursService::set_temporarily_closed).route_layer(from_fn(authorize)),
+        )
+        .route(
+            "/dashboard/amenities",
+            put(AmenitiesService::update_amenities).route_layer(from_fn(authorize)),
+        )
+        .route(
+            "/dashboard/profile",
+            patch(Station::update_profile).route_layer(from_fn(authorize)),
+        )
+        .route(
+            "/dashboard/profile/changes",
+            get(Station::get_profile_changes).route_layer(from_fn(authorize)),
+        )
+        .route(
+            "/dashboard/images",
+            post(MediaService::upload_image)
+                // Leave headroom for the multipart framing around the file.
+                .layer(DefaultBodyLimit::max(MAX_IMAGE_BYTES + 64 * 1024))
+                .route_layer(from_fn(authorize)),
+        )
+        .route(
+            "/dashboard/images/{image_id}",
+            delete(MediaService::delete_image).route_layer(from_fn(authorize)),
+        )
+        .route(
+            "/dashboard/verification",
+            get(VerificationService::get_verification)
+                .post(VerificationService::submit_verification)
+                .layer(DefaultBodyLimit::max(
+                    MAX_DOCUMENT_BYTES * MAX_DOCUMENTS_PER_REQUEST + 64 * 1024,
+                ))
+                .route_layer(from_fn(authorize)),
         )
         .route(
             "/closest",
@@ -49,3 +143,29 @@ pub fn stations_route() -> Router<AppState> {
                 .route_layer(from_fn_with_state(rate_limiter, closest_stations_rate_limit)),
         )
 }
+
+/// Billing sits outside the entitlement layer so a lapsed station can
+/// always pay.
+pub fn billing_route() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/dashboard/billing",
+            get(BillingService::get_billing).route_layer(from_fn(authorize)),
+        )
+        .route(
+            "/dashboard/billing/checkout",
+            post(PaymentService::checkout).route_layer(from_fn(authorize)),
+        )
+        .route(
+            "/dashboard/billing/invoices",
+            post(BillingService::create_invoice).route_layer(from_fn(authorize)),
+        )
+        .route(
+            "/dashboard/billing/subscription",
+            post(BillingService::update_subscription).route_layer(from_fn(authorize)),
+        )
+        .route(
+            "/dashboard/billing/subscription/history",
+            get(BillingService::get_subscription_history).route_layer(from_fn(authorize)),
+        )
+}
@@ -1,16 +1,28 @@
 use crate::{
-    app_state::AppState, authentication::station::authenticate::token::service::Claims, 
+    app_state::AppState,
+    authentication::station::authenticate::token::service::{Claims, TokenService},
     domain::{
-        stations::model::Station,
-        subscriptions::service::{get_station_notifications, mark_station_notification_read},
+        amenities::{
+            model::{AMENITIES, PAYMENT_METHODS},
+            service::{attach_amenities, parse_filter},
+        },
+        media::service::attach_images,
+        opening_hours::service::attach_open_status,
+        stations::{
+            dto::{UpdateStationProfileDto, UpdateStationProfileResponse},
+            model::{ProfileChange, RelocationRequest, Station},
+        },
+        notifications::service::create_dashboard_notification,
+        verification::service::attach_verification,
         utils::{dto::{AllStationsQuery, StationQueryParam}, errors::station_errors::StationError, schemas::{StationResponse, StationWithCommodity, map_rows_to_stations}, validate_boundary},
     }
 };
 use axum::{
-    http::StatusCode,
     Json,
-    extract::{Path, Query, Request, State},
+    extract::{Extension, Query, Request, State},
 };
+use sqlx::{PgPool, Postgres, Transaction};
+use std::collections::HashSet;
 use uuid::Uuid;
 
 impl Station {
@@ -19,8 +31,12 @@ impl Station {
         Query(query): Query<AllStationsQuery>,
     ) -> Result<Json<Vec<Station>>, StationError> {
         let station_type = query.station_type.unwrap_or("gas".to_string());
-        let stations = sqlx::query_as!(
-            Station,
+        let amenities = parse_filter(query.amenities.as_deref(), &AMENITIES, "amenity")?;
+        let payment_methods =
+            parse_filter(query.payment_methods.as_deref(), &PAYMENT_METHODS, "payment method")?;
+
+        // Lapsed stations sort last when demotion is on.
+        let stations = sqlx::query_as::<_, Station>(
             r#"
                 SELECT
                     id, name, address, email, phone, 
@@ -28,9 +44,16 @@ impl Station {
                     created_at, updated_at 
                 FROM stations
                 WHERE ($1::text IS NULL OR station_type = $1)
+                  AND amenities @> $2::text[]
+                  AND payment_methods @> $3::text[]
+                  AND ($4::bool IS TRUE OR station_subscription_is_current(id))
+                ORDER BY ($4::bool IS TRUE AND NOT station_subscription_is_current(id))
             "#,
-            station_type
         )
+        .bind(station_type)
+        .bind(&amenities)
+        .bind(&payment_methods)
+        .bind(app_state.demote_lapsed_stations)
         .fetch_all(&app_state.pool)
         .await
         .map_err(StationError::DatabaseError)?;
@@ -45,6 +68,9 @@ impl Station {
         let longitude = query.longitude;
         let latitude = query.latitude;
         let station_type = query.station_type.clone();
+        let amenities = parse_filter(query.amenities.as_deref(), &AMENITIES, "amenity")?;
+        let payment_methods =
+            parse_filter(query.payment_methods.as_deref(), &PAYMENT_METHODS, "payment method")?;
 
         let latitude = latitude
             .parse::<f64>()
@@ -55,8 +81,7 @@ impl Station {
 
         let _ = validate_boundary::validate_abuja_bounds(latitude, longitude)?;
 
-        let rows = sqlx::query_as!(
-            StationWithCommodity,
+        let rows = sqlx::query_as::<_, StationWithCommodity>(
             r#"
             SELECT
                 s.id AS id,
@@ -71,14 +96,14 @@ impl Station {
                 s.role AS role,
                 s.created_at AS created_at,
                 s.updated_at AS updated_at,
-                haversine($1::float8, $2::float8, s.latitude, s.longitude) AS "distance?",
+                haversine($1::float8, $2::float8, s.latitude, s.longitude) AS distance,
                 c.id AS commodity_id,
                 c.name AS commodity_name,
-                c.is_available AS "is_available!",
-                c.station_id AS "station_id!",
+                c.is_available AS is_available,
+                c.station_id AS station_id,
                 c.price AS price,
-                cd.is_enabled AS "discount_enabled?",
-                cd.percentage AS "discount_percentage?"
+                cd.is_enabled AS discount_enabled,
+                cd.percentage AS discount_percentage
             FROM stations AS s
             INNER JOIN commodities AS c ON s.id = c.station_id AND c.is_available = TRUE
             LEFT JOIN commodity_discounts AS cd ON cd.commodity_id = c.id
@@ -91,21 +116,44 @@ impl Station {
                     SELECT 1 FROM commodities AS sub_c 
                     WHERE sub_c.station_id = sub_s.id AND sub_c.is_available = TRUE
                 )
-                ORDER BY haversine($1::float8, $2::float8, sub_s.latitude, sub_s.longitude) ASC
+                  AND ($4::bool IS NOT TRUE
+                    OR (SELECT w.is_open FROM station_open_window(sub_s.id, now()) AS w))
+                  AND sub_s.amenities @> $5::text[]
+                  AND sub_s.payment_methods @> $6::text[]
+                  AND ($7::bool IS NOT TRUE OR sub_s.verification_status = 'verified')
+                  AND ($8::bool IS TRUE OR station_subscription_is_current(sub_s.id))
+                ORDER BY
+                    station_subscription_is_current(sub_s.id) DESC,
+                    haversine($1::float8, $2::float8, sub_s.latitude, sub_s.longitude) ASC
                 LIMIT 4
             )
             ORDER BY distance, s.id, c.name
             "#,
-            latitude,
-            longitude,
-            station_type
         )
+        .bind(latitude)
+        .bind(longitude)
+        .bind(station_type)
+        .bind(query.open_now)
+        .bind(&amenities)
+        .bind(&payment_methods)
+        .bind(app_state.hide_unverified_stations)
+        .bind(app_state.demote_lapsed_stations)
         .fetch_all(&app_state.pool)
         .await
         .map_err(StationError::DatabaseError)?;
 
-        let station_response = map_rows_to_stations(rows);
-    
+        let mut station_response = map_rows_to_stations(rows);
+
+        hydrate_station_responses(&app_state, &mut station_response)
+            .await
+            .map_err(StationError::DatabaseError)?;
+
+        if app_state.demote_lapsed_stations {
+            demote_lapsed_stations(&app_state.pool, &mut station_response)
+                .await
+                .map_err(StationError::DatabaseError)?;
+        }
+
         Ok(Json(station_response))
     }
 
@@ -121,90 +169,445 @@ impl Station {
         let station_id = claims.station_res.id;
         let station_type = claims.station_res.station_type.clone();
 
-        let rows = sqlx::query_as!(
-            StationWithCommodity,
-            r#"
-            SELECT
-                s.id AS id,
-                s.name AS name,
-                s.address AS address,
-                s.email AS email,
-                s.password AS password,
-                s.phone AS phone,
-                s.latitude AS latitude,
-                s.longitude AS longitude,
-                s.role AS role,
-                s.created_at AS created_at,
-                s.station_type AS station_type,
-                s.updated_at AS updated_at,
-                                s.distance AS "distance?",
-                                c.id AS "commodity_id!",
-                                c.name AS "commodity_name!",
-                                c.price AS "price!",
-                                c.is_available AS "is_available!",
-                                c.station_id AS "station_id!",
-                                cd.is_enabled AS "discount_enabled?",
-                                cd.percentage AS "discount_percentage?"
-            FROM stations s
-            LEFT JOIN commodities c ON s.id = c.station_id
-            LEFT JOIN commodity_discounts cd ON cd.commodity_id = c.id
-            WHERE s.id = $1
-              AND s.station_type = $2
-            "#,
-                        station_id,
-                        station_type
-                )
-        .fetch_all(&app_state.pool)
-        .await
-        .map_err(StationError::DatabaseError)?;
-
-        let station_with_commodities = map_rows_to_stations(rows)
-            .into_iter()
-            .next()
+        let station_with_commodities = load_station_response(&app_state, station_id, &station_type)
+            .await
+            .map_err(StationError::DatabaseError)?
             .ok_or_else(|| StationError::NotFound("Station not found".to_string()))?;
 
         Ok(Json(station_with_commodities))
     }
 
-    pub async fn get_dashboard_notifications(
+    pub async fn update_profile(
         State(app_state): State<AppState>,
-        request: Request,
-    ) -> Result<Json<Vec<crate::domain::subscriptions::model::DashboardNotification>>, StationError>
-    {
-        let claims = request
-            .extensions()
-            .get::<Claims>()
-            .ok_or_else(|| StationError::NotFound("Claims not present".to_string()))?;
-
+        Extension(claims): Extension<Claims>,
+        Json(body): Json<UpdateStationProfileDto>,
+    ) -> Result<Json<UpdateStationProfileResponse>, StationError> {
         let station_id = claims.station_res.id;
+        let station_type = claims.station_res.station_type.clone();
 
-        let notifications = get_station_notifications(&app_state.pool, station_id)
+        let (current_name, current_address, current_phone) =
+            sqlx::query_as::<_, (String, String, String)>(
+                "SELECT name, address, phone FROM stations WHERE id = $1",
+            )
+            .bind(station_id)
+            .fetch_optional(&app_state.pool)
             .await
-            .map_err(|err| StationError::WrongCredentials(err.to_string()))?;
+            .map_err(StationError::DatabaseError)?
+            .ok_or_else(|| StationError::NotFound(station_id.to_string()))?;
 
-        Ok(Json(notifications))
-    }
+        // Same normalisation as signup: names are stored upper-cased.
+        let name = normalize_profile_field(body.name, "name")?.map(|n| n.to_uppercase());
+        let address = normalize_profile_field(body.address, "address")?;
+        let phone = normalize_profile_field(body.phone, "phone")?;
 
-    pub async fn mark_dashboard_notification_read(
-        State(app_state): State<AppState>,
-        Path(notification_id): Path<Uuid>,
-        request: Request,
-    ) -> Result<StatusCode, StationError> {
-        let claims = request
-            .extensions()
-            .get::<Claims>()
-            .ok_or_else(|| StationError::NotFound("Claims not present".to_string()))?;
+        let mut changes: Vec<(&str, String, String)> = Vec::new();
+        for (field, current, updated) in [
+            ("name", current_name, name),
+            ("address", current_address, address),
+            ("phone", current_phone, phone),
+        ] {
+            if let Some(updated) = updated.filter(|u| *u != current) {
+                changes.push((field, current, updated));
+            }
+        }
 
-        let station_id = claims.station_res.id;
+        let coordinates = match (body.latitude, body.longitude) {
+            (Some(latitude), Some(longitude)) => {
+                validate_boundary::validate_abuja_bounds(latitude, longitude)?;
+                Some((latitude, longitude))
+            }
+            (None, None) => None,
+            _ => {
+                return Err(StationError::WrongCredentials(
+                    "latitude and longitude must be provided together".to_string(),
+                ));
+            }
+        };
+
+        let mut tx = app_state
+            .pool
+            .begin()
+            .await
+            .map_err(StationError::DatabaseError)?;
+
+        for (field, old_value, new_value) in &changes {
+            // `field` only ever comes from the fixed list above.
+            sqlx::query(&format!(
+                "UPDATE stations SET {field} = $1, updated_at = NOW() WHERE id = $2"
+            ))
+            .bind(new_value)
+            .bind(station_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(StationError::DatabaseError)?;
 
-        let was_updated = mark_station_notification_read(&app_state.pool, station_id, notification_id)
+            log_profile_change(&mut tx, station_id, field, Some(old_value), Some(new_value), None)
+                .await
+                .map_err(StationError::DatabaseError)?;
+        }
+
+        let mut relocation_request_id = None;
+        if let Some((latitude, longitude)) = coordinates {
+            // One pending request per station; a concurrent request loses on
+            // the unique index rather than on a racy pre-check.
+            let id: Uuid = sqlx::query_scalar(
+                r#"
+                INSERT INTO station_relocation_requests (station_id, latitude, longitude)
+                VALUES ($1, $2, $3)
+                RETURNING id
+                "#,
+            )
+            .bind(station_id)
+            .bind(latitude)
+            .bind(longitude)
+            .fetch_one(&mut *tx)
             .await
-            .map_err(|err| StationError::WrongCredentials(err.to_string()))?;
+            .map_err(|err| match err {
+                sqlx::Error::Database(db_err) if db_err.is_unique_violation() => {
+                    StationError::AlreadyExists
+                }
+                err => StationError::DatabaseError(err),
+            })?;
 
-        if !was_updated {
-            return Err(StationError::NotFound(notification_id.to_string()));
+            relocation_request_id = Some(id);
         }
 
-        Ok(StatusCode::NO_CONTENT)
+        tx.commit().await.map_err(StationError::DatabaseError)?;
+
+        let relocation_request = match relocation_request_id {
+            Some(id) => fetch_relocation_request(&app_state.pool, id)
+                .await
+                .map_err(StationError::DatabaseError)?,
+            None => None,
+        };
+
+        let station = load_station_response(&app_state, station_id, &station_type)
+            .await
+            .map_err(StationError::DatabaseError)?
+            .ok_or_else(|| StationError::NotFound(station_id.to_string()))?;
+
+        let jwt_secret = std::env::var("JWT_SECRET")
+            .map_err(|_| StationError::Internal("JWT_SECRET is not set".to_string()))?;
+        let access_token = TokenService::new(&jwt_secret)
+            .create_token(station.clone())
+            .map_err(|err| StationError::Internal(err.to_string()))?;
+
+        Ok(Json(UpdateStationProfileResponse {
+            station,
+            access_token,
+            relocation_request,
+        }))
+    }
+
+    pub async fn get_profile_changes(
+        State(app_state): State<AppState>,
+        Extension(claims): Extension<Claims>,
+    ) -> Result<Json<Vec<ProfileChange>>, StationError> {
+        let changes = list_profile_changes(&app_state.pool, claims.station_res.id)
+            .await
+            .map_err(StationError::DatabaseError)?;
+
+        Ok(Json(changes))
+    }
+}
+
+/// Fills in the computed and per-feature fields of `StationResponse` that the
+/// base station/commodity queries do not carry.
+pub async fn hydrate_station_responses(
+    app_state: &AppState,
+    stations: &mut [StationResponse],
+) -> Result<(), sqlx::Error> {
+    attach_open_status(&app_state.pool, stations).await?;
+    attach_amenities(&app_state.pool, stations).await?;
+    attach_images(app_state, stations).await?;
+    attach_verification(&app_state.pool, stations).await?;
+
+    Ok(())
+}
+
+/// Loads a station with its commodities and discount settings, hydrated the
+/// same way as the dashboard response.
+pub async fn load_station_response(
+    app_state: &AppState,
+    station_id: Uuid,
+    station_type: &str,
+) -> Result<Option<StationResponse>, sqlx::Error> {
+    let rows = sqlx::query_as::<_, StationWithCommodity>(
+        r#"
+        SELECT
+            s.id AS id,
+            s.name AS name,
+            s.address AS address,
+            s.email AS email,
+            s.password AS password,
+            s.phone AS phone,
+            s.latitude AS latitude,
+            s.longitude AS longitude,
+            s.role AS role,
+            s.created_at AS created_at,
+            s.station_type AS station_type,
+            s.updated_at AS updated_at,
+            s.distance AS distance,
+            c.id AS commodity_id,
+            c.name AS commodity_name,
+            c.price AS price,
+            c.is_available AS is_available,
+            c.station_id AS station_id,
+            cd.is_enabled AS discount_enabled,
+            cd.percentage AS discount_percentage
+        FROM stations s
+        LEFT JOIN commodities c ON s.id = c.station_id
+        LEFT JOIN commodity_discounts cd ON cd.commodity_id = c.id
+        WHERE s.id = $1
+          AND s.station_type = $2
+        "#,
+    )
+    .bind(station_id)
+    .bind(station_type)
+    .fetch_all(&app_state.pool)
+    .await?;
+
+    let Some(mut station) = map_rows_to_stations(rows).into_iter().next() else {
+        return Ok(None);
+    };
+
+    hydrate_station_responses(app_state, std::slice::from_mut(&mut station)).await?;
+
+    Ok(Some(station))
+}
+
+fn normalize_profile_field(
+    value: Option<String>,
+    field: &str,
+) -> Result<Option<String>, StationError> {
+    match value.map(|v| v.trim().to_string()) {
+        Some(v) if v.is_empty() => Err(StationError::WrongCredentials(format!(
+            "{field} cannot be empty"
+        ))),
+        other => Ok(other),
+    }
+}
+
+async fn log_profile_change(
+    tx: &mut Transaction<'_, Postgres>,
+    station_id: Uuid,
+    field: &str,
+    old_value: Option<&str>,
+    new_value: Option<&str>,
+    admin_id: Option<Uuid>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO station_profile_changes (station_id, field, old_value, new_value, changed_by_admin)
+        VALUES ($1, $2, $3, $4, $5)
+        "#,
+    )
+    .bind(station_id)
+    .bind(field)
+    .bind(old_value)
+    .bind(new_value)
+    .bind(admin_id)
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(())
+}
+
+const RELOCATION_REQUEST_COLUMNS: &str = r#"
+    r.id,
+    r.station_id,
+    s.name AS station_name,
+    s.latitude AS current_latitude,
+    s.longitude AS current_longitude,
+    r.latitude,
+    r.longitude,
+    r.status,
+    r.review_reason,
+    r.reviewed_at,
+    r.created_at
+"#;
+
+async fn fetch_relocation_request(
+    pool: &PgPool,
+    request_id: Uuid,
+) -> Result<Option<RelocationRequest>, sqlx::Error> {
+    sqlx::query_as::<_, RelocationRequest>(&format!(
+        r#"
+        SELECT {RELOCATION_REQUEST_COLUMNS}
+        FROM station_relocation_requests r
+        INNER JOIN stations s ON s.id = r.station_id
+        WHERE r.id = $1
+        "#
+    ))
+    .bind(request_id)
+    .fetch_optional(pool)
+    .await
+}
+
+pub async fn list_relocation_requests(
+    pool: &PgPool,
+    status: Option<&str>,
+) -> Result<Vec<RelocationRequest>, sqlx::Error> {
+    sqlx::query_as::<_, RelocationRequest>(&format!(
+        r#"
+        SELECT {RELOCATION_REQUEST_COLUMNS}
+        FROM station_relocation_requests r
+        INNER JOIN stations s ON s.id = r.station_id
+        WHERE ($1::text IS NULL OR r.status = $1)
+        ORDER BY r.created_at ASC
+        "#
+    ))
+    .bind(status)
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn list_profile_changes(
+    pool: &PgPool,
+    station_id: Uuid,
+) -> Result<Vec<ProfileChange>, sqlx::Error> {
+    sqlx::query_as::<_, ProfileChange>(
+        r#"
+        SELECT field, old_value, new_value, changed_by_admin, created_at
+        FROM station_profile_changes
+        WHERE station_id = $1
+        ORDER BY created_at DESC
+        "#,
+    )
+    .bind(station_id)
+    .fetch_all(pool)
+    .await
+}
+
+/// Approves or rejects a pending relocation request. Approval moves the
+/// station to the requested coordinates and records the change.
+pub async fn review_relocation_request(
+    pool: &PgPool,
+    request_id: Uuid,
+    admin_id: Uuid,
+    approved: bool,
+    reason: Option<String>,
+) -> Result<RelocationRequest, StationError> {
+    let mut tx = pool.begin().await.map_err(StationError::DatabaseError)?;
+
+    let (station_id, status, latitude, longitude) =
+        sqlx::query_as::<_, (Uuid, String, f64, f64)>(
+            r#"
+            SELECT station_id, status, latitude, longitude
+            FROM station_relocation_requests
+            WHERE id = $1
+            FOR UPDATE
+            "#,
+        )
+        .bind(request_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(StationError::DatabaseError)?
+        .ok_or_else(|| StationError::NotFound(request_id.to_string()))?;
+
+    if status != "pending" {
+        return Err(StationError::WrongCredentials(format!(
+            "relocation request is already {status}"
+        )));
     }
-}
\ No newline at end of file
+
+    sqlx::query(
+        r#"
+        UPDATE station_relocation_requests
+        SET status = $1, review_reason = $2, reviewed_by_admin = $3, reviewed_at = now()
+        WHERE id = $4
+        "#,
+    )
+    .bind(if approved { "approved" } else { "rejected" })
+    .bind(&reason)
+    .bind(admin_id)
+    .bind(request_id)
+    .execute(&mut *tx)
+    .await
+    .map_err(StationError::DatabaseError)?;
+
+    if approved {
+        let (old_latitude, old_longitude) = sqlx::query_as::<_, (f64, f64)>(
+            "SELECT latitude, longitude FROM stations WHERE id = $1 FOR UPDATE",
+        )
+        .bind(station_id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(StationError::DatabaseError)?;
+
+        sqlx::query(
+            r#"
+            UPDATE stations
+            SET latitude = $1, longitude = $2, updated_at = NOW()
+            WHERE id = $3
+            "#,
+        )
+        .bind(latitude)
+        .bind(longitude)
+        .bind(station_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(StationError::DatabaseError)?;
+
+        log_profile_change(
+            &mut tx,
+            station_id,
+            "coordinates",
+            Some(&format!("{old_latitude},{old_longitude}")),
+            Some(&format!("{latitude},{longitude}")),
+            Some(admin_id),
+        )
+        .await
+        .map_err(StationError::DatabaseError)?;
+    }
+
+    tx.commit().await.map_err(StationError::DatabaseError)?;
+
+    let body = match (approved, reason.as_deref()) {
+        (true, _) => "Your relocation request was approved. Your station now shows at the new location.".to_string(),
+        (false, Some(reason)) => format!("Your relocation request was rejected: {reason}"),
+        (false, None) => "Your relocation request was rejected.".to_string(),
+    };
+
+    // The review is committed; a failed notification must not report it as failed.
+    if let Err(err) =
+        create_dashboard_notification(pool, station_id, "Relocation request reviewed", &body, "profile")
+            .await
+    {
+        tracing::error!(
+            "failed to notify station {} of relocation review {}: {err:?}",
+            station_id,
+            request_id
+        );
+    }
+
+    fetch_relocation_request(pool, request_id)
+        .await
+        .map_err(StationError::DatabaseError)?
+        .ok_or_else(|| StationError::NotFound(request_id.to_string()))
+}
+
+/// Moves stations without a current subscription to the end, keeping the
+/// existing order within each group.
+async fn demote_lapsed_stations(
+    pool: &PgPool,
+    stations: &mut [StationResponse],
+) -> Result<(), sqlx::Error> {
+    let ids: Vec<Uuid> = stations.iter().map(|station| station.id).collect();
+
+    let current: HashSet<Uuid> = sqlx::query_scalar(
+        r#"
+        SELECT id FROM unnest($1::uuid[]) AS id
+        WHERE station_subscription_is_current(id)
+        "#,
+    )
+    .bind(&ids)
+    .fetch_all(pool)
+    .await?
+    .into_iter()
+    .collect();
+
+    stations.sort_by_key(|station| !current.contains(&station.id));
+
+    Ok(())
+}
@@ -0,0 +1,68 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::model::{SubscriptionInvoice, SubscriptionPlan};
+
+#[derive(Debug, Deserialize)]
+pub struct CreateInvoiceDto {
+    /// `monthly`, `quarterly` or `annual`.
+    pub plan_code: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AdminCreateInvoiceDto {
+    pub plan_code: String,
+    /// Defaults to seven days.
+    pub due_in_days: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct VoidInvoiceDto {
+    pub reason: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct InvoicesQuery {
+    pub status: Option<String>,
+    pub station_id: Option<Uuid>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdatePlanDto {
+    pub name: Option<String>,
+    pub price: Option<i32>,
+    pub discounts_enabled: Option<bool>,
+    pub analytics_enabled: Option<bool>,
+    pub is_active: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SubscriptionActionDto {
+    /// `cancel_at_period_end`, `withdraw_cancel`, `cancel_now`, `pause`,
+    /// `resume` or `change_plan`.
+    pub action: String,
+    /// Required for `change_plan`.
+    pub plan_code: Option<String>,
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CurrentSubscription {
+    pub id: Uuid,
+    pub status: String,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+    pub cancel_at_period_end: bool,
+    pub cancelled_at: Option<DateTime<Utc>>,
+    pub paused_at: Option<DateTime<Utc>>,
+    pub plan: SubscriptionPlan,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BillingResponse {
+    pub subscription: Option<CurrentSubscription>,
+    pub invoices: Vec<SubscriptionInvoice>,
+    /// Plans the station can be invoiced for.
+    pub plans: Vec<SubscriptionPlan>,
+}
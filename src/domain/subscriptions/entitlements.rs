@@ -0,0 +1,130 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::domain::utils::errors::station_errors::StationError;
+
+/// Grace period after a subscription ends when `SUBSCRIPTION_GRACE_DAYS` is
+/// not set.
+const DEFAULT_GRACE_DAYS: i64 = 3;
+
+/// Reads `SUBSCRIPTION_GRACE_DAYS`: how long a lapsed station keeps
+/// read-only access to its dashboard.
+pub fn grace_period_from_env() -> Duration {
+    let days = std::env::var("SUBSCRIPTION_GRACE_DAYS")
+        .ok()
+        .and_then(|value| value.trim().parse::<i64>().ok())
+        .filter(|days| *days >= 0)
+        .unwrap_or(DEFAULT_GRACE_DAYS);
+
+    Duration::days(days)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AccessLevel {
+    Full,
+    /// Subscription paused, or ended but the grace period has not; reads
+    /// only.
+    ReadOnly,
+    Lapsed,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Feature {
+    Discounts,
+    Analytics,
+}
+
+impl Feature {
+    fn name(self) -> &'static str {
+        match self {
+            Feature::Discounts => "discounts",
+            Feature::Analytics => "analytics",
+        }
+    }
+}
+
+/// What a station may do right now, from its latest subscription and plan.
+#[derive(Debug, Clone, Serialize)]
+pub struct Entitlements {
+    pub access: AccessLevel,
+    pub plan_code: Option<String>,
+    pub ends_at: Option<DateTime<Utc>>,
+    pub grace_ends_at: Option<DateTime<Utc>>,
+    pub discounts_enabled: bool,
+    pub analytics_enabled: bool,
+}
+
+impl Entitlements {
+    pub fn has(&self, feature: Feature) -> bool {
+        self.access != AccessLevel::Lapsed
+            && match feature {
+                Feature::Discounts => self.discounts_enabled,
+                Feature::Analytics => self.analytics_enabled,
+            }
+    }
+
+    pub fn require(&self, feature: Feature) -> Result<(), StationError> {
+        if self.has(feature) {
+            return Ok(());
+        }
+
+        Err(StationError::WrongCredentials(format!(
+            "your plan does not include {}",
+            feature.name()
+        )))
+    }
+}
+
+pub async fn station_entitlements(
+    pool: &PgPool,
+    station_id: Uuid,
+    grace: Duration,
+) -> Result<Entitlements, sqlx::Error> {
+    let latest = sqlx::query_as::<_, (String, DateTime<Utc>, String, bool, bool)>(
+        r#"
+        SELECT sub.status, sub.ends_at, p.code, p.discounts_enabled, p.analytics_enabled
+        FROM subscriptions sub
+        INNER JOIN subscription_plans p ON p.id = sub.plan_id
+        WHERE sub.station_id = $1
+        ORDER BY sub.created_at DESC
+        LIMIT 1
+        "#,
+    )
+    .bind(station_id)
+    .fetch_optional(pool)
+    .await?;
+
+    let Some((status, ends_at, plan_code, discounts_enabled, analytics_enabled)) = latest else {
+        return Ok(Entitlements {
+            access: AccessLevel::Lapsed,
+            plan_code: None,
+            ends_at: None,
+            grace_ends_at: None,
+            discounts_enabled: false,
+            analytics_enabled: false,
+        });
+    };
+
+    let now = Utc::now();
+    let grace_ends_at = ends_at + grace;
+    // Only a subscription that ran its course gets a grace period; one that
+    // was cancelled does not. A paused one keeps a read-only dashboard.
+    let access = match status.as_str() {
+        "active" if now < ends_at => AccessLevel::Full,
+        "paused" => AccessLevel::ReadOnly,
+        "active" | "expired" if now < grace_ends_at => AccessLevel::ReadOnly,
+        _ => AccessLevel::Lapsed,
+    };
+
+    Ok(Entitlements {
+        access,
+        plan_code: Some(plan_code),
+        ends_at: Some(ends_at),
+        grace_ends_at: Some(grace_ends_at),
+        discounts_enabled,
+        analytics_enabled,
+    })
+}
@@ -0,0 +1,526 @@
+use chrono::{DateTime, Duration, Utc};
+use sqlx::{PgExecutor, PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+use super::{
+    dto::SubscriptionActionDto,
+    model::{Subscription, SubscriptionEvent, SubscriptionPlan},
+};
+use crate::domain::utils::errors::station_errors::StationError;
+
+/// A paused subscription resumes on its own after this long.
+pub const MAX_PAUSE_DAYS: i64 = 30;
+const MAX_REASON_LENGTH: usize = 500;
+
+pub const SUBSCRIPTION_COLUMNS: &str = r#"
+    id, station_id, plan_id, starts_at, ends_at, status, cancel_at_period_end, cancelled_at,
+    paused_at, created_at
+"#;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubscriptionStatus {
+    Active,
+    Paused,
+    Expired,
+    Cancelled,
+}
+
+impl SubscriptionStatus {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "active" => Some(Self::Active),
+            "paused" => Some(Self::Paused),
+            "expired" => Some(Self::Expired),
+            "cancelled" => Some(Self::Cancelled),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Active => "active",
+            Self::Paused => "paused",
+            Self::Expired => "expired",
+            Self::Cancelled => "cancelled",
+        }
+    }
+}
+
+/// A change a station or admin asks for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LifecycleAction {
+    CancelAtPeriodEnd,
+    WithdrawCancel,
+    CancelNow,
+    Pause,
+    Resume,
+    ChangePlan(String),
+}
+
+impl LifecycleAction {
+    pub fn from_dto(body: &SubscriptionActionDto) -> Result<Self, StationError> {
+        match body.action.trim() {
+            "cancel_at_period_end" => Ok(Self::CancelAtPeriodEnd),
+            "withdraw_cancel" => Ok(Self::WithdrawCancel),
+            "cancel_now" => Ok(Self::CancelNow),
+            "pause" => Ok(Self::Pause),
+            "resume" => Ok(Self::Resume),
+            "change_plan" => body
+                .plan_code
+                .as_deref()
+                .map(|code| Self::ChangePlan(code.trim().to_lowercase()))
+                .ok_or_else(|| {
+                    StationError::WrongCredentials("plan_code is required".to_string())
+                }),
+            other => Err(StationError::WrongCredentials(format!(
+                "unknown subscription action {other}"
+            ))),
+        }
+    }
+
+    fn event_type(&self) -> &'static str {
+        match self {
+            Self::CancelAtPeriodEnd => "cancel_scheduled",
+            Self::WithdrawCancel => "cancel_withdrawn",
+            Self::CancelNow => "cancelled",
+            Self::Pause => "paused",
+            Self::Resume => "resumed",
+            Self::ChangePlan(_) => "plan_changed",
+        }
+    }
+
+    fn describe(&self) -> &'static str {
+        match self {
+            Self::CancelAtPeriodEnd => "schedule the cancellation of",
+            Self::WithdrawCancel => "withdraw the cancellation of",
+            Self::CancelNow => "cancel",
+            Self::Pause => "pause",
+            Self::Resume => "resume",
+            Self::ChangePlan(_) => "change the plan of",
+        }
+    }
+
+    /// The status the subscription moves to, or why it cannot.
+    pub fn next_status(
+        &self,
+        subscription: &Subscription,
+        now: DateTime<Utc>,
+    ) -> Result<SubscriptionStatus, StationError> {
+        let status = SubscriptionStatus::parse(&subscription.status).ok_or_else(|| {
+            StationError::WrongCredentials(format!(
+                "unknown subscription status {}",
+                subscription.status
+            ))
+        })?;
+        let running = status == SubscriptionStatus::Active && now < subscription.ends_at;
+
+        let next = match self {
+            Self::CancelAtPeriodEnd if running && !subscription.cancel_at_period_end => {
+                Some(SubscriptionStatus::Active)
+            }
+            Self::WithdrawCancel if running && subscription.cancel_at_period_end => {
+                Some(SubscriptionStatus::Active)
+            }
+            Self::CancelNow
+                if matches!(status, SubscriptionStatus::Active | SubscriptionStatus::Paused) =>
+            {
+                Some(SubscriptionStatus::Cancelled)
+            }
+            // Pausing would quietly push a scheduled cancellation back.
+            Self::Pause if running && !subscription.cancel_at_period_end => {
+                Some(SubscriptionStatus::Paused)
+            }
+            Self::Resume if status == SubscriptionStatus::Paused => Some(SubscriptionStatus::Active),
+            Self::ChangePlan(_) if running => Some(SubscriptionStatus::Active),
+            _ => None,
+        };
+
+        next.ok_or_else(|| {
+            let state = if subscription.cancel_at_period_end && status == SubscriptionStatus::Active
+            {
+                "active with a cancellation scheduled".to_string()
+            } else if status == SubscriptionStatus::Active && !running {
+                "past its end date".to_string()
+            } else {
+                status.as_str().to_string()
+            };
+
+            StationError::WrongCredentials(format!(
+                "cannot {} a subscription that is {state}",
+                self.describe()
+            ))
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum SubscriptionActor {
+    Station(Uuid),
+    Admin(Uuid),
+    /// Payment providers and the background worker.
+    System,
+}
+
+impl SubscriptionActor {
+    fn kind(self) -> &'static str {
+        match self {
+            SubscriptionActor::Station(_) => "station",
+            SubscriptionActor::Admin(_) => "admin",
+            SubscriptionActor::System => "system",
+        }
+    }
+
+    fn id(self) -> Option<Uuid> {
+        match self {
+            SubscriptionActor::Station(id) | SubscriptionActor::Admin(id) => Some(id),
+            SubscriptionActor::System => None,
+        }
+    }
+}
+
+/// What a transition changed, for the audit trail.
+pub struct SubscriptionChange<'a> {
+    pub subscription_id: Uuid,
+    pub station_id: Uuid,
+    pub event_type: &'a str,
+    pub from_status: Option<&'a str>,
+    pub to_status: &'a str,
+    pub from_plan_id: Option<Uuid>,
+    pub to_plan_id: Uuid,
+    pub previous_ends_at: Option<DateTime<Utc>>,
+    pub ends_at: DateTime<Utc>,
+    pub proration_credit: Option<i32>,
+    pub actor: SubscriptionActor,
+    pub reason: Option<&'a str>,
+}
+
+pub async fn record_subscription_event<'e, E: PgExecutor<'e>>(
+    executor: E,
+    change: SubscriptionChange<'_>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO subscription_events (
+            subscription_id, station_id, event_type, from_status, to_status, from_plan_id,
+            to_plan_id, previous_ends_at, ends_at, proration_credit, actor_type, actor_id, reason
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+        "#,
+    )
+    .bind(change.subscription_id)
+    .bind(change.station_id)
+    .bind(change.event_type)
+    .bind(change.from_status)
+    .bind(change.to_status)
+    .bind(change.from_plan_id)
+    .bind(change.to_plan_id)
+    .bind(change.previous_ends_at)
+    .bind(change.ends_at)
+    .bind(change.proration_credit)
+    .bind(change.actor.kind())
+    .bind(change.actor.id())
+    .bind(change.reason)
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
+
+/// Converts the unused value of the current plan into time on the new one:
+/// an upgrade shortens the remaining period and a downgrade lengthens it,
+/// so nothing is charged or refunded mid-period.
+///
+/// Returns the naira credited and the new end date.
+pub fn prorate(
+    current: &SubscriptionPlan,
+    next: &SubscriptionPlan,
+    ends_at: DateTime<Utc>,
+    now: DateTime<Utc>,
+) -> (i32, DateTime<Utc>) {
+    let remaining = (ends_at - now).num_seconds().max(0);
+    let period = Duration::days(current.period_days as i64).num_seconds();
+
+    let credit = remaining * current.price as i64 / period;
+    let new_remaining = remaining * current.price as i64 * next.period_days as i64
+        / (current.period_days as i64 * next.price as i64);
+
+    (credit as i32, now + Duration::seconds(new_remaining))
+}
+
+async fn lock_current_subscription(
+    tx: &mut Transaction<'_, Postgres>,
+    station_id: Uuid,
+) -> Result<Subscription, StationError> {
+    sqlx::query_as::<_, Subscription>(&format!(
+        r#"
+        SELECT {SUBSCRIPTION_COLUMNS}
+        FROM subscriptions
+        WHERE station_id = $1 AND status IN ('active', 'paused')
+        FOR UPDATE
+        "#
+    ))
+    .bind(station_id)
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(StationError::DatabaseError)?
+    .ok_or_else(|| StationError::NotFound("no current subscription".to_string()))
+}
+
+async fn find_plan<'e, E: PgExecutor<'e>>(
+    executor: E,
+    plan_id: Uuid,
+) -> Result<SubscriptionPlan, StationError> {
+    sqlx::query_as::<_, SubscriptionPlan>(
+        r#"
+        SELECT id, code, name, period_days, price, discounts_enabled, analytics_enabled,
+               is_public, is_active
+        FROM subscription_plans
+        WHERE id = $1
+        "#,
+    )
+    .bind(plan_id)
+    .fetch_one(executor)
+    .await
+    .map_err(StationError::DatabaseError)
+}
+
+async fn plan_for_change(
+    tx: &mut Transaction<'_, Postgres>,
+    current: &SubscriptionPlan,
+    plan_code: &str,
+) -> Result<SubscriptionPlan, StationError> {
+    if current.price <= 0 {
+        return Err(StationError::WrongCredentials(
+            "a free plan has no credit to carry over; pay an invoice for the new plan instead"
+                .to_string(),
+        ));
+    }
+
+    let next = sqlx::query_as::<_, SubscriptionPlan>(
+        r#"
+        SELECT id, code, name, period_days, price, discounts_enabled, analytics_enabled,
+               is_public, is_active
+        FROM subscription_plans
+        WHERE code = $1 AND is_public AND is_active AND price > 0
+        "#,
+    )
+    .bind(plan_code)
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(StationError::DatabaseError)?
+    .ok_or_else(|| StationError::NotFound(format!("plan {plan_code}")))?;
+
+    if next.id == current.id {
+        return Err(StationError::WrongCredentials(format!(
+            "the subscription is already on the {} plan",
+            next.code
+        )));
+    }
+
+    Ok(next)
+}
+
+/// Validates and applies one lifecycle action to the station's current
+/// subscription, recording it in `subscription_events`.
+pub async fn apply_action(
+    pool: &PgPool,
+    station_id: Uuid,
+    action: LifecycleAction,
+    actor: SubscriptionActor,
+    reason: Option<&str>,
+) -> Result<(), StationError> {
+    let reason = reason.map(str::trim).filter(|reason| !reason.is_empty());
+    if reason.is_some_and(|reason| reason.len() > MAX_REASON_LENGTH) {
+        return Err(StationError::WrongCredentials(format!(
+            "reason must be at most {MAX_REASON_LENGTH} characters"
+        )));
+    }
+
+    let mut tx = pool.begin().await.map_err(StationError::DatabaseError)?;
+
+    let subscription = lock_current_subscription(&mut tx, station_id).await?;
+    let now = Utc::now();
+    let next_status = action.next_status(&subscription, now)?;
+
+    let mut plan_id = subscription.plan_id;
+    let mut ends_at = subscription.ends_at;
+    let mut cancel_at_period_end = subscription.cancel_at_period_end;
+    let mut proration_credit = None;
+
+    match &action {
+        LifecycleAction::CancelAtPeriodEnd => cancel_at_period_end = true,
+        LifecycleAction::WithdrawCancel => cancel_at_period_end = false,
+        LifecycleAction::CancelNow => ends_at = ends_at.min(now),
+        LifecycleAction::Pause => {}
+        LifecycleAction::Resume => {
+            let paused_at = subscription.paused_at.unwrap_or(now);
+            ends_at += now - paused_at;
+        }
+        LifecycleAction::ChangePlan(plan_code) => {
+            let current = find_plan(&mut *tx, subscription.plan_id).await?;
+            let next = plan_for_change(&mut tx, &current, plan_code).await?;
+            let (credit, new_ends_at) = prorate(&current, &next, subscription.ends_at, now);
+
+            plan_id = next.id;
+            ends_at = new_ends_at;
+            proration_credit = Some(credit);
+        }
+    }
+
+    sqlx::query(
+        r#"
+        UPDATE subscriptions
+        SET status = $2,
+            plan_id = $3,
+            ends_at = $4,
+            cancel_at_period_end = $5,
+            cancelled_at = CASE WHEN $2 = 'cancelled' THEN now() ELSE cancelled_at END,
+            paused_at = CASE WHEN $2 = 'paused' THEN now() ELSE NULL END
+        WHERE id = $1
+        "#,
+    )
+    .bind(subscription.id)
+    .bind(next_status.as_str())
+    .bind(plan_id)
+    .bind(ends_at)
+    .bind(cancel_at_period_end)
+    .execute(&mut *tx)
+    .await
+    .map_err(StationError::DatabaseError)?;
+
+    record_subscription_event(
+        &mut *tx,
+        SubscriptionChange {
+            subscription_id: subscription.id,
+            station_id,
+            event_type: action.event_type(),
+            from_status: Some(&subscription.status),
+            to_status: next_status.as_str(),
+            from_plan_id: Some(subscription.plan_id),
+            to_plan_id: plan_id,
+            previous_ends_at: Some(subscription.ends_at),
+            ends_at,
+            proration_credit,
+            actor,
+            reason,
+        },
+    )
+    .await
+    .map_err(StationError::DatabaseError)?;
+
+    tx.commit().await.map_err(StationError::DatabaseError)?;
+
+    Ok(())
+}
+
+pub async fn subscription_history(
+    pool: &PgPool,
+    station_id: Uuid,
+) -> Result<Vec<SubscriptionEvent>, StationError> {
+    sqlx::query_as::<_, SubscriptionEvent>(
+        r#"
+        SELECT e.id, e.subscription_id, e.event_type, e.from_status, e.to_status,
+               fp.code AS from_plan_code, tp.code AS to_plan_code, e.previous_ends_at,
+               e.ends_at, e.proration_credit, e.actor_type, e.actor_id, e.reason, e.created_at
+        FROM subscription_events e
+        LEFT JOIN subscription_plans fp ON fp.id = e.from_plan_id
+        LEFT JOIN subscription_plans tp ON tp.id = e.to_plan_id
+        WHERE e.station_id = $1
+        ORDER BY e.created_at DESC
+        LIMIT 200
+        "#,
+    )
+    .bind(station_id)
+    .fetch_all(pool)
+    .await
+    .map_err(StationError::DatabaseError)
+}
+
+/// Ends a subscription whose period ran out: cancelled if the station asked
+/// to stop at period end, expired otherwise. Returns false when it was no
+/// longer active.
+pub async fn end_subscription(pool: &PgPool, subscription: &Subscription) -> anyhow::Result<bool> {
+    let status = if subscription.cancel_at_period_end {
+        SubscriptionStatus::Cancelled
+    } else {
+        SubscriptionStatus::Expired
+    };
+
+    let mut tx = pool.begin().await?;
+
+    let ended = sqlx::query(
+        r#"
+        UPDATE subscriptions
+        SET status = $2,
+            cancelled_at = CASE WHEN $2 = 'cancelled' THEN ends_at ELSE cancelled_at END
+        WHERE id = $1 AND status = 'active'
+        "#,
+    )
+    .bind(subscription.id)
+    .bind(status.as_str())
+    .execute(&mut *tx)
+    .await?
+    .rows_affected();
+
+    if ended == 0 {
+        return Ok(false);
+    }
+
+    record_subscription_event(
+        &mut *tx,
+        SubscriptionChange {
+            subscription_id: subscription.id,
+            station_id: subscription.station_id,
+            event_type: if status == SubscriptionStatus::Cancelled {
+                "cancelled"
+            } else {
+                "expired"
+            },
+            from_status: Some(&subscription.status),
+            to_status: status.as_str(),
+            from_plan_id: Some(subscription.plan_id),
+            to_plan_id: subscription.plan_id,
+            previous_ends_at: Some(subscription.ends_at),
+            ends_at: subscription.ends_at,
+            proration_credit: None,
+            actor: SubscriptionActor::System,
+            reason: None,
+        },
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(true)
+}
+
+/// Resumes subscriptions paused for longer than [`MAX_PAUSE_DAYS`].
+pub async fn resume_expired_pauses(pool: &PgPool) -> anyhow::Result<()> {
+    let station_ids: Vec<Uuid> = sqlx::query_scalar(
+        r#"
+        SELECT station_id
+        FROM subscriptions
+        WHERE status = 'paused' AND paused_at <= now() - make_interval(days => $1)
+        "#,
+    )
+    .bind(MAX_PAUSE_DAYS as i32)
+    .fetch_all(pool)
+    .await?;
+
+    for station_id in station_ids {
+        let resumed = apply_action(
+            pool,
+            station_id,
+            LifecycleAction::Resume,
+            SubscriptionActor::System,
+            Some("pause limit reached"),
+        )
+        .await;
+
+        if let Err(err) = resumed {
+            tracing::error!("failed to resume paused subscription of {station_id}: {err:?}");
+        }
+    }
+
+    Ok(())
+}
@@ -1,3 +1,5 @@
+pub mod dto;
+pub mod entitlements;
+pub mod lifecycle;
 pub mod model;
 pub mod service;
-pub mod worker;
@@ -7,22 +7,70 @@ use uuid::Uuid;
 pub struct Subscription {
     pub id: Uuid,
     pub station_id: Uuid,
+    pub plan_id: Uuid,
     pub starts_at: DateTime<Utc>,
     pub ends_at: DateTime<Utc>,
     pub status: String,
+    pub cancel_at_period_end: bool,
+    pub cancelled_at: Option<DateTime<Utc>>,
+    pub paused_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
 }
 
+/// One validated lifecycle transition of a station's subscription.
 #[derive(Debug, Clone, Serialize, FromRow)]
-pub struct DashboardNotification {
+pub struct SubscriptionEvent {
     pub id: Uuid,
-    pub title: String,
-    pub body: String,
-    pub kind: String,
-    pub is_read: bool,
+    pub subscription_id: Uuid,
+    pub event_type: String,
+    pub from_status: Option<String>,
+    pub to_status: String,
+    pub from_plan_code: Option<String>,
+    pub to_plan_code: Option<String>,
+    pub previous_ends_at: Option<DateTime<Utc>>,
+    pub ends_at: DateTime<Utc>,
+    pub proration_credit: Option<i32>,
+    pub actor_type: String,
+    pub actor_id: Option<Uuid>,
+    pub reason: Option<String>,
     pub created_at: DateTime<Utc>,
 }
 
+/// A billing plan and the features it entitles a station to.
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct SubscriptionPlan {
+    pub id: Uuid,
+    pub code: String,
+    pub name: String,
+    pub period_days: i32,
+    /// Whole naira per period.
+    pub price: i32,
+    pub discounts_enabled: bool,
+    pub analytics_enabled: bool,
+    pub is_public: bool,
+    pub is_active: bool,
+}
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct SubscriptionInvoice {
+    pub id: Uuid,
+    pub number: String,
+    pub station_id: Uuid,
+    pub plan_id: Uuid,
+    pub plan_code: String,
+    pub plan_name: String,
+    pub amount: i32,
+    pub period_days: i32,
+    pub status: String,
+    pub is_overdue: bool,
+    pub issued_at: DateTime<Utc>,
+    pub due_at: DateTime<Utc>,
+    pub paid_at: Option<DateTime<Utc>>,
+    pub subscription_id: Option<Uuid>,
+    pub voided_at: Option<DateTime<Utc>>,
+    pub void_reason: Option<String>,
+}
+
 #[derive(Debug, Clone, Copy)]
 pub enum ReminderType {
     D7,
@@ -1,178 +1,637 @@
 use anyhow::Context;
-use chrono::{Duration, Utc};
-use lettre::{
-    AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
-    transport::smtp::authentication::Credentials,
+use axum::{
+    Json,
+    extract::{Extension, State},
+    http::StatusCode,
 };
-use sqlx::PgPool;
-use std::env;
+use chrono::{DateTime, Duration, Utc};
+use sqlx::{PgExecutor, PgPool, Postgres, Transaction};
 use uuid::Uuid;
 
-use super::model::{DashboardNotification, ReminderType, Subscription};
+use super::{
+    dto::{
+        BillingResponse, CreateInvoiceDto, CurrentSubscription, SubscriptionActionDto,
+        UpdatePlanDto,
+    },
+    lifecycle::{
+        LifecycleAction, SUBSCRIPTION_COLUMNS, SubscriptionActor, SubscriptionChange,
+        apply_action, end_subscription, record_subscription_event, resume_expired_pauses,
+        subscription_history,
+    },
+    model::{
+        ReminderType, Subscription, SubscriptionEvent, SubscriptionInvoice, SubscriptionPlan,
+    },
+};
+use crate::{
+    app_state::AppState,
+    authentication::station::authenticate::token::service::Claims,
+    domain::{
+        emails::templates::{SUBSCRIPTION_EXPIRED, SUBSCRIPTION_REMINDER, TemplateVars},
+        notifications::service::{Notice, create_dashboard_notification, notify_station},
+        utils::errors::station_errors::StationError,
+    },
+};
 
 const SUBSCRIPTION_KIND: &str = "subscription";
+const TRIAL_PLAN_CODE: &str = "trial";
+
+pub const DEFAULT_INVOICE_DUE_DAYS: i64 = 7;
+const MAX_INVOICE_DUE_DAYS: i64 = 60;
+const MAX_VOID_REASON_LENGTH: usize = 500;
+
+const INVOICE_STATUSES: [&str; 3] = ["open", "paid", "void"];
+
+const PLAN_COLUMNS: &str = r#"
+    id, code, name, period_days, price, discounts_enabled, analytics_enabled, is_public,
+    is_active
+"#;
+
+const INVOICE_SELECT: &str = r#"
+    SELECT i.id, i.number, i.station_id, i.plan_id, p.code AS plan_code,
+           p.name AS plan_name, i.amount, i.period_days, i.status,
+           (i.status = 'open' AND i.due_at < now()) AS is_overdue,
+           i.issued_at, i.due_at, i.paid_at, i.subscription_id, i.voided_at, i.void_reason
+    FROM subscription_invoices i
+    INNER JOIN subscription_plans p ON p.id = i.plan_id
+"#;
+
+pub struct BillingService;
+
+impl BillingService {
+    pub async fn get_billing(
+        State(app_state): State<AppState>,
+        Extension(claims): Extension<Claims>,
+    ) -> Result<Json<BillingResponse>, StationError> {
+        let billing = station_billing(&app_state.pool, claims.station_res.id).await?;
+
+        Ok(Json(billing))
+    }
+
+    /// Lets a station pick a plan. The subscription is renewed once an admin
+    /// marks the invoice paid.
+    pub async fn create_invoice(
+        State(app_state): State<AppState>,
+        Extension(claims): Extension<Claims>,
+        Json(body): Json<CreateInvoiceDto>,
+    ) -> Result<(StatusCode, Json<SubscriptionInvoice>), StationError> {
+        let invoice = issue_invoice(
+            &app_state.pool,
+            claims.station_res.id,
+            &body.plan_code,
+            DEFAULT_INVOICE_DUE_DAYS,
+        )
+        .await?;
+
+        Ok((StatusCode::CREATED, Json(invoice)))
+    }
+
+    /// Cancels, pauses, resumes or changes the plan of the station's own
+    /// subscription.
+    pub async fn update_subscription(
+        State(app_state): State<AppState>,
+        Extension(claims): Extension<Claims>,
+        Json(body): Json<SubscriptionActionDto>,
+    ) -> Result<Json<CurrentSubscription>, StationError> {
+        let station_id = claims.station_res.id;
+        let action = LifecycleAction::from_dto(&body)?;
+
+        apply_action(
+            &app_state.pool,
+            station_id,
+            action,
+            SubscriptionActor::Station(station_id),
+            body.reason.as_deref(),
+        )
+        .await?;
+
+        current_subscription(&app_state.pool, station_id)
+            .await?
+            .map(Json)
+            .ok_or_else(|| StationError::NotFound("no current subscription".to_string()))
+    }
+
+    pub async fn get_subscription_history(
+        State(app_state): State<AppState>,
+        Extension(claims): Extension<Claims>,
+    ) -> Result<Json<Vec<SubscriptionEvent>>, StationError> {
+        let history = subscription_history(&app_state.pool, claims.station_res.id).await?;
+
+        Ok(Json(history))
+    }
+}
 
 pub async fn create_trial_subscription(pool: &PgPool, station_id: Uuid) -> anyhow::Result<()> {
-    let starts_at = Utc::now();
-    let ends_at = starts_at + Duration::days(30);
+    let mut tx = pool.begin().await?;
 
-    sqlx::query(
+    let (subscription_id, plan_id, ends_at) =
+        sqlx::query_as::<_, (Uuid, Uuid, DateTime<Utc>)>(
+            r#"
+            INSERT INTO subscriptions (station_id, plan_id, starts_at, ends_at, status)
+            SELECT $1, id, now(), now() + make_interval(days => period_days), 'active'
+            FROM subscription_plans
+            WHERE code = $2
+            RETURNING id, plan_id, ends_at
+            "#,
+        )
+        .bind(station_id)
+        .bind(TRIAL_PLAN_CODE)
+        .fetch_one(&mut *tx)
+        .await
+        .context("failed to create signup trial subscription")?;
+
+    record_subscription_event(
+        &mut *tx,
+        SubscriptionChange {
+            subscription_id,
+            station_id,
+            event_type: "started",
+            from_status: None,
+            to_status: "active",
+            from_plan_id: None,
+            to_plan_id: plan_id,
+            previous_ends_at: None,
+            ends_at,
+            proration_credit: None,
+            actor: SubscriptionActor::System,
+            reason: None,
+        },
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(())
+}
+
+pub async fn list_plans(
+    pool: &PgPool,
+    public_only: bool,
+) -> Result<Vec<SubscriptionPlan>, StationError> {
+    sqlx::query_as::<_, SubscriptionPlan>(&format!(
+        r#"
+        SELECT {PLAN_COLUMNS}
+        FROM subscription_plans
+        WHERE NOT $1 OR (is_public AND is_active)
+        ORDER BY period_days, code
+        "#
+    ))
+    .bind(public_only)
+    .fetch_all(pool)
+    .await
+    .map_err(StationError::DatabaseError)
+}
+
+pub async fn update_plan(
+    pool: &PgPool,
+    plan_id: Uuid,
+    changes: UpdatePlanDto,
+) -> Result<SubscriptionPlan, StationError> {
+    if changes.price.is_some_and(|price| price < 0) {
+        return Err(StationError::WrongCredentials(
+            "plan price cannot be negative".to_string(),
+        ));
+    }
+
+    let name = changes.name.map(|name| name.trim().to_string());
+    if name.as_deref().is_some_and(str::is_empty) {
+        return Err(StationError::WrongCredentials(
+            "plan name cannot be empty".to_string(),
+        ));
+    }
+
+    sqlx::query_as::<_, SubscriptionPlan>(&format!(
+        r#"
+        UPDATE subscription_plans
+        SET name = COALESCE($2, name),
+            price = COALESCE($3, price),
+            discounts_enabled = COALESCE($4, discounts_enabled),
+            analytics_enabled = COALESCE($5, analytics_enabled),
+            is_active = COALESCE($6, is_active),
+            updated_at = now()
+        WHERE id = $1
+        RETURNING {PLAN_COLUMNS}
+        "#
+    ))
+    .bind(plan_id)
+    .bind(name)
+    .bind(changes.price)
+    .bind(changes.discounts_enabled)
+    .bind(changes.analytics_enabled)
+    .bind(changes.is_active)
+    .fetch_optional(pool)
+    .await
+    .map_err(StationError::DatabaseError)?
+    .ok_or_else(|| StationError::NotFound(plan_id.to_string()))
+}
+
+async fn find_invoice<'e, E: PgExecutor<'e>>(
+    executor: E,
+    invoice_id: Uuid,
+) -> Result<SubscriptionInvoice, StationError> {
+    sqlx::query_as::<_, SubscriptionInvoice>(&format!("{INVOICE_SELECT} WHERE i.id = $1"))
+        .bind(invoice_id)
+        .fetch_optional(executor)
+        .await
+        .map_err(StationError::DatabaseError)?
+        .ok_or_else(|| StationError::NotFound(invoice_id.to_string()))
+}
+
+pub async fn list_invoices(
+    pool: &PgPool,
+    status: Option<&str>,
+    station_id: Option<Uuid>,
+) -> Result<Vec<SubscriptionInvoice>, StationError> {
+    if let Some(status) = status
+        && !INVOICE_STATUSES.contains(&status)
+    {
+        return Err(StationError::WrongCredentials(format!(
+            "invoice status must be one of {}",
+            INVOICE_STATUSES.join(", ")
+        )));
+    }
+
+    sqlx::query_as::<_, SubscriptionInvoice>(&format!(
+        r#"
+        {INVOICE_SELECT}
+        WHERE ($1::VARCHAR IS NULL OR i.status = $1)
+          AND ($2::UUID IS NULL OR i.station_id = $2)
+        ORDER BY i.issued_at DESC
+        LIMIT 200
+        "#
+    ))
+    .bind(status)
+    .bind(station_id)
+    .fetch_all(pool)
+    .await
+    .map_err(StationError::DatabaseError)
+}
+
+/// Issues an invoice for one period of a public plan, priced from the plan
+/// as it stands now. A station has at most one open invoice at a time.
+pub async fn issue_invoice(
+    pool: &PgPool,
+    station_id: Uuid,
+    plan_code: &str,
+    due_in_days: i64,
+) -> Result<SubscriptionInvoice, StationError> {
+    if !(1..=MAX_INVOICE_DUE_DAYS).contains(&due_in_days) {
+        return Err(StationError::WrongCredentials(format!(
+            "invoices must be due within 1 to {MAX_INVOICE_DUE_DAYS} days"
+        )));
+    }
+
+    let plan = sqlx::query_as::<_, SubscriptionPlan>(&format!(
         r#"
-        INSERT INTO subscriptions (station_id, starts_at, ends_at, status)
-        VALUES ($1, $2, $3, 'active')
+        SELECT {PLAN_COLUMNS}
+        FROM subscription_plans
+        WHERE code = $1 AND is_public AND is_active
+        "#
+    ))
+    .bind(plan_code.trim().to_lowercase())
+    .fetch_optional(pool)
+    .await
+    .map_err(StationError::DatabaseError)?
+    .ok_or_else(|| StationError::NotFound(format!("plan {plan_code}")))?;
+
+    let mut tx = pool.begin().await.map_err(StationError::DatabaseError)?;
+
+    // Locking the station keeps two requests from both seeing no open invoice.
+    sqlx::query("SELECT id FROM stations WHERE id = $1 FOR UPDATE")
+        .bind(station_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(StationError::DatabaseError)?
+        .ok_or_else(|| StationError::NotFound(station_id.to_string()))?;
+
+    let has_open_invoice: bool = sqlx::query_scalar(
+        r#"
+        SELECT EXISTS (
+            SELECT 1 FROM subscription_invoices WHERE station_id = $1 AND status = 'open'
+        )
         "#,
     )
     .bind(station_id)
-    .bind(starts_at)
-    .bind(ends_at)
-    .execute(pool)
+    .fetch_one(&mut *tx)
     .await
-    .context("failed to create signup trial subscription")?;
+    .map_err(StationError::DatabaseError)?;
 
-    Ok(())
+    if has_open_invoice {
+        return Err(StationError::WrongCredentials(
+            "station already has an open invoice".to_string(),
+        ));
+    }
+
+    let invoice_id: Uuid = sqlx::query_scalar(
+        r#"
+        INSERT INTO subscription_invoices (station_id, plan_id, amount, period_days, due_at)
+        VALUES ($1, $2, $3, $4, now() + make_interval(days => $5))
+        RETURNING id
+        "#,
+    )
+    .bind(station_id)
+    .bind(plan.id)
+    .bind(plan.price)
+    .bind(plan.period_days)
+    .bind(due_in_days as i32)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(StationError::DatabaseError)?;
+
+    let invoice = find_invoice(&mut *tx, invoice_id).await?;
+
+    tx.commit().await.map_err(StationError::DatabaseError)?;
+
+    Ok(invoice)
 }
 
+/// Renews a station's subscription on `plan_id` for `days`, inside the
+/// caller's transaction. Every renewal goes through here, whether an admin
+/// settles an invoice or a payment provider confirms one. Time left on the
+/// current subscription carries over. Returns the new subscription's id.
 pub async fn renew_subscription_manual(
-    pool: &PgPool,
+    tx: &mut Transaction<'_, Postgres>,
     station_id: Uuid,
-    admin_id: Uuid,
+    plan_id: Uuid,
+    admin_id: Option<Uuid>,
     days: i64,
-) -> anyhow::Result<()> {
-    let starts_at = Utc::now();
-    let ends_at = starts_at + Duration::days(days.max(1));
-
-    let mut tx = pool.begin().await?;
-
-    sqlx::query(
+) -> Result<Uuid, sqlx::Error> {
+    // A paused subscription carries over the time it still had when paused.
+    let current = sqlx::query_as::<_, (String, Uuid, DateTime<Utc>)>(
         r#"
-        UPDATE subscriptions
-        SET status = 'expired'
-        WHERE station_id = $1 AND status = 'active'
+        WITH current AS (
+            SELECT id, status, plan_id, ends_at + COALESCE(now() - paused_at, interval '0') AS ends_at
+            FROM subscriptions
+            WHERE station_id = $1 AND status IN ('active', 'paused')
+            FOR UPDATE
+        )
+        UPDATE subscriptions s
+        SET status = 'expired', paused_at = NULL
+        FROM current
+        WHERE s.id = current.id
+        RETURNING current.status, current.plan_id, current.ends_at
         "#,
     )
     .bind(station_id)
-    .execute(&mut *tx)
+    .fetch_optional(&mut **tx)
     .await?;
 
-    sqlx::query(
+    let starts_at = Utc::now();
+    let current_ends_at = current.as_ref().map(|(_, _, ends_at)| *ends_at);
+    let ends_at = current_ends_at.map_or(starts_at, |ends_at| ends_at.max(starts_at))
+        + Duration::days(days.max(1));
+
+    let subscription_id: Uuid = sqlx::query_scalar(
         r#"
-        INSERT INTO subscriptions (station_id, starts_at, ends_at, status, created_by_admin)
-        VALUES ($1, $2, $3, 'active', $4)
+        INSERT INTO subscriptions (station_id, plan_id, starts_at, ends_at, status, created_by_admin)
+        VALUES ($1, $2, $3, $4, 'active', $5)
+        RETURNING id
         "#,
     )
     .bind(station_id)
+    .bind(plan_id)
     .bind(starts_at)
     .bind(ends_at)
     .bind(admin_id)
-    .execute(&mut *tx)
+    .fetch_one(&mut **tx)
     .await?;
 
-    tx.commit().await?;
+    record_subscription_event(
+        &mut **tx,
+        SubscriptionChange {
+            subscription_id,
+            station_id,
+            event_type: "renewed",
+            from_status: current.as_ref().map(|(status, _, _)| status.as_str()),
+            to_status: "active",
+            from_plan_id: current.as_ref().map(|(_, plan_id, _)| *plan_id),
+            to_plan_id: plan_id,
+            previous_ends_at: current_ends_at,
+            ends_at,
+            proration_credit: None,
+            actor: admin_id.map_or(SubscriptionActor::System, SubscriptionActor::Admin),
+            reason: None,
+        },
+    )
+    .await?;
 
-    Ok(())
+    Ok(subscription_id)
+}
+
+/// What settling an invoice did.
+pub enum InvoiceSettlement {
+    /// The invoice is paid and the subscription renewed.
+    Paid(Box<SubscriptionInvoice>),
+    /// The invoice had already been paid or voided; nothing changed.
+    NotOpen { status: String },
 }
 
-pub async fn is_station_subscription_expired(pool: &PgPool, station_id: Uuid) -> anyhow::Result<bool> {
-    let latest = sqlx::query_as::<_, Subscription>(
+/// Marks an open invoice paid and renews the station's subscription for the
+/// invoiced period through `renew_subscription_manual`, inside the caller's
+/// transaction. `admin_id` is `None` when a payment provider confirmed the
+/// payment.
+pub async fn settle_invoice(
+    tx: &mut Transaction<'_, Postgres>,
+    invoice_id: Uuid,
+    admin_id: Option<Uuid>,
+) -> Result<InvoiceSettlement, StationError> {
+    let (station_id, plan_id, period_days, status) =
+        sqlx::query_as::<_, (Uuid, Uuid, i32, String)>(
+            r#"
+            SELECT station_id, plan_id, period_days, status
+            FROM subscription_invoices
+            WHERE id = $1
+            FOR UPDATE
+            "#,
+        )
+        .bind(invoice_id)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(StationError::DatabaseError)?
+        .ok_or_else(|| StationError::NotFound(invoice_id.to_string()))?;
+
+    if status != "open" {
+        return Ok(InvoiceSettlement::NotOpen { status });
+    }
+
+    let subscription_id = renew_subscription_manual(
+        tx,
+        station_id,
+        plan_id,
+        admin_id,
+        period_days as i64,
+    )
+    .await
+    .map_err(StationError::DatabaseError)?;
+
+    sqlx::query(
         r#"
-        SELECT id, station_id, starts_at, ends_at, status, created_at
-        FROM subscriptions
-        WHERE station_id = $1
-        ORDER BY created_at DESC
-        LIMIT 1
+        UPDATE subscription_invoices
+        SET status = 'paid',
+            paid_at = now(),
+            paid_by_admin = $2,
+            subscription_id = $3
+        WHERE id = $1
         "#,
     )
-    .bind(station_id)
-    .fetch_optional(pool)
-    .await?;
+    .bind(invoice_id)
+    .bind(admin_id)
+    .bind(subscription_id)
+    .execute(&mut **tx)
+    .await
+    .map_err(StationError::DatabaseError)?;
 
-    let now = Utc::now();
+    Ok(InvoiceSettlement::Paid(Box::new(
+        find_invoice(&mut **tx, invoice_id).await?,
+    )))
+}
+
+/// Tells the station its subscription was renewed. Failures are logged
+/// rather than returned, since the payment itself has already gone through.
+pub async fn notify_invoice_paid(pool: &PgPool, invoice: &SubscriptionInvoice) {
+    let ends_at: Result<DateTime<Utc>, sqlx::Error> =
+        sqlx::query_scalar("SELECT ends_at FROM subscriptions WHERE id = $1")
+            .bind(invoice.subscription_id)
+            .fetch_one(pool)
+            .await;
+
+    let result = match ends_at {
+        Ok(ends_at) => {
+            let body = format!(
+                "Invoice {} was paid. Your {} subscription now runs until {}.",
+                invoice.number,
+                invoice.plan_name,
+                ends_at.format("%Y-%m-%d")
+            );
+            create_dashboard_notification(
+                pool,
+                invoice.station_id,
+                "Subscription renewed",
+                &body,
+                SUBSCRIPTION_KIND,
+            )
+            .await
+            .map(|_| ())
+        }
+        Err(err) => Err(err.into()),
+    };
 
-    match latest {
-        None => Ok(true),
-        Some(sub) => Ok(sub.status != "active" || now >= sub.ends_at),
+    if let Err(err) = result {
+        tracing::error!(
+            "failed to notify station {} of renewal: {err:?}",
+            invoice.station_id
+        );
     }
 }
 
-pub async fn create_dashboard_notification(
+pub async fn void_invoice(
     pool: &PgPool,
-    station_id: Uuid,
-    title: &str,
-    body: &str,
-    kind: &str,
-) -> anyhow::Result<()> {
-    sqlx::query(
+    invoice_id: Uuid,
+    reason: &str,
+) -> Result<SubscriptionInvoice, StationError> {
+    let reason = reason.trim();
+    if reason.is_empty() || reason.len() > MAX_VOID_REASON_LENGTH {
+        return Err(StationError::WrongCredentials(format!(
+            "a reason of at most {MAX_VOID_REASON_LENGTH} characters is required"
+        )));
+    }
+
+    let voided = sqlx::query(
         r#"
-        INSERT INTO notifications (station_id, title, body, kind)
-        VALUES ($1, $2, $3, $4)
+        UPDATE subscription_invoices
+        SET status = 'void',
+            voided_at = now(),
+            void_reason = $2
+        WHERE id = $1 AND status = 'open'
         "#,
     )
-    .bind(station_id)
-    .bind(title)
-    .bind(body)
-    .bind(kind)
+    .bind(invoice_id)
+    .bind(reason)
     .execute(pool)
-    .await?;
-
-    Ok(())
-}
+    .await
+    .map_err(StationError::DatabaseError)?
+    .rows_affected();
 
-pub async fn create_expired_signin_notification(pool: &PgPool, station_id: Uuid) -> anyhow::Result<()> {
-    let body = "Your subscription has expired. Please contact admin for renewal.";
+    let invoice = find_invoice(pool, invoice_id).await?;
+    if voided == 0 {
+        return Err(StationError::WrongCredentials(format!(
+            "invoice is already {}",
+            invoice.status
+        )));
+    }
 
-    create_dashboard_notification(
-        pool,
-        station_id,
-        "Subscription expired",
-        body,
-        SUBSCRIPTION_KIND,
-    )
-    .await
+    Ok(invoice)
 }
 
-pub async fn get_station_notifications(
+pub async fn current_subscription(
     pool: &PgPool,
     station_id: Uuid,
-) -> anyhow::Result<Vec<DashboardNotification>> {
-    let notifications = sqlx::query_as::<_, DashboardNotification>(
+) -> Result<Option<CurrentSubscription>, StationError> {
+    let subscription = sqlx::query_as::<_, Subscription>(&format!(
         r#"
-        SELECT id, title, body, kind, is_read, created_at
-        FROM notifications
+        SELECT {SUBSCRIPTION_COLUMNS}
+        FROM subscriptions
         WHERE station_id = $1
         ORDER BY created_at DESC
-        LIMIT 50
-        "#,
-    )
+        LIMIT 1
+        "#
+    ))
     .bind(station_id)
-    .fetch_all(pool)
-    .await?;
+    .fetch_optional(pool)
+    .await
+    .map_err(StationError::DatabaseError)?;
+
+    let Some(subscription) = subscription else {
+        return Ok(None);
+    };
 
-    Ok(notifications)
+    let plan = sqlx::query_as::<_, SubscriptionPlan>(&format!(
+        "SELECT {PLAN_COLUMNS} FROM subscription_plans WHERE id = $1"
+    ))
+    .bind(subscription.plan_id)
+    .fetch_one(pool)
+    .await
+    .map_err(StationError::DatabaseError)?;
+
+    Ok(Some(CurrentSubscription {
+        id: subscription.id,
+        status: subscription.status,
+        starts_at: subscription.starts_at,
+        ends_at: subscription.ends_at,
+        cancel_at_period_end: subscription.cancel_at_period_end,
+        cancelled_at: subscription.cancelled_at,
+        paused_at: subscription.paused_at,
+        plan,
+    }))
 }
 
-pub async fn mark_station_notification_read(
+pub async fn station_billing(
     pool: &PgPool,
     station_id: Uuid,
-    notification_id: Uuid,
-) -> anyhow::Result<bool> {
-    let rows_affected = sqlx::query(
-        r#"
-        UPDATE notifications
-        SET is_read = TRUE
-        WHERE id = $1 AND station_id = $2
-        "#,
+) -> Result<BillingResponse, StationError> {
+    Ok(BillingResponse {
+        subscription: current_subscription(pool, station_id).await?,
+        invoices: list_invoices(pool, None, Some(station_id)).await?,
+        plans: list_plans(pool, true).await?,
+    })
+}
+
+pub async fn create_expired_signin_notification(pool: &PgPool, station_id: Uuid) -> anyhow::Result<()> {
+    let body = "Your subscription has expired. Please contact admin for renewal.";
+
+    create_dashboard_notification(
+        pool,
+        station_id,
+        "Subscription expired",
+        body,
+        SUBSCRIPTION_KIND,
     )
-    .bind(notification_id)
-    .bind(station_id)
-    .execute(pool)
-    .await?
-    .rows_affected();
+    .await?;
 
-    Ok(rows_affected > 0)
+    Ok(())
 }
 
-async fn create_reminder_log_once(
-    pool: &PgPool,
+async fn create_reminder_log_once<'e, E: PgExecutor<'e>>(
+    executor: E,
     subscription_id: Uuid,
     reminder_type: ReminderType,
 ) -> anyhow::Result<bool> {
@@ -185,69 +644,13 @@ async fn create_reminder_log_once(
     )
     .bind(subscription_id)
     .bind(reminder_type.as_str())
-    .execute(pool)
+    .execute(executor)
     .await?
     .rows_affected();
 
     Ok(rows_affected > 0)
 }
 
-async fn mark_subscription_expired(pool: &PgPool, subscription_id: Uuid) -> anyhow::Result<()> {
-    sqlx::query(
-        r#"
-        UPDATE subscriptions
-        SET status = 'expired'
-        WHERE id = $1 AND status = 'active'
-        "#,
-    )
-    .bind(subscription_id)
-    .execute(pool)
-    .await?;
-
-    Ok(())
-}
-
-async fn send_subscription_email(
-    _pool: &PgPool,
-    station_email: &str,
-    subject: &str,
-    body: &str,
-) -> anyhow::Result<()> {
-    let smtp_host = match env::var("SMTP_HOST") {
-        Ok(v) => v,
-        Err(_) => {
-            tracing::warn!("SMTP_HOST missing; skipping email send to {station_email}");
-            return Ok(());
-        }
-    };
-
-    let smtp_port = env::var("SMTP_PORT")
-        .ok()
-        .and_then(|v| v.parse::<u16>().ok())
-        .unwrap_or(587);
-    let smtp_username = env::var("SMTP_USERNAME").unwrap_or_default();
-    let smtp_password = env::var("SMTP_PASSWORD").unwrap_or_default();
-    let smtp_from = env::var("SMTP_FROM").unwrap_or_else(|_| "noreply@fuelgetter.local".to_string());
-
-    let email = Message::builder()
-        .from(smtp_from.parse()?)
-        .to(station_email.parse()?)
-        .subject(subject)
-        .body(body.to_string())?;
-
-    let creds = Credentials::new(smtp_username, smtp_password);
-    let mailer = AsyncSmtpTransport::<Tokio1Executor>::relay(&smtp_host)?
-        .credentials(creds)
-        .port(smtp_port)
-        .build();
-
-    if let Err(err) = mailer.send(email).await {
-        tracing::error!("failed to send subscription email to {}: {:?}", station_email, err);
-    }
-
-    Ok(())
-}
-
 fn eligible_reminder_type(time_left: Duration) -> Option<ReminderType> {
     if time_left <= Duration::zero() {
         return None;
@@ -263,46 +666,81 @@ fn eligible_reminder_type(time_left: Duration) -> Option<ReminderType> {
     }
 }
 
-pub async fn run_subscription_reminder_cycle(pool: &PgPool) -> anyhow::Result<()> {
-    let subscriptions = sqlx::query_as::<_, Subscription>(
-        r#"
-        SELECT id, station_id, starts_at, ends_at, status, created_at
-        FROM subscriptions
-        WHERE status = 'active'
-        "#,
+/// Records a reminder and notifies the station in one transaction, so each
+/// reminder goes out once even if the job is retried.
+async fn send_reminder(
+    pool: &PgPool,
+    subscription: &Subscription,
+    reminder_type: ReminderType,
+    title: &str,
+    body: &str,
+) -> anyhow::Result<()> {
+    let mut tx = pool.begin().await?;
+
+    if !create_reminder_log_once(&mut *tx, subscription.id, reminder_type).await? {
+        return Ok(());
+    }
+
+    let email = match reminder_type {
+        ReminderType::Expired => (&SUBSCRIPTION_EXPIRED, TemplateVars::new()),
+        _ => (
+            &SUBSCRIPTION_REMINDER,
+            TemplateVars::from_iter([("days_left".to_string(), reminder_type.days_left().into())]),
+        ),
+    };
+
+    notify_station(
+        &mut tx,
+        subscription.station_id,
+        Notice {
+            kind: SUBSCRIPTION_KIND,
+            title,
+            body,
+            email: Some(email),
+            dedupe_key: Some(format!(
+                "subscription-reminder:{}:{}",
+                subscription.id,
+                reminder_type.as_str()
+            )),
+        },
     )
-    .fetch_all(pool)
     .await?;
 
-    let now = Utc::now();
+    tx.commit().await?;
 
-    for subscription in subscriptions {
-        let station_email: Option<String> = sqlx::query_scalar(
-            r#"SELECT email FROM stations WHERE id = $1"#,
-        )
-        .bind(subscription.station_id)
-        .fetch_optional(pool)
-        .await?;
+    Ok(())
+}
 
-        let Some(station_email) = station_email else {
-            continue;
-        };
+pub async fn run_subscription_reminder_cycle(pool: &PgPool) -> anyhow::Result<()> {
+    resume_expired_pauses(pool).await?;
+
+    let subscriptions = sqlx::query_as::<_, Subscription>(&format!(
+        "SELECT {SUBSCRIPTION_COLUMNS} FROM subscriptions WHERE status = 'active'"
+    ))
+    .fetch_all(pool)
+    .await?;
+
+    let now = Utc::now();
 
+    for subscription in &subscriptions {
         if now >= subscription.ends_at {
-            if create_reminder_log_once(pool, subscription.id, ReminderType::Expired).await? {
-                let body = "Your subscription has expired. Please contact admin for renewal.";
-                create_dashboard_notification(
+            if !subscription.cancel_at_period_end {
+                send_reminder(
                     pool,
-                    subscription.station_id,
+                    subscription,
+                    ReminderType::Expired,
                     "Subscription expired",
-                    body,
-                    SUBSCRIPTION_KIND,
+                    "Your subscription has expired. Please renew from your dashboard.",
                 )
                 .await?;
-                send_subscription_email(pool, &station_email, "Subscription expired", body).await?;
             }
 
-            mark_subscription_expired(pool, subscription.id).await?;
+            end_subscription(pool, subscription).await?;
+            continue;
+        }
+
+        // The station already said it is leaving; no renewal reminders.
+        if subscription.cancel_at_period_end {
             continue;
         }
 
@@ -311,27 +749,19 @@ pub async fn run_subscription_reminder_cycle(pool: &PgPool) -> anyhow::Result<()
             continue;
         };
 
-        if !create_reminder_log_once(pool, subscription.id, reminder_type).await? {
-            continue;
-        }
-
-        let day_count = reminder_type.days_left();
         let body = format!(
             "Your subscription expires in {} day(s). Please renew to avoid interruption.",
-            day_count
+            reminder_type.days_left()
         );
 
-        create_dashboard_notification(
+        send_reminder(
             pool,
-            subscription.station_id,
+            subscription,
+            reminder_type,
             "Subscription reminder",
             &body,
-            SUBSCRIPTION_KIND,
         )
         .await?;
-
-        let subject = format!("Subscription expires in {} day(s)", day_count);
-        send_subscription_email(pool, &station_email, &subject, &body).await?;
     }
 
     Ok(())
@@ -0,0 +1,197 @@
+use axum::{
+    body::Body,
+    extract::{ConnectInfo, State},
+    http::{HeaderMap, Request},
+    middleware::Next,
+    response::Response,
+};
+use std::{
+    net::{IpAddr, SocketAddr},
+    str::FromStr,
+    sync::Arc,
+};
+
+/// Proxies trusted when `TRUSTED_PROXIES` is not set: only the local host.
+const DEFAULT_TRUSTED_PROXIES: &str = "127.0.0.0/8,::1/128";
+
+// ─── CIDR ranges ─────────────────────────────────────────────────────────────
+
+/// An IPv4 or IPv6 network such as `10.0.0.0/8`. A bare address is a
+/// single-host network.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cidr {
+    network: IpAddr,
+    prefix: u8,
+}
+
+impl Cidr {
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        match (self.network, canonical(ip)) {
+            (IpAddr::V4(network), IpAddr::V4(ip)) => {
+                prefix_matches(u32::from(network).into(), u32::from(ip).into(), 32, self.prefix)
+            }
+            (IpAddr::V6(network), IpAddr::V6(ip)) => {
+                prefix_matches(network.into(), ip.into(), 128, self.prefix)
+            }
+            _ => false,
+        }
+    }
+}
+
+fn prefix_matches(network: u128, ip: u128, bits: u8, prefix: u8) -> bool {
+    if prefix == 0 {
+        return true;
+    }
+    let shift = bits - prefix;
+    network >> shift == ip >> shift
+}
+
+impl FromStr for Cidr {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.trim();
+        let (address, prefix) = match value.split_once('/') {
+            Some((address, prefix)) => (address, Some(prefix)),
+            None => (value, None),
+        };
+
+        let network = canonical(
+            address
+                .parse::<IpAddr>()
+                .map_err(|_| format!("invalid proxy address: {value}"))?,
+        );
+        let max_prefix = if network.is_ipv4() { 32 } else { 128 };
+        let prefix = match prefix {
+            Some(prefix) => prefix
+                .parse::<u8>()
+                .ok()
+                .filter(|prefix| *prefix <= max_prefix)
+                .ok_or_else(|| format!("invalid proxy prefix: {value}"))?,
+            None => max_prefix,
+        };
+
+        Ok(Self { network, prefix })
+    }
+}
+
+/// IPv4-mapped IPv6 addresses (`::ffff:1.2.3.4`) compare as IPv4.
+fn canonical(ip: IpAddr) -> IpAddr {
+    match ip {
+        IpAddr::V6(v6) => v6.to_ipv4_mapped().map(IpAddr::V4).unwrap_or(ip),
+        IpAddr::V4(_) => ip,
+    }
+}
+
+// ─── Resolver ────────────────────────────────────────────────────────────────
+
+/// Works out the real client address behind a chain of reverse proxies.
+///
+/// Forwarding headers are only believed when the connecting peer is a
+/// trusted proxy; anyone else could set them to whatever they like.
+#[derive(Debug, Clone)]
+pub struct ClientIpResolver {
+    trusted_proxies: Vec<Cidr>,
+}
+
+impl ClientIpResolver {
+    pub fn new(trusted_proxies: Vec<Cidr>) -> Self {
+        Self { trusted_proxies }
+    }
+
+    /// Reads comma-separated CIDRs from `TRUSTED_PROXIES`, trusting only the
+    /// local host when unset. Invalid entries are logged and skipped.
+    pub fn from_env() -> Self {
+        let configured = std::env::var("TRUSTED_PROXIES")
+            .unwrap_or_else(|_| DEFAULT_TRUSTED_PROXIES.to_string());
+
+        let trusted_proxies = configured
+            .split(',')
+            .filter(|entry| !entry.trim().is_empty())
+            .filter_map(|entry| match entry.parse::<Cidr>() {
+                Ok(cidr) => Some(cidr),
+                Err(err) => {
+                    tracing::warn!("ignoring TRUSTED_PROXIES entry: {err}");
+                    None
+                }
+            })
+            .collect();
+
+        Self::new(trusted_proxies)
+    }
+
+    pub fn is_trusted(&self, ip: IpAddr) -> bool {
+        self.trusted_proxies.iter().any(|cidr| cidr.contains(ip))
+    }
+
+    /// Resolves the client address from the connecting `peer` and the
+    /// request headers.
+    ///
+    /// `X-Forwarded-For` is walked from the right, skipping trusted proxies;
+    /// the first untrusted hop is the client. Without that header a trusted
+    /// proxy may name the client in `CF-Connecting-IP` or `X-Real-IP`.
+    pub fn resolve(&self, peer: Option<IpAddr>, headers: &HeaderMap) -> Option<IpAddr> {
+        let peer = canonical(peer?);
+        if !self.is_trusted(peer) {
+            return Some(peer);
+        }
+
+        let chain: Vec<&str> = headers
+            .get_all("x-forwarded-for")
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .map(str::trim)
+            .filter(|hop| !hop.is_empty())
+            .collect();
+
+        if !chain.is_empty() {
+            let mut client = peer;
+            for hop in chain.iter().rev() {
+                // A hop we cannot parse was not written by a proxy we trust,
+                // so the last address we could vouch for is the answer.
+                let Ok(hop) = hop.parse::<IpAddr>().map(canonical) else {
+                    return Some(client);
+                };
+                client = hop;
+                if !self.is_trusted(hop) {
+                    return Some(hop);
+                }
+            }
+            return Some(client);
+        }
+
+        ["cf-connecting-ip", "x-real-ip"]
+            .iter()
+            .filter_map(|name| headers.get(*name))
+            .filter_map(|value| value.to_str().ok())
+            .find_map(|value| value.trim().parse::<IpAddr>().ok())
+            .map(canonical)
+            .or(Some(peer))
+    }
+}
+
+// ─── Axum middleware ─────────────────────────────────────────────────────────
+
+/// The resolved client address, put in the request extensions by
+/// [`resolve_client_ip`]. `None` when the server was not given connect info.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClientIp(pub Option<IpAddr>);
+
+/// Resolves the client address once per request so handlers and other
+/// middleware agree on it.
+pub async fn resolve_client_ip(
+    State(resolver): State<Arc<ClientIpResolver>>,
+    mut req: Request<Body>,
+    next: Next,
+) -> Response {
+    let peer = req
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip());
+
+    let client_ip = resolver.resolve(peer, req.headers());
+    req.extensions_mut().insert(ClientIp(client_ip));
+
+    next.run(req).await
+}
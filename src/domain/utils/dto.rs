@@ -4,10 +4,17 @@ use serde::Deserialize;
 pub struct StationQueryParam {
     pub longitude: String,
     pub latitude: String,
-    pub station_type: String
+    pub station_type: String,
+    pub open_now: Option<bool>,
+    /// Comma-separated amenity keys, e.g. `atm,shop`.
+    pub amenities: Option<String>,
+    /// Comma-separated payment method keys, e.g. `transfer,pos`.
+    pub payment_methods: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct AllStationsQuery {
-    pub station_type: Option<String>
+    pub station_type: Option<String>,
+    pub amenities: Option<String>,
+    pub payment_methods: Option<String>,
 }
\ No newline at end of file
@@ -0,0 +1,12 @@
+/// Whether `APP_ENV` marks this process as a development or test deployment.
+///
+/// Stand-ins that only make sense locally (derived signing keys, log-only
+/// delivery, the local payment provider) are refused unless this is set, so
+/// a production deployment missing real configuration fails at startup
+/// instead of quietly degrading.
+pub fn is_development() -> bool {
+    matches!(
+        std::env::var("APP_ENV").as_deref().map(str::trim),
+        Ok("development" | "dev" | "test")
+    )
+}
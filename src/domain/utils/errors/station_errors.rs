@@ -21,6 +21,11 @@ pub enum StationError {
 
     #[error("Database error: {0}")]
     DatabaseError(#[from] sqlx::Error),
+
+    /// Server-side failures outside the database (storage, configuration).
+    /// The detail is logged, never sent to the client.
+    #[error("Internal error: {0}")]
+    Internal(String),
 }
 
 // Helper struct for consistent JSON error responses
@@ -51,6 +56,13 @@ impl IntoResponse for StationError {
                     "Internal server error.".to_string(),
                 )
             }
+            StationError::Internal(err) => {
+                tracing::error!("Internal Error Occurred: {}", err);
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "Internal server error.".to_string(),
+                )
+            }
         };
 
         // Return the structured JSON error response
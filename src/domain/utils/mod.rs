@@ -5,3 +5,5 @@ pub mod schemas;
 pub mod dto;
 pub mod validate_boundary;
 pub mod rate_limiter;
+pub mod client_ip;
+pub mod environment;
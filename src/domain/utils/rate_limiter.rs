@@ -7,11 +7,12 @@ use axum::{
 };
 use dashmap::DashMap;
 use std::{
-    net::SocketAddr,
     sync::Arc,
     time::{Duration, Instant},
 };
 
+use super::client_ip::ClientIp;
+
 // ─── data stored per IP ─────────────────────────────────────────────────────
 
 struct WindowState {
@@ -70,28 +71,28 @@ impl RateLimiter {
             .map
             .retain(|_, ws| now.duration_since(ws.window_start) < window * 2);
     }
+
+    /// Runs `cleanup` every two windows for as long as the process lives.
+    pub fn spawn_cleanup(&self) {
+        let limiter = self.clone();
+        tokio::spawn(async move {
+            let interval = limiter.0.window * 2;
+            loop {
+                tokio::time::sleep(interval).await;
+                limiter.cleanup();
+            }
+        });
+    }
 }
 
 // ─── IP extraction ───────────────────────────────────────────────────────────
 
 fn extract_ip(req: &Request<Body>) -> String {
-    // Respect reverse-proxy headers first.
-    req.headers()
-        .get("x-forwarded-for")
-        .and_then(|v| v.to_str().ok())
-        .and_then(|s| s.split(',').next())
-        .map(|s| s.trim().to_string())
-        .or_else(|| {
-            req.headers()
-                .get("x-real-ip")
-                .and_then(|v| v.to_str().ok())
-                .map(|s| s.to_string())
-        })
-        .or_else(|| {
-            req.extensions()
-                .get::<axum::extract::ConnectInfo<SocketAddr>>()
-                .map(|ci| ci.0.ip().to_string())
-        })
+    // Resolved once per request by the client IP middleware.
+    req.extensions()
+        .get::<ClientIp>()
+        .and_then(|ClientIp(ip)| *ip)
+        .map(|ip| ip.to_string())
         .unwrap_or_else(|| "unknown".to_string())
 }
 
@@ -119,3 +120,24 @@ pub async fn closest_stations_rate_limit(
             .into_response()
     }
 }
+
+/// Axum `from_fn_with_state` middleware that rate-limits any endpoint by IP
+/// with the limiter it is given.
+pub async fn rate_limit(
+    State(limiter): State<RateLimiter>,
+    req: Request<Body>,
+    next: Next,
+) -> Response {
+    let ip = extract_ip(&req);
+
+    if limiter.is_allowed(&ip) {
+        next.run(req).await
+    } else {
+        (
+            StatusCode::TOO_MANY_REQUESTS,
+            [(header::CONTENT_TYPE, "application/json")],
+            r#"{"error":"Too many requests. Please wait a moment and try again."}"#,
+        )
+            .into_response()
+    }
+}
@@ -3,7 +3,7 @@ use std::collections::HashMap;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::domain::stations::model::Station;
+use crate::domain::{media::dto::StationImageResponse, stations::model::Station};
 
 
 #[derive(Debug, sqlx::FromRow, Serialize)]
@@ -47,6 +47,20 @@ pub struct StationResponse {
     pub created_at: chrono::NaiveDateTime,
     pub updated_at: chrono::NaiveDateTime,
     pub distance: Option<f64>,
+    #[serde(default)]
+    pub is_open: bool,
+    #[serde(default)]
+    pub closes_at: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(default)]
+    pub amenities: Vec<String>,
+    #[serde(default)]
+    pub payment_methods: Vec<String>,
+    #[serde(default)]
+    pub logo: Option<StationImageResponse>,
+    #[serde(default)]
+    pub photos: Vec<StationImageResponse>,
+    #[serde(default)]
+    pub is_verified: bool,
 
     pub commodities: Vec<CommoditiesResponse>,
 }
@@ -81,6 +95,13 @@ impl From<Vec<StationWithCommodity>> for StationResponse {
             created_at: first.created_at,
             updated_at: first.updated_at,
             distance: first.distance, // Carrying over the Option<f64>
+            is_open: false,
+            closes_at: None,
+            amenities: vec![],
+            payment_methods: vec![],
+            logo: None,
+            photos: vec![],
+            is_verified: false,
 
             // Map each row's commodity fields into the nested struct
             commodities: rows
@@ -122,6 +143,13 @@ pub fn map_rows_to_stations(rows: Vec<StationWithCommodity>) -> Vec<StationRespo
                 created_at: row.created_at,
                 updated_at: row.updated_at,
                 distance: row.distance,
+                is_open: false,
+                closes_at: None,
+                amenities: Vec::new(),
+                payment_methods: Vec::new(),
+                logo: None,
+                photos: Vec::new(),
+                is_verified: false,
                 commodities: Vec::new(),
             });
 
@@ -168,6 +196,13 @@ impl From<Station> for StationResponse {
             created_at: station.created_at,
             updated_at: station.updated_at,
             distance: Some(0.0),
+            is_open: false,
+            closes_at: None,
+            amenities: vec![],
+            payment_methods: vec![],
+            logo: None,
+            photos: vec![],
+            is_verified: false,
             commodities: vec![],
         }
     }
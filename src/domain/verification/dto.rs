@@ -0,0 +1,18 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use super::model::{VerificationDocument, VerificationRequest};
+
+#[derive(Debug, Serialize)]
+pub struct VerificationRequestResponse {
+    #[serde(flatten)]
+    pub request: VerificationRequest,
+    pub documents: Vec<VerificationDocument>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StationVerificationResponse {
+    pub verification_status: String,
+    pub verified_at: Option<DateTime<Utc>>,
+    pub latest_request: Option<VerificationRequestResponse>,
+}
@@ -0,0 +1,3 @@
+pub mod dto;
+pub mod model;
+pub mod service;
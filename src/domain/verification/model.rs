@@ -0,0 +1,31 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Documents a station can submit to get verified.
+pub const DOCUMENT_TYPES: [&str; 2] = ["business_registration", "dpr_licence"];
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct VerificationRequest {
+    pub id: Uuid,
+    pub station_id: Uuid,
+    pub station_name: String,
+    pub document_type: String,
+    pub document_number: String,
+    pub status: String,
+    pub review_reason: Option<String>,
+    pub reviewed_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct VerificationDocument {
+    pub id: Uuid,
+    pub request_id: Uuid,
+    #[serde(skip)]
+    pub storage_key: String,
+    pub content_type: String,
+    pub size_bytes: i32,
+    pub created_at: DateTime<Utc>,
+}
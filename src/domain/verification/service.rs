@@ -0,0 +1,547 @@
+use std::collections::HashMap;
+
+use axum::{
+    Json,
+    extract::{Extension, Multipart, State},
+    http::StatusCode,
+};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    app_state::AppState,
+    authentication::station::authenticate::token::service::Claims,
+    domain::{
+        notifications::service::create_dashboard_notification,
+        utils::{errors::station_errors::StationError, schemas::StationResponse},
+        verification::{
+            dto::{StationVerificationResponse, VerificationRequestResponse},
+            model::{DOCUMENT_TYPES, VerificationDocument, VerificationRequest},
+        },
+    },
+};
+
+/// Largest accepted verification document.
+pub const MAX_DOCUMENT_BYTES: usize = 5 * 1024 * 1024;
+pub const MAX_DOCUMENTS_PER_REQUEST: usize = 5;
+const ALLOWED_DOCUMENT_TYPES: [(&str, &str); 3] = [
+    ("application/pdf", "pdf"),
+    ("image/jpeg", "jpg"),
+    ("image/png", "png"),
+];
+const VERIFICATION_KIND: &str = "verification";
+
+const VERIFICATION_REQUEST_COLUMNS: &str = r#"
+    r.id,
+    r.station_id,
+    s.name AS station_name,
+    r.document_type,
+    r.document_number,
+    r.status,
+    r.review_reason,
+    r.reviewed_at,
+    r.created_at
+"#;
+
+pub struct VerificationService;
+
+impl VerificationService {
+    pub async fn get_verification(
+        State(app_state): State<AppState>,
+        Extension(claims): Extension<Claims>,
+    ) -> Result<Json<StationVerificationResponse>, StationError> {
+        let station_id = claims.station_res.id;
+
+        let response = load_station_verification(&app_state.pool, station_id)
+            .await
+            .map_err(StationError::DatabaseError)?;
+
+        Ok(Json(response))
+    }
+
+    pub async fn submit_verification(
+        State(app_state): State<AppState>,
+        Extension(claims): Extension<Claims>,
+        mut multipart: Multipart,
+    ) -> Result<(StatusCode, Json<StationVerificationResponse>), StationError> {
+        let station_id = claims.station_res.id;
+
+        let mut document_type: Option<String> = None;
+        let mut document_number: Option<String> = None;
+        let mut documents: Vec<(String, Vec<u8>)> = Vec::new();
+
+        while let Some(field) = multipart
+            .next_field()
+            .await
+            .map_err(|err| StationError::WrongCredentials(err.body_text()))?
+        {
+            match field.name() {
+                Some("document_type") => {
+                    let value = field
+                        .text()
+                        .await
+                        .map_err(|err| StationError::WrongCredentials(err.body_text()))?;
+                    document_type = Some(value.trim().to_ascii_lowercase());
+                }
+                Some("document_number") => {
+                    let value = field
+                        .text()
+                        .await
+                        .map_err(|err| StationError::WrongCredentials(err.body_text()))?;
+                    document_number = Some(value.trim().to_string());
+                }
+                Some("documents") => {
+                    let content_type = field.content_type().unwrap_or_default().to_string();
+                    let bytes = field
+                        .bytes()
+                        .await
+                        .map_err(|err| StationError::WrongCredentials(err.body_text()))?;
+                    documents.push((content_type, bytes.to_vec()));
+                }
+                _ => {}
+            }
+        }
+
+        let document_type = document_type
+            .filter(|t| DOCUMENT_TYPES.contains(&t.as_str()))
+            .ok_or_else(|| {
+                StationError::WrongCredentials(format!(
+                    "document_type must be one of: {}",
+                    DOCUMENT_TYPES.join(", ")
+                ))
+            })?;
+        let document_number = document_number
+            .filter(|n| !n.is_empty() && n.len() <= 64)
+            .ok_or_else(|| {
+                StationError::WrongCredentials(
+                    "document_number is required (max 64 characters)".to_string(),
+                )
+            })?;
+
+        if documents.is_empty() || documents.len() > MAX_DOCUMENTS_PER_REQUEST {
+            return Err(StationError::WrongCredentials(format!(
+                "between 1 and {MAX_DOCUMENTS_PER_REQUEST} documents are required"
+            )));
+        }
+
+        for (content_type, bytes) in &documents {
+            validate_document(content_type, bytes)?;
+        }
+
+        let verification_status: String =
+            sqlx::query_scalar("SELECT verification_status FROM stations WHERE id = $1")
+                .bind(station_id)
+                .fetch_optional(&app_state.pool)
+                .await
+                .map_err(StationError::DatabaseError)?
+                .ok_or_else(|| StationError::NotFound(station_id.to_string()))?;
+
+        match verification_status.as_str() {
+            "verified" => {
+                return Err(StationError::WrongCredentials(
+                    "station is already verified".to_string(),
+                ));
+            }
+            "pending" => return Err(StationError::AlreadyExists),
+            _ => {}
+        }
+
+        let request_id = Uuid::new_v4();
+        let mut stored = Vec::with_capacity(documents.len());
+
+        for (content_type, bytes) in documents {
+            let document_id = Uuid::new_v4();
+            let extension = ALLOWED_DOCUMENT_TYPES
+                .iter()
+                .find(|(allowed, _)| *allowed == content_type)
+                .map(|(_, extension)| *extension)
+                .unwrap_or("bin");
+            let storage_key =
+                format!("verification/{station_id}/{request_id}/{document_id}.{extension}");
+            let size_bytes = bytes.len() as i32;
+
+            if let Err(err) = app_state
+                .documents
+                .put(&storage_key, bytes, &content_type)
+                .await
+            {
+                delete_stored_documents(&app_state, &stored).await;
+                return Err(StationError::Internal(format!(
+                    "failed to store verification document: {err}"
+                )));
+            }
+
+            stored.push((document_id, storage_key, content_type, size_bytes));
+        }
+
+        let saved = save_verification_request(
+            &app_state.pool,
+            station_id,
+            request_id,
+            &document_type,
+            &document_number,
+            &stored,
+        )
+        .await;
+
+        // The documents are already stored; don't leave them behind without rows.
+        if let Err(err) = saved {
+            delete_stored_documents(&app_state, &stored).await;
+            return Err(StationError::DatabaseError(err));
+        }
+
+        let response = load_station_verification(&app_state.pool, station_id)
+            .await
+            .map_err(StationError::DatabaseError)?;
+
+        Ok((StatusCode::CREATED, Json(response)))
+    }
+}
+
+/// (document id, storage key, content type, size in bytes) of an uploaded file.
+type StoredDocument = (Uuid, String, String, i32);
+
+/// Records the request and its documents and marks the station pending.
+async fn save_verification_request(
+    pool: &PgPool,
+    station_id: Uuid,
+    request_id: Uuid,
+    document_type: &str,
+    document_number: &str,
+    stored: &[StoredDocument],
+) -> Result<(), sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO station_verification_requests (id, station_id, document_type, document_number)
+        VALUES ($1, $2, $3, $4)
+        "#,
+    )
+    .bind(request_id)
+    .bind(station_id)
+    .bind(document_type)
+    .bind(document_number)
+    .execute(&mut *tx)
+    .await?;
+
+    for (document_id, storage_key, content_type, size_bytes) in stored {
+        sqlx::query(
+            r#"
+            INSERT INTO station_verification_documents (id, request_id, storage_key, content_type, size_bytes)
+            VALUES ($1, $2, $3, $4, $5)
+            "#,
+        )
+        .bind(document_id)
+        .bind(request_id)
+        .bind(storage_key)
+        .bind(content_type)
+        .bind(size_bytes)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    sqlx::query(
+        r#"
+        UPDATE stations
+        SET verification_status = 'pending', updated_at = NOW()
+        WHERE id = $1
+        "#,
+    )
+    .bind(station_id)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await
+}
+
+async fn delete_stored_documents(app_state: &AppState, stored: &[StoredDocument]) {
+    for (_, storage_key, _, _) in stored {
+        if let Err(err) = app_state.documents.delete(storage_key).await {
+            tracing::warn!("failed to delete verification document {}: {:?}", storage_key, err);
+        }
+    }
+}
+
+/// Checks the declared type against the allow-list and the file's magic bytes.
+fn validate_document(content_type: &str, bytes: &[u8]) -> Result<(), StationError> {
+    if bytes.is_empty() || bytes.len() > MAX_DOCUMENT_BYTES {
+        return Err(StationError::WrongCredentials(format!(
+            "each document must be between 1 byte and {} MB",
+            MAX_DOCUMENT_BYTES / (1024 * 1024)
+        )));
+    }
+
+    if !ALLOWED_DOCUMENT_TYPES.iter().any(|(allowed, _)| *allowed == content_type) {
+        return Err(StationError::WrongCredentials(
+            "documents must be PDF, JPEG or PNG files".to_string(),
+        ));
+    }
+
+    let matches_content = match content_type {
+        "application/pdf" => bytes.starts_with(b"%PDF-"),
+        "image/jpeg" => image::guess_format(bytes).ok() == Some(image::ImageFormat::Jpeg),
+        "image/png" => image::guess_format(bytes).ok() == Some(image::ImageFormat::Png),
+        _ => false,
+    };
+
+    if !matches_content {
+        return Err(StationError::WrongCredentials(
+            "document content does not match its declared type".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+async fn load_station_verification(
+    pool: &PgPool,
+    station_id: Uuid,
+) -> Result<StationVerificationResponse, sqlx::Error> {
+    let (verification_status, verified_at) = sqlx::query_as::<_, (String, Option<chrono::DateTime<chrono::Utc>>)>(
+        "SELECT verification_status, verified_at FROM stations WHERE id = $1",
+    )
+    .bind(station_id)
+    .fetch_one(pool)
+    .await?;
+
+    let latest = sqlx::query_as::<_, VerificationRequest>(&format!(
+        r#"
+        SELECT {VERIFICATION_REQUEST_COLUMNS}
+        FROM station_verification_requests r
+        INNER JOIN stations s ON s.id = r.station_id
+        WHERE r.station_id = $1
+        ORDER BY r.created_at DESC
+        LIMIT 1
+        "#
+    ))
+    .bind(station_id)
+    .fetch_optional(pool)
+    .await?;
+
+    let latest_request = match latest {
+        Some(request) => Some(with_documents(pool, request).await?),
+        None => None,
+    };
+
+    Ok(StationVerificationResponse {
+        verification_status,
+        verified_at,
+        latest_request,
+    })
+}
+
+async fn with_documents(
+    pool: &PgPool,
+    request: VerificationRequest,
+) -> Result<VerificationRequestResponse, sqlx::Error> {
+    let documents = sqlx::query_as::<_, VerificationDocument>(
+        r#"
+        SELECT id, request_id, storage_key, content_type, size_bytes, created_at
+        FROM station_verification_documents
+        WHERE request_id = $1
+        ORDER BY created_at
+        "#,
+    )
+    .bind(request.id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(VerificationRequestResponse { request, documents })
+}
+
+pub async fn list_verification_requests(
+    pool: &PgPool,
+    status: Option<&str>,
+) -> Result<Vec<VerificationRequestResponse>, sqlx::Error> {
+    let requests = sqlx::query_as::<_, VerificationRequest>(&format!(
+        r#"
+        SELECT {VERIFICATION_REQUEST_COLUMNS}
+        FROM station_verification_requests r
+        INNER JOIN stations s ON s.id = r.station_id
+        WHERE ($1::text IS NULL OR r.status = $1)
+        ORDER BY r.created_at ASC
+        "#
+    ))
+    .bind(status)
+    .fetch_all(pool)
+    .await?;
+
+    let request_ids: Vec<Uuid> = requests.iter().map(|request| request.id).collect();
+
+    let documents = sqlx::query_as::<_, VerificationDocument>(
+        r#"
+        SELECT id, request_id, storage_key, content_type, size_bytes, created_at
+        FROM station_verification_documents
+        WHERE request_id = ANY($1)
+        ORDER BY created_at
+        "#,
+    )
+    .bind(&request_ids)
+    .fetch_all(pool)
+    .await?;
+
+    let mut documents_by_request: HashMap<Uuid, Vec<VerificationDocument>> = HashMap::new();
+    for document in documents {
+        documents_by_request
+            .entry(document.request_id)
+            .or_default()
+            .push(document);
+    }
+
+    Ok(requests
+        .into_iter()
+        .map(|request| VerificationRequestResponse {
+            documents: documents_by_request.remove(&request.id).unwrap_or_default(),
+            request,
+        })
+        .collect())
+}
+
+pub async fn find_verification_document(
+    pool: &PgPool,
+    document_id: Uuid,
+) -> Result<Option<VerificationDocument>, sqlx::Error> {
+    sqlx::query_as::<_, VerificationDocument>(
+        r#"
+        SELECT id, request_id, storage_key, content_type, size_bytes, created_at
+        FROM station_verification_documents
+        WHERE id = $1
+        "#,
+    )
+    .bind(document_id)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Approves or rejects a pending verification request and updates the
+/// station's badge. Rejections must carry a reason for the station.
+pub async fn review_verification_request(
+    pool: &PgPool,
+    request_id: Uuid,
+    admin_id: Uuid,
+    approved: bool,
+    reason: Option<String>,
+) -> Result<VerificationRequestResponse, StationError> {
+    if !approved && reason.is_none() {
+        return Err(StationError::WrongCredentials(
+            "a reason is required when rejecting verification".to_string(),
+        ));
+    }
+
+    let mut tx = pool.begin().await.map_err(StationError::DatabaseError)?;
+
+    let (station_id, status) = sqlx::query_as::<_, (Uuid, String)>(
+        r#"
+        SELECT station_id, status
+        FROM station_verification_requests
+        WHERE id = $1
+        FOR UPDATE
+        "#,
+    )
+    .bind(request_id)
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(StationError::DatabaseError)?
+    .ok_or_else(|| StationError::NotFound(request_id.to_string()))?;
+
+    if status != "pending" {
+        return Err(StationError::WrongCredentials(format!(
+            "verification request is already {status}"
+        )));
+    }
+
+    sqlx::query(
+        r#"
+        UPDATE station_verification_requests
+        SET status = $1, review_reason = $2, reviewed_by_admin = $3, reviewed_at = now()
+        WHERE id = $4
+        "#,
+    )
+    .bind(if approved { "approved" } else { "rejected" })
+    .bind(&reason)
+    .bind(admin_id)
+    .bind(request_id)
+    .execute(&mut *tx)
+    .await
+    .map_err(StationError::DatabaseError)?;
+
+    sqlx::query(
+        r#"
+        UPDATE stations
+        SET verification_status = $1,
+            verified_at = CASE WHEN $1 = 'verified' THEN now() ELSE NULL END,
+            updated_at = NOW()
+        WHERE id = $2
+        "#,
+    )
+    .bind(if approved { "verified" } else { "rejected" })
+    .bind(station_id)
+    .execute(&mut *tx)
+    .await
+    .map_err(StationError::DatabaseError)?;
+
+    tx.commit().await.map_err(StationError::DatabaseError)?;
+
+    let body = match reason.as_deref() {
+        _ if approved => "Your station has been verified. Drivers now see a verified badge.".to_string(),
+        Some(reason) => format!("Your verification request was rejected: {reason}"),
+        None => "Your verification request was rejected.".to_string(),
+    };
+
+    // The review is committed; a failed notification must not report it as failed.
+    if let Err(err) =
+        create_dashboard_notification(pool, station_id, "Verification reviewed", &body, VERIFICATION_KIND)
+            .await
+    {
+        tracing::error!(
+            "failed to notify station {} of verification review {}: {err:?}",
+            station_id,
+            request_id
+        );
+    }
+
+    let request = sqlx::query_as::<_, VerificationRequest>(&format!(
+        r#"
+        SELECT {VERIFICATION_REQUEST_COLUMNS}
+        FROM station_verification_requests r
+        INNER JOIN stations s ON s.id = r.station_id
+        WHERE r.id = $1
+        "#
+    ))
+    .bind(request_id)
+    .fetch_one(pool)
+    .await
+    .map_err(StationError::DatabaseError)?;
+
+    with_documents(pool, request)
+        .await
+        .map_err(StationError::DatabaseError)
+}
+
+/// Fills in the `is_verified` badge on each station.
+pub async fn attach_verification(
+    pool: &PgPool,
+    stations: &mut [StationResponse],
+) -> Result<(), sqlx::Error> {
+    if stations.is_empty() {
+        return Ok(());
+    }
+
+    let ids: Vec<Uuid> = stations.iter().map(|s| s.id).collect();
+
+    let rows = sqlx::query_as::<_, (Uuid, String)>(
+        "SELECT id, verification_status FROM stations WHERE id = ANY($1)",
+    )
+    .bind(&ids)
+    .fetch_all(pool)
+    .await?;
+
+    let statuses: HashMap<Uuid, String> = rows.into_iter().collect();
+
+    for station in stations.iter_mut() {
+        station.is_verified = statuses.get(&station.id).map(String::as_str) == Some("verified");
+    }
+
+    Ok(())
+}
@@ -3,11 +3,15 @@
 use axum::{
     Router,
     http::StatusCode,
+    middleware::from_fn_with_state,
     routing::get,
 };
 use http::{HeaderName, header::{AUTHORIZATION, CONTENT_TYPE}};
 use std::collections::HashSet;
-use tower_http::cors::{Any, CorsLayer};
+use tower_http::{
+    cors::{Any, CorsLayer},
+    services::ServeDir,
+};
 
 pub mod app_state;
 pub mod authentication;
@@ -17,12 +21,16 @@ use crate::{
     app_state::AppState,
     authentication::{
         admin::routes::admin_routes,
+        middleware::entitlements::enforce_entitlements,
         station::authenticate::routes::auth_routes,
     },
     domain::{
         commodities::routes::commodities_route,
         discounts::routes::discounts_route,
-        stations::routes::stations_route,
+        loyalty::routes::loyalty_route,
+        payments::routes::payments_route,
+        stations::routes::{billing_route, stations_route},
+        utils::client_ip::resolve_client_ip,
     },
 };
 
@@ -61,23 +69,42 @@ pub fn build_app(app_state: AppState) -> Router {
             AUTHORIZATION,
             CONTENT_TYPE,
             HeaderName::from_static("x-admin-password"),
+            HeaderName::from_static("x-loyalty-key"),
         ])
         .expose_headers([
             HeaderName::from_static("x-admin-password"),
         ]);
 
-    Router::new()
-        .route("/healthz", get(healthz))
+    let client_ip = app_state.client_ip.clone();
+    // Station routes that depend on a live subscription; billing is merged
+    // in outside it.
+    let entitlements = from_fn_with_state(app_state.clone(), enforce_entitlements);
+
+    let mut router = Router::new().route("/healthz", get(healthz));
+
+    if let Some(media_root) = app_state.media.local_root() {
+        router = router.nest_service("/media", ServeDir::new(media_root));
+    }
+
+    router
         .nest(
             "/api/v1",
             Router::new()
                 .route("/healthz", get(healthz))
                 .nest("/auth", auth_routes())
-                .nest("/stations", stations_route())
-                .nest("/commodities", commodities_route())
-                .nest("/discounts", discounts_route())
+                .nest(
+                    "/stations",
+                    stations_route()
+                        .route_layer(entitlements.clone())
+                        .merge(billing_route()),
+                )
+                .nest("/commodities", commodities_route().route_layer(entitlements.clone()))
+                .nest("/discounts", discounts_route().route_layer(entitlements.clone()))
+                .nest("/loyalty", loyalty_route().route_layer(entitlements))
+                .nest("/payments", payments_route())
                 .nest("/admin", admin_routes()),
         )
         .with_state(app_state)
+        .layer(from_fn_with_state(client_ip, resolve_client_ip))
         .layer(cors)
 }
\ No newline at end of file
@@ -1,10 +1,12 @@
 #![forbid(clippy::unwrap_used)]
 
+use std::net::SocketAddr;
+
 use fuelfinder_server::{
     app_state::AppState,
     build_app,
     domain::{
-        subscriptions::worker::start as start_subscription_worker,
+        jobs::worker::start as start_job_worker, notifications::channels::Channels,
         utils::setup_tracing::setup_tracing,
     },
     listen_addr,
@@ -22,9 +24,10 @@ async fn main() {
 
     let app_state = AppState::init()
         .await
-        .expect("Failed to initialize database");
+        .expect("Failed to initialize app state");
 
-    tokio::spawn(start_subscription_worker(app_state.pool.clone()));
+    let channels = Channels::from_env().expect("Failed to configure notification channels");
+    tokio::spawn(start_job_worker(app_state.pool.clone(), channels));
 
     let app = build_app(app_state);
 
@@ -38,7 +41,11 @@ async fn main() {
         listener.local_addr().expect("Failed to get local address")
     );
 
-    axum::serve(listener, app)
+    // Connect info gives the client IP resolver the real peer address.
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
         .await
         .expect("Failed to start server");
 }
@@ -155,7 +155,7 @@ async fn signup_signin_and_dashboard_happy_path() {
 
 #[tokio::test]
 #[serial]
-async fn renew_subscription_creates_new_active_subscription() {
+async fn paying_an_invoice_renews_the_subscription() {
     let Some(pool) = db_pool().await else {
         eprintln!("Skipping DB-backed auth test: TEST_DATABASE_URL not set");
         return;
@@ -200,22 +200,48 @@ async fn renew_subscription_creates_new_active_subscription() {
 
     let station_id = station_id_by_email(&pool, &email).await;
 
-    let renew_response = call(
-        app,
-        request_with_json(
+    let invoice_response = call(
+        app.clone(),
+        common::request_with_headers_and_json(
             "POST",
-            "/api/v1/auth/subscriptions/renew",
-            json!({
-                "station_id": station_id,
-                "days": 15,
-                "super_password": "super-secret"
-            }),
+            &format!("/api/v1/admin/stations/{station_id}/invoices"),
+            &[("x-admin-password", "super-secret")],
+            json!({ "plan_code": "monthly" }),
         ),
     )
     .await;
+    assert_eq!(invoice_response.status(), StatusCode::CREATED);
+    let invoice: Value = decode_json(invoice_response).await;
+    let invoice_id = invoice["id"].as_str().unwrap().to_string();
 
+    let renew = || {
+        request_with_json(
+            "POST",
+            "/api/v1/auth/subscriptions/renew",
+            json!({ "invoice_id": invoice_id, "super_password": "super-secret" }),
+        )
+    };
+
+    let renew_response = call(app.clone(), renew()).await;
     assert_eq!(renew_response.status(), StatusCode::OK);
 
+    let paid_again = call(app, renew()).await;
+    assert_eq!(paid_again.status(), StatusCode::UNAUTHORIZED);
+
+    // The 30-day trial's remaining time carries over into the paid month.
+    let days_left: f64 = sqlx::query_scalar(
+        r#"
+        SELECT EXTRACT(EPOCH FROM ends_at - now())::FLOAT8 / 86400
+        FROM subscriptions
+        WHERE station_id = $1 AND status = 'active'
+        "#,
+    )
+    .bind(station_id)
+    .fetch_one(&pool)
+    .await
+    .expect("active subscription should exist");
+    assert!((59.9..60.1).contains(&days_left), "{days_left}");
+
     let active_count: i64 = sqlx::query_scalar(
         "SELECT COUNT(*) FROM subscriptions WHERE station_id = $1 AND status = 'active'",
     )
@@ -0,0 +1,383 @@
+mod common;
+
+use std::sync::Arc;
+
+use axum::{Router, http::StatusCode};
+use fuelfinder_server::domain::{
+    emails::transport::MemoryEmailTransport, jobs::worker::run_pending_jobs,
+};
+use serde_json::{Value, json};
+use serial_test::serial;
+use uuid::Uuid;
+
+use common::{
+    call, channels_with_email, create_station_and_signin, db_pool, decode_json, log_channels,
+    request, request_with_auth, request_with_headers, request_with_headers_and_json, reset_db,
+    seed_admin, test_app, test_app_with_pool,
+};
+
+const ADMIN: (&str, &str) = ("x-admin-password", "super-secret");
+
+async fn create_broadcast(app: Router, body: Value) -> axum::response::Response {
+    call(
+        app,
+        request_with_headers_and_json("POST", "/api/v1/admin/broadcasts", &[ADMIN], body),
+    )
+    .await
+}
+
+async fn broadcast_report(app: Router, broadcast_id: &str) -> Value {
+    let response = call(
+        app,
+        request_with_headers(
+            "GET",
+            &format!("/api/v1/admin/broadcasts/{broadcast_id}"),
+            &[ADMIN],
+        ),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    decode_json(response).await
+}
+
+async fn announcements(pool: &sqlx::PgPool, station_id: Uuid) -> Vec<String> {
+    sqlx::query_scalar(
+        "SELECT title FROM notifications WHERE station_id = $1 AND kind = 'announcement' ORDER BY created_at",
+    )
+    .bind(station_id)
+    .fetch_all(pool)
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn admin_broadcasts_route_exists() {
+    let response = call(test_app(), request("DELETE", "/api/v1/admin/broadcasts")).await;
+    assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+}
+
+#[tokio::test]
+#[serial]
+async fn broadcasts_reach_their_audience_with_delivery_and_read_reporting() {
+    let Some(pool) = db_pool().await else {
+        eprintln!("Skipping DB-backed broadcasts test: TEST_DATABASE_URL not set");
+        return;
+    };
+
+    reset_db(&pool).await;
+    seed_admin(&pool, "super-secret").await;
+
+    let app = test_app_with_pool(pool.clone());
+    let jabi = format!("{}@example.com", Uuid::new_v4().simple());
+    let kubwa = format!("{}@example.com", Uuid::new_v4().simple());
+    let utako = format!("{}@example.com", Uuid::new_v4().simple());
+    let (jabi_id, jabi_token) =
+        create_station_and_signin(app.clone(), &jabi, "gas", (9.06, 7.41)).await;
+    let (kubwa_id, kubwa_token) =
+        create_station_and_signin(app.clone(), &kubwa, "gas", (9.20, 7.30)).await;
+    let (utako_id, _) =
+        create_station_and_signin(app.clone(), &utako, "petrol", (9.07, 7.42)).await;
+
+    let unauthorized = call(
+        app.clone(),
+        request_with_headers_and_json(
+            "POST",
+            "/api/v1/admin/broadcasts",
+            &[("x-admin-password", "wrong")],
+            json!({ "title": "Hi", "body": "Hello", "audience": "all" }),
+        ),
+    )
+    .await;
+    assert_eq!(unauthorized.status(), StatusCode::UNAUTHORIZED);
+
+    let invalid = create_broadcast(
+        app.clone(),
+        json!({ "title": "Hi", "body": "Hello", "audience": "area", "latitude": 9.06 }),
+    )
+    .await;
+    assert_eq!(invalid.status(), StatusCode::UNAUTHORIZED);
+
+    let unknown_station = create_broadcast(
+        app.clone(),
+        json!({
+            "title": "Hi",
+            "body": "Hello",
+            "audience": "stations",
+            "station_ids": [Uuid::new_v4()]
+        }),
+    )
+    .await;
+    assert_eq!(unknown_station.status(), StatusCode::NOT_FOUND);
+
+    // Kubwa mutes announcements on the dashboard but still gets the email.
+    let muted = call(
+        app.clone(),
+        request_with_headers_and_json(
+            "PUT",
+            "/api/v1/stations/dashboard/notification-preferences/announcement",
+            &[("authorization", &format!("Bearer {kubwa_token}"))],
+            json!({ "in_app": false }),
+        ),
+    )
+    .await;
+    assert_eq!(muted.status(), StatusCode::OK);
+
+    let area = create_broadcast(
+        app.clone(),
+        json!({
+            "title": "Price cap",
+            "body": "New regulator price cap effective tomorrow.",
+            "audience": "area",
+            "latitude": 9.06,
+            "longitude": 7.41,
+            "radius_km": 3,
+            "send_email": true,
+            "scheduled_for": "2099-01-01T08:00:00Z"
+        }),
+    )
+    .await;
+    assert_eq!(area.status(), StatusCode::CREATED);
+    let area: Value = decode_json(area).await;
+    let area_id = area["id"].as_str().unwrap().to_string();
+    assert_eq!(area["status"], "scheduled");
+    assert_eq!(area["recipients"], 0);
+
+    let petrol = create_broadcast(
+        app.clone(),
+        json!({
+            "title": "Petrol only",
+            "body": "Check your pump calibration.",
+            "audience": "station_type",
+            "station_type": "petrol"
+        }),
+    )
+    .await;
+    let petrol: Value = decode_json(petrol).await;
+
+    let listed = create_broadcast(
+        app.clone(),
+        json!({
+            "title": "Gas stations",
+            "body": "Cylinder inspection next week.",
+            "audience": "stations",
+            "station_ids": [jabi_id, kubwa_id],
+            "send_email": true
+        }),
+    )
+    .await;
+    let listed: Value = decode_json(listed).await;
+
+    let transport = Arc::new(MemoryEmailTransport::default());
+    let channels = channels_with_email(transport.clone());
+    run_pending_jobs(&pool, &channels, "test-worker", 20)
+        .await
+        .unwrap();
+
+    // The area broadcast is not due yet.
+    assert_eq!(
+        broadcast_report(app.clone(), &area_id).await["status"],
+        "scheduled"
+    );
+    assert_eq!(announcements(&pool, utako_id).await, vec!["Petrol only"]);
+    assert_eq!(announcements(&pool, jabi_id).await, vec!["Gas stations"]);
+    assert!(announcements(&pool, kubwa_id).await.is_empty());
+    assert_eq!(transport.sent().len(), 2);
+
+    let report = broadcast_report(app.clone(), listed["id"].as_str().unwrap()).await;
+    assert_eq!(report["status"], "sent");
+    assert_eq!(report["recipients"], 2);
+    assert_eq!(report["in_app_delivered"], 1);
+    assert_eq!(report["emails_queued"], 2);
+    assert_eq!(report["emails_sent"], 2);
+    assert_eq!(report["read_count"], 0);
+
+    let petrol_report = broadcast_report(app.clone(), petrol["id"].as_str().unwrap()).await;
+    assert_eq!(petrol_report["recipients"], 1);
+    assert_eq!(petrol_report["emails_queued"], 0);
+
+    sqlx::query("UPDATE jobs SET run_at = now() WHERE kind = 'send_broadcast'")
+        .execute(&pool)
+        .await
+        .unwrap();
+    run_pending_jobs(&pool, &log_channels(), "test-worker", 20)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        announcements(&pool, jabi_id).await,
+        vec!["Gas stations", "Price cap"]
+    );
+    assert_eq!(
+        announcements(&pool, utako_id).await,
+        vec!["Petrol only", "Price cap"]
+    );
+    assert!(announcements(&pool, kubwa_id).await.is_empty());
+
+    let jabi_notifications: Value = decode_json(
+        call(
+            app.clone(),
+            request_with_auth(
+                "GET",
+                "/api/v1/stations/dashboard/notifications?kind=announcement",
+                &jabi_token,
+            ),
+        )
+        .await,
+    )
+    .await;
+    let price_cap = jabi_notifications
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|notification| notification["title"] == "Price cap")
+        .unwrap();
+    let read = call(
+        app.clone(),
+        request_with_auth(
+            "PATCH",
+            &format!(
+                "/api/v1/stations/dashboard/notifications/{}/read",
+                price_cap["id"].as_str().unwrap()
+            ),
+            &jabi_token,
+        ),
+    )
+    .await;
+    assert_eq!(read.status(), StatusCode::NO_CONTENT);
+
+    let report = broadcast_report(app.clone(), &area_id).await;
+    assert_eq!(report["status"], "sent");
+    assert_eq!(report["recipients"], 2);
+    assert_eq!(report["in_app_delivered"], 2);
+    assert_eq!(report["read_count"], 1);
+    assert_eq!(report["read_rate"], 0.5);
+    assert_eq!(report["emails_queued"], 2);
+    assert_eq!(report["emails_sent"], 2);
+
+    let sent: Value = decode_json(
+        call(
+            app,
+            request_with_headers("GET", "/api/v1/admin/broadcasts?status=sent", &[ADMIN]),
+        )
+        .await,
+    )
+    .await;
+    assert_eq!(sent.as_array().unwrap().len(), 3);
+}
+
+#[tokio::test]
+#[serial]
+async fn cancelled_broadcasts_are_never_sent() {
+    let Some(pool) = db_pool().await else {
+        eprintln!("Skipping DB-backed broadcasts test: TEST_DATABASE_URL not set");
+        return;
+    };
+
+    reset_db(&pool).await;
+    seed_admin(&pool, "super-secret").await;
+
+    let app = test_app_with_pool(pool.clone());
+    let email = format!("{}@example.com", Uuid::new_v4().simple());
+    let (station_id, _) = create_station_and_signin(app.clone(), &email, "gas", (9.06, 7.41)).await;
+
+    let created = create_broadcast(
+        app.clone(),
+        json!({
+            "title": "Maintenance",
+            "body": "The dashboard is down on Sunday.",
+            "audience": "all",
+            "scheduled_for": "2099-01-01T08:00:00Z"
+        }),
+    )
+    .await;
+    let created: Value = decode_json(created).await;
+    let cancel_path = format!(
+        "/api/v1/admin/broadcasts/{}/cancel",
+        created["id"].as_str().unwrap()
+    );
+
+    let cancelled = call(
+        app.clone(),
+        request_with_headers("POST", &cancel_path, &[ADMIN]),
+    )
+    .await;
+    assert_eq!(cancelled.status(), StatusCode::OK);
+    let cancelled: Value = decode_json(cancelled).await;
+    assert_eq!(cancelled["status"], "cancelled");
+
+    sqlx::query("UPDATE jobs SET run_at = now()")
+        .execute(&pool)
+        .await
+        .unwrap();
+    run_pending_jobs(&pool, &log_channels(), "test-worker", 20)
+        .await
+        .unwrap();
+    assert!(announcements(&pool, station_id).await.is_empty());
+
+    let job_status: String =
+        sqlx::query_scalar("SELECT status FROM jobs WHERE kind = 'send_broadcast'")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(job_status, "succeeded");
+
+    let again = call(app, request_with_headers("POST", &cancel_path, &[ADMIN])).await;
+    assert_eq!(again.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+#[serial]
+async fn interrupted_broadcasts_resume_without_repeating_deliveries() {
+    let Some(pool) = db_pool().await else {
+        eprintln!("Skipping DB-backed broadcasts test: TEST_DATABASE_URL not set");
+        return;
+    };
+
+    reset_db(&pool).await;
+    seed_admin(&pool, "super-secret").await;
+
+    let app = test_app_with_pool(pool.clone());
+    let jabi = format!("{}@example.com", Uuid::new_v4().simple());
+    let kubwa = format!("{}@example.com", Uuid::new_v4().simple());
+    let (jabi_id, _) = create_station_and_signin(app.clone(), &jabi, "gas", (9.06, 7.41)).await;
+    let (kubwa_id, _) = create_station_and_signin(app.clone(), &kubwa, "gas", (9.20, 7.30)).await;
+
+    let created = create_broadcast(
+        app.clone(),
+        json!({
+            "title": "Maintenance",
+            "body": "The dashboard is down on Sunday.",
+            "audience": "all",
+            "scheduled_for": "2099-01-01T08:00:00Z"
+        }),
+    )
+    .await;
+    let created: Value = decode_json(created).await;
+    let broadcast_id = created["id"].as_str().unwrap();
+
+    // An earlier attempt committed Jabi's batch before it stopped.
+    sqlx::query(
+        "INSERT INTO broadcast_deliveries (broadcast_id, station_id, in_app) VALUES ($1, $2, FALSE)",
+    )
+    .bind(Uuid::parse_str(broadcast_id).unwrap())
+    .bind(jabi_id)
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    sqlx::query("UPDATE jobs SET run_at = now()")
+        .execute(&pool)
+        .await
+        .unwrap();
+    run_pending_jobs(&pool, &log_channels(), "test-worker", 20)
+        .await
+        .unwrap();
+
+    assert!(announcements(&pool, jabi_id).await.is_empty());
+    assert_eq!(announcements(&pool, kubwa_id).await, vec!["Maintenance"]);
+
+    let report = broadcast_report(app, broadcast_id).await;
+    assert_eq!(report["status"], "sent");
+    assert_eq!(report["recipients"], 2);
+}
@@ -5,8 +5,8 @@ use serde_json::{Value, json};
 use serial_test::serial;
 
 use common::{
-    call, commodity_id_for_station, db_pool, decode_json, request, request_with_json, reset_db,
-    seed_admin, station_id_by_email, test_app, test_app_with_pool,
+    call, commodity_id_for_station, create_station_and_signin, db_pool, decode_json, request,
+    request_with_json, reset_db, seed_admin, station_id_by_email, test_app, test_app_with_pool,
 };
 
 #[tokio::test]
@@ -164,3 +164,1503 @@ async fn admin_can_enable_discount_generate_code_redeem_and_view_stats() {
     assert_eq!(admin_stats_body["created_codes"].as_i64(), Some(1));
     assert_eq!(admin_stats_body["redeemed_codes"].as_i64(), Some(1));
 }
+
+#[tokio::test]
+#[serial]
+async fn discount_code_covers_chosen_commodity_and_quantity() {
+    let Some(pool) = db_pool().await else {
+        eprintln!("Skipping DB-backed discount test: TEST_DATABASE_URL not set");
+        return;
+    };
+
+    reset_db(&pool).await;
+    seed_admin(&pool, "super-secret").await;
+
+    let app = test_app_with_pool(pool.clone());
+    let email = format!("{}@example.com", uuid::Uuid::new_v4().simple());
+    let (_, token) =
+        create_station_and_signin(app.clone(), &email, "petrol", (9.08, 7.48)).await;
+    let station_id = station_id_by_email(&pool, &email).await;
+
+    let diesel_id: uuid::Uuid = sqlx::query_scalar(
+        "INSERT INTO commodities (name, price, station_id) VALUES ('diesel', 1200, $1) RETURNING id",
+    )
+    .bind(station_id)
+    .fetch_one(&pool)
+    .await
+    .expect("diesel commodity should insert");
+
+    let enable_discount_response = call(
+        app.clone(),
+        common::request_with_headers_and_json(
+            "PATCH",
+            &format!("/api/v1/admin/discounts/{diesel_id}"),
+            &[("x-admin-password", "super-secret")],
+            json!({ "commodity_id": diesel_id, "enabled": true, "percentage": 5 }),
+        ),
+    )
+    .await;
+    assert_eq!(enable_discount_response.status(), StatusCode::NO_CONTENT);
+
+    let petrol_id = commodity_id_for_station(&pool, station_id).await;
+    let petrol_response = call(
+        app.clone(),
+        common::request_with_headers_and_json(
+            "POST",
+            "/api/v1/discounts/generate",
+            &[("x-forwarded-for", "203.0.113.50")],
+            json!({ "station_id": station_id, "commodity_id": petrol_id, "litres": 10 }),
+        ),
+    )
+    .await;
+    assert_eq!(petrol_response.status(), StatusCode::UNAUTHORIZED);
+
+    let both_response = call(
+        app.clone(),
+        common::request_with_headers_and_json(
+            "POST",
+            "/api/v1/discounts/generate",
+            &[("x-forwarded-for", "203.0.113.50")],
+            json!({ "station_id": station_id, "commodity_id": diesel_id, "litres": 10, "amount": 5000 }),
+        ),
+    )
+    .await;
+    assert_eq!(both_response.status(), StatusCode::UNAUTHORIZED);
+
+    let generate_response = call(
+        app.clone(),
+        common::request_with_headers_and_json(
+            "POST",
+            "/api/v1/discounts/generate",
+            &[("x-forwarded-for", "203.0.113.50")],
+            json!({ "station_id": station_id, "commodity_id": diesel_id, "litres": 20 }),
+        ),
+    )
+    .await;
+    assert_eq!(generate_response.status(), StatusCode::CREATED);
+
+    let generate_body: Value = decode_json(generate_response).await;
+    assert_eq!(generate_body["commodity_name"].as_str(), Some("diesel"));
+    assert_eq!(generate_body["quantity_litres"].as_f64(), Some(20.0));
+    assert_eq!(generate_body["total_price"].as_i64(), Some(24_000));
+    assert_eq!(generate_body["total_discounted_price"].as_i64(), Some(22_800));
+    assert_eq!(generate_body["total_saving"].as_i64(), Some(1_200));
+    let discount_code = generate_body["code"].as_str().unwrap().to_string();
+
+    let amount_response = call(
+        app.clone(),
+        common::request_with_headers_and_json(
+            "POST",
+            "/api/v1/discounts/generate",
+            &[("x-forwarded-for", "203.0.113.50")],
+            json!({ "station_id": station_id, "commodity_id": diesel_id, "amount": 6000 }),
+        ),
+    )
+    .await;
+    assert_eq!(amount_response.status(), StatusCode::CREATED);
+    let amount_body: Value = decode_json(amount_response).await;
+    assert_eq!(amount_body["quantity_unit"].as_str(), Some("amount"));
+    assert_eq!(amount_body["quantity_litres"].as_f64(), Some(5.0));
+    assert_eq!(amount_body["total_discounted_price"].as_i64(), Some(5_700));
+
+    let redeem_response = call(
+        app.clone(),
+        common::request_with_headers_and_json(
+            "POST",
+            "/api/v1/discounts/redeem",
+            &[("authorization", &format!("Bearer {token}"))],
+            json!({ "code": discount_code }),
+        ),
+    )
+    .await;
+    assert_eq!(redeem_response.status(), StatusCode::OK);
+
+    let redeem_body: Value = decode_json(redeem_response).await;
+    assert_eq!(redeem_body["message"].as_str(), Some("code redeemed successfully"));
+    assert_eq!(redeem_body["commodity_name"].as_str(), Some("diesel"));
+    assert_eq!(redeem_body["amount_to_charge"].as_i64(), Some(22_800));
+    assert_eq!(redeem_body["total_saving"].as_i64(), Some(1_200));
+
+    sqlx::query("UPDATE commodities SET price = 100000000 WHERE id = $1")
+        .bind(diesel_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    let oversized_response = call(
+        app,
+        common::request_with_headers_and_json(
+            "POST",
+            "/api/v1/discounts/generate",
+            &[("x-forwarded-for", "203.0.113.51")],
+            json!({ "station_id": station_id, "commodity_id": diesel_id, "litres": 200 }),
+        ),
+    )
+    .await;
+    assert_eq!(oversized_response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn station_campaigns_require_auth() {
+    let response = call(test_app(), request("GET", "/api/v1/discounts/station/campaigns")).await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+#[serial]
+async fn campaign_discounts_codes_until_budget_is_spent() {
+    let Some(pool) = db_pool().await else {
+        eprintln!("Skipping DB-backed discount test: TEST_DATABASE_URL not set");
+        return;
+    };
+
+    reset_db(&pool).await;
+    seed_admin(&pool, "super-secret").await;
+
+    let app = test_app_with_pool(pool.clone());
+    let email = format!("{}@example.com", uuid::Uuid::new_v4().simple());
+    let (_, token) =
+        create_station_and_signin(app.clone(), &email, "petrol", (9.08, 7.48)).await;
+    let station_id = station_id_by_email(&pool, &email).await;
+    let commodity_id = commodity_id_for_station(&pool, station_id).await;
+
+    sqlx::query("UPDATE commodities SET price = 700 WHERE id = $1")
+        .bind(commodity_id)
+        .execute(&pool)
+        .await
+        .expect("price should update");
+
+    let now = chrono::Utc::now();
+
+    let upcoming_response = call(
+        app.clone(),
+        common::request_with_headers_and_json(
+            "POST",
+            "/api/v1/admin/campaigns",
+            &[("x-admin-password", "super-secret")],
+            json!({
+                "commodity_id": commodity_id,
+                "name": "Next week",
+                "discount_type": "percentage",
+                "discount_value": 10,
+                "starts_at": now + chrono::Duration::days(7),
+            }),
+        ),
+    )
+    .await;
+    assert_eq!(upcoming_response.status(), StatusCode::CREATED);
+    let upcoming_body: Value = decode_json(upcoming_response).await;
+    assert_eq!(upcoming_body["is_live"].as_bool(), Some(false));
+
+    let create_response = call(
+        app.clone(),
+        common::request_with_headers_and_json(
+            "POST",
+            "/api/v1/admin/campaigns",
+            &[("x-admin-password", "super-secret")],
+            json!({
+                "commodity_id": commodity_id,
+                "name": "Launch week",
+                "discount_type": "fixed",
+                "discount_value": 100,
+                "starts_at": now - chrono::Duration::hours(1),
+                "ends_at": now + chrono::Duration::days(1),
+                "budget": 1500
+            }),
+        ),
+    )
+    .await;
+    assert_eq!(create_response.status(), StatusCode::CREATED);
+    let create_body: Value = decode_json(create_response).await;
+    assert_eq!(create_body["is_live"].as_bool(), Some(true));
+    let campaign_id = create_body["id"].as_str().unwrap().to_string();
+
+    let generate_response = call(
+        app.clone(),
+        common::request_with_headers_and_json(
+            "POST",
+            "/api/v1/discounts/generate",
+            &[("x-forwarded-for", "203.0.113.70")],
+            json!({ "station_id": station_id, "litres": 10 }),
+        ),
+    )
+    .await;
+    assert_eq!(generate_response.status(), StatusCode::CREATED);
+    let generate_body: Value = decode_json(generate_response).await;
+    assert_eq!(generate_body["discount_type"].as_str(), Some("fixed"));
+    assert_eq!(generate_body["campaign_id"].as_str(), Some(campaign_id.as_str()));
+    assert_eq!(generate_body["discounted_price"].as_i64(), Some(600));
+    assert_eq!(generate_body["total_saving"].as_i64(), Some(1_000));
+
+    let exhausted_response = call(
+        app.clone(),
+        common::request_with_headers_and_json(
+            "POST",
+            "/api/v1/discounts/generate",
+            &[("x-forwarded-for", "203.0.113.70")],
+            json!({ "station_id": station_id, "litres": 10 }),
+        ),
+    )
+    .await;
+    assert_eq!(exhausted_response.status(), StatusCode::UNAUTHORIZED);
+
+    let campaigns_response = call(
+        app,
+        common::request_with_auth("GET", "/api/v1/discounts/station/campaigns", &token),
+    )
+    .await;
+    assert_eq!(campaigns_response.status(), StatusCode::OK);
+    let campaigns_body: Value = decode_json(campaigns_response).await;
+    let launch = campaigns_body
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|c| c["id"].as_str() == Some(campaign_id.as_str()))
+        .expect("campaign should be listed");
+    assert_eq!(launch["codes_issued"].as_i64(), Some(1));
+    assert_eq!(launch["budget_committed"].as_i64(), Some(1_000));
+    assert_eq!(launch["budget_spent"].as_i64(), Some(0));
+    assert_eq!(launch["budget_remaining"].as_i64(), Some(500));
+}
+
+#[tokio::test]
+async fn station_discount_config_requires_auth() {
+    let response = call(test_app(), request("GET", "/api/v1/discounts/station/config")).await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+#[serial]
+async fn station_tunes_discount_within_admin_limits_until_locked() {
+    let Some(pool) = db_pool().await else {
+        eprintln!("Skipping DB-backed discount test: TEST_DATABASE_URL not set");
+        return;
+    };
+
+    reset_db(&pool).await;
+    seed_admin(&pool, "super-secret").await;
+
+    let app = test_app_with_pool(pool.clone());
+    let email = format!("{}@example.com", uuid::Uuid::new_v4().simple());
+    let (_, token) =
+        create_station_and_signin(app.clone(), &email, "petrol", (9.08, 7.48)).await;
+    let station_id = station_id_by_email(&pool, &email).await;
+    let commodity_id = commodity_id_for_station(&pool, station_id).await;
+    let station_path = format!("/api/v1/discounts/station/config/{commodity_id}");
+    let auth = format!("Bearer {token}");
+
+    let station_update = |percentage: i32| {
+        common::request_with_headers_and_json(
+            "PUT",
+            &station_path,
+            &[("authorization", &auth)],
+            json!({ "enabled": true, "percentage": percentage }),
+        )
+    };
+
+    let without_limits_response = call(app.clone(), station_update(5)).await;
+    assert_eq!(without_limits_response.status(), StatusCode::UNAUTHORIZED);
+
+    let limits_response = call(
+        app.clone(),
+        common::request_with_headers_and_json(
+            "PUT",
+            &format!("/api/v1/admin/stations/{station_id}/discount-limits"),
+            &[("x-admin-password", "super-secret")],
+            json!({ "min_percentage": 2, "max_percentage": 6 }),
+        ),
+    )
+    .await;
+    assert_eq!(limits_response.status(), StatusCode::OK);
+
+    let above_ceiling_response = call(app.clone(), station_update(8)).await;
+    assert_eq!(above_ceiling_response.status(), StatusCode::UNAUTHORIZED);
+
+    let within_limits_response = call(app.clone(), station_update(5)).await;
+    assert_eq!(within_limits_response.status(), StatusCode::NO_CONTENT);
+
+    let config_response = call(
+        app.clone(),
+        common::request_with_auth("GET", "/api/v1/discounts/station/config", &token),
+    )
+    .await;
+    assert_eq!(config_response.status(), StatusCode::OK);
+    let config_body: Value = decode_json(config_response).await;
+    assert_eq!(config_body["limits"]["max_percentage"].as_i64(), Some(6));
+    assert_eq!(config_body["commodities"][0]["percentage"].as_i64(), Some(5));
+    assert_eq!(config_body["commodities"][0]["updated_by_type"].as_str(), Some("station"));
+
+    let admin_override_response = call(
+        app.clone(),
+        common::request_with_headers_and_json(
+            "PATCH",
+            &format!("/api/v1/admin/discounts/{commodity_id}"),
+            &[("x-admin-password", "super-secret")],
+            json!({ "commodity_id": commodity_id, "enabled": true, "percentage": 3 }),
+        ),
+    )
+    .await;
+    assert_eq!(admin_override_response.status(), StatusCode::NO_CONTENT);
+
+    let locked_response = call(app.clone(), station_update(4)).await;
+    assert_eq!(locked_response.status(), StatusCode::UNAUTHORIZED);
+
+    let unlock_response = call(
+        app.clone(),
+        common::request_with_headers_and_json(
+            "PATCH",
+            &format!("/api/v1/admin/discounts/{commodity_id}"),
+            &[("x-admin-password", "super-secret")],
+            json!({ "commodity_id": commodity_id, "enabled": true, "percentage": 3, "locked": false }),
+        ),
+    )
+    .await;
+    assert_eq!(unlock_response.status(), StatusCode::NO_CONTENT);
+
+    let unlocked_response = call(app.clone(), station_update(4)).await;
+    assert_eq!(unlocked_response.status(), StatusCode::NO_CONTENT);
+
+    let lowered_response = call(
+        app.clone(),
+        common::request_with_headers_and_json(
+            "PUT",
+            &format!("/api/v1/admin/stations/{station_id}/discount-limits"),
+            &[("x-admin-password", "super-secret")],
+            json!({ "min_percentage": 1, "max_percentage": 3 }),
+        ),
+    )
+    .await;
+    assert_eq!(lowered_response.status(), StatusCode::OK);
+    let clamped: i32 =
+        sqlx::query_scalar("SELECT percentage FROM commodity_discounts WHERE commodity_id = $1")
+            .bind(commodity_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(clamped, 3);
+
+    let changes_response = call(
+        app,
+        common::request_with_headers(
+            "GET",
+            &format!("/api/v1/admin/discounts/{commodity_id}/changes"),
+            &[("x-admin-password", "super-secret")],
+        ),
+    )
+    .await;
+    assert_eq!(changes_response.status(), StatusCode::OK);
+    let changes_body: Value = decode_json(changes_response).await;
+    let actors: Vec<&str> = changes_body
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter_map(|c| c["actor_type"].as_str())
+        .collect();
+    assert_eq!(actors, vec!["admin", "station", "admin", "admin", "station"]);
+    assert_eq!(changes_body[0]["previous_percentage"].as_i64(), Some(4));
+    assert_eq!(changes_body[0]["new_percentage"].as_i64(), Some(3));
+    assert_eq!(changes_body[1]["previous_percentage"].as_i64(), Some(3));
+    assert_eq!(changes_body[1]["new_percentage"].as_i64(), Some(4));
+}
+
+#[tokio::test]
+#[serial]
+async fn discount_code_qr_token_is_signed_and_redeemable() {
+    let Some(pool) = db_pool().await else {
+        eprintln!("Skipping DB-backed discount test: TEST_DATABASE_URL not set");
+        return;
+    };
+
+    reset_db(&pool).await;
+    seed_admin(&pool, "super-secret").await;
+
+    let app = test_app_with_pool(pool.clone());
+    let email = format!("{}@example.com", uuid::Uuid::new_v4().simple());
+    let (_, token) =
+        create_station_and_signin(app.clone(), &email, "petrol", (9.08, 7.48)).await;
+    let station_id = station_id_by_email(&pool, &email).await;
+    let commodity_id = commodity_id_for_station(&pool, station_id).await;
+
+    call(
+        app.clone(),
+        common::request_with_headers_and_json(
+            "PATCH",
+            &format!("/api/v1/admin/discounts/{commodity_id}"),
+            &[("x-admin-password", "super-secret")],
+            json!({ "commodity_id": commodity_id, "enabled": true, "percentage": 5 }),
+        ),
+    )
+    .await;
+
+    let generate_response = call(
+        app.clone(),
+        common::request_with_headers_and_json(
+            "POST",
+            "/api/v1/discounts/generate",
+            &[("x-forwarded-for", "203.0.113.80")],
+            json!({ "station_id": station_id }),
+        ),
+    )
+    .await;
+    assert_eq!(generate_response.status(), StatusCode::CREATED);
+    let generate_body: Value = decode_json(generate_response).await;
+    let discount_code = generate_body["code"].as_str().unwrap().to_string();
+    let qr_token = generate_body["qr_token"].as_str().unwrap().to_string();
+    assert!(qr_token.starts_with("FFD1."));
+
+    let anonymous_qr = call(
+        app.clone(),
+        request("GET", &format!("/api/v1/discounts/{discount_code}/qr")),
+    )
+    .await;
+    assert_eq!(anonymous_qr.status(), StatusCode::UNAUTHORIZED);
+
+    let png_response = call(
+        app.clone(),
+        common::request_with_headers(
+            "GET",
+            &format!("/api/v1/discounts/{discount_code}/qr"),
+            &[("x-discount-token", &qr_token)],
+        ),
+    )
+    .await;
+    assert_eq!(png_response.status(), StatusCode::OK);
+    assert_eq!(
+        png_response.headers()["content-type"].to_str().unwrap(),
+        "image/png"
+    );
+    let png = image::load_from_memory(&common::body_bytes(png_response).await)
+        .expect("qr png should decode");
+    assert!(png.width() >= 256);
+
+    let svg_response = call(
+        app.clone(),
+        common::request_with_headers(
+            "GET",
+            &format!("/api/v1/discounts/{discount_code}/qr?format=svg"),
+            &[("x-discount-token", &qr_token)],
+        ),
+    )
+    .await;
+    assert_eq!(svg_response.status(), StatusCode::OK);
+    assert!(common::body_text(svg_response).await.contains("<svg"));
+
+    let key_response = call(app.clone(), request("GET", "/api/v1/discounts/signing-key")).await;
+    let key_body: Value = decode_json(key_response).await;
+    assert_eq!(key_body["algorithm"].as_str(), Some("Ed25519"));
+
+    let mut tampered: Vec<char> = qr_token.chars().collect();
+    let index = tampered.len() - 10;
+    tampered[index] = if tampered[index] == 'A' { 'B' } else { 'A' };
+    let tampered: String = tampered.into_iter().collect();
+
+    let tampered_response = call(
+        app.clone(),
+        common::request_with_headers_and_json(
+            "POST",
+            "/api/v1/discounts/redeem",
+            &[("authorization", &format!("Bearer {token}"))],
+            json!({ "code": tampered }),
+        ),
+    )
+    .await;
+    assert_eq!(tampered_response.status(), StatusCode::UNAUTHORIZED);
+
+    let redeem_response = call(
+        app,
+        common::request_with_headers_and_json(
+            "POST",
+            "/api/v1/discounts/redeem",
+            &[("authorization", &format!("Bearer {token}"))],
+            json!({ "code": qr_token }),
+        ),
+    )
+    .await;
+    assert_eq!(redeem_response.status(), StatusCode::OK);
+    let redeem_body: Value = decode_json(redeem_response).await;
+    assert_eq!(redeem_body["message"].as_str(), Some("code redeemed successfully"));
+    assert_eq!(redeem_body["code"].as_str(), Some(discount_code.as_str()));
+}
+
+#[tokio::test]
+async fn offline_redemption_sync_requires_auth() {
+    let response = call(test_app(), request("POST", "/api/v1/discounts/redeem/sync")).await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+#[serial]
+async fn offline_redemptions_sync_with_earliest_redemption_winning() {
+    let Some(pool) = db_pool().await else {
+        eprintln!("Skipping DB-backed discount test: TEST_DATABASE_URL not set");
+        return;
+    };
+
+    reset_db(&pool).await;
+    seed_admin(&pool, "super-secret").await;
+
+    let app = test_app_with_pool(pool.clone());
+    let email = format!("{}@example.com", uuid::Uuid::new_v4().simple());
+    let (_, token) =
+        create_station_and_signin(app.clone(), &email, "petrol", (9.08, 7.48)).await;
+    let station_id = station_id_by_email(&pool, &email).await;
+    let commodity_id = commodity_id_for_station(&pool, station_id).await;
+
+    call(
+        app.clone(),
+        common::request_with_headers_and_json(
+            "PATCH",
+            &format!("/api/v1/admin/discounts/{commodity_id}"),
+            &[("x-admin-password", "super-secret")],
+            json!({ "commodity_id": commodity_id, "enabled": true, "percentage": 5 }),
+        ),
+    )
+    .await;
+
+    let mut codes = Vec::new();
+    for ip in ["203.0.113.90", "203.0.113.91"] {
+        let response = call(
+            app.clone(),
+            common::request_with_headers_and_json(
+                "POST",
+                "/api/v1/discounts/generate",
+                &[("x-forwarded-for", ip)],
+                json!({ "station_id": station_id }),
+            ),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let body: Value = decode_json(response).await;
+        codes.push((
+            body["code"].as_str().unwrap().to_string(),
+            body["qr_token"].as_str().unwrap().to_string(),
+        ));
+    }
+    let (first_code, first_token) = codes[0].clone();
+    let (expired_code, expired_token) = codes[1].clone();
+
+    sqlx::query(
+        "UPDATE discount_codes SET created_at = now() - interval '2 hours', expires_at = now() - interval '30 minutes'",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+    sqlx::query("UPDATE discount_codes SET expires_at = now() + interval '1 hour' WHERE code = $1")
+        .bind(&first_code)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let key_response = call(
+        app.clone(),
+        common::request_with_auth("GET", "/api/v1/discounts/station/sync-key", &token),
+    )
+    .await;
+    assert_eq!(key_response.status(), StatusCode::OK);
+    let key_body: Value = decode_json(key_response).await;
+    let sync_key = key_body["key"].as_str().unwrap().to_string();
+    let sign = |code: &str, redeemed_at: chrono::DateTime<chrono::Utc>| {
+        sign_offline_redemption(&sync_key, code, redeemed_at)
+    };
+
+    let now = chrono::Utc::now();
+    let later = now - chrono::Duration::minutes(10);
+    let earlier = now - chrono::Duration::minutes(20);
+
+    let mut tampered: Vec<char> = first_token.chars().collect();
+    let index = tampered.len() - 10;
+    tampered[index] = if tampered[index] == 'A' { 'B' } else { 'A' };
+    let tampered: String = tampered.into_iter().collect();
+
+    let response = call(
+        app.clone(),
+        common::request_with_headers_and_json(
+            "POST",
+            "/api/v1/discounts/redeem/sync",
+            &[("authorization", &format!("Bearer {token}"))],
+            json!({
+                "redemptions": [
+                    {
+                        "code": first_token,
+                        "redeemed_at": later,
+                        "signature": sign(&first_token, later),
+                        "client_reference": "a"
+                    },
+                    {
+                        "code": first_code,
+                        "redeemed_at": earlier,
+                        "signature": sign(&first_code, earlier),
+                        "client_reference": "b"
+                    },
+                    {
+                        "code": expired_token,
+                        "redeemed_at": later,
+                        "signature": sign(&expired_token, later),
+                        "client_reference": "c"
+                    },
+                    {
+                        "code": tampered,
+                        "redeemed_at": later,
+                        "signature": sign(&tampered, later),
+                        "client_reference": "d"
+                    },
+                    {
+                        "code": first_code,
+                        "redeemed_at": now + chrono::Duration::hours(1),
+                        "signature": sign(&first_code, now + chrono::Duration::hours(1))
+                    },
+                    {
+                        "code": first_code,
+                        "redeemed_at": earlier - chrono::Duration::minutes(5),
+                        "signature": sign(&first_code, later),
+                        "client_reference": "forged"
+                    },
+                ]
+            }),
+        ),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: Value = decode_json(response).await;
+    let results = body["results"].as_array().unwrap();
+    let statuses: Vec<&str> = results
+        .iter()
+        .map(|result| result["status"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        statuses,
+        ["duplicate", "redeemed", "expired", "invalid", "invalid", "invalid"]
+    );
+    assert_eq!(
+        results[5]["message"].as_str(),
+        Some("invalid redemption signature")
+    );
+    assert_eq!(results[0]["client_reference"].as_str(), Some("a"));
+    assert_eq!(results[2]["code"].as_str(), Some(expired_code.as_str()));
+
+    let (redeemed_at, source): (chrono::DateTime<chrono::Utc>, String) = sqlx::query_as(
+        "SELECT redeemed_at, redemption_source FROM discount_codes WHERE code = $1",
+    )
+    .bind(&first_code)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(redeemed_at.timestamp(), earlier.timestamp());
+    assert_eq!(source, "offline");
+
+    let retry = call(
+        app.clone(),
+        common::request_with_headers_and_json(
+            "POST",
+            "/api/v1/discounts/redeem/sync",
+            &[("authorization", &format!("Bearer {token}"))],
+            json!({ "redemptions": [{
+                "code": first_code,
+                "redeemed_at": earlier,
+                "signature": sign(&first_code, earlier)
+            }] }),
+        ),
+    )
+    .await;
+    let retry_body: Value = decode_json(retry).await;
+    assert_eq!(retry_body["results"][0]["status"].as_str(), Some("duplicate"));
+
+    let stale = now - chrono::Duration::days(8);
+    let stale_sync = call(
+        app.clone(),
+        common::request_with_headers_and_json(
+            "POST",
+            "/api/v1/discounts/redeem/sync",
+            &[("authorization", &format!("Bearer {token}"))],
+            json!({ "redemptions": [{
+                "code": first_code,
+                "redeemed_at": stale,
+                "signature": sign(&first_code, stale)
+            }] }),
+        ),
+    )
+    .await;
+    let stale_body: Value = decode_json(stale_sync).await;
+    assert_eq!(stale_body["results"][0]["status"].as_str(), Some("invalid"));
+
+    // A redemption recorded online is never replaced by an offline one,
+    // however early the offline one claims to be.
+    let generated = call(
+        app.clone(),
+        common::request_with_headers_and_json(
+            "POST",
+            "/api/v1/discounts/generate",
+            &[("x-forwarded-for", "203.0.113.92")],
+            json!({ "station_id": station_id }),
+        ),
+    )
+    .await;
+    let online_code = decode_json::<Value>(generated).await["code"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    let online = call(
+        app.clone(),
+        common::request_with_headers_and_json(
+            "POST",
+            "/api/v1/discounts/redeem",
+            &[("authorization", &format!("Bearer {token}"))],
+            json!({ "code": online_code }),
+        ),
+    )
+    .await;
+    assert_eq!(online.status(), StatusCode::OK);
+
+    let issued_at: chrono::DateTime<chrono::Utc> =
+        sqlx::query_scalar("SELECT created_at FROM discount_codes WHERE code = $1")
+            .bind(&online_code)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    let backdated = issued_at + chrono::Duration::milliseconds(1);
+    let overwrite = call(
+        app,
+        common::request_with_headers_and_json(
+            "POST",
+            "/api/v1/discounts/redeem/sync",
+            &[("authorization", &format!("Bearer {token}"))],
+            json!({ "redemptions": [{
+                "code": online_code,
+                "redeemed_at": backdated,
+                "signature": sign(&online_code, backdated)
+            }] }),
+        ),
+    )
+    .await;
+    let overwrite_body: Value = decode_json(overwrite).await;
+    assert_eq!(overwrite_body["results"][0]["status"].as_str(), Some("duplicate"));
+
+    let source: String =
+        sqlx::query_scalar("SELECT redemption_source FROM discount_codes WHERE code = $1")
+            .bind(&online_code)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(source, "online");
+}
+
+/// Signs an offline redemption the way the attendant app does.
+fn sign_offline_redemption(
+    key: &str,
+    code: &str,
+    redeemed_at: chrono::DateTime<chrono::Utc>,
+) -> String {
+    use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+    use hmac::{Hmac, Mac};
+
+    let key = URL_SAFE_NO_PAD.decode(key).unwrap();
+    let mut mac = Hmac::<sha2::Sha256>::new_from_slice(&key).unwrap();
+    mac.update(format!("{code}.{}", redeemed_at.timestamp_millis()).as_bytes());
+    URL_SAFE_NO_PAD.encode(mac.finalize().into_bytes())
+}
+
+#[tokio::test]
+async fn redemption_reversal_requires_auth() {
+    let response = call(test_app(), request("POST", "/api/v1/discounts/redeem/reverse")).await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+#[serial]
+async fn discount_codes_can_be_cancelled_reversed_and_voided() {
+    let Some(pool) = db_pool().await else {
+        eprintln!("Skipping DB-backed discount test: TEST_DATABASE_URL not set");
+        return;
+    };
+
+    reset_db(&pool).await;
+    seed_admin(&pool, "super-secret").await;
+
+    let app = test_app_with_pool(pool.clone());
+    let email = format!("{}@example.com", uuid::Uuid::new_v4().simple());
+    let (_, token) =
+        create_station_and_signin(app.clone(), &email, "petrol", (9.08, 7.48)).await;
+    let station_id = station_id_by_email(&pool, &email).await;
+    let commodity_id = commodity_id_for_station(&pool, station_id).await;
+    let auth = format!("Bearer {token}");
+
+    call(
+        app.clone(),
+        common::request_with_headers_and_json(
+            "PATCH",
+            &format!("/api/v1/admin/discounts/{commodity_id}"),
+            &[("x-admin-password", "super-secret")],
+            json!({ "commodity_id": commodity_id, "enabled": true, "percentage": 5 }),
+        ),
+    )
+    .await;
+
+    let mut codes = Vec::new();
+    for ip in ["203.0.113.100", "203.0.113.101"] {
+        let response = call(
+            app.clone(),
+            common::request_with_headers_and_json(
+                "POST",
+                "/api/v1/discounts/generate",
+                &[("x-forwarded-for", ip)],
+                json!({ "station_id": station_id }),
+            ),
+        )
+        .await;
+        let body: Value = decode_json(response).await;
+        codes.push((
+            body["code"].as_str().unwrap().to_string(),
+            body["qr_token"].as_str().unwrap().to_string(),
+        ));
+    }
+    let ((cancelled, cancelled_token), (reversed, reversed_token)) =
+        (codes[0].clone(), codes[1].clone());
+
+    let redeem = |code: String| {
+        common::request_with_headers_and_json(
+            "POST",
+            "/api/v1/discounts/redeem",
+            &[("authorization", &auth)],
+            json!({ "code": code }),
+        )
+    };
+
+    let cancel = |driver_token: &str| {
+        common::request_with_headers(
+            "POST",
+            &format!("/api/v1/discounts/{cancelled}/cancel"),
+            &[("x-discount-token", driver_token)],
+        )
+    };
+
+    let anonymous_cancel = call(
+        app.clone(),
+        request("POST", &format!("/api/v1/discounts/{cancelled}/cancel")),
+    )
+    .await;
+    assert_eq!(anonymous_cancel.status(), StatusCode::UNAUTHORIZED);
+
+    // A genuine token for a different code does not cancel this one.
+    let wrong_token_cancel = call(app.clone(), cancel(&reversed_token)).await;
+    assert_eq!(wrong_token_cancel.status(), StatusCode::UNAUTHORIZED);
+
+    let cancel_response = call(app.clone(), cancel(&cancelled_token)).await;
+    assert_eq!(cancel_response.status(), StatusCode::OK);
+    let cancel_body: Value = decode_json(cancel_response).await;
+    assert_eq!(cancel_body["status"].as_str(), Some("cancelled"));
+
+    let cancelled_redeem: Value = decode_json(call(app.clone(), redeem(cancelled.clone())).await).await;
+    assert_eq!(cancelled_redeem["message"].as_str(), Some("code was cancelled"));
+
+    let cancel_again = call(app.clone(), cancel(&cancelled_token)).await;
+    assert_eq!(cancel_again.status(), StatusCode::UNAUTHORIZED);
+
+    call(app.clone(), redeem(reversed.clone())).await;
+
+    let reverse = |reason: &str| {
+        common::request_with_headers_and_json(
+            "POST",
+            "/api/v1/discounts/redeem/reverse",
+            &[("authorization", &auth)],
+            json!({ "code": reversed, "reason": reason }),
+        )
+    };
+
+    let missing_reason = call(app.clone(), reverse("  ")).await;
+    assert_eq!(missing_reason.status(), StatusCode::UNAUTHORIZED);
+
+    let reverse_response = call(app.clone(), reverse("wrong pump")).await;
+    assert_eq!(reverse_response.status(), StatusCode::OK);
+    let reverse_body: Value = decode_json(reverse_response).await;
+    assert_eq!(reverse_body["status"].as_str(), Some("active"));
+
+    let second_redeem: Value = decode_json(call(app.clone(), redeem(reversed.clone())).await).await;
+    assert_eq!(second_redeem["message"].as_str(), Some("code redeemed successfully"));
+
+    sqlx::query("UPDATE discount_codes SET redeemed_at = now() - interval '1 hour' WHERE code = $1")
+        .bind(&reversed)
+        .execute(&pool)
+        .await
+        .unwrap();
+    let late_reverse = call(app.clone(), reverse("too late")).await;
+    assert_eq!(late_reverse.status(), StatusCode::UNAUTHORIZED);
+
+    let void_response = call(
+        app.clone(),
+        common::request_with_headers_and_json(
+            "POST",
+            &format!("/api/v1/admin/discount-codes/{reversed}/void"),
+            &[("x-admin-password", "super-secret")],
+            json!({ "reason": "duplicate fill-up" }),
+        ),
+    )
+    .await;
+    assert_eq!(void_response.status(), StatusCode::OK);
+
+    let stats_response = call(
+        app.clone(),
+        common::request_with_headers(
+            "GET",
+            "/api/v1/discounts/station/stats",
+            &[("authorization", &auth)],
+        ),
+    )
+    .await;
+    let stats: Value = decode_json(stats_response).await;
+    assert_eq!(stats["redeemed_codes"].as_i64(), Some(0));
+    assert_eq!(stats["cancelled_codes"].as_i64(), Some(1));
+    assert_eq!(stats["voided_codes"].as_i64(), Some(1));
+    assert_eq!(stats["reversed_redemptions"].as_i64(), Some(1));
+
+    let events_response = call(
+        app,
+        common::request_with_headers(
+            "GET",
+            &format!("/api/v1/admin/discount-codes/{reversed}/events"),
+            &[("x-admin-password", "super-secret")],
+        ),
+    )
+    .await;
+    let events: Value = decode_json(events_response).await;
+    let event_types: Vec<&str> = events
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|event| event["event_type"].as_str().unwrap())
+        .collect();
+    assert_eq!(event_types, ["issued", "redeemed", "reversed", "redeemed", "voided"]);
+    assert_eq!(events[2]["reason"].as_str(), Some("wrong pump"));
+}
+
+#[tokio::test]
+async fn station_discount_analytics_requires_auth() {
+    let response = call(test_app(), request("GET", "/api/v1/discounts/station/analytics")).await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+#[serial]
+async fn discount_analytics_come_from_the_rollup() {
+    let Some(pool) = db_pool().await else {
+        eprintln!("Skipping DB-backed discount test: TEST_DATABASE_URL not set");
+        return;
+    };
+
+    reset_db(&pool).await;
+    seed_admin(&pool, "super-secret").await;
+
+    let app = test_app_with_pool(pool.clone());
+    let email = format!("{}@example.com", uuid::Uuid::new_v4().simple());
+    let (_, token) =
+        create_station_and_signin(app.clone(), &email, "petrol", (9.08, 7.48)).await;
+    let station_id = station_id_by_email(&pool, &email).await;
+    let commodity_id = commodity_id_for_station(&pool, station_id).await;
+    let auth = format!("Bearer {token}");
+
+    sqlx::query("UPDATE commodities SET price = 1000 WHERE id = $1")
+        .bind(commodity_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    call(
+        app.clone(),
+        common::request_with_headers_and_json(
+            "PATCH",
+            &format!("/api/v1/admin/discounts/{commodity_id}"),
+            &[("x-admin-password", "super-secret")],
+            json!({ "commodity_id": commodity_id, "enabled": true, "percentage": 5 }),
+        ),
+    )
+    .await;
+
+    let mut codes = Vec::new();
+    for ip in ["203.0.113.110", "203.0.113.111"] {
+        let response = call(
+            app.clone(),
+            common::request_with_headers_and_json(
+                "POST",
+                "/api/v1/discounts/generate",
+                &[("x-forwarded-for", ip)],
+                json!({ "station_id": station_id, "litres": 10 }),
+            ),
+        )
+        .await;
+        let body: Value = decode_json(response).await;
+        codes.push(body["code"].as_str().unwrap().to_string());
+    }
+
+    call(
+        app.clone(),
+        common::request_with_headers_and_json(
+            "POST",
+            "/api/v1/discounts/redeem",
+            &[("authorization", &auth)],
+            json!({ "code": codes[0] }),
+        ),
+    )
+    .await;
+
+    let analytics = |path: &str| {
+        common::request_with_headers(
+            "GET",
+            path,
+            &[("authorization", &auth), ("x-admin-password", "super-secret")],
+        )
+    };
+
+    // Nothing shows up until the rollup is refreshed.
+    let stale: Value = decode_json(
+        call(app.clone(), analytics("/api/v1/discounts/station/analytics")).await,
+    )
+    .await;
+    assert_eq!(stale["codes_created"].as_i64(), Some(0));
+
+    let refresh = call(
+        app.clone(),
+        common::request_with_headers(
+            "POST",
+            "/api/v1/admin/discounts/analytics/refresh",
+            &[("x-admin-password", "super-secret")],
+        ),
+    )
+    .await;
+    assert_eq!(refresh.status(), StatusCode::OK);
+
+    let station_response = call(
+        app.clone(),
+        analytics("/api/v1/discounts/station/analytics?period=weekly"),
+    )
+    .await;
+    assert_eq!(station_response.status(), StatusCode::OK);
+    let station: Value = decode_json(station_response).await;
+    assert_eq!(station["period"].as_str(), Some("weekly"));
+    assert_eq!(station["codes_created"].as_i64(), Some(2));
+    assert_eq!(station["codes_redeemed"].as_i64(), Some(1));
+    assert_eq!(station["redemption_rate"].as_f64(), Some(0.5));
+    assert_eq!(station["naira_discounted"].as_i64(), Some(500));
+    assert_eq!(station["heatmap"].as_array().unwrap().len(), 1);
+    let series = station["series"].as_array().unwrap();
+    assert!(series.len() >= 5);
+    assert_eq!(
+        series.iter().map(|bucket| bucket["codes_created"].as_i64().unwrap()).sum::<i64>(),
+        2
+    );
+
+    let admin: Value = decode_json(
+        call(
+            app.clone(),
+            analytics(&format!(
+                "/api/v1/admin/discounts/analytics?commodity_id={commodity_id}"
+            )),
+        )
+        .await,
+    )
+    .await;
+    assert_eq!(admin["series"].as_array().unwrap().len(), 30);
+    assert_eq!(admin["top_stations"][0]["station_id"].as_str(), Some(station_id.to_string().as_str()));
+    assert_eq!(admin["top_stations"][0]["codes_redeemed"].as_i64(), Some(1));
+
+    let other_commodity: Value = decode_json(
+        call(
+            app.clone(),
+            analytics(&format!(
+                "/api/v1/admin/discounts/analytics?commodity_id={}",
+                uuid::Uuid::new_v4()
+            )),
+        )
+        .await,
+    )
+    .await;
+    assert_eq!(other_commodity["codes_created"].as_i64(), Some(0));
+
+    let bad_range = call(
+        app,
+        analytics("/api/v1/admin/discounts/analytics?from=2026-02-01&to=2026-01-01"),
+    )
+    .await;
+    assert_eq!(bad_range.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+#[serial]
+async fn sustained_fraud_flags_suspend_station_discounts() {
+    let Some(pool) = db_pool().await else {
+        eprintln!("Skipping DB-backed discount test: TEST_DATABASE_URL not set");
+        return;
+    };
+
+    reset_db(&pool).await;
+    seed_admin(&pool, "super-secret").await;
+
+    let app = test_app_with_pool(pool.clone());
+    let email = format!("{}@example.com", uuid::Uuid::new_v4().simple());
+    let (_, token) =
+        create_station_and_signin(app.clone(), &email, "petrol", (9.08, 7.48)).await;
+    let station_id = station_id_by_email(&pool, &email).await;
+    let commodity_id = commodity_id_for_station(&pool, station_id).await;
+    let admin = [("x-admin-password", "super-secret")];
+
+    call(
+        app.clone(),
+        common::request_with_headers_and_json(
+            "PATCH",
+            &format!("/api/v1/admin/discounts/{commodity_id}"),
+            &admin,
+            json!({ "commodity_id": commodity_id, "enabled": true, "percentage": 5 }),
+        ),
+    )
+    .await;
+
+    let generate = |ip: &'static str| {
+        common::request_with_headers_and_json(
+            "POST",
+            "/api/v1/discounts/generate",
+            &[("x-forwarded-for", ip)],
+            json!({ "station_id": station_id }),
+        )
+    };
+
+    // Three codes redeemed seconds after issue, at 02:00 on two different days.
+    for (ip, days_ago) in [("203.0.113.120", 1), ("203.0.113.121", 2), ("203.0.113.122", 2)] {
+        let body: Value = decode_json(call(app.clone(), generate(ip)).await).await;
+        let code = body["code"].as_str().unwrap().to_string();
+
+        sqlx::query(
+            r#"
+            UPDATE discount_codes
+            SET redeemed_at = (date_trunc('day', now() AT TIME ZONE 'Africa/Lagos')
+                    - make_interval(days => $2) + interval '2 hours') AT TIME ZONE 'Africa/Lagos',
+                created_at = (date_trunc('day', now() AT TIME ZONE 'Africa/Lagos')
+                    - make_interval(days => $2) + interval '2 hours') AT TIME ZONE 'Africa/Lagos'
+                    - interval '10 seconds',
+                redeemed_by_station_id = station_id,
+                redemption_source = 'offline',
+                redemption_synced_at = now()
+            WHERE code = $1
+            "#,
+        )
+        .bind(&code)
+        .bind(days_ago)
+        .execute(&pool)
+        .await
+        .unwrap();
+    }
+
+    let scan = call(
+        app.clone(),
+        common::request_with_headers("POST", "/api/v1/admin/fraud/scan", &admin),
+    )
+    .await;
+    assert_eq!(scan.status(), StatusCode::OK);
+    let scan: Value = decode_json(scan).await;
+    assert_eq!(scan["flags_created"].as_u64(), Some(6));
+    assert_eq!(
+        scan["stations_suspended"][0].as_str(),
+        Some(station_id.to_string().as_str())
+    );
+
+    let flags: Value = decode_json(
+        call(
+            app.clone(),
+            common::request_with_headers(
+                "GET",
+                &format!("/api/v1/admin/fraud/flags?station_id={station_id}"),
+                &admin,
+            ),
+        )
+        .await,
+    )
+    .await;
+    let flags = flags.as_array().unwrap();
+    assert_eq!(flags.len(), 6);
+    assert!(flags.iter().any(|flag| flag["rule"] == "fast_redemption"));
+    assert!(flags.iter().any(|flag| flag["rule"] == "odd_hour_redemption"));
+
+    let review = call(
+        app.clone(),
+        common::request_with_headers_and_json(
+            "PATCH",
+            &format!("/api/v1/admin/fraud/flags/{}", flags[0]["id"].as_str().unwrap()),
+            &admin,
+            json!({ "status": "confirmed", "note": "attendant admitted it" }),
+        ),
+    )
+    .await;
+    assert_eq!(review.status(), StatusCode::OK);
+    let review: Value = decode_json(review).await;
+    assert_eq!(review["status"].as_str(), Some("confirmed"));
+
+    let suspended = call(app.clone(), generate("203.0.113.123")).await;
+    assert_eq!(suspended.status(), StatusCode::UNAUTHORIZED);
+
+    let notifications: Value = decode_json(
+        call(
+            app.clone(),
+            common::request_with_headers(
+                "GET",
+                "/api/v1/stations/dashboard/notifications",
+                &[("authorization", &format!("Bearer {token}"))],
+            ),
+        )
+        .await,
+    )
+    .await;
+    assert!(
+        notifications
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|notification| notification["kind"] == "fraud")
+    );
+
+    let lift = call(
+        app.clone(),
+        common::request_with_headers_and_json(
+            "PUT",
+            &format!("/api/v1/admin/stations/{station_id}/discount-suspension"),
+            &admin,
+            json!({ "suspended": false }),
+        ),
+    )
+    .await;
+    assert_eq!(lift.status(), StatusCode::OK);
+
+    let reinstated = call(app.clone(), generate("203.0.113.123")).await;
+    assert_eq!(reinstated.status(), StatusCode::CREATED);
+
+    // Flags raised before the lift do not suspend the station again.
+    let rescan: Value = decode_json(
+        call(
+            app,
+            common::request_with_headers("POST", "/api/v1/admin/fraud/scan", &admin),
+        )
+        .await,
+    )
+    .await;
+    assert_eq!(rescan["flags_created"].as_u64(), Some(0));
+    assert_eq!(rescan["stations_suspended"].as_array().unwrap().len(), 0);
+}
+
+#[tokio::test]
+async fn loyalty_account_requires_key() {
+    let response = call(test_app(), request("GET", "/api/v1/loyalty/account")).await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+#[serial]
+async fn redemptions_earn_loyalty_points_that_buy_vouchers_and_expire() {
+    let Some(pool) = db_pool().await else {
+        eprintln!("Skipping DB-backed discount test: TEST_DATABASE_URL not set");
+        return;
+    };
+
+    reset_db(&pool).await;
+    seed_admin(&pool, "super-secret").await;
+
+    let app = test_app_with_pool(pool.clone());
+    let email = format!("{}@example.com", uuid::Uuid::new_v4().simple());
+    let (_, token) =
+        create_station_and_signin(app.clone(), &email, "petrol", (9.08, 7.48)).await;
+    let station_id = station_id_by_email(&pool, &email).await;
+    let auth = format!("Bearer {token}");
+
+    let diesel_id: uuid::Uuid = sqlx::query_scalar(
+        "INSERT INTO commodities (name, price, station_id) VALUES ('diesel', 1200, $1) RETURNING id",
+    )
+    .bind(station_id)
+    .fetch_one(&pool)
+    .await
+    .expect("diesel commodity should insert");
+
+    call(
+        app.clone(),
+        common::request_with_headers_and_json(
+            "PATCH",
+            &format!("/api/v1/admin/discounts/{diesel_id}"),
+            &[("x-admin-password", "super-secret")],
+            json!({ "commodity_id": diesel_id, "enabled": true, "percentage": 5 }),
+        ),
+    )
+    .await;
+
+    let account_response = call(app.clone(), request("POST", "/api/v1/loyalty/accounts")).await;
+    assert_eq!(account_response.status(), StatusCode::CREATED);
+    let account: Value = decode_json(account_response).await;
+    let key = account["key"].as_str().unwrap().to_string();
+
+    let bad_key = call(
+        app.clone(),
+        common::request_with_headers(
+            "GET",
+            "/api/v1/loyalty/account",
+            &[("x-loyalty-key", "not-a-key")],
+        ),
+    )
+    .await;
+    assert_eq!(bad_key.status(), StatusCode::UNAUTHORIZED);
+
+    let generate = |ip: &str, litres: i64| {
+        common::request_with_headers_and_json(
+            "POST",
+            "/api/v1/discounts/generate",
+            &[("x-forwarded-for", ip), ("x-loyalty-key", &key)],
+            json!({ "station_id": station_id, "commodity_id": diesel_id, "litres": litres }),
+        )
+    };
+    let redeem = |code: &str| {
+        common::request_with_headers_and_json(
+            "POST",
+            "/api/v1/discounts/redeem",
+            &[("authorization", &auth)],
+            json!({ "code": code }),
+        )
+    };
+    let get_account = || {
+        common::request_with_headers("GET", "/api/v1/loyalty/account", &[("x-loyalty-key", &key)])
+    };
+
+    let first: Value = decode_json(call(app.clone(), generate("203.0.113.110", 200)).await).await;
+    assert_eq!(first["loyalty_tier"].as_str(), Some("bronze"));
+    assert_eq!(first["total_discounted_price"].as_i64(), Some(228_000));
+    call(app.clone(), redeem(first["code"].as_str().unwrap())).await;
+
+    let summary: Value = decode_json(call(app.clone(), get_account()).await).await;
+    assert_eq!(summary["balance"].as_i64(), Some(2_280));
+    assert_eq!(summary["tier"].as_str(), Some("silver"));
+    assert_eq!(summary["points_to_next_tier"].as_i64(), Some(2_720));
+
+    // Silver adds a percentage point to the station's discount.
+    let second: Value = decode_json(call(app.clone(), generate("203.0.113.111", 100)).await).await;
+    assert_eq!(second["loyalty_tier"].as_str(), Some("silver"));
+    assert_eq!(second["total_discounted_price"].as_i64(), Some(112_800));
+    let second_code = second["code"].as_str().unwrap().to_string();
+    call(app.clone(), redeem(&second_code)).await;
+
+    let summary: Value = decode_json(call(app.clone(), get_account()).await).await;
+    assert_eq!(summary["balance"].as_i64(), Some(3_408));
+
+    call(
+        app.clone(),
+        common::request_with_headers_and_json(
+            "POST",
+            "/api/v1/discounts/redeem/reverse",
+            &[("authorization", &auth)],
+            json!({ "code": second_code, "reason": "wrong pump" }),
+        ),
+    )
+    .await;
+
+    let summary: Value = decode_json(call(app.clone(), get_account()).await).await;
+    assert_eq!(summary["balance"].as_i64(), Some(2_280));
+    let kinds: Vec<&str> = summary["transactions"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|transaction| transaction["kind"].as_str().unwrap())
+        .collect();
+    assert_eq!(kinds, ["reversal", "earn", "earn"]);
+
+    let create_voucher = |points: i64| {
+        common::request_with_headers_and_json(
+            "POST",
+            "/api/v1/loyalty/vouchers",
+            &[("x-loyalty-key", &key)],
+            json!({ "points": points }),
+        )
+    };
+
+    let too_many = call(app.clone(), create_voucher(5_000)).await;
+    assert_eq!(too_many.status(), StatusCode::UNAUTHORIZED);
+
+    let voucher_response = call(app.clone(), create_voucher(500)).await;
+    assert_eq!(voucher_response.status(), StatusCode::CREATED);
+    let voucher: Value = decode_json(voucher_response).await;
+    assert_eq!(voucher["value"].as_i64(), Some(500));
+    let voucher_code = voucher["code"].as_str().unwrap().to_string();
+
+    let redeem_voucher = || {
+        common::request_with_headers_and_json(
+            "POST",
+            "/api/v1/loyalty/vouchers/redeem",
+            &[("authorization", &auth)],
+            json!({ "code": voucher_code }),
+        )
+    };
+    let redeemed: Value = decode_json(call(app.clone(), redeem_voucher()).await).await;
+    assert_eq!(redeemed["message"].as_str(), Some("voucher redeemed successfully"));
+    assert_eq!(redeemed["value"].as_i64(), Some(500));
+    let again: Value = decode_json(call(app.clone(), redeem_voucher()).await).await;
+    assert_eq!(again["message"].as_str(), Some("voucher already redeemed"));
+    assert_eq!(again["value"].as_i64(), Some(0));
+
+    // A voucher that lapses unredeemed gives its points back.
+    let lapsed_voucher: Value = decode_json(call(app.clone(), create_voucher(500)).await).await;
+    sqlx::query(
+        "UPDATE loyalty_vouchers SET expires_at = now() - interval '1 minute' WHERE code = $1",
+    )
+    .bind(lapsed_voucher["code"].as_str().unwrap())
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let tamper = sqlx::query("UPDATE loyalty_entries SET amount = amount * 10")
+        .execute(&pool)
+        .await;
+    assert!(tamper.is_err(), "the ledger should be append-only");
+
+    // Age the first code's points past their lifetime, bypassing the
+    // append-only trigger the way only a superuser could.
+    let mut tx = pool.begin().await.unwrap();
+    sqlx::query("SET LOCAL session_replication_role = replica")
+        .execute(&mut *tx)
+        .await
+        .unwrap();
+    sqlx::query(
+        r#"
+        UPDATE loyalty_transactions
+        SET expires_at = now() - interval '1 day'
+        WHERE kind = 'earn'
+          AND discount_code_id = (SELECT id FROM discount_codes WHERE code = $1)
+        "#,
+    )
+    .bind(first["code"].as_str().unwrap())
+    .execute(&mut *tx)
+    .await
+    .unwrap();
+    tx.commit().await.unwrap();
+
+    let expired = fuelfinder_server::domain::loyalty::service::run_points_expiry(&pool)
+        .await
+        .unwrap();
+    assert_eq!(expired, 1);
+    let expired_again = fuelfinder_server::domain::loyalty::service::run_points_expiry(&pool)
+        .await
+        .unwrap();
+    assert_eq!(expired_again, 0);
+
+    let summary: Value = decode_json(call(app.clone(), get_account()).await).await;
+    assert_eq!(summary["balance"].as_i64(), Some(0));
+    assert_eq!(summary["transactions"][0]["kind"].as_str(), Some("expire"));
+    assert_eq!(summary["transactions"][0]["points"].as_i64(), Some(-1_780));
+    assert_eq!(summary["transactions"][1]["kind"].as_str(), Some("refund"));
+    assert_eq!(summary["transactions"][1]["points"].as_i64(), Some(500));
+    assert!(summary["vouchers"][0]["refunded_at"].is_string());
+
+    // Opening accounts is rate limited per address.
+    let mut statuses = Vec::new();
+    for _ in 0..6 {
+        let response = call(
+            app.clone(),
+            common::request_with_headers(
+                "POST",
+                "/api/v1/loyalty/accounts",
+                &[("x-forwarded-for", "203.0.113.120")],
+            ),
+        )
+        .await;
+        statuses.push(response.status());
+    }
+    assert_eq!(statuses[4], StatusCode::CREATED);
+    assert_eq!(statuses[5], StatusCode::TOO_MANY_REQUESTS);
+}
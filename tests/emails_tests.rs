@@ -0,0 +1,245 @@
+mod common;
+
+use std::sync::Arc;
+
+use axum::http::StatusCode;
+use fuelfinder_server::domain::{
+    emails::{
+        service::queue_email,
+        templates::{SUBSCRIPTION_EXPIRED, SUBSCRIPTION_REMINDER, TemplateVars, find_template},
+        transport::{EmailFuture, EmailTransport, MemoryEmailTransport, OutgoingEmail},
+    },
+    jobs::{
+        service::{EnqueueOptions, enqueue},
+        worker::{SUBSCRIPTION_REMINDERS_JOB, run_pending_jobs},
+    },
+};
+use serde_json::{Value, json};
+use serial_test::serial;
+use uuid::Uuid;
+
+use common::{
+    call, channels_with_email, create_named_station, db_pool, decode_json, request_with_headers,
+    reset_db, seed_admin, test_app_with_pool,
+};
+
+const ADMIN: (&str, &str) = ("x-admin-password", "super-secret");
+
+struct FailingTransport;
+
+impl EmailTransport for FailingTransport {
+    fn name(&self) -> &'static str {
+        "failing"
+    }
+
+    fn send<'a>(&'a self, _email: &'a OutgoingEmail) -> EmailFuture<'a, ()> {
+        Box::pin(async { anyhow::bail!("relay refused the message") })
+    }
+}
+
+fn vars(value: Value) -> TemplateVars {
+    match value {
+        Value::Object(vars) => vars,
+        _ => panic!("template variables must be an object"),
+    }
+}
+
+#[test]
+fn templates_render_text_and_escaped_html() {
+    let rendered = SUBSCRIPTION_REMINDER
+        .render(&vars(
+            json!({ "station_name": "Tom & Sons <Jabi>", "days_left": 4 }),
+        ))
+        .expect("template should render");
+
+    assert_eq!(rendered.subject, "Subscription expires in 4 day(s)");
+    assert!(rendered.text.starts_with("Hello Tom & Sons <Jabi>,"));
+    assert!(rendered.html.contains("Hello Tom &amp; Sons &lt;Jabi&gt;,"));
+    assert!(rendered.html.contains("<strong>4 day(s)</strong>"));
+
+    let missing = SUBSCRIPTION_EXPIRED.render(&TemplateVars::new());
+    assert!(missing.is_err());
+
+    assert!(find_template("subscription_expired").is_some());
+    assert!(find_template("welcome").is_none());
+}
+
+#[tokio::test]
+#[serial]
+async fn subscription_reminder_is_rendered_sent_once_and_tracked() {
+    let Some(pool) = db_pool().await else {
+        eprintln!("Skipping DB-backed emails test: TEST_DATABASE_URL not set");
+        return;
+    };
+
+    reset_db(&pool).await;
+    seed_admin(&pool, "super-secret").await;
+
+    let app = test_app_with_pool(pool.clone());
+    let email = format!("{}@example.com", Uuid::new_v4().simple());
+    let station_id =
+        create_named_station(app.clone(), &email, "Wuse Fuel", "gas", (9.06, 7.41)).await;
+
+    sqlx::query(
+        "UPDATE subscriptions SET ends_at = now() + interval '12 hours' WHERE station_id = $1",
+    )
+    .bind(station_id)
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let transport = Arc::new(MemoryEmailTransport::default());
+    let channels = channels_with_email(transport.clone());
+
+    for _ in 0..2 {
+        enqueue(
+            &pool,
+            SUBSCRIPTION_REMINDERS_JOB,
+            &json!({}),
+            EnqueueOptions::default(),
+        )
+        .await
+        .unwrap();
+        run_pending_jobs(&pool, &channels, "test-worker", 10)
+            .await
+            .unwrap();
+    }
+
+    let sent = transport.sent();
+    assert_eq!(sent.len(), 1);
+    assert_eq!(sent[0].to, email);
+    assert_eq!(sent[0].subject, "Subscription expires in 1 day(s)");
+    assert!(sent[0].text.contains("Hello WUSE FUEL,"));
+    assert!(sent[0].html.contains("<strong>1 day(s)</strong>"));
+
+    let outbox: Value = decode_json(
+        call(
+            app.clone(),
+            request_with_headers(
+                "GET",
+                &format!("/api/v1/admin/emails?status=sent&recipient={email}"),
+                &[ADMIN],
+            ),
+        )
+        .await,
+    )
+    .await;
+    let outbox = outbox.as_array().expect("emails should be a list");
+    assert_eq!(outbox.len(), 1);
+    assert_eq!(outbox[0]["template"], "subscription_reminder");
+    assert_eq!(outbox[0]["transport"], "memory");
+    assert_eq!(outbox[0]["attempts"], 1);
+    assert_eq!(outbox[0]["variables"]["days_left"], 1);
+
+    let detail = call(
+        app.clone(),
+        request_with_headers(
+            "GET",
+            &format!("/api/v1/admin/emails/{}", outbox[0]["id"].as_str().unwrap()),
+            &[ADMIN],
+        ),
+    )
+    .await;
+    assert_eq!(detail.status(), StatusCode::OK);
+
+    let unauthorized = call(
+        app,
+        request_with_headers(
+            "GET",
+            "/api/v1/admin/emails",
+            &[("x-admin-password", "wrong")],
+        ),
+    )
+    .await;
+    assert_eq!(unauthorized.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+#[serial]
+async fn failed_delivery_is_recorded_and_recovers_on_retry() {
+    let Some(pool) = db_pool().await else {
+        eprintln!("Skipping DB-backed emails test: TEST_DATABASE_URL not set");
+        return;
+    };
+
+    reset_db(&pool).await;
+    seed_admin(&pool, "super-secret").await;
+
+    let mut tx = pool.begin().await.unwrap();
+    let email_id = queue_email(
+        &mut tx,
+        &SUBSCRIPTION_EXPIRED,
+        "owner@example.com",
+        vars(json!({ "station_name": "Garki Gas" })),
+        None,
+        Some("expired-once".to_string()),
+    )
+    .await
+    .unwrap()
+    .expect("email should be queued");
+    let duplicate = queue_email(
+        &mut tx,
+        &SUBSCRIPTION_EXPIRED,
+        "owner@example.com",
+        vars(json!({ "station_name": "Garki Gas" })),
+        None,
+        Some("expired-once".to_string()),
+    )
+    .await
+    .unwrap();
+    assert!(duplicate.is_none());
+    tx.commit().await.unwrap();
+
+    sqlx::query("UPDATE jobs SET max_attempts = 1")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    run_pending_jobs(
+        &pool,
+        &channels_with_email(Arc::new(FailingTransport)),
+        "test-worker",
+        10,
+    )
+    .await
+    .unwrap();
+
+    let (status, attempts, last_error): (String, i32, Option<String>) =
+        sqlx::query_as("SELECT status, attempts, last_error FROM email_outbox WHERE id = $1")
+            .bind(email_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!((status.as_str(), attempts), ("failed", 1));
+    assert_eq!(last_error.as_deref(), Some("relay refused the message"));
+
+    let job_id: Uuid = sqlx::query_scalar("SELECT id FROM jobs WHERE status = 'dead'")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    let retried = call(
+        test_app_with_pool(pool.clone()),
+        request_with_headers(
+            "POST",
+            &format!("/api/v1/admin/jobs/{job_id}/retry"),
+            &[ADMIN],
+        ),
+    )
+    .await;
+    assert_eq!(retried.status(), StatusCode::OK);
+
+    let transport = Arc::new(MemoryEmailTransport::default());
+    let channels = channels_with_email(transport.clone());
+    run_pending_jobs(&pool, &channels, "test-worker", 10)
+        .await
+        .unwrap();
+    assert_eq!(transport.sent().len(), 1);
+
+    let (status, attempts): (String, i32) =
+        sqlx::query_as("SELECT status, attempts FROM email_outbox WHERE id = $1")
+            .bind(email_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!((status.as_str(), attempts), ("sent", 2));
+}
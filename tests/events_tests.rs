@@ -0,0 +1,292 @@
+mod common;
+
+use std::time::Duration;
+
+use axum::{
+    Router,
+    body::BodyDataStream,
+    http::{StatusCode, header},
+};
+use fuelfinder_server::{
+    app_state::AppState,
+    authentication::station::authenticate::token::service::TokenService,
+    build_app,
+};
+use jsonwebtoken::{EncodingKey, Header, encode};
+use serde_json::{Value, json};
+use serial_test::serial;
+use tokio_stream::StreamExt;
+use uuid::Uuid;
+
+use common::{
+    call, commodity_id_for_station, create_notification, create_station_and_signin, db_pool,
+    decode_json, end_station_subscription, request, request_with_auth,
+    request_with_headers_and_json, reset_db, seed_admin, test_app,
+};
+
+/// Reads server-sent events until `count` have arrived, skipping
+/// keep-alive comments.
+async fn next_events(stream: &mut BodyDataStream, count: usize) -> Vec<(String, Value)> {
+    let mut buffer = String::new();
+    let mut events = Vec::new();
+
+    while events.len() < count {
+        let chunk = tokio::time::timeout(Duration::from_secs(10), stream.next())
+            .await
+            .expect("events should arrive in time")
+            .expect("stream should stay open")
+            .expect("chunk should be readable");
+        buffer.push_str(std::str::from_utf8(&chunk).unwrap());
+
+        while let Some(end) = buffer.find("\n\n") {
+            let block: String = buffer.drain(..end + 2).collect();
+            let mut name = None;
+            let mut data = None;
+            for line in block.lines() {
+                if let Some(value) = line.strip_prefix("event: ") {
+                    name = Some(value.to_string());
+                } else if let Some(value) = line.strip_prefix("data: ") {
+                    data = Some(serde_json::from_str(value).unwrap());
+                }
+            }
+            if let (Some(name), Some(data)) = (name, data) {
+                events.push((name, data));
+            }
+        }
+    }
+
+    events
+}
+
+/// Reads the stream until the server closes it, returning the names of any
+/// events that arrived first.
+async fn events_until_closed(stream: &mut BodyDataStream) -> Vec<String> {
+    let mut body = String::new();
+    while let Some(chunk) = tokio::time::timeout(Duration::from_secs(10), stream.next())
+        .await
+        .expect("stream should close in time")
+    {
+        body.push_str(std::str::from_utf8(&chunk.expect("chunk should be readable")).unwrap());
+    }
+
+    body.lines()
+        .filter_map(|line| line.strip_prefix("event: "))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Re-signs `token` to expire `seconds` from now, with the same secret the
+/// app signs with.
+fn expiring_in(token: &str, seconds: i64) -> String {
+    let secret = std::env::var("JWT_SECRET").expect("JWT_SECRET should be set");
+    let mut claims = TokenService::new(&secret)
+        .decode(token.to_string())
+        .unwrap()
+        .claims;
+    claims.exp = (chrono::Utc::now().timestamp() + seconds) as usize;
+
+    encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(secret.as_bytes()),
+    )
+    .unwrap()
+}
+
+async fn listening_app(pool: &sqlx::PgPool) -> Router {
+    let state = AppState::with_pool(pool.clone()).expect("app state should build");
+    state.events.listen().await.unwrap();
+    build_app(state)
+}
+
+async fn open_stream(app: Router, token: &str) -> BodyDataStream {
+    let response = call(
+        app,
+        request_with_auth("GET", "/api/v1/stations/dashboard/events", token),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    response.into_body().into_data_stream()
+}
+
+#[tokio::test]
+async fn dashboard_events_require_auth() {
+    let response = call(
+        test_app(),
+        request("GET", "/api/v1/stations/dashboard/events"),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+#[serial]
+async fn dashboard_events_stream_notifications_codes_and_price_changes() {
+    let Some(pool) = db_pool().await else {
+        eprintln!("Skipping DB-backed events test: TEST_DATABASE_URL not set");
+        return;
+    };
+
+    reset_db(&pool).await;
+    seed_admin(&pool, "super-secret").await;
+
+    let app = listening_app(&pool).await;
+
+    let email = format!("{}@example.com", Uuid::new_v4().simple());
+    let other_email = format!("{}@example.com", Uuid::new_v4().simple());
+    let (station_id, token) =
+        create_station_and_signin(app.clone(), &email, "petrol", (9.07, 7.47)).await;
+    let (other_id, _) =
+        create_station_and_signin(app.clone(), &other_email, "petrol", (9.07, 7.47)).await;
+    let commodity_id = commodity_id_for_station(&pool, station_id).await;
+
+    let response = call(
+        app.clone(),
+        request_with_auth("GET", "/api/v1/stations/dashboard/events", &token),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers()[header::CONTENT_TYPE],
+        "text/event-stream"
+    );
+    let mut stream = response.into_body().into_data_stream();
+
+    create_notification(&pool, other_id, "Not yours", "", "profile").await;
+    let notification_id = create_notification(
+        &pool,
+        station_id,
+        "Verification reviewed",
+        "Approved",
+        "verification",
+    )
+    .await;
+
+    let price = call(
+        app.clone(),
+        request_with_headers_and_json(
+            "PATCH",
+            &format!("/api/v1/commodities/{commodity_id}"),
+            &[("authorization", &format!("Bearer {token}"))],
+            json!({ "price": 950, "is_available": true }),
+        ),
+    )
+    .await;
+    assert_eq!(price.status(), StatusCode::OK);
+
+    let enabled = call(
+        app.clone(),
+        request_with_headers_and_json(
+            "PATCH",
+            &format!("/api/v1/admin/discounts/{commodity_id}"),
+            &[("x-admin-password", "super-secret")],
+            json!({ "commodity_id": commodity_id, "enabled": true, "percentage": 10 }),
+        ),
+    )
+    .await;
+    assert_eq!(enabled.status(), StatusCode::NO_CONTENT);
+
+    let generated = call(
+        app,
+        request_with_headers_and_json(
+            "POST",
+            "/api/v1/discounts/generate",
+            &[("x-forwarded-for", "203.0.113.50")],
+            json!({ "station_id": station_id }),
+        ),
+    )
+    .await;
+    assert_eq!(generated.status(), StatusCode::CREATED);
+    let generated: Value = decode_json(generated).await;
+
+    let events = next_events(&mut stream, 3).await;
+    let names: Vec<&str> = events.iter().map(|(name, _)| name.as_str()).collect();
+    assert_eq!(names, vec!["notification", "price_change", "discount_code"]);
+
+    let notification = &events[0].1;
+    assert_eq!(notification["id"], notification_id.to_string());
+    assert_eq!(notification["title"], "Verification reviewed");
+    assert_eq!(notification["kind"], "verification");
+
+    let price_change = &events[1].1;
+    assert_eq!(price_change["commodity_id"], commodity_id.to_string());
+    assert_eq!(price_change["price"], 950);
+
+    let code = &events[2].1;
+    assert_eq!(code["code"], generated["code"]);
+    assert_eq!(code["commodity_id"], commodity_id.to_string());
+}
+
+#[tokio::test]
+#[serial]
+async fn dashboard_events_end_when_the_token_expires() {
+    let Some(pool) = db_pool().await else {
+        eprintln!("Skipping DB-backed events test: TEST_DATABASE_URL not set");
+        return;
+    };
+
+    reset_db(&pool).await;
+    seed_admin(&pool, "super-secret").await;
+
+    let app = listening_app(&pool).await;
+    let email = format!("{}@example.com", Uuid::new_v4().simple());
+    let (_, token) = create_station_and_signin(app.clone(), &email, "petrol", (9.07, 7.47)).await;
+
+    let mut stream = open_stream(app, &expiring_in(&token, 2)).await;
+    assert!(events_until_closed(&mut stream).await.is_empty());
+}
+
+#[tokio::test]
+#[serial]
+async fn dashboard_events_end_when_the_subscription_lapses() {
+    let Some(pool) = db_pool().await else {
+        eprintln!("Skipping DB-backed events test: TEST_DATABASE_URL not set");
+        return;
+    };
+
+    reset_db(&pool).await;
+    seed_admin(&pool, "super-secret").await;
+
+    let app = listening_app(&pool).await;
+    let email = format!("{}@example.com", Uuid::new_v4().simple());
+    let (station_id, token) =
+        create_station_and_signin(app.clone(), &email, "petrol", (9.07, 7.47)).await;
+    let mut stream = open_stream(app, &token).await;
+
+    end_station_subscription(&pool, station_id, 30).await;
+    create_notification(&pool, station_id, "Renew now", "", "subscription").await;
+
+    assert!(events_until_closed(&mut stream).await.is_empty());
+}
+
+#[tokio::test]
+#[serial]
+async fn dashboard_events_ask_for_a_resync_after_the_listener_reconnects() {
+    let Some(pool) = db_pool().await else {
+        eprintln!("Skipping DB-backed events test: TEST_DATABASE_URL not set");
+        return;
+    };
+
+    reset_db(&pool).await;
+    seed_admin(&pool, "super-secret").await;
+
+    let app = listening_app(&pool).await;
+    let email = format!("{}@example.com", Uuid::new_v4().simple());
+    let (_, token) = create_station_and_signin(app.clone(), &email, "petrol", (9.07, 7.47)).await;
+    let mut stream = open_stream(app, &token).await;
+
+    sqlx::query(
+        r#"
+        SELECT pg_terminate_backend(pid)
+        FROM pg_stat_activity
+        WHERE datname = current_database() AND query LIKE 'LISTEN %'
+        "#,
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let events = next_events(&mut stream, 1).await;
+    assert_eq!(events[0].0, "resync");
+}
@@ -0,0 +1,258 @@
+mod common;
+
+use axum::http::StatusCode;
+use fuelfinder_server::domain::jobs::{
+    service::{EnqueueOptions, backoff, enqueue, enqueue_due_schedules},
+    worker::{SUBSCRIPTION_REMINDERS_JOB, run_pending_jobs},
+};
+use serde_json::{Value, json};
+use serial_test::serial;
+use uuid::Uuid;
+
+use common::{
+    call, db_pool, decode_json, log_channels, request, request_with_headers, reset_db, seed_admin,
+    test_app, test_app_with_pool,
+};
+
+const ADMIN: (&str, &str) = ("x-admin-password", "super-secret");
+
+#[test]
+fn backoff_doubles_and_is_capped() {
+    assert_eq!(backoff(1).num_seconds(), 30);
+    assert_eq!(backoff(2).num_seconds(), 60);
+    assert_eq!(backoff(4).num_seconds(), 240);
+    assert_eq!(backoff(40).num_seconds(), 3600);
+}
+
+#[tokio::test]
+async fn admin_job_retry_route_exists() {
+    let response = call(
+        test_app(),
+        request(
+            "GET",
+            "/api/v1/admin/jobs/550e8400-e29b-41d4-a716-446655440000/retry",
+        ),
+    )
+    .await;
+
+    assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+}
+
+#[tokio::test]
+#[serial]
+async fn failing_job_backs_off_then_dead_letters_and_can_be_retried() {
+    let Some(pool) = db_pool().await else {
+        eprintln!("Skipping DB-backed jobs test: TEST_DATABASE_URL not set");
+        return;
+    };
+
+    reset_db(&pool).await;
+    seed_admin(&pool, "super-secret").await;
+    let app = test_app_with_pool(pool.clone());
+
+    let job_id = enqueue(
+        &pool,
+        "no_such_job",
+        &json!({}),
+        EnqueueOptions {
+            max_attempts: Some(2),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap()
+    .expect("job should be queued");
+
+    let unauthorized = call(
+        app.clone(),
+        request_with_headers(
+            "GET",
+            "/api/v1/admin/jobs",
+            &[("x-admin-password", "wrong")],
+        ),
+    )
+    .await;
+    assert_eq!(unauthorized.status(), StatusCode::UNAUTHORIZED);
+
+    assert_eq!(
+        run_pending_jobs(&pool, &log_channels(), "test-worker", 10)
+            .await
+            .unwrap(),
+        1
+    );
+
+    let (status, attempts, waits): (String, i32, bool) = sqlx::query_as(
+        "SELECT status, attempts, run_at > now() + interval '20 seconds' FROM jobs WHERE id = $1",
+    )
+    .bind(job_id)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!((status.as_str(), attempts, waits), ("queued", 1, true));
+
+    // Not due yet, so nothing runs.
+    assert_eq!(
+        run_pending_jobs(&pool, &log_channels(), "test-worker", 10)
+            .await
+            .unwrap(),
+        0
+    );
+
+    sqlx::query("UPDATE jobs SET run_at = now() WHERE id = $1")
+        .bind(job_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    assert_eq!(
+        run_pending_jobs(&pool, &log_channels(), "test-worker", 10)
+            .await
+            .unwrap(),
+        1
+    );
+
+    let dead: Value = decode_json(
+        call(
+            app.clone(),
+            request_with_headers("GET", "/api/v1/admin/jobs?status=dead", &[ADMIN]),
+        )
+        .await,
+    )
+    .await;
+    let dead = dead.as_array().expect("jobs should be a list");
+    assert_eq!(dead.len(), 1);
+    assert_eq!(dead[0]["attempts"], 2);
+    assert_eq!(dead[0]["last_error"], "unknown job kind no_such_job");
+
+    let bad_filter = call(
+        app.clone(),
+        request_with_headers("GET", "/api/v1/admin/jobs?status=lost", &[ADMIN]),
+    )
+    .await;
+    assert_eq!(bad_filter.status(), StatusCode::UNAUTHORIZED);
+
+    let retried = call(
+        app.clone(),
+        request_with_headers(
+            "POST",
+            &format!("/api/v1/admin/jobs/{job_id}/retry"),
+            &[ADMIN],
+        ),
+    )
+    .await;
+    assert_eq!(retried.status(), StatusCode::OK);
+    let retried: Value = decode_json(retried).await;
+    assert_eq!(retried["status"], "queued");
+    assert_eq!(retried["attempts"], 0);
+
+    let again = call(
+        app.clone(),
+        request_with_headers(
+            "POST",
+            &format!("/api/v1/admin/jobs/{job_id}/retry"),
+            &[ADMIN],
+        ),
+    )
+    .await;
+    assert_eq!(again.status(), StatusCode::UNAUTHORIZED);
+
+    let missing = call(
+        app,
+        request_with_headers(
+            "GET",
+            &format!("/api/v1/admin/jobs/{}", Uuid::new_v4()),
+            &[ADMIN],
+        ),
+    )
+    .await;
+    assert_eq!(missing.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+#[serial]
+async fn dedupe_key_allows_one_pending_job() {
+    let Some(pool) = db_pool().await else {
+        eprintln!("Skipping DB-backed jobs test: TEST_DATABASE_URL not set");
+        return;
+    };
+
+    reset_db(&pool).await;
+
+    let options = || EnqueueOptions {
+        dedupe_key: Some("only-once".to_string()),
+        ..Default::default()
+    };
+
+    let first = enqueue(&pool, "no_such_job", &json!({}), options())
+        .await
+        .unwrap();
+    let second = enqueue(&pool, "no_such_job", &json!({}), options())
+        .await
+        .unwrap();
+    assert!(first.is_some());
+    assert!(second.is_none());
+
+    sqlx::query("UPDATE jobs SET status = 'succeeded', finished_at = now()")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let after_finish = enqueue(&pool, "no_such_job", &json!({}), options())
+        .await
+        .unwrap();
+    assert!(after_finish.is_some());
+}
+
+#[tokio::test]
+#[serial]
+async fn due_schedules_are_enqueued_once_and_advanced() {
+    let Some(pool) = db_pool().await else {
+        eprintln!("Skipping DB-backed jobs test: TEST_DATABASE_URL not set");
+        return;
+    };
+
+    reset_db(&pool).await;
+    seed_admin(&pool, "super-secret").await;
+
+    sqlx::query("UPDATE job_schedules SET next_run_at = now() + interval '1 day'")
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query(
+        "UPDATE job_schedules SET next_run_at = now() - interval '1 minute' WHERE name = $1",
+    )
+    .bind(SUBSCRIPTION_REMINDERS_JOB)
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    assert_eq!(enqueue_due_schedules(&pool).await.unwrap(), 1);
+    assert_eq!(enqueue_due_schedules(&pool).await.unwrap(), 0);
+
+    let kinds: Vec<String> = sqlx::query_scalar("SELECT kind FROM jobs")
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+    assert_eq!(kinds, vec![SUBSCRIPTION_REMINDERS_JOB.to_string()]);
+
+    let schedules: Value = decode_json(
+        call(
+            test_app_with_pool(pool.clone()),
+            request_with_headers("GET", "/api/v1/admin/job-schedules", &[ADMIN]),
+        )
+        .await,
+    )
+    .await;
+    let reminders = schedules
+        .as_array()
+        .and_then(|schedules| {
+            schedules
+                .iter()
+                .find(|schedule| schedule["name"] == SUBSCRIPTION_REMINDERS_JOB)
+        })
+        .expect("reminder schedule should be listed");
+    assert!(reminders["last_job_id"].is_string());
+
+    let next_run_at: chrono::DateTime<chrono::Utc> =
+        serde_json::from_value(reminders["next_run_at"].clone()).unwrap();
+    assert!(next_run_at > chrono::Utc::now());
+}
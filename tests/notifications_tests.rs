@@ -0,0 +1,774 @@
+mod common;
+
+use std::sync::{Arc, Mutex};
+
+use axum::{
+    Json, Router,
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+    routing::post,
+};
+use fuelfinder_server::domain::{
+    emails::transport::MemoryEmailTransport,
+    jobs::worker::run_pending_jobs,
+    notifications::channels::{Channels, HttpSmsProvider, WhatsAppCloudProvider, normalize_phone},
+    subscriptions::service::run_subscription_reminder_cycle,
+};
+use serde_json::{Value, json};
+use serial_test::serial;
+use uuid::Uuid;
+
+use common::{
+    call, create_notification, create_station_and_signin, db_pool, decode_json, request_with_auth,
+    request_with_headers, request_with_headers_and_json, reset_db, seed_admin, test_app_with_pool,
+};
+
+const ADMIN: (&str, &str) = ("x-admin-password", "super-secret");
+
+#[derive(Clone)]
+struct RecordedRequest {
+    path: String,
+    authorization: Option<String>,
+    body: Value,
+}
+
+/// Stands in for the SMS and WhatsApp HTTP APIs and records every call.
+#[derive(Clone, Default)]
+struct MockProvider {
+    requests: Arc<Mutex<Vec<RecordedRequest>>>,
+}
+
+impl MockProvider {
+    fn requests(&self) -> Vec<RecordedRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    fn record(&self, path: String, headers: &HeaderMap, body: Value) {
+        let authorization = headers
+            .get("authorization")
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+        self.requests.lock().unwrap().push(RecordedRequest {
+            path,
+            authorization,
+            body,
+        });
+    }
+}
+
+async fn mock_sms(
+    State(mock): State<MockProvider>,
+    headers: HeaderMap,
+    Json(body): Json<Value>,
+) -> Json<Value> {
+    mock.record("/api/sms/send".to_string(), &headers, body);
+    Json(json!({ "message_id": "sms-1", "message": "Successfully Sent" }))
+}
+
+async fn mock_whatsapp(
+    State(mock): State<MockProvider>,
+    Path(phone_number_id): Path<String>,
+    headers: HeaderMap,
+    Json(body): Json<Value>,
+) -> Json<Value> {
+    mock.record(format!("/{phone_number_id}/messages"), &headers, body);
+    Json(json!({ "messaging_product": "whatsapp", "messages": [{ "id": "wamid.1" }] }))
+}
+
+async fn spawn_mock_provider() -> (String, MockProvider) {
+    let mock = MockProvider::default();
+    let router = Router::new()
+        .route("/api/sms/send", post(mock_sms))
+        .route("/{phone_number_id}/messages", post(mock_whatsapp))
+        .with_state(mock.clone());
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+
+    (format!("http://{addr}"), mock)
+}
+
+fn mock_channels(base_url: &str, email: Arc<MemoryEmailTransport>) -> Channels {
+    Channels {
+        email,
+        sms: Some(Arc::new(
+            HttpSmsProvider::new(base_url, "sms-key", "FuelFinder").expect("sms provider should build"),
+        )),
+        whatsapp: Some(Arc::new(
+            WhatsAppCloudProvider::new(base_url, "PHONE_ID", "wa-token")
+                .expect("whatsapp provider should build"),
+        )),
+    }
+}
+
+async fn update_channels(app: Router, token: &str, body: Value) -> axum::response::Response {
+    call(
+        app,
+        request_with_headers_and_json(
+            "PUT",
+            "/api/v1/stations/dashboard/notification-channels",
+            &[("authorization", &format!("Bearer {token}"))],
+            body,
+        ),
+    )
+    .await
+}
+
+async fn list_notifications(app: Router, token: &str, query: &str) -> Vec<Value> {
+    let response = call(
+        app,
+        request_with_auth(
+            "GET",
+            &format!("/api/v1/stations/dashboard/notifications{query}"),
+            token,
+        ),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let notifications: Value = decode_json(response).await;
+    notifications.as_array().unwrap().clone()
+}
+
+#[test]
+fn phones_are_normalized_to_international_format() {
+    assert_eq!(
+        normalize_phone("0803 123 4567").as_deref(),
+        Some("2348031234567")
+    );
+    assert_eq!(
+        normalize_phone("+234-803-123-4567").as_deref(),
+        Some("2348031234567")
+    );
+    assert_eq!(
+        normalize_phone("2347031234567").as_deref(),
+        Some("2347031234567")
+    );
+    assert_eq!(normalize_phone("0603 123 4567"), None);
+    assert_eq!(normalize_phone("12345"), None);
+    assert_eq!(normalize_phone("0803123456x"), None);
+}
+
+#[tokio::test]
+#[serial]
+async fn channel_preferences_default_to_email_and_validate_phone() {
+    let Some(pool) = db_pool().await else {
+        eprintln!("Skipping DB-backed notifications test: TEST_DATABASE_URL not set");
+        return;
+    };
+
+    reset_db(&pool).await;
+    seed_admin(&pool, "super-secret").await;
+
+    let app = test_app_with_pool(pool.clone());
+    let email = format!("{}@example.com", Uuid::new_v4().simple());
+    let (_, token) = create_station_and_signin(app.clone(), &email, "gas", (9.15, 7.33)).await;
+
+    let defaults: Value = decode_json(
+        call(
+            app.clone(),
+            request_with_headers(
+                "GET",
+                "/api/v1/stations/dashboard/notification-channels",
+                &[("authorization", &format!("Bearer {token}"))],
+            ),
+        )
+        .await,
+    )
+    .await;
+    assert_eq!(defaults["email"], true);
+    assert_eq!(defaults["sms"], false);
+    assert_eq!(defaults["whatsapp"], false);
+    assert_eq!(defaults["destination"], "2348000001111");
+
+    let bad_phone = update_channels(app.clone(), &token, json!({ "phone": "12345" })).await;
+    assert_eq!(bad_phone.status(), StatusCode::UNAUTHORIZED);
+
+    let updated = update_channels(
+        app.clone(),
+        &token,
+        json!({ "sms": true, "phone": "+234 803 123 4567" }),
+    )
+    .await;
+    assert_eq!(updated.status(), StatusCode::OK);
+    let updated: Value = decode_json(updated).await;
+    assert_eq!(updated["sms"], true);
+    assert_eq!(updated["email"], true);
+    assert_eq!(updated["destination"], "2348031234567");
+
+    let cleared: Value =
+        decode_json(update_channels(app, &token, json!({ "phone": "" })).await).await;
+    assert!(cleared["phone"].is_null());
+    assert_eq!(cleared["destination"], "2348000001111");
+}
+
+#[tokio::test]
+#[serial]
+async fn reminders_fan_out_to_every_preferred_channel_once() {
+    let Some(pool) = db_pool().await else {
+        eprintln!("Skipping DB-backed notifications test: TEST_DATABASE_URL not set");
+        return;
+    };
+
+    reset_db(&pool).await;
+    seed_admin(&pool, "super-secret").await;
+
+    let app = test_app_with_pool(pool.clone());
+    let email = format!("{}@example.com", Uuid::new_v4().simple());
+    let (station_id, token) =
+        create_station_and_signin(app.clone(), &email, "gas", (9.15, 7.33)).await;
+
+    let updated = update_channels(app, &token, json!({ "sms": true, "whatsapp": true })).await;
+    assert_eq!(updated.status(), StatusCode::OK);
+
+    sqlx::query(
+        "UPDATE subscriptions SET ends_at = now() + interval '4 days 2 hours' WHERE station_id = $1",
+    )
+    .bind(station_id)
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let (base_url, mock) = spawn_mock_provider().await;
+    let email_transport = Arc::new(MemoryEmailTransport::default());
+    let channels = mock_channels(&base_url, email_transport.clone());
+
+    for _ in 0..2 {
+        run_subscription_reminder_cycle(&pool).await.unwrap();
+        run_pending_jobs(&pool, &channels, "test-worker", 10)
+            .await
+            .unwrap();
+    }
+
+    assert_eq!(email_transport.sent().len(), 1);
+
+    let requests = mock.requests();
+    assert_eq!(requests.len(), 2);
+
+    let sms = &requests
+        .iter()
+        .find(|request| request.path == "/api/sms/send")
+        .expect("an sms should be sent")
+        .body;
+    assert_eq!(sms["to"], "2348000001111");
+    assert_eq!(sms["api_key"], "sms-key");
+    assert_eq!(sms["from"], "FuelFinder");
+    assert!(
+        sms["sms"]
+            .as_str()
+            .unwrap()
+            .starts_with("Subscription reminder: Your subscription expires in 4 day(s)")
+    );
+
+    let whatsapp = requests
+        .iter()
+        .find(|request| request.path == "/PHONE_ID/messages")
+        .expect("a whatsapp message should be sent");
+    assert_eq!(whatsapp.authorization.as_deref(), Some("Bearer wa-token"));
+    let whatsapp = &whatsapp.body;
+    assert_eq!(whatsapp["to"], "2348000001111");
+    assert_eq!(whatsapp["type"], "text");
+
+    let messages: Vec<(String, String, Option<String>)> = sqlx::query_as(
+        "SELECT channel, status, provider_message_id FROM outbound_messages ORDER BY channel",
+    )
+    .fetch_all(&pool)
+    .await
+    .unwrap();
+    assert_eq!(
+        messages,
+        vec![
+            (
+                "sms".to_string(),
+                "sent".to_string(),
+                Some("sms-1".to_string())
+            ),
+            (
+                "whatsapp".to_string(),
+                "sent".to_string(),
+                Some("wamid.1".to_string())
+            ),
+        ]
+    );
+}
+
+#[tokio::test]
+#[serial]
+async fn suspension_notices_skip_disabled_channels_and_record_provider_errors() {
+    let Some(pool) = db_pool().await else {
+        eprintln!("Skipping DB-backed notifications test: TEST_DATABASE_URL not set");
+        return;
+    };
+
+    reset_db(&pool).await;
+    seed_admin(&pool, "super-secret").await;
+
+    let app = test_app_with_pool(pool.clone());
+    let email = format!("{}@example.com", Uuid::new_v4().simple());
+    let (station_id, token) =
+        create_station_and_signin(app.clone(), &email, "gas", (9.15, 7.33)).await;
+
+    let updated =
+        update_channels(app.clone(), &token, json!({ "email": false, "sms": true })).await;
+    assert_eq!(updated.status(), StatusCode::OK);
+
+    let suspended = call(
+        app,
+        request_with_headers_and_json(
+            "PUT",
+            &format!("/api/v1/admin/stations/{station_id}/discount-suspension"),
+            &[ADMIN],
+            json!({ "suspended": true, "reason": "codes resold online" }),
+        ),
+    )
+    .await;
+    assert_eq!(suspended.status(), StatusCode::OK);
+
+    let emails: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM email_outbox")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(emails, 0);
+
+    // The provider is down: nothing answers under this path.
+    let (base_url, mock) = spawn_mock_provider().await;
+    let channels = mock_channels(
+        &format!("{base_url}/down"),
+        Arc::new(MemoryEmailTransport::default()),
+    );
+    run_pending_jobs(&pool, &channels, "test-worker", 10)
+        .await
+        .unwrap();
+    assert!(mock.requests().is_empty());
+
+    let (channel, body, status, attempts, last_error): (
+        String,
+        String,
+        String,
+        i32,
+        Option<String>,
+    ) = sqlx::query_as("SELECT channel, body, status, attempts, last_error FROM outbound_messages")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(channel, "sms");
+    assert_eq!(
+        body,
+        "Discounts suspended: Discount codes are paused for your station: codes resold online"
+    );
+    assert_eq!((status.as_str(), attempts), ("pending", 1));
+    assert!(last_error.is_some());
+
+    let job_status: String =
+        sqlx::query_scalar("SELECT status FROM jobs WHERE kind = 'send_message'")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(job_status, "queued");
+}
+
+#[tokio::test]
+#[serial]
+async fn messages_for_an_unconfigured_channel_fail_without_retrying() {
+    let Some(pool) = db_pool().await else {
+        eprintln!("Skipping DB-backed notifications test: TEST_DATABASE_URL not set");
+        return;
+    };
+
+    reset_db(&pool).await;
+    seed_admin(&pool, "super-secret").await;
+
+    let app = test_app_with_pool(pool.clone());
+    let email = format!("{}@example.com", Uuid::new_v4().simple());
+    let (station_id, token) =
+        create_station_and_signin(app.clone(), &email, "gas", (9.15, 7.33)).await;
+
+    let updated =
+        update_channels(app.clone(), &token, json!({ "email": false, "sms": true })).await;
+    assert_eq!(updated.status(), StatusCode::OK);
+
+    let suspended = call(
+        app,
+        request_with_headers_and_json(
+            "PUT",
+            &format!("/api/v1/admin/stations/{station_id}/discount-suspension"),
+            &[ADMIN],
+            json!({ "suspended": true, "reason": "codes resold online" }),
+        ),
+    )
+    .await;
+    assert_eq!(suspended.status(), StatusCode::OK);
+
+    let channels = Channels {
+        email: Arc::new(MemoryEmailTransport::default()),
+        sms: None,
+        whatsapp: None,
+    };
+    run_pending_jobs(&pool, &channels, "test-worker", 10)
+        .await
+        .unwrap();
+
+    let (status, provider_message_id, last_error): (String, Option<String>, Option<String>) =
+        sqlx::query_as("SELECT status, provider_message_id, last_error FROM outbound_messages")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(status, "failed");
+    assert!(provider_message_id.is_none());
+    assert_eq!(last_error.as_deref(), Some("no provider configured for sms"));
+
+    let job_status: String =
+        sqlx::query_scalar("SELECT status FROM jobs WHERE kind = 'send_message'")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(job_status, "succeeded");
+}
+
+#[tokio::test]
+#[serial]
+async fn dashboard_notifications_page_filter_and_bulk_update() {
+    let Some(pool) = db_pool().await else {
+        eprintln!("Skipping DB-backed notifications test: TEST_DATABASE_URL not set");
+        return;
+    };
+
+    reset_db(&pool).await;
+    seed_admin(&pool, "super-secret").await;
+
+    let app = test_app_with_pool(pool.clone());
+    let email = format!("{}@example.com", Uuid::new_v4().simple());
+    let (station_id, token) =
+        create_station_and_signin(app.clone(), &email, "gas", (9.15, 7.33)).await;
+    let auth = format!("Bearer {token}");
+
+    sqlx::query("DELETE FROM notifications WHERE station_id = $1")
+        .bind(station_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    let mut ids = Vec::new();
+    for (index, kind) in [
+        "subscription",
+        "fraud",
+        "subscription",
+        "profile",
+        "subscription",
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        let id = create_notification(&pool, station_id, &format!("Notice {index}"), "", kind).await;
+        sqlx::query(
+            "UPDATE notifications SET created_at = now() - make_interval(mins => 10 - $2) WHERE id = $1",
+        )
+        .bind(id)
+        .bind(index as i32)
+        .execute(&pool)
+        .await
+        .unwrap();
+        ids.push(id);
+    }
+
+    let first = list_notifications(app.clone(), &token, "?limit=2").await;
+    assert_eq!(first.len(), 2);
+    assert_eq!(first[0]["title"], "Notice 4");
+    assert_eq!(first[1]["title"], "Notice 3");
+
+    let before = first[1]["id"].as_str().unwrap();
+    let before_created_at = first[1]["created_at"].as_str().unwrap();
+    let next_page = format!("?limit=2&before={before}&before_created_at={before_created_at}");
+    let second = list_notifications(app.clone(), &token, &next_page).await;
+    let titles: Vec<&str> = second
+        .iter()
+        .map(|n| n["title"].as_str().unwrap())
+        .collect();
+    assert_eq!(titles, vec!["Notice 2", "Notice 1"]);
+
+    let half_cursor = call(
+        app.clone(),
+        request_with_auth(
+            "GET",
+            &format!("/api/v1/stations/dashboard/notifications?before={before}"),
+            &token,
+        ),
+    )
+    .await;
+    assert_eq!(half_cursor.status(), StatusCode::UNAUTHORIZED);
+
+    let subscription = list_notifications(app.clone(), &token, "?kind=subscription").await;
+    assert_eq!(subscription.len(), 3);
+
+    let too_many = call(
+        app.clone(),
+        request_with_auth(
+            "GET",
+            "/api/v1/stations/dashboard/notifications?limit=500",
+            &token,
+        ),
+    )
+    .await;
+    assert_eq!(too_many.status(), StatusCode::UNAUTHORIZED);
+
+    let read = call(
+        app.clone(),
+        request_with_auth(
+            "PATCH",
+            &format!("/api/v1/stations/dashboard/notifications/{}/read", ids[0]),
+            &token,
+        ),
+    )
+    .await;
+    assert_eq!(read.status(), StatusCode::NO_CONTENT);
+
+    let archived = call(
+        app.clone(),
+        request_with_auth(
+            "PATCH",
+            &format!(
+                "/api/v1/stations/dashboard/notifications/{}/archive",
+                ids[1]
+            ),
+            &token,
+        ),
+    )
+    .await;
+    assert_eq!(archived.status(), StatusCode::NO_CONTENT);
+    assert_eq!(list_notifications(app.clone(), &token, "").await.len(), 4);
+    let archive = list_notifications(app.clone(), &token, "?archived=true").await;
+    assert_eq!(archive.len(), 1);
+    assert_eq!(archive[0]["kind"], "fraud");
+
+    let count: Value = decode_json(
+        call(
+            app.clone(),
+            request_with_auth(
+                "GET",
+                "/api/v1/stations/dashboard/notifications/unread-count",
+                &token,
+            ),
+        )
+        .await,
+    )
+    .await;
+    assert_eq!(count["unread"], 3);
+    assert_eq!(count["by_kind"], json!({ "subscription": 2, "profile": 1 }));
+    assert_eq!(
+        list_notifications(app.clone(), &token, "?unread=true")
+            .await
+            .len(),
+        3
+    );
+
+    let bulk: Value = decode_json(
+        call(
+            app.clone(),
+            request_with_headers_and_json(
+                "POST",
+                "/api/v1/stations/dashboard/notifications/bulk",
+                &[("authorization", &auth)],
+                json!({ "action": "delete", "ids": [ids[2], ids[3], Uuid::new_v4()] }),
+            ),
+        )
+        .await,
+    )
+    .await;
+    assert_eq!(bulk["updated"], 2);
+
+    // The cursor's notification is gone but the position holds.
+    let after_delete = list_notifications(app.clone(), &token, &next_page).await;
+    assert_eq!(after_delete.len(), 1);
+    assert_eq!(after_delete[0]["title"], "Notice 0");
+
+    let unknown_action = call(
+        app.clone(),
+        request_with_headers_and_json(
+            "POST",
+            "/api/v1/stations/dashboard/notifications/bulk",
+            &[("authorization", &auth)],
+            json!({ "action": "pin", "ids": [ids[4]] }),
+        ),
+    )
+    .await;
+    assert_eq!(unknown_action.status(), StatusCode::UNAUTHORIZED);
+
+    let read_all: Value = decode_json(
+        call(
+            app.clone(),
+            request_with_auth(
+                "POST",
+                "/api/v1/stations/dashboard/notifications/read-all?kind=subscription",
+                &token,
+            ),
+        )
+        .await,
+    )
+    .await;
+    assert_eq!(read_all["updated"], 1);
+
+    let deleted = call(
+        app.clone(),
+        request_with_auth(
+            "DELETE",
+            &format!("/api/v1/stations/dashboard/notifications/{}", ids[4]),
+            &token,
+        ),
+    )
+    .await;
+    assert_eq!(deleted.status(), StatusCode::NO_CONTENT);
+
+    let missing = call(
+        app.clone(),
+        request_with_auth(
+            "DELETE",
+            &format!("/api/v1/stations/dashboard/notifications/{}", ids[4]),
+            &token,
+        ),
+    )
+    .await;
+    assert_eq!(missing.status(), StatusCode::NOT_FOUND);
+
+    let remaining = list_notifications(app, &token, "").await;
+    assert_eq!(remaining.len(), 1);
+    assert_eq!(remaining[0]["is_read"], true);
+}
+
+#[tokio::test]
+#[serial]
+async fn kind_preferences_silence_in_app_and_channel_notices() {
+    let Some(pool) = db_pool().await else {
+        eprintln!("Skipping DB-backed notifications test: TEST_DATABASE_URL not set");
+        return;
+    };
+
+    reset_db(&pool).await;
+    seed_admin(&pool, "super-secret").await;
+
+    let app = test_app_with_pool(pool.clone());
+    let email = format!("{}@example.com", Uuid::new_v4().simple());
+    let (station_id, token) =
+        create_station_and_signin(app.clone(), &email, "gas", (9.15, 7.33)).await;
+    let auth = format!("Bearer {token}");
+
+    let updated = update_channels(app.clone(), &token, json!({ "sms": true })).await;
+    assert_eq!(updated.status(), StatusCode::OK);
+
+    let preference: Value = decode_json(
+        call(
+            app.clone(),
+            request_with_headers_and_json(
+                "PUT",
+                "/api/v1/stations/dashboard/notification-preferences/subscription",
+                &[("authorization", &auth)],
+                json!({ "in_app": false, "sms": false }),
+            ),
+        )
+        .await,
+    )
+    .await;
+    assert_eq!(
+        preference,
+        json!({
+            "kind": "subscription",
+            "in_app": false,
+            "email": true,
+            "sms": false,
+            "whatsapp": true
+        })
+    );
+
+    let unknown = call(
+        app.clone(),
+        request_with_headers_and_json(
+            "PUT",
+            "/api/v1/stations/dashboard/notification-preferences/marketing",
+            &[("authorization", &auth)],
+            json!({ "email": false }),
+        ),
+    )
+    .await;
+    assert_eq!(unknown.status(), StatusCode::NOT_FOUND);
+
+    let preferences: Value = decode_json(
+        call(
+            app.clone(),
+            request_with_auth(
+                "GET",
+                "/api/v1/stations/dashboard/notification-preferences",
+                &token,
+            ),
+        )
+        .await,
+    )
+    .await;
+    let preferences = preferences.as_array().unwrap();
+    assert_eq!(preferences.len(), 5);
+    assert!(
+        preferences
+            .iter()
+            .any(|p| p["kind"] == "fraud" && p["in_app"] == true)
+    );
+
+    sqlx::query("DELETE FROM notifications WHERE station_id = $1")
+        .bind(station_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query(
+        "UPDATE subscriptions SET ends_at = now() + interval '4 days 2 hours' WHERE station_id = $1",
+    )
+    .bind(station_id)
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    run_subscription_reminder_cycle(&pool).await.unwrap();
+
+    let in_app: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM notifications WHERE station_id = $1")
+            .bind(station_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(in_app, 0);
+
+    let emails: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM email_outbox")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(emails, 1);
+
+    let messages: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM outbound_messages")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(messages, 0);
+
+    // Other kinds still reach the dashboard and SMS.
+    let suspended = call(
+        app,
+        request_with_headers_and_json(
+            "PUT",
+            &format!("/api/v1/admin/stations/{station_id}/discount-suspension"),
+            &[ADMIN],
+            json!({ "suspended": true, "reason": "codes resold online" }),
+        ),
+    )
+    .await;
+    assert_eq!(suspended.status(), StatusCode::OK);
+
+    let fraud: Vec<String> =
+        sqlx::query_scalar("SELECT kind FROM notifications WHERE station_id = $1")
+            .bind(station_id)
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+    assert_eq!(fraud, vec!["fraud".to_string()]);
+
+    let channels: Vec<String> = sqlx::query_scalar("SELECT channel FROM outbound_messages")
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+    assert_eq!(channels, vec!["sms".to_string()]);
+}
@@ -0,0 +1,239 @@
+mod common;
+
+use std::sync::Arc;
+
+use axum::{Router, body::Body, http::Request, http::StatusCode};
+use serde_json::{Value, json};
+use serial_test::serial;
+
+use common::{
+    call, create_station_and_signin, db_pool, decode_json, request_with_headers,
+    request_with_headers_and_json, reset_db, seed_admin, test_app,
+};
+use fuelfinder_server::{
+    app_state::AppState, build_app, domain::payments::provider::LocalPaymentProvider,
+};
+
+const WEBHOOK_SECRET: &str = "test-webhook-secret";
+
+fn app_with_local_payments(pool: sqlx::PgPool) -> Router {
+    build_app(AppState {
+        payments: Arc::new(LocalPaymentProvider::new(WEBHOOK_SECRET)),
+        ..AppState::with_pool(pool).expect("app state should build")
+    })
+}
+
+fn webhook(body: &Value, signature: Option<String>) -> Request<Body> {
+    let body = serde_json::to_vec(body).unwrap();
+    let signature =
+        signature.unwrap_or_else(|| LocalPaymentProvider::new(WEBHOOK_SECRET).sign(&body));
+
+    Request::builder()
+        .method("POST")
+        .uri("/api/v1/payments/webhook")
+        .header("content-type", "application/json")
+        .header("x-local-signature", signature)
+        .body(Body::from(body))
+        .unwrap()
+}
+
+#[tokio::test]
+async fn webhook_rejects_unsigned_events() {
+    let response = call(
+        test_app(),
+        webhook(&json!({ "id": "evt_1" }), Some("00".to_string())),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+#[serial]
+async fn checkout_webhook_renews_subscription_once() {
+    let Some(pool) = db_pool().await else {
+        eprintln!("Skipping DB-backed payments test: TEST_DATABASE_URL not set");
+        return;
+    };
+
+    reset_db(&pool).await;
+    seed_admin(&pool, "super-secret").await;
+
+    let app = app_with_local_payments(pool.clone());
+    let email = format!("{}@example.com", uuid::Uuid::new_v4().simple());
+    let (station_id, token) =
+        create_station_and_signin(app.clone(), &email, "petrol", (9.09, 7.49)).await;
+    let auth = format!("Bearer {token}");
+
+    let invoice: Value = decode_json(
+        call(
+            app.clone(),
+            request_with_headers_and_json(
+                "POST",
+                "/api/v1/stations/dashboard/billing/invoices",
+                &[("authorization", &auth)],
+                json!({ "plan_code": "monthly" }),
+            ),
+        )
+        .await,
+    )
+    .await;
+    let invoice_id = invoice["id"].as_str().unwrap().to_string();
+
+    let checkout_response = call(
+        app.clone(),
+        request_with_headers_and_json(
+            "POST",
+            "/api/v1/stations/dashboard/billing/checkout",
+            &[("authorization", &auth)],
+            json!({ "invoice_id": invoice_id }),
+        ),
+    )
+    .await;
+    assert_eq!(checkout_response.status(), StatusCode::CREATED);
+    let checkout: Value = decode_json(checkout_response).await;
+    assert_eq!(checkout["provider"].as_str(), Some("local"));
+    let reference = checkout["reference"].as_str().unwrap().to_string();
+    assert_eq!(
+        checkout["authorization_url"].as_str(),
+        Some(format!("local://checkout/{reference}").as_str())
+    );
+
+    let tampered = call(
+        app.clone(),
+        webhook(
+            &json!({ "id": "evt_1", "type": "payment.succeeded", "reference": reference, "amount": 1_500_000 }),
+            Some(LocalPaymentProvider::new("someone-else").sign(b"{}")),
+        ),
+    )
+    .await;
+    assert_eq!(tampered.status(), StatusCode::UNAUTHORIZED);
+
+    let unknown: Value = decode_json(
+        call(
+            app.clone(),
+            webhook(
+                &json!({ "id": "evt_0", "type": "payment.succeeded", "reference": "FFS-unknown", "amount": 1_500_000 }),
+                None,
+            ),
+        )
+        .await,
+    )
+    .await;
+    assert_eq!(unknown["outcome"].as_str(), Some("unknown_reference"));
+
+    let event = json!({
+        "id": "evt_1",
+        "type": "payment.succeeded",
+        "reference": reference,
+        "amount": 1_500_000
+    });
+
+    let first: Value = decode_json(call(app.clone(), webhook(&event, None)).await).await;
+    assert_eq!(first["outcome"].as_str(), Some("renewed"));
+
+    let redelivered = call(app.clone(), webhook(&event, None)).await;
+    assert_eq!(redelivered.status(), StatusCode::OK);
+    let redelivered: Value = decode_json(redelivered).await;
+    assert_eq!(redelivered["outcome"].as_str(), Some("duplicate"));
+
+    let subscriptions: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM subscriptions WHERE station_id = $1",
+    )
+    .bind(station_id)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(subscriptions, 2);
+
+    let billing: Value = decode_json(
+        call(
+            app.clone(),
+            request_with_headers("GET", "/api/v1/stations/dashboard/billing", &[("authorization", &auth)]),
+        )
+        .await,
+    )
+    .await;
+    assert_eq!(billing["subscription"]["plan"]["code"].as_str(), Some("monthly"));
+    assert_eq!(billing["invoices"][0]["status"].as_str(), Some("paid"));
+
+    let payment_status: String =
+        sqlx::query_scalar("SELECT status FROM subscription_payments WHERE reference = $1")
+            .bind(&reference)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(payment_status, "succeeded");
+
+    // A second invoice settled by bank transfer still goes through admins.
+    let second: Value = decode_json(
+        call(
+            app.clone(),
+            request_with_headers_and_json(
+                "POST",
+                &format!("/api/v1/admin/stations/{station_id}/invoices"),
+                &[("x-admin-password", "super-secret")],
+                json!({ "plan_code": "annual" }),
+            ),
+        )
+        .await,
+    )
+    .await;
+
+    let wrong_amount_checkout: Value = decode_json(
+        call(
+            app.clone(),
+            request_with_headers_and_json(
+                "POST",
+                "/api/v1/stations/dashboard/billing/checkout",
+                &[("authorization", &auth)],
+                json!({ "invoice_id": second["id"] }),
+            ),
+        )
+        .await,
+    )
+    .await;
+    let underpaid: Value = decode_json(
+        call(
+            app.clone(),
+            webhook(
+                &json!({
+                    "id": "evt_2",
+                    "type": "payment.succeeded",
+                    "reference": wrong_amount_checkout["reference"],
+                    "amount": 100
+                }),
+                None,
+            ),
+        )
+        .await,
+    )
+    .await;
+    assert_eq!(underpaid["outcome"].as_str(), Some("amount_mismatch"));
+
+    let transfer = call(
+        app,
+        request_with_headers(
+            "POST",
+            &format!("/api/v1/admin/invoices/{}/pay", second["id"].as_str().unwrap()),
+            &[("x-admin-password", "super-secret")],
+        ),
+    )
+    .await;
+    assert_eq!(transfer.status(), StatusCode::OK);
+
+    let providers: Vec<(String, String)> = sqlx::query_as(
+        "SELECT provider, status FROM subscription_payments WHERE station_id = $1 ORDER BY created_at",
+    )
+    .bind(station_id)
+    .fetch_all(&pool)
+    .await
+    .unwrap();
+    assert_eq!(
+        providers,
+        [
+            ("local".to_string(), "succeeded".to_string()),
+            ("local".to_string(), "failed".to_string()),
+            ("bank_transfer".to_string(), "succeeded".to_string()),
+        ]
+    );
+}
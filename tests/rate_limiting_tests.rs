@@ -1,12 +1,100 @@
 mod common;
 
-use std::time::Duration;
-
-use axum::http::StatusCode;
-use fuelfinder_server::domain::utils::rate_limiter::RateLimiter;
+use std::{
+    net::{IpAddr, SocketAddr},
+    time::Duration,
+};
+
+use axum::{
+    extract::ConnectInfo,
+    http::{HeaderMap, HeaderValue, StatusCode},
+};
+use fuelfinder_server::domain::utils::{
+    client_ip::{Cidr, ClientIpResolver},
+    rate_limiter::RateLimiter,
+};
 
 use common::{body_text, call, request_with_headers, test_app};
 
+fn resolver(trusted: &[&str]) -> ClientIpResolver {
+    ClientIpResolver::new(trusted.iter().map(|cidr| cidr.parse::<Cidr>().unwrap()).collect())
+}
+
+fn headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    for (name, value) in pairs {
+        headers.append(*name, HeaderValue::from_str(value).unwrap());
+    }
+    headers
+}
+
+fn ip(value: &str) -> Option<IpAddr> {
+    Some(value.parse().unwrap())
+}
+
+#[test]
+fn resolver_ignores_forwarding_headers_from_untrusted_peers() {
+    let resolver = resolver(&["127.0.0.0/8"]);
+    let headers = headers(&[("x-forwarded-for", "1.2.3.4"), ("x-real-ip", "1.2.3.4")]);
+
+    assert_eq!(resolver.resolve(ip("198.51.100.7"), &headers), ip("198.51.100.7"));
+    assert_eq!(resolver.resolve(None, &headers), None);
+}
+
+#[test]
+fn resolver_walks_forwarded_chain_past_trusted_proxies() {
+    let resolver = resolver(&["127.0.0.1", "10.0.0.0/8"]);
+    let headers = headers(&[
+        ("x-forwarded-for", "6.6.6.6, 203.0.113.5"),
+        ("x-forwarded-for", "10.0.0.2"),
+    ]);
+
+    // 6.6.6.6 was written by the client itself and cannot be trusted.
+    assert_eq!(resolver.resolve(ip("127.0.0.1"), &headers), ip("203.0.113.5"));
+    assert_eq!(
+        resolver.resolve(ip("::ffff:127.0.0.1"), &headers),
+        ip("203.0.113.5")
+    );
+}
+
+#[test]
+fn resolver_falls_back_to_single_client_headers_then_peer() {
+    let resolver = resolver(&["10.0.0.0/8"]);
+
+    let cloudflare = headers(&[("cf-connecting-ip", "203.0.113.9"), ("x-real-ip", "1.1.1.1")]);
+    assert_eq!(resolver.resolve(ip("10.1.2.3"), &cloudflare), ip("203.0.113.9"));
+
+    let garbage = headers(&[("x-forwarded-for", "not-an-ip, 10.0.0.9")]);
+    assert_eq!(resolver.resolve(ip("10.1.2.3"), &garbage), ip("10.0.0.9"));
+
+    assert_eq!(resolver.resolve(ip("10.1.2.3"), &HeaderMap::new()), ip("10.1.2.3"));
+}
+
+#[tokio::test]
+async fn closest_endpoint_ignores_spoofed_forwarded_for_from_untrusted_peer() {
+    let app = test_app();
+
+    let request = |n: u32| {
+        let mut request = request_with_headers(
+            "GET",
+            "/api/v1/stations/closest?latitude=0.0&longitude=0.0&station_type=petrol",
+            &[("x-forwarded-for", &format!("192.0.2.{n}"))],
+        );
+        request
+            .extensions_mut()
+            .insert(ConnectInfo(SocketAddr::from(([198, 51, 100, 99], 50000))));
+        request
+    };
+
+    for n in 0..10 {
+        let response = call(app.clone(), request(n)).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    let response = call(app, request(10)).await;
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+}
+
 #[test]
 fn limiter_blocks_requests_after_limit() {
     let limiter = RateLimiter::new(2, Duration::from_secs(60));
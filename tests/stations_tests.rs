@@ -6,72 +6,13 @@ use serial_test::serial;
 use uuid::Uuid;
 
 use common::{
-    body_text, call, create_notification, db_pool, decode_json, mark_station_subscription_expired,
-    request, request_with_auth, request_with_json, reset_db, seed_admin,
-    test_app, test_app_with_pool, valid_token,
+    body_bytes, body_text, call, create_notification, create_station_and_signin, db_pool,
+    decode_json, end_station_subscription, mark_station_commodities_available,
+    mark_station_subscription_expired, request, request_with_auth, request_with_headers_and_json,
+    request_with_headers, request_with_json, request_with_multipart, reset_db, seed_admin, test_app, test_app_with_pool,
+    valid_token,
 };
 
-async fn create_station_and_signin(
-    app: axum::Router,
-    email: &str,
-    station_type: &str,
-) -> (Uuid, String) {
-    let code = format!("REG-{}", uuid::Uuid::new_v4().simple());
-
-    let _ = call(
-        app.clone(),
-        request_with_json(
-            "POST",
-            "/api/v1/auth/reg-code",
-            json!({ "code": code, "super_password": "super-secret" }),
-        ),
-    )
-    .await;
-
-    let signup = call(
-        app.clone(),
-        request_with_json(
-            "POST",
-            "/api/v1/auth/signup",
-            json!({
-                "name": format!("{} station", station_type),
-                "address": "Central Area",
-                "email": email,
-                "phone": "08000001111",
-                "password": "station-pass",
-                "latitude": 9.08,
-                "longitude": 7.48,
-                "code": code,
-                "station_type": station_type
-            }),
-        ),
-    )
-    .await;
-    assert_eq!(signup.status(), StatusCode::CREATED);
-
-    let signup_body: Value = decode_json(signup).await;
-    let station_id = uuid::Uuid::parse_str(signup_body["id"].as_str().unwrap()).unwrap();
-
-    let signin = call(
-        app,
-        request_with_json(
-            "POST",
-            "/api/v1/auth/signin",
-            json!({
-                "email": email,
-                "password": "station-pass",
-                "station_type": station_type
-            }),
-        ),
-    )
-    .await;
-    assert_eq!(signin.status(), StatusCode::OK);
-
-    let signin_body: Value = decode_json(signin).await;
-    let token = signin_body["access_token"].as_str().unwrap().to_string();
-    (station_id, token)
-}
-
 #[tokio::test]
 async fn root_healthz_returns_ok() {
     let response = call(test_app(), request("GET", "/healthz")).await;
@@ -107,14 +48,24 @@ async fn station_dashboard_rejects_invalid_token() {
 }
 
 #[tokio::test]
-async fn station_dashboard_accepts_valid_token_then_reaches_handler() {
+#[serial]
+async fn station_dashboard_accepts_valid_token_then_checks_subscription() {
+    let Some(pool) = db_pool().await else {
+        eprintln!("Skipping DB-backed stations test: TEST_DATABASE_URL not set");
+        return;
+    };
+
+    reset_db(&pool).await;
+
     let response = call(
-        test_app(),
+        test_app_with_pool(pool),
         request_with_auth("GET", "/api/v1/stations/dashboard", &valid_token()),
     )
     .await;
 
-    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    // The token is genuine but its station has never had a subscription.
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    assert!(body_text(response).await.contains("subscription expired"));
 }
 
 #[tokio::test]
@@ -171,7 +122,8 @@ async fn dashboard_notifications_returns_seeded_notifications() {
 
     let app = test_app_with_pool(pool.clone());
     let email = format!("{}@example.com", uuid::Uuid::new_v4().simple());
-    let (station_id, token) = create_station_and_signin(app.clone(), &email, "petrol").await;
+    let (station_id, token) =
+        create_station_and_signin(app.clone(), &email, "petrol", (9.08, 7.48)).await;
 
     create_notification(
         &pool,
@@ -208,7 +160,8 @@ async fn mark_notification_read_updates_record() {
 
     let app = test_app_with_pool(pool.clone());
     let email = format!("{}@example.com", uuid::Uuid::new_v4().simple());
-    let (station_id, token) = create_station_and_signin(app.clone(), &email, "petrol").await;
+    let (station_id, token) =
+        create_station_and_signin(app.clone(), &email, "petrol", (9.08, 7.48)).await;
 
     let notification_id = create_notification(
         &pool,
@@ -254,7 +207,8 @@ async fn expired_subscription_signin_creates_notification() {
     let app = test_app_with_pool(pool.clone());
     let email = format!("{}@example.com", uuid::Uuid::new_v4().simple());
 
-    let (station_id, _token) = create_station_and_signin(app.clone(), &email, "gas").await;
+    let (station_id, _token) =
+        create_station_and_signin(app.clone(), &email, "gas", (9.08, 7.48)).await;
     mark_station_subscription_expired(&pool, station_id).await;
 
     let signin_response = call(
@@ -283,3 +237,1115 @@ async fn expired_subscription_signin_creates_notification() {
 
     assert_eq!(notification_count, 1);
 }
+
+#[tokio::test]
+async fn opening_hours_require_auth() {
+    let response = call(
+        test_app(),
+        request("GET", "/api/v1/stations/dashboard/opening-hours"),
+    )
+    .await;
+
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+#[serial]
+async fn open_now_filter_hides_closed_stations() {
+    let Some(pool) = db_pool().await else {
+        eprintln!("Skipping DB-backed stations test: TEST_DATABASE_URL not set");
+        return;
+    };
+
+    reset_db(&pool).await;
+    seed_admin(&pool, "super-secret").await;
+
+    let app = test_app_with_pool(pool.clone());
+    let email = format!("{}@example.com", uuid::Uuid::new_v4().simple());
+    let (station_id, token) =
+        create_station_and_signin(app.clone(), &email, "petrol", (9.08, 7.48)).await;
+    mark_station_commodities_available(&pool, station_id).await;
+
+    let hours: Vec<Value> = (1..=7)
+        .map(|day| json!({ "day_of_week": day, "opens_at": "00:00:00", "closes_at": "00:00:00" }))
+        .collect();
+
+    let update_response = call(
+        app.clone(),
+        request_with_headers_and_json(
+            "PUT",
+            "/api/v1/stations/dashboard/opening-hours",
+            &[("authorization", &format!("Bearer {token}"))],
+            json!({ "hours": hours }),
+        ),
+    )
+    .await;
+    assert_eq!(update_response.status(), StatusCode::OK);
+
+    let update_body: Value = decode_json(update_response).await;
+    assert_eq!(update_body["hours"].as_array().map(Vec::len), Some(7));
+
+    let closest_path =
+        "/api/v1/stations/closest?latitude=9.07&longitude=7.47&station_type=petrol&open_now=true";
+
+    let open_response = call(app.clone(), request("GET", closest_path)).await;
+    assert_eq!(open_response.status(), StatusCode::OK);
+    let open_body: Value = decode_json(open_response).await;
+    assert_eq!(open_body.as_array().map(Vec::len), Some(1));
+    assert_eq!(open_body[0]["is_open"].as_bool(), Some(true));
+    assert!(open_body[0]["closes_at"].as_str().is_some());
+
+    let close_response = call(
+        app.clone(),
+        request_with_headers_and_json(
+            "PATCH",
+            "/api/v1/stations/dashboard/temporarily-closed",
+            &[("authorization", &format!("Bearer {token}"))],
+            json!({ "temporarily_closed": true }),
+        ),
+    )
+    .await;
+    assert_eq!(close_response.status(), StatusCode::NO_CONTENT);
+
+    let closed_response = call(app.clone(), request("GET", closest_path)).await;
+    assert_eq!(closed_response.status(), StatusCode::OK);
+    let closed_body: Value = decode_json(closed_response).await;
+    assert_eq!(closed_body.as_array().map(Vec::len), Some(0));
+
+    let unfiltered_response = call(
+        app,
+        request(
+            "GET",
+            "/api/v1/stations/closest?latitude=9.07&longitude=7.47&station_type=petrol",
+        ),
+    )
+    .await;
+    assert_eq!(unfiltered_response.status(), StatusCode::OK);
+    let unfiltered_body: Value = decode_json(unfiltered_response).await;
+    assert_eq!(unfiltered_body.as_array().map(Vec::len), Some(1));
+    assert_eq!(unfiltered_body[0]["is_open"].as_bool(), Some(false));
+}
+
+#[tokio::test]
+#[serial]
+async fn amenity_filters_narrow_closest_and_all_stations() {
+    let Some(pool) = db_pool().await else {
+        eprintln!("Skipping DB-backed stations test: TEST_DATABASE_URL not set");
+        return;
+    };
+
+    reset_db(&pool).await;
+    seed_admin(&pool, "super-secret").await;
+
+    let app = test_app_with_pool(pool.clone());
+    let atm_email = format!("atm-{}@example.com", uuid::Uuid::new_v4().simple());
+    let plain_email = format!("plain-{}@example.com", uuid::Uuid::new_v4().simple());
+    let (atm_station_id, atm_token) =
+        create_station_and_signin(app.clone(), &atm_email, "petrol", (9.08, 7.48)).await;
+    let (plain_station_id, _plain_token) =
+        create_station_and_signin(app.clone(), &plain_email, "petrol", (9.08, 7.48)).await;
+    mark_station_commodities_available(&pool, atm_station_id).await;
+    mark_station_commodities_available(&pool, plain_station_id).await;
+
+    let update_response = call(
+        app.clone(),
+        request_with_headers_and_json(
+            "PUT",
+            "/api/v1/stations/dashboard/amenities",
+            &[("authorization", &format!("Bearer {atm_token}"))],
+            json!({ "amenities": ["ATM", "shop", "atm"], "payment_methods": ["pos"] }),
+        ),
+    )
+    .await;
+    assert_eq!(update_response.status(), StatusCode::OK);
+    let update_body: Value = decode_json(update_response).await;
+    assert_eq!(update_body["amenities"], json!(["atm", "shop"]));
+
+    let invalid_response = call(
+        app.clone(),
+        request_with_headers_and_json(
+            "PUT",
+            "/api/v1/stations/dashboard/amenities",
+            &[("authorization", &format!("Bearer {atm_token}"))],
+            json!({ "amenities": ["helipad"], "payment_methods": [] }),
+        ),
+    )
+    .await;
+    assert_eq!(invalid_response.status(), StatusCode::UNAUTHORIZED);
+
+    let claimed_response = call(
+        app.clone(),
+        request_with_headers_and_json(
+            "PUT",
+            "/api/v1/stations/dashboard/amenities",
+            &[("authorization", &format!("Bearer {atm_token}"))],
+            json!({ "amenities": ["open_24_hours"], "payment_methods": [] }),
+        ),
+    )
+    .await;
+    assert_eq!(claimed_response.status(), StatusCode::UNAUTHORIZED);
+
+    let hours: Vec<Value> = (1..=7)
+        .map(|day| json!({ "day_of_week": day, "opens_at": "00:00:00", "closes_at": "00:00:00" }))
+        .collect();
+    let hours_response = call(
+        app.clone(),
+        request_with_headers_and_json(
+            "PUT",
+            "/api/v1/stations/dashboard/opening-hours",
+            &[("authorization", &format!("Bearer {atm_token}"))],
+            json!({ "hours": hours }),
+        ),
+    )
+    .await;
+    assert_eq!(hours_response.status(), StatusCode::OK);
+
+    let closest_response = call(
+        app.clone(),
+        request(
+            "GET",
+            "/api/v1/stations/closest?latitude=9.07&longitude=7.47&station_type=petrol&amenities=atm,open_24_hours&payment_methods=pos",
+        ),
+    )
+    .await;
+    assert_eq!(closest_response.status(), StatusCode::OK);
+    let closest_body: Value = decode_json(closest_response).await;
+    assert_eq!(closest_body.as_array().map(Vec::len), Some(1));
+    assert_eq!(closest_body[0]["id"].as_str(), Some(atm_station_id.to_string().as_str()));
+    assert_eq!(closest_body[0]["payment_methods"], json!(["pos"]));
+    assert_eq!(closest_body[0]["amenities"], json!(["atm", "open_24_hours", "shop"]));
+
+    let shorter_hours = call(
+        app.clone(),
+        request_with_headers_and_json(
+            "PUT",
+            "/api/v1/stations/dashboard/opening-hours",
+            &[("authorization", &format!("Bearer {atm_token}"))],
+            json!({ "hours": [{ "day_of_week": 1, "opens_at": "06:00:00", "closes_at": "22:00:00" }] }),
+        ),
+    )
+    .await;
+    assert_eq!(shorter_hours.status(), StatusCode::OK);
+    let amenities: Vec<String> =
+        sqlx::query_scalar("SELECT unnest(amenities) FROM stations WHERE id = $1")
+            .bind(atm_station_id)
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+    assert_eq!(amenities, vec!["atm", "shop"]);
+
+    let all_response = call(
+        app.clone(),
+        request("GET", "/api/v1/stations?station_type=petrol&amenities=shop"),
+    )
+    .await;
+    assert_eq!(all_response.status(), StatusCode::OK);
+    let all_body: Value = decode_json(all_response).await;
+    assert_eq!(all_body.as_array().map(Vec::len), Some(1));
+    assert_eq!(all_body[0]["id"].as_str(), Some(atm_station_id.to_string().as_str()));
+    assert!(all_body[0].get("commodities").is_none());
+
+    let unfiltered_response = call(app, request("GET", "/api/v1/stations?station_type=petrol")).await;
+    let unfiltered_body: Value = decode_json(unfiltered_response).await;
+    assert_eq!(unfiltered_body.as_array().map(Vec::len), Some(2));
+}
+
+#[tokio::test]
+async fn profile_update_requires_auth() {
+    let response = call(test_app(), request("PATCH", "/api/v1/stations/dashboard/profile")).await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+#[serial]
+async fn profile_update_applies_details_and_queues_relocation_for_admin() {
+    let Some(pool) = db_pool().await else {
+        eprintln!("Skipping DB-backed stations test: TEST_DATABASE_URL not set");
+        return;
+    };
+
+    reset_db(&pool).await;
+    seed_admin(&pool, "super-secret").await;
+
+    let app = test_app_with_pool(pool.clone());
+    let email = format!("{}@example.com", uuid::Uuid::new_v4().simple());
+    let (station_id, token) =
+        create_station_and_signin(app.clone(), &email, "petrol", (9.08, 7.48)).await;
+
+    let update_response = call(
+        app.clone(),
+        request_with_headers_and_json(
+            "PATCH",
+            "/api/v1/stations/dashboard/profile",
+            &[("authorization", &format!("Bearer {token}"))],
+            json!({
+                "name": "Renamed Station",
+                "phone": "08055556666",
+                "latitude": 9.02,
+                "longitude": 7.40
+            }),
+        ),
+    )
+    .await;
+    assert_eq!(update_response.status(), StatusCode::OK);
+
+    let update_body: Value = decode_json(update_response).await;
+    assert_eq!(update_body["station"]["name"].as_str(), Some("RENAMED STATION"));
+    assert_eq!(update_body["station"]["latitude"].as_f64(), Some(9.08));
+    assert_eq!(update_body["relocation_request"]["status"].as_str(), Some("pending"));
+    assert!(update_body["access_token"].as_str().is_some());
+
+    let request_id = update_body["relocation_request"]["id"].as_str().unwrap().to_string();
+
+    let duplicate_response = call(
+        app.clone(),
+        request_with_headers_and_json(
+            "PATCH",
+            "/api/v1/stations/dashboard/profile",
+            &[("authorization", &format!("Bearer {token}"))],
+            json!({ "latitude": 9.03, "longitude": 7.41 }),
+        ),
+    )
+    .await;
+    assert_eq!(duplicate_response.status(), StatusCode::CONFLICT);
+
+    let pending_response = call(
+        app.clone(),
+        request_with_headers(
+            "GET",
+            "/api/v1/admin/relocations",
+            &[("x-admin-password", "super-secret")],
+        ),
+    )
+    .await;
+    assert_eq!(pending_response.status(), StatusCode::OK);
+    let pending_body: Value = decode_json(pending_response).await;
+    assert_eq!(pending_body.as_array().map(Vec::len), Some(1));
+
+    let approve_response = call(
+        app.clone(),
+        request_with_headers_and_json(
+            "PATCH",
+            &format!("/api/v1/admin/relocations/{request_id}"),
+            &[("x-admin-password", "super-secret")],
+            json!({ "approved": true }),
+        ),
+    )
+    .await;
+    assert_eq!(approve_response.status(), StatusCode::OK);
+
+    let (latitude, longitude): (f64, f64) =
+        sqlx::query_as("SELECT latitude, longitude FROM stations WHERE id = $1")
+            .bind(station_id)
+            .fetch_one(&pool)
+            .await
+            .expect("station should exist");
+    assert_eq!((latitude, longitude), (9.02, 7.40));
+
+    let changes_response = call(
+        app,
+        request_with_auth("GET", "/api/v1/stations/dashboard/profile/changes", &token),
+    )
+    .await;
+    assert_eq!(changes_response.status(), StatusCode::OK);
+    let changes_body: Value = decode_json(changes_response).await;
+    let fields: Vec<&str> = changes_body
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter_map(|c| c["field"].as_str())
+        .collect();
+    assert_eq!(fields.len(), 3);
+    assert!(fields.contains(&"name"));
+    assert!(fields.contains(&"phone"));
+    assert!(fields.contains(&"coordinates"));
+}
+
+fn sample_png(width: u32, height: u32) -> Vec<u8> {
+    let image = image::RgbImage::from_pixel(width, height, image::Rgb([200, 30, 30]));
+    let mut bytes = Vec::new();
+    image
+        .write_to(&mut std::io::Cursor::new(&mut bytes), image::ImageFormat::Png)
+        .expect("png should encode");
+    bytes
+}
+
+#[tokio::test]
+#[serial]
+async fn station_can_upload_and_remove_logo() {
+    let Some(pool) = db_pool().await else {
+        eprintln!("Skipping DB-backed stations test: TEST_DATABASE_URL not set");
+        return;
+    };
+
+    let media_dir = std::env::temp_dir().join(format!("fuelfinder-media-{}", Uuid::new_v4()));
+    unsafe {
+        std::env::set_var("MEDIA_STORAGE_DIR", &media_dir);
+    }
+
+    reset_db(&pool).await;
+    seed_admin(&pool, "super-secret").await;
+
+    let app = test_app_with_pool(pool.clone());
+    let email = format!("{}@example.com", uuid::Uuid::new_v4().simple());
+    let (_station_id, token) =
+        create_station_and_signin(app.clone(), &email, "petrol", (9.08, 7.48)).await;
+
+    let rejected_response = call(
+        app.clone(),
+        request_with_multipart(
+            "/api/v1/stations/dashboard/images",
+            &token,
+            &[("kind", "logo")],
+            ("file", "image/png", b"definitely not a png".to_vec()),
+        ),
+    )
+    .await;
+    assert_eq!(rejected_response.status(), StatusCode::UNAUTHORIZED);
+
+    let upload_response = call(
+        app.clone(),
+        request_with_multipart(
+            "/api/v1/stations/dashboard/images",
+            &token,
+            &[("kind", "logo")],
+            ("file", "image/png", sample_png(640, 320)),
+        ),
+    )
+    .await;
+    assert_eq!(upload_response.status(), StatusCode::CREATED);
+
+    let upload_body: Value = decode_json(upload_response).await;
+    let image_id = upload_body["id"].as_str().unwrap().to_string();
+    let thumbnail_url = upload_body["thumbnail_url"].as_str().unwrap().to_string();
+    assert_eq!(upload_body["width"].as_i64(), Some(640));
+
+    let thumbnail_response = call(app.clone(), request("GET", &thumbnail_url)).await;
+    assert_eq!(thumbnail_response.status(), StatusCode::OK);
+    let thumbnail = image::load_from_memory(&body_bytes(thumbnail_response).await)
+        .expect("thumbnail should decode");
+    assert_eq!((thumbnail.width(), thumbnail.height()), (256, 128));
+
+    let dashboard_response = call(
+        app.clone(),
+        request_with_auth("GET", "/api/v1/stations/dashboard", &token),
+    )
+    .await;
+    let dashboard_body: Value = decode_json(dashboard_response).await;
+    assert_eq!(dashboard_body["logo"]["id"].as_str(), Some(image_id.as_str()));
+
+    let delete_response = call(
+        app.clone(),
+        request_with_auth(
+            "DELETE",
+            &format!("/api/v1/stations/dashboard/images/{image_id}"),
+            &token,
+        ),
+    )
+    .await;
+    assert_eq!(delete_response.status(), StatusCode::NO_CONTENT);
+
+    let missing_response = call(app, request("GET", &thumbnail_url)).await;
+    assert_eq!(missing_response.status(), StatusCode::NOT_FOUND);
+
+    let _ = std::fs::remove_dir_all(media_dir);
+}
+
+#[tokio::test]
+async fn verification_requires_auth() {
+    let response = call(
+        test_app(),
+        request("GET", "/api/v1/stations/dashboard/verification"),
+    )
+    .await;
+
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+#[serial]
+async fn station_verification_is_reviewed_by_admin() {
+    let Some(pool) = db_pool().await else {
+        eprintln!("Skipping DB-backed stations test: TEST_DATABASE_URL not set");
+        return;
+    };
+
+    let documents_dir = std::env::temp_dir().join(format!("fuelfinder-docs-{}", Uuid::new_v4()));
+    unsafe {
+        std::env::set_var("DOCUMENT_STORAGE_DIR", &documents_dir);
+    }
+
+    reset_db(&pool).await;
+    seed_admin(&pool, "super-secret").await;
+
+    let app = test_app_with_pool(pool.clone());
+    let email = format!("{}@example.com", uuid::Uuid::new_v4().simple());
+    let (_station_id, token) =
+        create_station_and_signin(app.clone(), &email, "petrol", (9.08, 7.48)).await;
+
+    let document = b"%PDF-1.4 certificate of incorporation".to_vec();
+    let fields = [
+        ("document_type", "business_registration"),
+        ("document_number", "RC-123456"),
+    ];
+
+    let mismatched_response = call(
+        app.clone(),
+        request_with_multipart(
+            "/api/v1/stations/dashboard/verification",
+            &token,
+            &fields,
+            ("documents", "application/pdf", b"not a pdf".to_vec()),
+        ),
+    )
+    .await;
+    assert_eq!(mismatched_response.status(), StatusCode::UNAUTHORIZED);
+
+    let submit_response = call(
+        app.clone(),
+        request_with_multipart(
+            "/api/v1/stations/dashboard/verification",
+            &token,
+            &fields,
+            ("documents", "application/pdf", document.clone()),
+        ),
+    )
+    .await;
+    assert_eq!(submit_response.status(), StatusCode::CREATED);
+    let submit_body: Value = decode_json(submit_response).await;
+    assert_eq!(submit_body["verification_status"].as_str(), Some("pending"));
+    let request_id = submit_body["latest_request"]["id"].as_str().unwrap().to_string();
+    let document_id = submit_body["latest_request"]["documents"][0]["id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+
+    let duplicate_response = call(
+        app.clone(),
+        request_with_multipart(
+            "/api/v1/stations/dashboard/verification",
+            &token,
+            &fields,
+            ("documents", "application/pdf", document.clone()),
+        ),
+    )
+    .await;
+    assert_eq!(duplicate_response.status(), StatusCode::CONFLICT);
+
+    let pending_response = call(
+        app.clone(),
+        request_with_headers(
+            "GET",
+            "/api/v1/admin/verifications",
+            &[("x-admin-password", "super-secret")],
+        ),
+    )
+    .await;
+    assert_eq!(pending_response.status(), StatusCode::OK);
+    let pending_body: Value = decode_json(pending_response).await;
+    assert_eq!(pending_body.as_array().map(Vec::len), Some(1));
+
+    let document_response = call(
+        app.clone(),
+        request_with_headers(
+            "GET",
+            &format!("/api/v1/admin/verifications/documents/{document_id}"),
+            &[("x-admin-password", "super-secret")],
+        ),
+    )
+    .await;
+    assert_eq!(document_response.status(), StatusCode::OK);
+    assert_eq!(body_bytes(document_response).await, document);
+
+    let reason_missing_response = call(
+        app.clone(),
+        request_with_headers_and_json(
+            "PATCH",
+            &format!("/api/v1/admin/verifications/{request_id}"),
+            &[("x-admin-password", "super-secret")],
+            json!({ "approved": false }),
+        ),
+    )
+    .await;
+    assert_eq!(reason_missing_response.status(), StatusCode::UNAUTHORIZED);
+
+    let approve_response = call(
+        app.clone(),
+        request_with_headers_and_json(
+            "PATCH",
+            &format!("/api/v1/admin/verifications/{request_id}"),
+            &[("x-admin-password", "super-secret")],
+            json!({ "approved": true }),
+        ),
+    )
+    .await;
+    assert_eq!(approve_response.status(), StatusCode::OK);
+
+    let dashboard_response = call(
+        app,
+        request_with_auth("GET", "/api/v1/stations/dashboard", &token),
+    )
+    .await;
+    let dashboard_body: Value = decode_json(dashboard_response).await;
+    assert_eq!(dashboard_body["is_verified"].as_bool(), Some(true));
+
+    let _ = std::fs::remove_dir_all(documents_dir);
+}
+
+#[tokio::test]
+async fn billing_requires_auth() {
+    let response = call(test_app(), request("GET", "/api/v1/stations/dashboard/billing")).await;
+
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+#[serial]
+async fn station_billing_is_renewed_by_paid_invoices() {
+    let Some(pool) = db_pool().await else {
+        eprintln!("Skipping DB-backed stations test: TEST_DATABASE_URL not set");
+        return;
+    };
+
+    reset_db(&pool).await;
+    seed_admin(&pool, "super-secret").await;
+
+    let app = test_app_with_pool(pool.clone());
+    let email = format!("{}@example.com", uuid::Uuid::new_v4().simple());
+    let (station_id, token) =
+        create_station_and_signin(app.clone(), &email, "petrol", (9.08, 7.48)).await;
+    let admin = [("x-admin-password", "super-secret")];
+
+    let billing_response = call(
+        app.clone(),
+        request_with_auth("GET", "/api/v1/stations/dashboard/billing", &token),
+    )
+    .await;
+    assert_eq!(billing_response.status(), StatusCode::OK);
+    let billing: Value = decode_json(billing_response).await;
+    assert_eq!(billing["subscription"]["plan"]["code"].as_str(), Some("trial"));
+    let plan_codes: Vec<&str> = billing["plans"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|plan| plan["code"].as_str().unwrap())
+        .collect();
+    assert_eq!(plan_codes, ["monthly", "quarterly", "annual"]);
+
+    let request_invoice = |plan_code: &str| {
+        request_with_headers_and_json(
+            "POST",
+            "/api/v1/stations/dashboard/billing/invoices",
+            &[("authorization", &format!("Bearer {token}"))],
+            json!({ "plan_code": plan_code }),
+        )
+    };
+
+    let trial_invoice = call(app.clone(), request_invoice("trial")).await;
+    assert_eq!(trial_invoice.status(), StatusCode::NOT_FOUND);
+
+    let first_response = call(app.clone(), request_invoice("monthly")).await;
+    assert_eq!(first_response.status(), StatusCode::CREATED);
+    let first: Value = decode_json(first_response).await;
+    assert_eq!(first["amount"].as_i64(), Some(15_000));
+    assert_eq!(first["status"].as_str(), Some("open"));
+
+    let second_open = call(app.clone(), request_invoice("annual")).await;
+    assert_eq!(second_open.status(), StatusCode::UNAUTHORIZED);
+
+    let void_response = call(
+        app.clone(),
+        request_with_headers_and_json(
+            "POST",
+            &format!("/api/v1/admin/invoices/{}/void", first["id"].as_str().unwrap()),
+            &admin,
+            json!({ "reason": "picked the wrong plan" }),
+        ),
+    )
+    .await;
+    assert_eq!(void_response.status(), StatusCode::OK);
+
+    let plans: Value = decode_json(
+        call(
+            app.clone(),
+            request_with_headers("GET", "/api/v1/admin/subscription-plans", &admin),
+        )
+        .await,
+    )
+    .await;
+    let quarterly = plans
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|plan| plan["code"] == "quarterly")
+        .unwrap();
+    let quarterly_path = format!(
+        "/api/v1/admin/subscription-plans/{}",
+        quarterly["id"].as_str().unwrap()
+    );
+    let original_price = quarterly["price"].as_i64().unwrap();
+
+    let update_price = |price: i64| {
+        request_with_headers_and_json("PATCH", &quarterly_path, &admin, json!({ "price": price }))
+    };
+    assert_eq!(call(app.clone(), update_price(42_000)).await.status(), StatusCode::OK);
+
+    let second: Value = decode_json(call(app.clone(), request_invoice("quarterly")).await).await;
+    assert_eq!(second["amount"].as_i64(), Some(42_000));
+    assert_eq!(second["period_days"].as_i64(), Some(90));
+
+    // Invoices keep the price they were issued at.
+    assert_eq!(
+        call(app.clone(), update_price(original_price)).await.status(),
+        StatusCode::OK
+    );
+
+    let open_invoices: Value = decode_json(
+        call(
+            app.clone(),
+            request_with_headers(
+                "GET",
+                &format!("/api/v1/admin/invoices?status=open&station_id={station_id}"),
+                &admin,
+            ),
+        )
+        .await,
+    )
+    .await;
+    assert_eq!(open_invoices.as_array().unwrap().len(), 1);
+    assert_eq!(open_invoices[0]["amount"].as_i64(), Some(42_000));
+
+    let pay_response = call(
+        app.clone(),
+        request_with_headers(
+            "POST",
+            &format!("/api/v1/admin/invoices/{}/pay", second["id"].as_str().unwrap()),
+            &admin,
+        ),
+    )
+    .await;
+    assert_eq!(pay_response.status(), StatusCode::OK);
+    let paid: Value = decode_json(pay_response).await;
+    assert_eq!(paid["status"].as_str(), Some("paid"));
+
+    let billing: Value = decode_json(
+        call(
+            app,
+            request_with_auth("GET", "/api/v1/stations/dashboard/billing", &token),
+        )
+        .await,
+    )
+    .await;
+    assert_eq!(billing["subscription"]["plan"]["code"].as_str(), Some("quarterly"));
+    assert_eq!(
+        billing["subscription"]["id"].as_str(),
+        paid["subscription_id"].as_str()
+    );
+    let statuses: Vec<&str> = billing["invoices"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|invoice| invoice["status"].as_str().unwrap())
+        .collect();
+    assert_eq!(statuses, ["paid", "void"]);
+
+    let renewed: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM notifications WHERE station_id = $1 AND title = 'Subscription renewed'",
+    )
+    .bind(station_id)
+    .fetch_one(&pool)
+    .await
+    .expect("notification count should load");
+    assert_eq!(renewed, 1);
+}
+
+#[tokio::test]
+#[serial]
+async fn lapsed_subscription_is_read_only_during_grace_then_blocked() {
+    let Some(pool) = db_pool().await else {
+        eprintln!("Skipping DB-backed stations test: TEST_DATABASE_URL not set");
+        return;
+    };
+
+    reset_db(&pool).await;
+    seed_admin(&pool, "super-secret").await;
+
+    let app = test_app_with_pool(pool.clone());
+    let email = format!("{}@example.com", uuid::Uuid::new_v4().simple());
+    let (station_id, token) =
+        create_station_and_signin(app.clone(), &email, "petrol", (9.08, 7.48)).await;
+    mark_station_commodities_available(&pool, station_id).await;
+    let auth = format!("Bearer {token}");
+
+    let closest_path = "/api/v1/stations/closest?latitude=9.07&longitude=7.47&station_type=petrol";
+    let listed: Value = decode_json(call(app.clone(), request("GET", closest_path)).await).await;
+    assert_eq!(listed.as_array().map(Vec::len), Some(1));
+
+    // Ended yesterday: inside the default three day grace period.
+    end_station_subscription(&pool, station_id, 1).await;
+
+    let hidden: Value = decode_json(call(app.clone(), request("GET", closest_path)).await).await;
+    assert_eq!(hidden.as_array().map(Vec::len), Some(0));
+
+    let dashboard = call(app.clone(), request_with_auth("GET", "/api/v1/stations/dashboard", &token)).await;
+    assert_eq!(dashboard.status(), StatusCode::OK);
+
+    let close_attempt = call(
+        app.clone(),
+        request_with_headers_and_json(
+            "PATCH",
+            "/api/v1/stations/dashboard/temporarily-closed",
+            &[("authorization", &auth)],
+            json!({ "temporarily_closed": true }),
+        ),
+    )
+    .await;
+    assert_eq!(close_attempt.status(), StatusCode::UNAUTHORIZED);
+    assert!(body_text(close_attempt).await.contains("read-only"));
+
+    let signin = call(
+        app.clone(),
+        request_with_json(
+            "POST",
+            "/api/v1/auth/signin",
+            json!({ "email": email, "password": "station-pass", "station_type": "petrol" }),
+        ),
+    )
+    .await;
+    assert_eq!(signin.status(), StatusCode::OK);
+
+    // Past the grace period even reads are refused, but billing stays open
+    // so the station can pay.
+    sqlx::query("UPDATE subscriptions SET ends_at = now() - interval '30 days' WHERE station_id = $1")
+        .bind(station_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let dashboard = call(app.clone(), request_with_auth("GET", "/api/v1/stations/dashboard", &token)).await;
+    assert_eq!(dashboard.status(), StatusCode::UNAUTHORIZED);
+
+    let billing = call(
+        app.clone(),
+        request_with_auth("GET", "/api/v1/stations/dashboard/billing", &token),
+    )
+    .await;
+    assert_eq!(billing.status(), StatusCode::OK);
+
+    let invoice = call(
+        app,
+        request_with_headers_and_json(
+            "POST",
+            "/api/v1/stations/dashboard/billing/invoices",
+            &[("authorization", &auth)],
+            json!({ "plan_code": "monthly" }),
+        ),
+    )
+    .await;
+    assert_eq!(invoice.status(), StatusCode::CREATED);
+}
+
+#[tokio::test]
+#[serial]
+async fn lapsed_station_is_blocked_across_station_routes_except_billing() {
+    let Some(pool) = db_pool().await else {
+        eprintln!("Skipping DB-backed stations test: TEST_DATABASE_URL not set");
+        return;
+    };
+
+    reset_db(&pool).await;
+    seed_admin(&pool, "super-secret").await;
+
+    let app = test_app_with_pool(pool.clone());
+    let email = format!("{}@example.com", uuid::Uuid::new_v4().simple());
+    let (station_id, token) =
+        create_station_and_signin(app.clone(), &email, "petrol", (9.08, 7.48)).await;
+    let auth = format!("Bearer {token}");
+
+    let stats = call(
+        app.clone(),
+        request_with_auth("GET", "/api/v1/discounts/station/stats", &token),
+    )
+    .await;
+    assert_eq!(stats.status(), StatusCode::OK);
+
+    end_station_subscription(&pool, station_id, 30).await;
+
+    let stats = call(
+        app.clone(),
+        request_with_auth("GET", "/api/v1/discounts/station/stats", &token),
+    )
+    .await;
+    assert_eq!(stats.status(), StatusCode::UNAUTHORIZED);
+    assert!(body_text(stats).await.contains("subscription expired"));
+
+    let commodity = call(
+        app.clone(),
+        request_with_headers_and_json(
+            "PATCH",
+            &format!("/api/v1/commodities/{}", Uuid::new_v4()),
+            &[("authorization", &auth)],
+            json!({ "price": 700 }),
+        ),
+    )
+    .await;
+    assert_eq!(commodity.status(), StatusCode::UNAUTHORIZED);
+
+    let voucher = call(
+        app.clone(),
+        request_with_headers_and_json(
+            "POST",
+            "/api/v1/loyalty/vouchers/redeem",
+            &[("authorization", &auth)],
+            json!({ "code": "NOPE" }),
+        ),
+    )
+    .await;
+    assert_eq!(voucher.status(), StatusCode::UNAUTHORIZED);
+    assert!(body_text(voucher).await.contains("subscription expired"));
+
+    let history = call(
+        app.clone(),
+        request_with_auth("GET", "/api/v1/stations/dashboard/billing/subscription/history", &token),
+    )
+    .await;
+    assert_eq!(history.status(), StatusCode::OK);
+
+    // Public routes still answer callers without a token.
+    let listed = call(
+        app,
+        request("GET", "/api/v1/stations/closest?latitude=9.07&longitude=7.47&station_type=petrol"),
+    )
+    .await;
+    assert_eq!(listed.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+#[serial]
+async fn subscription_lifecycle_transitions_are_validated_and_audited() {
+    let Some(pool) = db_pool().await else {
+        eprintln!("Skipping DB-backed stations test: TEST_DATABASE_URL not set");
+        return;
+    };
+
+    reset_db(&pool).await;
+    seed_admin(&pool, "super-secret").await;
+
+    let app = test_app_with_pool(pool.clone());
+    let email = format!("{}@example.com", uuid::Uuid::new_v4().simple());
+    let (station_id, token) =
+        create_station_and_signin(app.clone(), &email, "petrol", (9.08, 7.48)).await;
+    let auth = format!("Bearer {token}");
+    let admin = [("x-admin-password", "super-secret")];
+
+    let act = |body: Value| {
+        request_with_headers_and_json(
+            "POST",
+            "/api/v1/stations/dashboard/billing/subscription",
+            &[("authorization", &auth)],
+            body,
+        )
+    };
+    let ends_at = |subscription: &Value| {
+        chrono::DateTime::parse_from_rfc3339(subscription["ends_at"].as_str().unwrap()).unwrap()
+    };
+
+    let from_trial = call(app.clone(), act(json!({ "action": "change_plan", "plan_code": "annual" }))).await;
+    assert_eq!(from_trial.status(), StatusCode::UNAUTHORIZED);
+
+    let invoice: Value = decode_json(
+        call(
+            app.clone(),
+            request_with_headers_and_json(
+                "POST",
+                &format!("/api/v1/admin/stations/{station_id}/invoices"),
+                &admin,
+                json!({ "plan_code": "monthly" }),
+            ),
+        )
+        .await,
+    )
+    .await;
+    let paid = call(
+        app.clone(),
+        request_with_headers(
+            "POST",
+            &format!("/api/v1/admin/invoices/{}/pay", invoice["id"].as_str().unwrap()),
+            &admin,
+        ),
+    )
+    .await;
+    assert_eq!(paid.status(), StatusCode::OK);
+
+    // About 60 days of monthly (500/day) buys about 67.5 days of quarterly.
+    let upgraded = call(app.clone(), act(json!({ "action": "change_plan", "plan_code": "quarterly" }))).await;
+    assert_eq!(upgraded.status(), StatusCode::OK);
+    let upgraded: Value = decode_json(upgraded).await;
+    assert_eq!(upgraded["plan"]["code"].as_str(), Some("quarterly"));
+    let days_left = (ends_at(&upgraded).with_timezone(&chrono::Utc) - chrono::Utc::now()).num_hours();
+    assert!((67 * 24..=68 * 24).contains(&days_left), "{days_left} hours left");
+
+    let paused: Value = decode_json(call(app.clone(), act(json!({ "action": "pause" }))).await).await;
+    assert_eq!(paused["status"].as_str(), Some("paused"));
+
+    let paused_again = call(app.clone(), act(json!({ "action": "pause" }))).await;
+    assert_eq!(paused_again.status(), StatusCode::UNAUTHORIZED);
+    assert!(body_text(paused_again).await.contains("cannot pause a subscription that is paused"));
+
+    let dashboard = call(app.clone(), request_with_auth("GET", "/api/v1/stations/dashboard", &token)).await;
+    assert_eq!(dashboard.status(), StatusCode::OK);
+    let close_attempt = call(
+        app.clone(),
+        request_with_headers_and_json(
+            "PATCH",
+            "/api/v1/stations/dashboard/temporarily-closed",
+            &[("authorization", &auth)],
+            json!({ "temporarily_closed": true }),
+        ),
+    )
+    .await;
+    assert_eq!(close_attempt.status(), StatusCode::UNAUTHORIZED);
+
+    sqlx::query("UPDATE subscriptions SET paused_at = paused_at - interval '5 days' WHERE station_id = $1 AND status = 'paused'")
+        .bind(station_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let resumed: Value = decode_json(call(app.clone(), act(json!({ "action": "resume" }))).await).await;
+    assert_eq!(resumed["status"].as_str(), Some("active"));
+    let shift = ends_at(&resumed) - ends_at(&upgraded);
+    assert_eq!(shift.num_days(), 5);
+
+    let scheduled: Value =
+        decode_json(call(app.clone(), act(json!({ "action": "cancel_at_period_end" }))).await).await;
+    assert_eq!(scheduled["cancel_at_period_end"].as_bool(), Some(true));
+    assert_eq!(scheduled["status"].as_str(), Some("active"));
+
+    let pause_while_leaving = call(app.clone(), act(json!({ "action": "pause" }))).await;
+    assert_eq!(pause_while_leaving.status(), StatusCode::UNAUTHORIZED);
+
+    let withdrawn: Value =
+        decode_json(call(app.clone(), act(json!({ "action": "withdraw_cancel" }))).await).await;
+    assert_eq!(withdrawn["cancel_at_period_end"].as_bool(), Some(false));
+
+    let cancelled = call(
+        app.clone(),
+        request_with_headers_and_json(
+            "POST",
+            &format!("/api/v1/admin/stations/{station_id}/subscription"),
+            &admin,
+            json!({ "action": "cancel_now", "reason": "closed down" }),
+        ),
+    )
+    .await;
+    assert_eq!(cancelled.status(), StatusCode::OK);
+    let cancelled: Value = decode_json(cancelled).await;
+    assert_eq!(cancelled["status"].as_str(), Some("cancelled"));
+
+    let dashboard = call(app.clone(), request_with_auth("GET", "/api/v1/stations/dashboard", &token)).await;
+    assert_eq!(dashboard.status(), StatusCode::UNAUTHORIZED);
+
+    let resume_cancelled = call(app.clone(), act(json!({ "action": "resume" }))).await;
+    assert_eq!(resume_cancelled.status(), StatusCode::NOT_FOUND);
+
+    let history: Value = decode_json(
+        call(
+            app.clone(),
+            request_with_auth("GET", "/api/v1/stations/dashboard/billing/subscription/history", &token),
+        )
+        .await,
+    )
+    .await;
+    let events: Vec<&str> = history
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|event| event["event_type"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        events,
+        [
+            "cancelled", "cancel_withdrawn", "cancel_scheduled", "resumed", "paused",
+            "plan_changed", "renewed", "started"
+        ]
+    );
+    assert_eq!(history[0]["actor_type"].as_str(), Some("admin"));
+    assert_eq!(history[0]["reason"].as_str(), Some("closed down"));
+    assert_eq!(history[5]["from_plan_code"].as_str(), Some("monthly"));
+    assert_eq!(history[5]["to_plan_code"].as_str(), Some("quarterly"));
+    let credit = history[5]["proration_credit"].as_i64().unwrap();
+    assert!((29_990..=30_000).contains(&credit), "credit {credit}");
+
+    let admin_history: Value = decode_json(
+        call(
+            app,
+            request_with_headers(
+                "GET",
+                &format!("/api/v1/admin/stations/{station_id}/subscription/history"),
+                &admin,
+            ),
+        )
+        .await,
+    )
+    .await;
+    assert_eq!(admin_history.as_array().map(Vec::len), Some(8));
+}
+
+#[tokio::test]
+#[serial]
+async fn scheduled_cancellation_takes_effect_at_period_end() {
+    let Some(pool) = db_pool().await else {
+        eprintln!("Skipping DB-backed stations test: TEST_DATABASE_URL not set");
+        return;
+    };
+
+    reset_db(&pool).await;
+    seed_admin(&pool, "super-secret").await;
+
+    let app = test_app_with_pool(pool.clone());
+    let email = format!("{}@example.com", uuid::Uuid::new_v4().simple());
+    let (station_id, token) =
+        create_station_and_signin(app.clone(), &email, "gas", (9.08, 7.48)).await;
+
+    let scheduled = call(
+        app,
+        request_with_headers_and_json(
+            "POST",
+            "/api/v1/stations/dashboard/billing/subscription",
+            &[("authorization", &format!("Bearer {token}"))],
+            json!({ "action": "cancel_at_period_end" }),
+        ),
+    )
+    .await;
+    assert_eq!(scheduled.status(), StatusCode::OK);
+
+    sqlx::query("UPDATE subscriptions SET ends_at = now() - interval '1 minute' WHERE station_id = $1")
+        .bind(station_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    fuelfinder_server::domain::subscriptions::service::run_subscription_reminder_cycle(&pool)
+        .await
+        .expect("reminder cycle should run");
+
+    let (status, cancelled_at): (String, Option<chrono::DateTime<chrono::Utc>>) = sqlx::query_as(
+        "SELECT status, cancelled_at FROM subscriptions WHERE station_id = $1",
+    )
+    .bind(station_id)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(status, "cancelled");
+    assert!(cancelled_at.is_some());
+
+    let (event_type, actor_type): (String, String) = sqlx::query_as(
+        "SELECT event_type, actor_type FROM subscription_events WHERE station_id = $1 ORDER BY created_at DESC LIMIT 1",
+    )
+    .bind(station_id)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!((event_type.as_str(), actor_type.as_str()), ("cancelled", "system"));
+}